@@ -0,0 +1,229 @@
+use as3_parser::ns::*;
+use crate::model::*;
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// The path of a package directory, relative to the site root,
+/// ending with a slash unless it is the top-level package.
+pub fn package_directory(package: &str) -> String {
+    if package.is_empty() { String::new() } else { format!("{}/", package.replace('.', "/")) }
+}
+
+pub fn package_display_name(package: &str) -> &str {
+    if package.is_empty() { "Top Level" } else { package }
+}
+
+/// The first sentence of an ASDoc main body, used in summary tables.
+pub fn summary(asdoc: Option<&Rc<AsDoc>>) -> String {
+    let Some((body, _)) = asdoc.and_then(|asdoc| asdoc.main_body.as_ref()) else {
+        return String::new();
+    };
+    let body = body.trim();
+    match body.find(". ") {
+        Some(i) => body[..=i].to_owned(),
+        None => body.to_owned(),
+    }
+}
+
+/// Builds links from a page to other pages of the site.
+pub struct Linker<'a> {
+    pub model: &'a DocModel,
+    pub resolver: &'a NameResolver,
+    /// Relative path from the page to the site root, such as `"../../"`.
+    pub root: String,
+    /// The package of the page, used to look up `@see` references.
+    pub package: String,
+}
+
+impl Linker<'_> {
+    pub fn definition_url(&self, definition: &DefinitionDoc) -> String {
+        if definition.has_page() {
+            format!("{}{}{}.html", self.root, package_directory(&definition.package), definition.symbol.name)
+        } else {
+            format!("{}#{}", self.package_url(&definition.package), member_anchor(&definition.symbol))
+        }
+    }
+
+    pub fn package_url(&self, package: &str) -> String {
+        format!("{}{}package-detail.html", self.root, package_directory(package))
+    }
+
+    pub fn definition_link(&self, definition: &DefinitionDoc) -> String {
+        format!("<a href=\"{}\">{}</a>", self.definition_url(definition), escape(&definition.symbol.name))
+    }
+
+    /// Renders a symbol as a link to its page if it is documented.
+    pub fn symbol_link(&self, symbol: &Rc<Symbol>) -> String {
+        self.model.definition(symbol).map_or_else(|| escape(&symbol.name), |definition| self.definition_link(&definition))
+    }
+
+    /// Renders a type expression, linking the documented definitions it refers to.
+    pub fn type_expression(&self, exp: &Rc<Expression>) -> String {
+        let base = match exp.as_ref() {
+            Expression::WithTypeArguments(exp) => &exp.base,
+            _ => exp,
+        };
+        let Some(definition) = self.resolver.resolutions().get(base).and_then(|symbol| self.model.definition(&symbol)) else {
+            return escape(&exp.location().text());
+        };
+        let text = exp.location().text();
+        let suffix = &text[base.location().last_offset() - exp.location().first_offset()..];
+        format!("{}{}", self.definition_link(&definition), escape(suffix))
+    }
+
+    /// Renders a `@see` or `@throws` reference, linking it if it refers to a
+    /// documented definition or member.
+    pub fn reference(&self, reference: &AsDocReference, display_text: Option<&str>, current: Option<&DefinitionDoc>) -> String {
+        let base_text = reference.base.as_ref().map(|base| base.location().text());
+        let property = reference.instance_property.as_ref().and_then(|id| id.to_identifier_name()).map(|(name, _)| name);
+        let text = display_text.map(|text| text.trim().to_owned()).filter(|text| !text.is_empty()).unwrap_or_else(|| {
+            match (&base_text, &property) {
+                (Some(base), Some(property)) => format!("{base}.{property}"),
+                (Some(base), None) => base.clone(),
+                (None, Some(property)) => property.clone(),
+                (None, None) => String::new(),
+            }
+        });
+        let definition = match &base_text {
+            Some(base) => self.model.lookup(base, &self.package),
+            None => current.and_then(|current| self.model.definition(&current.symbol)),
+        };
+        let Some(definition) = definition else {
+            return format!("<code>{}</code>", escape(&text));
+        };
+        let mut url = self.definition_url(&definition);
+        if let Some(property) = &property {
+            let anchor = definition.members.iter().find(|member| &member.symbol.name == property)
+                .map_or(property.clone(), |member| member.anchor());
+            url.push('#');
+            url.push_str(&anchor);
+        }
+        format!("<a href=\"{url}\"><code>{}</code></a>", escape(&text))
+    }
+
+    pub fn throws_class(&self, class_reference: &Rc<Expression>) -> String {
+        let text = class_reference.location().text();
+        match self.model.lookup(&text, &self.package) {
+            Some(definition) => format!("<a href=\"{}\"><code>{}</code></a>", self.definition_url(&definition), escape(&text)),
+            None => format!("<code>{}</code>", escape(&text)),
+        }
+    }
+}
+
+/// Renders the description of an ASDoc comment: a deprecation banner,
+/// the main body, which may contain HTML as in ASDoc, and the `@default`,
+/// `@see` and `@example` tags. Parameter, return and throws tags are
+/// rendered by `function_sections()`.
+pub fn asdoc_description(asdoc: Option<&Rc<AsDoc>>, linker: &Linker, current: Option<&DefinitionDoc>) -> String {
+    let Some(asdoc) = asdoc else {
+        return String::new();
+    };
+    let mut html = String::new();
+    for (tag, _) in &asdoc.tags {
+        if let AsDocTag::Deprecated { message } = tag {
+            html.push_str("<div class=\"deprecated\"><strong>Deprecated</strong>");
+            if let Some(message) = message {
+                html.push_str(&format!(": {}", escape(message.trim())));
+            }
+            html.push_str("</div>\n");
+        }
+    }
+    if let Some((body, _)) = &asdoc.main_body {
+        html.push_str(&format!("<div class=\"description\">{}</div>\n", body.trim()));
+    }
+    for (tag, _) in &asdoc.tags {
+        if let AsDocTag::Default(value) = tag {
+            html.push_str(&format!("<p class=\"default\"><strong>Default value:</strong> <code>{}</code></p>\n", escape(value.trim())));
+        }
+    }
+    let see: Vec<String> = asdoc.tags.iter().filter_map(|(tag, _)| match tag {
+        AsDocTag::See { reference, display_text } => Some(linker.reference(reference, display_text.as_deref(), current)),
+        _ => None,
+    }).collect();
+    if !see.is_empty() {
+        html.push_str("<h4>See also</h4>\n<ul class=\"see\">\n");
+        for link in see {
+            html.push_str(&format!("<li>{link}</li>\n"));
+        }
+        html.push_str("</ul>\n");
+    }
+    for (tag, _) in &asdoc.tags {
+        if let AsDocTag::Example(example) = tag {
+            html.push_str(&format!("<h4>Example</h4>\n<pre class=\"example\">{}</pre>\n", escape(example.trim())));
+        }
+    }
+    html
+}
+
+/// Renders the parameters, result and thrown errors of a function.
+pub fn function_sections(common: &FunctionCommon, asdoc: Option<&Rc<AsDoc>>, linker: &Linker) -> String {
+    let tags: Vec<&AsDocTag> = asdoc.map_or(vec![], |asdoc| asdoc.tags.iter().map(|(tag, _)| tag).collect());
+    let mut html = String::new();
+    if !common.signature.parameters.is_empty() {
+        html.push_str("<h4>Parameters</h4>\n<dl class=\"parameters\">\n");
+        for parameter in &common.signature.parameters {
+            let name = parameter.destructuring.destructuring.location().text();
+            let description = tags.iter().find_map(|tag| match tag {
+                AsDocTag::Param { name: param_name, description } if *param_name == name => Some(description.trim()),
+                _ => None,
+            }).unwrap_or_default();
+            html.push_str(&format!("<dt><code>{}</code></dt><dd>{description}</dd>\n", parameter_signature(parameter, linker)));
+        }
+        html.push_str("</dl>\n");
+    }
+    let returns = tags.iter().find_map(|tag| match tag {
+        AsDocTag::Return(description) => Some(description.trim()),
+        _ => None,
+    });
+    if common.signature.result_type.is_some() || returns.is_some() {
+        let result_type = common.signature.result_type.as_ref().map(|t| linker.type_expression(t));
+        if result_type.as_deref() != Some("void") || returns.is_some() {
+            html.push_str("<h4>Returns</h4>\n<p class=\"returns\">");
+            if let Some(result_type) = result_type {
+                html.push_str(&format!("<code>{result_type}</code> "));
+            }
+            html.push_str(&format!("{}</p>\n", returns.unwrap_or_default()));
+        }
+    }
+    let throws: Vec<String> = tags.iter().filter_map(|tag| match tag {
+        AsDocTag::Throws { class_reference, description } => {
+            Some(format!("{} {}", linker.throws_class(class_reference), description.as_deref().unwrap_or_default().trim()))
+        },
+        _ => None,
+    }).collect();
+    if !throws.is_empty() {
+        html.push_str("<h4>Throws</h4>\n<ul class=\"throws\">\n");
+        for throw in throws {
+            html.push_str(&format!("<li>{}</li>\n", throw.trim_end()));
+        }
+        html.push_str("</ul>\n");
+    }
+    html
+}
+
+pub fn parameter_signature(parameter: &Parameter, linker: &Linker) -> String {
+    let mut signature = String::new();
+    if parameter.kind == ParameterKind::Rest {
+        signature.push_str("...");
+    }
+    signature.push_str(&escape(&parameter.destructuring.destructuring.location().text()));
+    if let Some(type_annotation) = &parameter.destructuring.type_annotation {
+        signature.push_str(&format!(": {}", linker.type_expression(type_annotation)));
+    }
+    if let Some(default_value) = &parameter.default_value {
+        signature.push_str(&format!(" = {}", escape(&default_value.location().text())));
+    }
+    signature
+}
@@ -0,0 +1,72 @@
+use clap::Parser;
+use std::{fs, path::PathBuf, process::ExitCode};
+use as3_parser::ns::*;
+
+mod html;
+mod model;
+mod site;
+
+use model::DocModel;
+use site::{generate_site, SiteOptions};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Arguments {
+    /// Source directories, searched recursively for `.as` files, or source files.
+    #[arg(required = true)]
+    sources: Vec<PathBuf>,
+
+    /// Output directory of the generated site.
+    #[arg(short, long, default_value = "asdoc-output")]
+    output: PathBuf,
+
+    /// Title of the index page.
+    #[arg(short, long, default_value = "API Documentation")]
+    title: String,
+}
+
+fn main() -> ExitCode {
+    let arguments = Arguments::parse();
+
+    let options = ProjectOptions {
+        source_roots: arguments.sources,
+        include: vec!["**/*.as".into()],
+        ..default()
+    };
+    let project = match Project::parse(&options) {
+        Ok(project) => project,
+        Err(error) => {
+            eprintln!("Failed to read {}: {}", error.path.display(), error.error);
+            return ExitCode::FAILURE;
+        },
+    };
+    for error in project.errors() {
+        eprintln!("Failed to read {}: {}", error.path.display(), error.error);
+    }
+    for diagnostic in project.diagnostics() {
+        eprintln!("{}", diagnostic.format_english());
+    }
+    if !project.errors().is_empty() || project.error_count() != 0 {
+        return ExitCode::FAILURE;
+    }
+    let programs = project.programs();
+
+    // Unresolved names are not reported, since the sources usually
+    // refer to libraries that are not documented.
+    let mut resolver = NameResolver::new();
+    resolver.resolve_programs(&programs);
+    let model = DocModel::new(&programs, &resolver);
+
+    let options = SiteOptions {
+        title: arguments.title,
+    };
+    for page in generate_site(&model, &resolver, &options) {
+        let path = arguments.output.join(&page.path);
+        let result = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, page.content));
+        if let Err(error) = result {
+            eprintln!("Failed to write {}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
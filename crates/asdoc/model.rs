@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use as3_parser::ns::*;
+
+/// The documented definitions of a set of programs, by package.
+///
+/// Only public package-level definitions and their public and protected
+/// members are documented; interface and enum members are always public.
+/// A definition or member whose ASDoc comment contains a `@private` or
+/// `@internal` tag is hidden.
+pub struct DocModel {
+    pub packages: BTreeMap<String, PackageDoc>,
+    definitions: HashMap<NodeAsKey<Rc<Symbol>>, Rc<DefinitionDoc>>,
+}
+
+pub struct PackageDoc {
+    /// The package name, which is empty for the top-level package.
+    pub name: String,
+    pub definitions: Vec<Rc<DefinitionDoc>>,
+}
+
+pub struct DefinitionDoc {
+    pub symbol: Rc<Symbol>,
+    pub package: String,
+    /// Members in declaration order, excluding the constructor.
+    pub members: Vec<MemberDoc>,
+    pub constructor: Option<Rc<Symbol>>,
+    /// The resolved base class of a class.
+    pub base_class: Option<Rc<Symbol>>,
+    /// The resolved interfaces implemented by a class or extended by an interface.
+    pub interfaces: Vec<Rc<Symbol>>,
+}
+
+impl DefinitionDoc {
+    /// Indicates whether the definition has a page of its own, rather than
+    /// being documented in the page of its package.
+    pub fn has_page(&self) -> bool {
+        matches!(self.symbol.kind, SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum)
+    }
+}
+
+#[derive(Clone)]
+pub struct MemberDoc {
+    pub symbol: Rc<Symbol>,
+    pub is_static: bool,
+    pub is_protected: bool,
+}
+
+impl MemberDoc {
+    /// The anchor of the member within the page of its definition.
+    pub fn anchor(&self) -> String {
+        member_anchor(&self.symbol)
+    }
+}
+
+pub fn member_anchor(symbol: &Symbol) -> String {
+    if symbol.kind == SymbolKind::Function {
+        format!("{}()", symbol.name)
+    } else {
+        symbol.name.clone()
+    }
+}
+
+/// Indicates whether an ASDoc comment hides its definition.
+pub fn is_hidden(asdoc: Option<&Rc<AsDoc>>) -> bool {
+    asdoc.is_some_and(|asdoc| asdoc.tags.iter().any(|(tag, _)| matches!(tag, AsDocTag::Private | AsDocTag::Internal(_))))
+}
+
+impl DocModel {
+    pub fn new(programs: &[Rc<Program>], resolver: &NameResolver) -> Self {
+        let mut model = Self {
+            packages: BTreeMap::new(),
+            definitions: HashMap::new(),
+        };
+        for program in programs {
+            for package in &program.packages {
+                let name = package.name.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>().join(".");
+                let Some(scope) = resolver.scopes().get(package) else {
+                    continue;
+                };
+                let definitions: Vec<Rc<DefinitionDoc>> = scope.symbols().into_iter()
+                    .filter(|symbol| symbol.definition.is_some() && Attribute::find_public(symbol.attributes()).is_some() && !is_hidden(symbol.asdoc().as_ref()))
+                    .map(|symbol| Rc::new(Self::definition_doc(&symbol, &name, resolver)))
+                    .collect();
+                for definition in &definitions {
+                    model.definitions.insert(NodeAsKey(definition.symbol.clone()), definition.clone());
+                }
+                let package_doc = model.packages.entry(name.clone()).or_insert_with(|| PackageDoc {
+                    name,
+                    definitions: vec![],
+                });
+                package_doc.definitions.extend(definitions);
+            }
+        }
+        for package in model.packages.values_mut() {
+            package.definitions.sort_by(|a, b| a.symbol.name.cmp(&b.symbol.name));
+        }
+        model
+    }
+
+    fn definition_doc(symbol: &Rc<Symbol>, package: &str, resolver: &NameResolver) -> DefinitionDoc {
+        let definition = symbol.definition.as_ref().unwrap();
+        let mut doc = DefinitionDoc {
+            symbol: symbol.clone(),
+            package: package.to_owned(),
+            members: vec![],
+            constructor: None,
+            base_class: None,
+            interfaces: vec![],
+        };
+        let (scope, implicitly_public) = match definition.as_ref() {
+            Directive::ClassDefinition(defn) => {
+                doc.base_class = defn.extends_clause.as_ref().and_then(|exp| resolve_type(exp, resolver));
+                doc.interfaces = defn.implements_clause.iter().flatten().filter_map(|exp| resolve_type(exp, resolver)).collect();
+                (resolver.scopes().get(definition), false)
+            },
+            Directive::InterfaceDefinition(defn) => {
+                doc.interfaces = defn.extends_clause.iter().flatten().filter_map(|exp| resolve_type(exp, resolver)).collect();
+                (resolver.scopes().get(definition), true)
+            },
+            Directive::EnumDefinition(defn) => (resolver.scopes().get(&defn.block), true),
+            _ => (None, false),
+        };
+        for member in scope.map_or(vec![], |scope| scope.symbols()) {
+            if member.kind == SymbolKind::TypeParameter || member.definition.is_none() || is_hidden(member.asdoc().as_ref()) {
+                continue;
+            }
+            if member.kind == SymbolKind::Constructor {
+                doc.constructor = Some(member);
+                continue;
+            }
+            let attributes = member.attributes();
+            let is_protected = Attribute::find_protected(attributes).is_some();
+            if !(implicitly_public || is_protected || Attribute::find_public(attributes).is_some()) {
+                continue;
+            }
+            doc.members.push(MemberDoc {
+                is_static: Attribute::find_static(attributes).is_some(),
+                is_protected,
+                symbol: member,
+            });
+        }
+        doc
+    }
+
+    pub fn definition(&self, symbol: &Rc<Symbol>) -> Option<Rc<DefinitionDoc>> {
+        self.definitions.get(&NodeAsKey(symbol.clone())).cloned()
+    }
+
+    /// Looks up a documented definition by its fully qualified name,
+    /// by its name within `package` or, failing that, by its name if it is unique.
+    pub fn lookup(&self, name: &str, package: &str) -> Option<Rc<DefinitionDoc>> {
+        let (qualifier, simple_name) = name.rsplit_once('.').unwrap_or(("", name));
+        let find_in = |package: &str| self.packages.get(package).and_then(|package| {
+            package.definitions.iter().find(|definition| definition.symbol.name == simple_name).cloned()
+        });
+        if !qualifier.is_empty() {
+            return find_in(qualifier);
+        }
+        if let Some(definition) = find_in(package) {
+            return Some(definition);
+        }
+        let mut candidates = self.packages.values().flat_map(|package| &package.definitions).filter(|definition| definition.symbol.name == simple_name);
+        let candidate = candidates.next().cloned();
+        if candidates.next().is_some() { None } else { candidate }
+    }
+
+    /// The documented ancestors of a class, from its base class upwards,
+    /// or the documented interfaces an interface inherits, breadth first.
+    pub fn ancestors(&self, definition: &Rc<DefinitionDoc>) -> Vec<Rc<DefinitionDoc>> {
+        let mut ancestors: Vec<Rc<DefinitionDoc>> = vec![];
+        let mut pending = vec![definition.clone()];
+        while !pending.is_empty() {
+            let current = pending.remove(0);
+            let bases = if current.symbol.kind == SymbolKind::Class {
+                current.base_class.iter().cloned().collect::<Vec<_>>()
+            } else {
+                current.interfaces.clone()
+            };
+            for base in bases {
+                let Some(base) = self.definition(&base) else {
+                    continue;
+                };
+                if Rc::ptr_eq(&base, definition) || ancestors.iter().any(|ancestor| Rc::ptr_eq(ancestor, &base)) {
+                    continue;
+                }
+                ancestors.push(base.clone());
+                pending.push(base);
+            }
+        }
+        ancestors
+    }
+
+    /// The members a definition inherits from its documented ancestors,
+    /// excluding those it overrides, along with the ancestor defining each.
+    pub fn inherited_members(&self, definition: &Rc<DefinitionDoc>) -> Vec<(MemberDoc, Rc<DefinitionDoc>)> {
+        let mut names: HashSet<String> = definition.members.iter().filter(|member| !member.is_static).map(|member| member.symbol.name.clone()).collect();
+        let mut inherited = vec![];
+        for ancestor in self.ancestors(definition) {
+            for member in &ancestor.members {
+                if member.is_static || names.contains(&member.symbol.name) {
+                    continue;
+                }
+                inherited.push((member.clone(), ancestor.clone()));
+            }
+            names.extend(ancestor.members.iter().map(|member| member.symbol.name.clone()));
+        }
+        inherited
+    }
+}
+
+fn resolve_type(exp: &Rc<Expression>, resolver: &NameResolver) -> Option<Rc<Symbol>> {
+    match exp.as_ref() {
+        Expression::WithTypeArguments(exp) => resolve_type(&exp.base, resolver),
+        _ => resolver.resolutions().get(exp),
+    }
+}
@@ -0,0 +1,501 @@
+use std::path::PathBuf;
+use as3_parser::ns::*;
+use crate::{html::*, model::*};
+
+pub struct SiteOptions {
+    /// Title of the index page.
+    pub title: String,
+}
+
+impl Default for SiteOptions {
+    fn default() -> Self {
+        Self {
+            title: "API Documentation".into(),
+        }
+    }
+}
+
+/// A generated file, with a path relative to the output directory.
+pub struct Page {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+const STYLESHEET: &str = include_str!("style.css");
+
+/// Generates the site of a documentation model: an index of packages and
+/// definitions, a page per package, listing its definitions and documenting
+/// its functions, variables and namespaces, and a page per class, interface
+/// and enum.
+pub fn generate_site(model: &DocModel, resolver: &NameResolver, options: &SiteOptions) -> Vec<Page> {
+    let mut pages = vec![Page {
+        path: "style.css".into(),
+        content: STYLESHEET.into(),
+    }];
+    pages.push(Page {
+        path: "index.html".into(),
+        content: index_page(model, resolver, options),
+    });
+    for package in model.packages.values() {
+        let directory = package_directory(&package.name);
+        pages.push(Page {
+            path: format!("{directory}package-detail.html").into(),
+            content: package_page(package, model, resolver),
+        });
+        for definition in &package.definitions {
+            if definition.has_page() {
+                pages.push(Page {
+                    path: format!("{directory}{}.html", definition.symbol.name).into(),
+                    content: definition_page(definition, model, resolver),
+                });
+            }
+        }
+    }
+    pages
+}
+
+fn linker<'a>(model: &'a DocModel, resolver: &'a NameResolver, package: &str, root: String) -> Linker<'a> {
+    Linker {
+        model,
+        resolver,
+        root,
+        package: package.to_owned(),
+    }
+}
+
+fn root_of_package(package: &str) -> String {
+    if package.is_empty() { String::new() } else { "../".repeat(package.split('.').count()) }
+}
+
+fn layout(title: &str, root: &str, body: &str) -> String {
+    format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n<nav><a href=\"{root}index.html\">Index</a></nav>\n<main>\n{body}</main>\n</body>\n</html>\n", escape(title))
+}
+
+fn index_page(model: &DocModel, resolver: &NameResolver, options: &SiteOptions) -> String {
+    let linker = linker(model, resolver, "", String::new());
+    let mut body = format!("<h1>{}</h1>\n<h2>Packages</h2>\n<table class=\"summary\">\n", escape(&options.title));
+    for package in model.packages.values() {
+        body.push_str(&format!("<tr><td><a href=\"{}\">{}</a></td></tr>\n", linker.package_url(&package.name), escape(package_display_name(&package.name))));
+    }
+    body.push_str("</table>\n<h2>All Classes</h2>\n<table class=\"summary\">\n");
+    let mut definitions: Vec<&Rc<DefinitionDoc>> = model.packages.values().flat_map(|package| &package.definitions).filter(|definition| definition.has_page()).collect();
+    definitions.sort_by(|a, b| a.symbol.name.cmp(&b.symbol.name));
+    for definition in definitions {
+        body.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            linker.definition_link(definition), escape(package_display_name(&definition.package)), summary(definition.symbol.asdoc().as_ref())));
+    }
+    body.push_str("</table>\n");
+    layout(&options.title, "", &body)
+}
+
+fn package_page(package: &PackageDoc, model: &DocModel, resolver: &NameResolver) -> String {
+    let root = root_of_package(&package.name);
+    let linker = linker(model, resolver, &package.name, root.clone());
+    let mut body = format!("<h1>Package {}</h1>\n", escape(package_display_name(&package.name)));
+    let sections = [
+        ("Interfaces", &[SymbolKind::Interface][..]),
+        ("Classes", &[SymbolKind::Class][..]),
+        ("Enums", &[SymbolKind::Enum][..]),
+        ("Functions", &[SymbolKind::Function][..]),
+        ("Properties", &[SymbolKind::Variable, SymbolKind::Getter, SymbolKind::Setter][..]),
+        ("Constants", &[SymbolKind::Constant][..]),
+        ("Namespaces", &[SymbolKind::Namespace][..]),
+        ("Types", &[SymbolKind::Type][..]),
+    ];
+    let mut details = String::new();
+    for (title, kinds) in sections {
+        let definitions: Vec<&Rc<DefinitionDoc>> = package.definitions.iter().filter(|definition| kinds.contains(&definition.symbol.kind)).collect();
+        if definitions.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{title}</h2>\n<table class=\"summary\">\n"));
+        for definition in definitions {
+            let member = MemberDoc {
+                symbol: definition.symbol.clone(),
+                is_static: false,
+                is_protected: false,
+            };
+            let link = if definition.has_page() {
+                linker.definition_link(definition)
+            } else {
+                details.push_str(&member_detail(&member, &linker, None));
+                format!("<a href=\"#{}\">{}</a>", escape(&member.anchor()), escape(&definition.symbol.name))
+            };
+            body.push_str(&format!("<tr><td>{link}</td><td>{}</td></tr>\n", summary(definition.symbol.asdoc().as_ref())));
+        }
+        body.push_str("</table>\n");
+    }
+    if !details.is_empty() {
+        body.push_str(&format!("<h2>Details</h2>\n{details}"));
+    }
+    layout(&format!("Package {}", package_display_name(&package.name)), &root, &body)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum MemberCategory {
+    Property,
+    Method,
+    Constant,
+}
+
+fn category(symbol: &Symbol) -> Option<MemberCategory> {
+    match symbol.kind {
+        SymbolKind::Variable | SymbolKind::Getter | SymbolKind::Setter => Some(MemberCategory::Property),
+        SymbolKind::Function => Some(MemberCategory::Method),
+        SymbolKind::Constant => Some(MemberCategory::Constant),
+        _ => None,
+    }
+}
+
+/// Removes the setter of a property that also has a getter.
+fn merge_accessors(members: Vec<(MemberDoc, Option<Rc<DefinitionDoc>>)>) -> Vec<(MemberDoc, Option<Rc<DefinitionDoc>>)> {
+    let mut merged: Vec<(MemberDoc, Option<Rc<DefinitionDoc>>)> = vec![];
+    for (member, defined_by) in members {
+        let accessor = matches!(member.symbol.kind, SymbolKind::Getter | SymbolKind::Setter);
+        if accessor && merged.iter().any(|(other, _)| {
+            other.symbol.name == member.symbol.name && other.is_static == member.is_static && matches!(other.symbol.kind, SymbolKind::Getter | SymbolKind::Setter)
+        }) {
+            continue;
+        }
+        merged.push((member, defined_by));
+    }
+    merged
+}
+
+fn definition_page(definition: &Rc<DefinitionDoc>, model: &DocModel, resolver: &NameResolver) -> String {
+    let root = root_of_package(&definition.package);
+    let linker = linker(model, resolver, &definition.package, root.clone());
+    let symbol = &definition.symbol;
+    let asdoc = symbol.asdoc();
+    let keyword = match symbol.kind {
+        SymbolKind::Interface => "interface",
+        SymbolKind::Enum => "enum",
+        _ => "class",
+    };
+
+    let mut body = format!("<p class=\"package\">Package: <a href=\"{}\">{}</a></p>\n", linker.package_url(&definition.package), escape(package_display_name(&definition.package)));
+    body.push_str(&format!("<h1>{} {}</h1>\n<table class=\"info\">\n", escape(&capitalize(keyword)), escape(&symbol.name)));
+    if symbol.kind == SymbolKind::Class {
+        let mut chain = vec![escape(&symbol.name)];
+        chain.extend(model.ancestors(definition).iter().map(|ancestor| linker.definition_link(ancestor)));
+        if model.ancestors(definition).is_empty() {
+            if let Some(base_class) = &definition.base_class {
+                chain.push(linker.symbol_link(base_class));
+            }
+        }
+        body.push_str(&format!("<tr><th>Inheritance</th><td>{}</td></tr>\n", chain.join(" &rarr; ")));
+    }
+    if !definition.interfaces.is_empty() {
+        let label = if symbol.kind == SymbolKind::Interface { "Extends" } else { "Implements" };
+        let interfaces: Vec<String> = definition.interfaces.iter().map(|interface| linker.symbol_link(interface)).collect();
+        body.push_str(&format!("<tr><th>{label}</th><td>{}</td></tr>\n", interfaces.join(", ")));
+    }
+    body.push_str("</table>\n");
+    body.push_str(&asdoc_description(asdoc.as_ref(), &linker, Some(definition)));
+
+    let mut members: Vec<(MemberDoc, Option<Rc<DefinitionDoc>>)> = definition.members.iter().map(|member| (member.clone(), None)).collect();
+    members.extend(model.inherited_members(definition).into_iter().map(|(member, ancestor)| (member, Some(ancestor))));
+    let members = merge_accessors(members);
+
+    let sections = [
+        ("Public Properties", MemberCategory::Property, false),
+        ("Protected Properties", MemberCategory::Property, true),
+        ("Public Methods", MemberCategory::Method, false),
+        ("Protected Methods", MemberCategory::Method, true),
+        ("Public Constants", MemberCategory::Constant, false),
+        ("Protected Constants", MemberCategory::Constant, true),
+    ];
+    for (title, section_category, protected) in sections {
+        let rows: Vec<&(MemberDoc, Option<Rc<DefinitionDoc>>)> = members.iter().filter(|(member, _)| {
+            category(&member.symbol) == Some(section_category) && member.is_protected == protected
+        }).collect();
+        if rows.is_empty() && !(section_category == MemberCategory::Method && !protected && definition.constructor.is_some()) {
+            continue;
+        }
+        body.push_str(&format!("<h2>{title}</h2>\n<table class=\"summary\">\n<tr><th>Member</th><th>Description</th><th>Defined by</th></tr>\n"));
+        if section_category == MemberCategory::Method && !protected {
+            if let Some(constructor) = &definition.constructor {
+                let member = MemberDoc { symbol: constructor.clone(), is_static: false, is_protected: false };
+                body.push_str(&format!("<tr><td><a href=\"#{}\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+                    escape(&constructor_anchor(constructor)), member_summary_signature(&member, &linker),
+                    summary(constructor.asdoc().as_ref()), escape(&symbol.name)));
+            }
+        }
+        for (member, defined_by) in rows {
+            let (url, defined_by_html) = match defined_by {
+                Some(ancestor) => (format!("{}#{}", linker.definition_url(ancestor), member.anchor()), linker.definition_link(ancestor)),
+                None => (format!("#{}", member.anchor()), escape(&symbol.name)),
+            };
+            let class = if defined_by.is_some() { " class=\"inherited\"" } else { "" };
+            let static_marker = if member.is_static { " <span class=\"static\">[static]</span>" } else { "" };
+            body.push_str(&format!("<tr{class}><td><a href=\"{}\">{}</a>{static_marker}</td><td>{}</td><td>{defined_by_html}</td></tr>\n",
+                escape(&url), member_summary_signature(member, &linker), summary(member.symbol.asdoc().as_ref())));
+        }
+        body.push_str("</table>\n");
+    }
+
+    if let Some(constructor) = &definition.constructor {
+        body.push_str("<h2>Constructor Detail</h2>\n");
+        let member = MemberDoc { symbol: constructor.clone(), is_static: false, is_protected: false };
+        body.push_str(&member_detail(&member, &linker, Some(definition)));
+    }
+    let details = [
+        ("Property Detail", MemberCategory::Property),
+        ("Method Detail", MemberCategory::Method),
+        ("Constant Detail", MemberCategory::Constant),
+    ];
+    for (title, detail_category) in details {
+        let own: Vec<&MemberDoc> = members.iter().filter(|(member, defined_by)| defined_by.is_none() && category(&member.symbol) == Some(detail_category)).map(|(member, _)| member).collect();
+        if own.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{title}</h2>\n"));
+        for member in own {
+            body.push_str(&member_detail(member, &linker, Some(definition)));
+        }
+    }
+    layout(&symbol.name, &root, &body)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
+
+fn constructor_anchor(constructor: &Symbol) -> String {
+    format!("{}()", constructor.name)
+}
+
+fn function_common(symbol: &Symbol) -> Option<Rc<FunctionCommon>> {
+    match symbol.definition.as_ref()?.as_ref() {
+        Directive::FunctionDefinition(defn) => Some(defn.common.clone()),
+        _ => None,
+    }
+}
+
+fn variable_binding(symbol: &Symbol) -> Option<Rc<VariableBinding>> {
+    let Directive::VariableDefinition(defn) = symbol.definition.as_ref()?.as_ref() else {
+        return None;
+    };
+    defn.bindings.iter().find(|binding| binding.destructuring.destructuring.location().first_offset() == symbol.location.first_offset()).cloned()
+}
+
+/// The type of a property: the result type of its getter, the parameter
+/// type of its setter or the type annotation of its variable.
+fn property_type(symbol: &Symbol) -> Option<Rc<Expression>> {
+    let common = function_common(symbol);
+    match symbol.kind {
+        SymbolKind::Getter => common?.signature.result_type.clone(),
+        SymbolKind::Setter => common?.signature.parameters.first()?.destructuring.type_annotation.clone(),
+        _ => symbol.type_annotation.clone(),
+    }
+}
+
+fn member_summary_signature(member: &MemberDoc, linker: &Linker) -> String {
+    let symbol = &member.symbol;
+    let mut signature = format!("<code>{}", escape(&symbol.name));
+    if let Some(common) = function_common(symbol).filter(|_| matches!(symbol.kind, SymbolKind::Function | SymbolKind::Constructor)) {
+        let parameters: Vec<String> = common.signature.parameters.iter().map(|parameter| parameter_signature(parameter, linker)).collect();
+        signature.push_str(&format!("({})", parameters.join(", ")));
+        if let Some(result_type) = &common.signature.result_type {
+            signature.push_str(&format!(": {}", linker.type_expression(result_type)));
+        }
+    } else if let Some(property_type) = property_type(symbol) {
+        signature.push_str(&format!(": {}", linker.type_expression(&property_type)));
+    }
+    signature.push_str("</code>");
+    signature
+}
+
+fn member_detail(member: &MemberDoc, linker: &Linker, current: Option<&DefinitionDoc>) -> String {
+    let symbol = &member.symbol;
+    let mut modifiers = vec![if member.is_protected { "protected" } else { "public" }];
+    if member.is_static {
+        modifiers.push("static");
+    }
+    let attributes = symbol.attributes();
+    if Attribute::find_override(attributes).is_some() {
+        modifiers.push("override");
+    }
+    if Attribute::find_final(attributes).is_some() {
+        modifiers.push("final");
+    }
+    let interface_member = current.is_some_and(|current| current.symbol.kind == SymbolKind::Interface);
+    let prefix = if interface_member { String::new() } else { format!("{} ", modifiers.join(" ")) };
+
+    let anchor = if symbol.kind == SymbolKind::Constructor { constructor_anchor(symbol) } else { member.anchor() };
+    let mut html = format!("<section class=\"member\" id=\"{}\">\n<h3>{}</h3>\n", escape(&anchor), escape(&symbol.name));
+    let signature = match symbol.kind {
+        SymbolKind::Function | SymbolKind::Constructor | SymbolKind::Getter | SymbolKind::Setter => {
+            let common = function_common(symbol).unwrap();
+            let keyword = match symbol.kind {
+                SymbolKind::Getter => "function get",
+                SymbolKind::Setter => "function set",
+                _ => "function",
+            };
+            let parameters: Vec<String> = common.signature.parameters.iter().map(|parameter| parameter_signature(parameter, linker)).collect();
+            let mut signature = format!("{prefix}{keyword} {}({})", escape(&symbol.name), parameters.join(", "));
+            if let Some(result_type) = &common.signature.result_type {
+                signature.push_str(&format!(": {}", linker.type_expression(result_type)));
+            }
+            signature
+        },
+        SymbolKind::Variable | SymbolKind::Constant => {
+            let keyword = if symbol.kind == SymbolKind::Constant { "const" } else { "var" };
+            let mut signature = format!("{prefix}{keyword} {}", escape(&symbol.name));
+            if let Some(type_annotation) = &symbol.type_annotation {
+                signature.push_str(&format!(": {}", linker.type_expression(type_annotation)));
+            }
+            if symbol.kind == SymbolKind::Constant {
+                if let Some(initializer) = variable_binding(symbol).and_then(|binding| binding.initializer.clone()) {
+                    signature.push_str(&format!(" = {}", escape(&initializer.location().text())));
+                }
+            }
+            signature
+        },
+        SymbolKind::Namespace => format!("{prefix}namespace {}", escape(&symbol.name)),
+        _ => format!("{prefix}type {}", escape(&symbol.name)),
+    };
+    html.push_str(&format!("<pre class=\"signature\">{signature}</pre>\n"));
+
+    // The documentation of an accessor pair is taken from its getter,
+    // unless only the setter is documented.
+    let asdoc = symbol.asdoc().or_else(|| current.and_then(|current| {
+        current.members.iter().find(|other| {
+            other.symbol.name == symbol.name && !Rc::ptr_eq(&other.symbol, symbol) && matches!(other.symbol.kind, SymbolKind::Getter | SymbolKind::Setter)
+        }).and_then(|other| other.symbol.asdoc())
+    }));
+    if matches!(symbol.kind, SymbolKind::Getter | SymbolKind::Setter) {
+        let has_getter = symbol.kind == SymbolKind::Getter || current.is_some_and(|current| current.members.iter().any(|other| other.symbol.name == symbol.name && other.symbol.kind == SymbolKind::Getter));
+        let has_setter = symbol.kind == SymbolKind::Setter || current.is_some_and(|current| current.members.iter().any(|other| other.symbol.name == symbol.name && other.symbol.kind == SymbolKind::Setter));
+        if has_getter != has_setter {
+            html.push_str(&format!("<p class=\"access\">{}</p>\n", if has_getter { "[read-only]" } else { "[write-only]" }));
+        }
+    }
+    html.push_str(&asdoc_description(asdoc.as_ref(), linker, current));
+    if matches!(symbol.kind, SymbolKind::Function | SymbolKind::Constructor) {
+        html.push_str(&function_sections(&function_common(symbol).unwrap(), asdoc.as_ref(), linker));
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use as3_parser::ns::*;
+    use super::*;
+
+    fn generate(sources: &[&str]) -> HashMap<String, String> {
+        let programs: Vec<Rc<Program>> = sources.iter().map(|source| {
+            let cu = CompilationUnit::new(None, (*source).into());
+            let program = ParserFacade(&cu, default()).parse_program();
+            assert!(cu.nested_diagnostics().is_empty(), "{source}");
+            program
+        }).collect();
+        let mut resolver = NameResolver::new();
+        resolver.resolve_programs(&programs);
+        let model = DocModel::new(&programs, &resolver);
+        generate_site(&model, &resolver, &default()).into_iter().map(|page| (page.path.to_string_lossy().replace('\\', "/"), page.content)).collect()
+    }
+
+    #[test]
+    fn test_site() {
+        let pages = generate(&[
+            r#"
+            package com.example {
+                /**
+                 * Base of all shapes.
+                 */
+                public class Shape {
+                    /** Name of the shape. */
+                    public var name: String;
+                    /**
+                     * Computes the area.
+                     * @return The area.
+                     */
+                    public function area(): Number { return 0 }
+                    /** @private */
+                    public function hidden(): void {}
+                    private var secret: int;
+                }
+            }
+            "#,
+            r#"
+            package com.example.shapes {
+                import com.example.Shape;
+                /**
+                 * A circle.
+                 * @see com.example.Shape#area
+                 * @deprecated Use Ellipse.
+                 */
+                public class Circle extends Shape {
+                    /**
+                     * Constructs a circle.
+                     * @param radius The radius.
+                     * @throws ArgumentError If the radius is negative.
+                     */
+                    public function Circle(radius: Number = 1) {}
+                    /** The radius. */
+                    public function get radius(): Number { return 0 }
+                    public function set radius(value: Number): void {}
+                    override public function area(): Number { return 0 }
+                    /** @internal Not part of the public API. */
+                    public static const PI: Number = 3.14;
+                }
+            }
+            "#,
+        ]);
+        let mut paths: Vec<&String> = pages.keys().collect();
+        paths.sort();
+        assert_eq!(paths, [
+            "com/example/Shape.html",
+            "com/example/package-detail.html",
+            "com/example/shapes/Circle.html",
+            "com/example/shapes/package-detail.html",
+            "index.html",
+            "style.css",
+        ]);
+
+        let shape = &pages["com/example/Shape.html"];
+        assert!(shape.contains("<div class=\"description\">Base of all shapes.</div>"));
+        assert!(shape.contains("<pre class=\"signature\">public function area(): Number</pre>"));
+        assert!(shape.contains("<h4>Returns</h4>\n<p class=\"returns\"><code>Number</code> The area.</p>"));
+        assert!(!shape.contains("hidden"));
+        assert!(!shape.contains("secret"));
+
+        let circle = &pages["com/example/shapes/Circle.html"];
+        assert!(circle.contains("<tr><th>Inheritance</th><td>Circle &rarr; <a href=\"../../../com/example/Shape.html\">Shape</a></td></tr>"));
+        assert!(circle.contains("<div class=\"deprecated\"><strong>Deprecated</strong>: Use Ellipse.</div>"));
+        assert!(circle.contains("<li><a href=\"../../../com/example/Shape.html#area()\"><code>com.example.Shape.area</code></a></li>"));
+        assert!(circle.contains("<pre class=\"signature\">public function Circle(radius: Number = 1)</pre>"));
+        assert!(circle.contains("<dt><code>radius: Number = 1</code></dt><dd>The radius.</dd>"));
+        assert!(circle.contains("<li><code>ArgumentError</code> If the radius is negative.</li>"));
+        // The accessor pair is documented once, and the inherited variable is listed.
+        assert_eq!(circle.matches("<section class=\"member\" id=\"radius\">").count(), 1);
+        assert!(circle.contains("<tr class=\"inherited\"><td><a href=\"../../../com/example/Shape.html#name\"><code>name: String</code></a></td><td>Name of the shape.</td><td><a href=\"../../../com/example/Shape.html\">Shape</a></td></tr>"));
+        // The overridden method is not listed as inherited.
+        assert!(!circle.contains("Shape.html#area()\"><code>area"));
+        assert!(!circle.contains("PI"));
+
+        let index = &pages["index.html"];
+        assert!(index.contains("<tr><td><a href=\"com/example/shapes/package-detail.html\">com.example.shapes</a></td></tr>"));
+        assert!(index.contains("<tr><td><a href=\"com/example/shapes/Circle.html\">Circle</a></td><td>com.example.shapes</td><td>A circle.</td></tr>"));
+    }
+
+    #[test]
+    fn test_package_page() {
+        let pages = generate(&[r#"
+            package {
+                /** Logs a message. @param message The message. */
+                public function log(message: String): void {}
+                public const VERSION: String = "1.0";
+                internal var hidden: int;
+            }
+        "#]);
+        let package = &pages["package-detail.html"];
+        assert!(package.contains("<h1>Package Top Level</h1>"));
+        assert!(package.contains("<tr><td><a href=\"#log()\">log</a></td><td>Logs a message.</td></tr>"));
+        assert!(package.contains("<pre class=\"signature\">public const VERSION: String = &quot;1.0&quot;</pre>"));
+        assert!(!package.contains("hidden"));
+    }
+}
@@ -0,0 +1,138 @@
+use clap::Parser;
+use std::{env, fs, io::{self, Read, Write}, path::{Path, PathBuf}, process::ExitCode};
+use as3_parser::ns::*;
+
+/// Name of the configuration file looked up from the current directory upwards.
+const CONFIG_FILE_NAME: &str = "as3_formatter.json";
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Arguments {
+    /// Source files or directories to format. Directories are searched
+    /// recursively for `.as` files. If omitted, the standard input is formatted.
+    paths: Vec<PathBuf>,
+
+    /// Configuration file. Defaults to the nearest `as3_formatter.json`.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    /// Reports files that are not formatted, without changing them.
+    #[arg(long)]
+    check: bool,
+
+    /// Writes the formatted sources back to their files.
+    #[arg(short, long)]
+    write: bool,
+}
+
+fn main() -> ExitCode {
+    let arguments = Arguments::parse();
+
+    let formatter = match load_formatter(arguments.config.as_deref()) {
+        Ok(formatter) => formatter,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    if arguments.paths.is_empty() {
+        let mut source = String::new();
+        if let Err(error) = io::stdin().read_to_string(&mut source) {
+            eprintln!("Failed to read standard input: {error}");
+            return ExitCode::FAILURE;
+        }
+        return match formatter.format_text(&source) {
+            Ok(formatted) if arguments.check => {
+                if formatted == source { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+            },
+            Ok(formatted) => {
+                print!("{formatted}");
+                ExitCode::SUCCESS
+            },
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            },
+        };
+    }
+
+    let mut files = vec![];
+    for path in &arguments.paths {
+        if let Err(error) = collect_source_files(path, &mut files) {
+            eprintln!("Failed to read {}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut success = true;
+    for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("Failed to read {}: {error}", file.display());
+                success = false;
+                continue;
+            },
+        };
+        let compilation_unit = CompilationUnit::new(Some(file.to_string_lossy().into_owned()), source.clone());
+        let formatted = match formatter.format(&compilation_unit) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                eprintln!("{error}");
+                success = false;
+                continue;
+            },
+        };
+        if arguments.check {
+            if formatted != source {
+                println!("{}", file.display());
+                success = false;
+            }
+        } else if arguments.write {
+            if formatted != source {
+                if let Err(error) = fs::write(file, formatted) {
+                    eprintln!("Failed to write {}: {error}", file.display());
+                    success = false;
+                }
+            }
+        } else {
+            let _ = io::stdout().write_all(formatted.as_bytes());
+        }
+    }
+
+    if success { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn load_formatter(config: Option<&Path>) -> Result<Formatter, FormatterError> {
+    if let Some(config) = config {
+        return Formatter::from_config_file(config);
+    }
+    let mut directory = env::current_dir().ok();
+    while let Some(directory_1) = directory {
+        let config = directory_1.join(CONFIG_FILE_NAME);
+        if config.is_file() {
+            return Formatter::from_config_file(config);
+        }
+        directory = directory_1.parent().map(|parent| parent.to_owned());
+    }
+    Ok(Formatter(default()))
+}
+
+/// Collects a source file, or the `.as` files in a directory, in a sorted order.
+fn collect_source_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_source_files(&entry, files)?;
+        } else if entry.extension().is_some_and(|extension| extension == "as") {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use as3_parser::ns::*;
+use lsp_types::{DiagnosticSeverity, NumberOrString, Position, Range, Url};
+
+/// An open text document, parsed according to its file extension.
+pub struct Document {
+    pub version: i32,
+    pub compilation_unit: Rc<CompilationUnit>,
+    pub syntax: DocumentSyntax,
+    /// Diagnostics reported while parsing the document, including
+    /// those of included compilation units.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Name resolution of an ActionScript document, used for hover.
+    pub resolver: Option<NameResolver>,
+}
+
+pub enum DocumentSyntax {
+    Program(Rc<Program>),
+    Mxml(Rc<Mxml>),
+    Css(Rc<CssDocument>),
+}
+
+impl Document {
+    /// Parses a document. Documents whose path ends with `.mxml` are parsed
+    /// as MXML, those ending with `.css` as CSS and any other as ActionScript.
+    pub fn parse(uri: Url, version: i32, text: String) -> Self {
+        let file_path = uri.to_file_path().ok().map(|path| path.to_string_lossy().into_owned());
+        let compilation_unit = CompilationUnit::new(file_path, text);
+        let path = uri.path().to_lowercase();
+        let syntax = if path.ends_with(".mxml") {
+            DocumentSyntax::Mxml(ParserFacade(&compilation_unit, default()).parse_mxml())
+        } else if path.ends_with(".css") {
+            DocumentSyntax::Css(CssParserFacade(&compilation_unit, default()).parse_document())
+        } else {
+            DocumentSyntax::Program(ParserFacade(&compilation_unit, default()).parse_program())
+        };
+        compilation_unit.sort_diagnostics();
+        let diagnostics = compilation_unit.nested_diagnostics();
+
+        // The resolver reports undefined names, which are not published,
+        // since the other programs of the project are unknown.
+        let resolver = if let DocumentSyntax::Program(program) = &syntax {
+            let mut resolver = NameResolver::new();
+            resolver.resolve_programs(std::slice::from_ref(program));
+            Some(resolver)
+        } else {
+            None
+        };
+
+        Self {
+            version,
+            compilation_unit,
+            syntax,
+            diagnostics,
+            resolver,
+        }
+    }
+
+    /// Converts a byte offset into a position whose character
+    /// is counted in UTF-16 code units.
+    pub fn position_at(&self, offset: usize) -> Position {
+        let cu = &self.compilation_unit;
+        let offset = offset.min(cu.text().len());
+        let line = cu.get_line_number(offset) - 1;
+        let line_offset = cu.get_line_offset_from_offset(offset).min(offset);
+        let character = cu.text()[line_offset..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// Converts a position into a byte offset, clamping it to the end of its line.
+    pub fn offset_at(&self, position: Position) -> usize {
+        let text = self.compilation_unit.text();
+        let Some(line_offset) = self.compilation_unit.get_line_offset(position.line as usize + 1) else {
+            return text.len();
+        };
+        let mut units = 0;
+        for (i, ch) in text[line_offset..].char_indices() {
+            if units >= position.character as usize || ch == '\r' || ch == '\n' {
+                return line_offset + i;
+            }
+            units += ch.len_utf16();
+        }
+        text.len()
+    }
+
+    pub fn range(&self, location: &Location) -> Range {
+        Range::new(self.position_at(location.first_offset()), self.position_at(location.last_offset()))
+    }
+
+    /// Converts the parser diagnostics into LSP diagnostics.
+    ///
+    /// A diagnostic of an included compilation unit is placed at the
+    /// start of the document, with its file path in the message.
+    pub fn lsp_diagnostics(&self) -> Vec<lsp_types::Diagnostic> {
+        self.diagnostics.iter().map(|diagnostic| {
+            let location = diagnostic.location();
+            let (range, message) = if Rc::ptr_eq(&location.compilation_unit(), &self.compilation_unit) {
+                (self.range(&location), diagnostic.format_message_english())
+            } else {
+                (Range::default(), diagnostic.format_english())
+            };
+            lsp_types::Diagnostic {
+                range,
+                severity: Some(if diagnostic.is_warning() { DiagnosticSeverity::WARNING } else { DiagnosticSeverity::ERROR }),
+                code: Some(NumberOrString::Number(diagnostic.id())),
+                source: Some("as3".into()),
+                message,
+                ..default()
+            }
+        }).collect()
+    }
+}
@@ -0,0 +1,378 @@
+use as3_parser::ns::*;
+use lsp_types::{DocumentSymbol, FoldingRange, FoldingRangeKind, Hover, HoverContents, MarkupContent, MarkupKind, Position};
+use crate::document::*;
+
+/// Returns the outline of a document: the packages and definitions of an
+/// ActionScript program, the elements of an MXML document or the
+/// rules and at-rules of a CSS document.
+pub fn document_symbols(document: &Document) -> Vec<DocumentSymbol> {
+    match &document.syntax {
+        DocumentSyntax::Program(program) => {
+            let mut symbols: Vec<DocumentSymbol> = program.packages.iter().map(|package| {
+                let name = package.name.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>().join(".");
+                let selection = package.name.first().map_or(package.location.clone(), |(_, first)| {
+                    first.combine_with(package.name.last().unwrap().1.clone())
+                });
+                symbol(document, if name.is_empty() { "(top level)".into() } else { name }, None, lsp_types::SymbolKind::PACKAGE, &package.location, &selection,
+                    directive_symbols(document, &package.block.directives, DefinitionContext::Package))
+            }).collect();
+            symbols.extend(directive_symbols(document, &program.directives, DefinitionContext::Package));
+            symbols
+        },
+        DocumentSyntax::Mxml(mxml) => mxml_symbols(document, &mxml.content),
+        DocumentSyntax::Css(css) => css.directives.iter().filter_map(|directive| css_symbol(document, directive)).collect(),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum DefinitionContext {
+    Package,
+    Class,
+    Enum,
+}
+
+#[allow(deprecated)]
+fn symbol(document: &Document, name: String, detail: Option<String>, kind: lsp_types::SymbolKind, location: &Location, selection: &Location, children: Vec<DocumentSymbol>) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: document.range(location),
+        selection_range: document.range(selection),
+        children: if children.is_empty() { None } else { Some(children) },
+    }
+}
+
+fn directive_symbols(document: &Document, directives: &[Rc<Directive>], context: DefinitionContext) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    for directive in directives {
+        match directive.as_ref() {
+            Directive::ClassDefinition(defn) => {
+                symbols.push(symbol(document, defn.name.0.clone(), None, lsp_types::SymbolKind::CLASS, &defn.location, &defn.name.1,
+                    directive_symbols(document, &defn.block.directives, DefinitionContext::Class)));
+            },
+            Directive::InterfaceDefinition(defn) => {
+                symbols.push(symbol(document, defn.name.0.clone(), None, lsp_types::SymbolKind::INTERFACE, &defn.location, &defn.name.1,
+                    directive_symbols(document, &defn.block.directives, DefinitionContext::Class)));
+            },
+            Directive::EnumDefinition(defn) => {
+                symbols.push(symbol(document, defn.name.0.clone(), None, lsp_types::SymbolKind::ENUM, &defn.location, &defn.name.1,
+                    directive_symbols(document, &defn.block.directives, DefinitionContext::Enum)));
+            },
+            Directive::FunctionDefinition(defn) => {
+                let (name, name_location) = defn.name_identifier();
+                let (kind, detail) = match &defn.name {
+                    FunctionName::Constructor(_) => (lsp_types::SymbolKind::CONSTRUCTOR, None),
+                    FunctionName::Getter(_) => (lsp_types::SymbolKind::PROPERTY, Some("get".into())),
+                    FunctionName::Setter(_) => (lsp_types::SymbolKind::PROPERTY, Some("set".into())),
+                    FunctionName::Identifier(_) if context == DefinitionContext::Class => (lsp_types::SymbolKind::METHOD, None),
+                    FunctionName::Identifier(_) => (lsp_types::SymbolKind::FUNCTION, None),
+                };
+                symbols.push(symbol(document, name, detail, kind, &defn.location, &name_location, vec![]));
+            },
+            Directive::VariableDefinition(defn) => {
+                let kind = match (context, defn.kind.0) {
+                    (DefinitionContext::Enum, VariableDefinitionKind::Const) => lsp_types::SymbolKind::ENUM_MEMBER,
+                    (DefinitionContext::Class, _) => lsp_types::SymbolKind::FIELD,
+                    (_, VariableDefinitionKind::Const) => lsp_types::SymbolKind::CONSTANT,
+                    (_, VariableDefinitionKind::Var) => lsp_types::SymbolKind::VARIABLE,
+                };
+                for binding in &defn.bindings {
+                    let Expression::QualifiedIdentifier(id) = binding.destructuring.destructuring.as_ref() else {
+                        continue;
+                    };
+                    let Some((name, name_location)) = id.to_identifier_name() else {
+                        continue;
+                    };
+                    let detail = binding.destructuring.type_annotation.as_ref().map(|t| t.location().text());
+                    symbols.push(symbol(document, name, detail, kind, &binding.location(), &name_location, vec![]));
+                }
+            },
+            Directive::NamespaceDefinition(defn) => {
+                symbols.push(symbol(document, defn.left.0.clone(), None, lsp_types::SymbolKind::NAMESPACE, &defn.location, &defn.left.1, vec![]));
+            },
+            Directive::TypeDefinition(defn) => {
+                symbols.push(symbol(document, defn.left.0.clone(), Some(defn.right.location().text()), lsp_types::SymbolKind::CLASS, &defn.location, &defn.left.1, vec![]));
+            },
+            Directive::ConfigurationDirective(defn) => {
+                symbols.extend(directive_symbols(document, std::slice::from_ref(&defn.directive), context));
+            },
+            Directive::Block(block) => {
+                symbols.extend(directive_symbols(document, &block.directives, context));
+            },
+            Directive::IfStatement(stmt) => {
+                symbols.extend(directive_symbols(document, std::slice::from_ref(&stmt.consequent), context));
+                if let Some(alternative) = &stmt.alternative {
+                    symbols.extend(directive_symbols(document, std::slice::from_ref(alternative), context));
+                }
+            },
+            _ => {},
+        }
+    }
+    symbols
+}
+
+fn mxml_symbols(document: &Document, content: &[Rc<MxmlContent>]) -> Vec<DocumentSymbol> {
+    content.iter().filter_map(|content| {
+        let MxmlContent::Element(element) = content.as_ref() else {
+            return None;
+        };
+        let name = element.name.location.text();
+        let id = element.attributes.iter().find(|attribute| !attribute.xmlns && attribute.name.prefix.is_none() && attribute.name.name == "id");
+        let detail = id.map(|attribute| attribute.value.0.clone());
+        let children = element.content.as_ref().map_or(vec![], |content| mxml_symbols(document, content));
+        Some(symbol(document, name, detail, lsp_types::SymbolKind::OBJECT, &element.location, &element.name.location, children))
+    }).collect()
+}
+
+fn css_symbol(document: &Document, directive: &Rc<CssDirective>) -> Option<DocumentSymbol> {
+    match directive.as_ref() {
+        CssDirective::Rule(rule) => Some(css_rule_symbol(document, rule)),
+        CssDirective::MediaQuery(media) => {
+            let conditions = media.conditions.iter().map(|condition| condition.location().text()).collect::<Vec<_>>().join(", ");
+            let children = media.rules.iter().map(|rule| css_rule_symbol(document, rule)).collect();
+            Some(symbol(document, format!("@media {conditions}"), None, lsp_types::SymbolKind::MODULE, &media.location, &media.location, children))
+        },
+        CssDirective::FontFace(font_face) => {
+            Some(symbol(document, "@font-face".into(), None, lsp_types::SymbolKind::MODULE, &font_face.location, &font_face.location, vec![]))
+        },
+        CssDirective::NamespaceDefinition(defn) => {
+            Some(symbol(document, defn.prefix.0.clone(), Some(defn.uri.0.clone()), lsp_types::SymbolKind::NAMESPACE, &defn.location, &defn.prefix.1, vec![]))
+        },
+        CssDirective::Invalidated(_) => None,
+    }
+}
+
+fn css_rule_symbol(document: &Document, rule: &CssRule) -> DocumentSymbol {
+    let name = rule.selectors.iter().map(|selector| selector.location().text()).collect::<Vec<_>>().join(", ");
+    let selection = rule.selectors.first().map_or(rule.location.clone(), |first| {
+        first.location().combine_with(rule.selectors.last().unwrap().location())
+    });
+    symbol(document, name, None, lsp_types::SymbolKind::CLASS, &rule.location, &selection, vec![])
+}
+
+/// Returns the folding ranges of a document: multi-line blocks, comments
+/// and import groups of ActionScript, multi-line MXML elements and comments,
+/// and multi-line CSS rules.
+pub fn folding_ranges(document: &Document) -> Vec<FoldingRange> {
+    let mut folder = Folder {
+        document,
+        ranges: vec![],
+    };
+    match &document.syntax {
+        DocumentSyntax::Program(program) => {
+            let _ = walk_program(&mut folder, program);
+            folder.add_import_groups(&program.directives);
+            for package in &program.packages {
+                folder.add_import_groups(&package.block.directives);
+            }
+        },
+        DocumentSyntax::Mxml(mxml) => folder.add_mxml_content(&mxml.content),
+        DocumentSyntax::Css(css) => {
+            for directive in &css.directives {
+                folder.add(&directive.location(), true, None);
+                if let CssDirective::MediaQuery(media) = directive.as_ref() {
+                    for rule in &media.rules {
+                        folder.add(&rule.location, true, None);
+                    }
+                }
+            }
+        },
+    }
+    for comment in document.compilation_unit.comments() {
+        if comment.multiline() {
+            folder.add(&comment.location(), false, Some(FoldingRangeKind::Comment));
+        }
+    }
+    folder.ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    folder.ranges
+}
+
+struct Folder<'a> {
+    document: &'a Document,
+    ranges: Vec<FoldingRange>,
+}
+
+impl Folder<'_> {
+    /// Adds a range spanning multiple lines. If `closed` is true, the last
+    /// line, which holds the closing delimiter, remains visible.
+    fn add(&mut self, location: &Location, closed: bool, kind: Option<FoldingRangeKind>) {
+        let start_line = self.document.position_at(location.first_offset()).line;
+        let mut end_line = self.document.position_at(location.last_offset()).line;
+        if closed {
+            end_line = end_line.saturating_sub(1);
+        }
+        if end_line <= start_line {
+            return;
+        }
+        self.ranges.push(FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        });
+    }
+
+    fn add_import_groups(&mut self, directives: &[Rc<Directive>]) {
+        let mut group: Option<Location> = None;
+        for directive in directives {
+            if let Directive::ImportDirective(import) = directive.as_ref() {
+                group = Some(group.map_or(import.location.clone(), |group| group.combine_with(import.location.clone())));
+                continue;
+            }
+            if let Some(group) = group.take() {
+                self.add(&group, false, Some(FoldingRangeKind::Imports));
+            }
+        }
+        if let Some(group) = group {
+            self.add(&group, false, Some(FoldingRangeKind::Imports));
+        }
+    }
+
+    fn add_mxml_content(&mut self, content: &[Rc<MxmlContent>]) {
+        for content in content {
+            match content.as_ref() {
+                MxmlContent::Element(element) => {
+                    self.add(&element.location, true, None);
+                    if let Some(content) = &element.content {
+                        self.add_mxml_content(content);
+                    }
+                },
+                MxmlContent::Comment((_, location)) => self.add(location, false, Some(FoldingRangeKind::Comment)),
+                MxmlContent::CData((_, location)) => self.add(location, false, None),
+                _ => {},
+            }
+        }
+    }
+}
+
+impl Visitor for Folder<'_> {
+    fn visit_block(&mut self, node: &Block) -> VisitorControl {
+        self.add(&node.location, true, None);
+        VisitorControl::Continue
+    }
+
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        if matches!(node.as_ref(), Expression::ObjectInitializer(_) | Expression::ArrayLiteral(_)) {
+            self.add(&node.location(), true, None);
+        }
+        VisitorControl::Continue
+    }
+}
+
+/// Returns the hover of the ActionScript definition declared or referred to
+/// at a position, consisting of a short signature and its ASDoc comment.
+pub fn hover(document: &Document, position: Position) -> Option<Hover> {
+    let (DocumentSyntax::Program(program), Some(resolver)) = (&document.syntax, &document.resolver) else {
+        return None;
+    };
+    let offset = document.offset_at(position);
+    let program_scope = resolver.scopes().get(program)?;
+    let (symbol, location) = declared_symbol_at(&program_scope, &document.compilation_unit, offset).map(|symbol| {
+        let location = symbol.location.clone();
+        (symbol, location)
+    }).or_else(|| {
+        let mut finder = ReferenceFinder {
+            resolutions: resolver.resolutions(),
+            compilation_unit: &document.compilation_unit,
+            offset,
+            found: None,
+        };
+        let _ = walk_program(&mut finder, program);
+        finder.found
+    })?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: hover_markdown(&symbol),
+        }),
+        range: Some(document.range(&location)),
+    })
+}
+
+/// Indicates whether a location contains an offset of a compilation unit.
+/// Locations of included files never contain offsets of the document.
+fn contains_offset(location: &Location, compilation_unit: &Rc<CompilationUnit>, offset: usize) -> bool {
+    Rc::ptr_eq(&location.compilation_unit(), compilation_unit) && location.first_offset() <= offset && offset <= location.last_offset()
+}
+
+fn declared_symbol_at(scope: &Rc<Scope>, compilation_unit: &Rc<CompilationUnit>, offset: usize) -> Option<Rc<Symbol>> {
+    scope.symbols().into_iter().find(|symbol| contains_offset(&symbol.location, compilation_unit, offset))
+        .or_else(|| scope.children().iter().find_map(|child| declared_symbol_at(child, compilation_unit, offset)))
+}
+
+/// Finds the innermost resolved expression at an offset.
+struct ReferenceFinder<'a> {
+    resolutions: &'a TreeSemantics<Rc<Symbol>>,
+    compilation_unit: &'a Rc<CompilationUnit>,
+    offset: usize,
+    found: Option<(Rc<Symbol>, Location)>,
+}
+
+impl Visitor for ReferenceFinder<'_> {
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        let location = node.location();
+        if !contains_offset(&location, self.compilation_unit, self.offset) {
+            return VisitorControl::SkipChildren;
+        }
+        if let Some(symbol) = self.resolutions.get(node) {
+            self.found = Some((symbol, location));
+        }
+        VisitorControl::Continue
+    }
+}
+
+fn hover_markdown(symbol: &Symbol) -> String {
+    let keyword = match symbol.kind {
+        SymbolKind::Class => "class",
+        SymbolKind::Interface => "interface",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Function | SymbolKind::Constructor => "function",
+        SymbolKind::Getter => "function get",
+        SymbolKind::Setter => "function set",
+        SymbolKind::Variable | SymbolKind::Parameter => "var",
+        SymbolKind::Constant => "const",
+        SymbolKind::TypeParameter => "type parameter",
+        SymbolKind::Namespace => "namespace",
+        SymbolKind::Type => "type",
+    };
+    let mut signature = format!("{keyword} {}", symbol.name);
+    if let Some(type_annotation) = &symbol.type_annotation {
+        signature.push_str(&format!(": {}", type_annotation.location().text()));
+    }
+    let mut markdown = format!("```actionscript\n{signature}\n```");
+    if let Some(asdoc) = symbol.asdoc() {
+        markdown.push_str("\n\n");
+        markdown.push_str(&asdoc_markdown(&asdoc));
+    }
+    markdown
+}
+
+fn asdoc_markdown(asdoc: &AsDoc) -> String {
+    let mut sections = vec![];
+    if let Some((body, _)) = &asdoc.main_body {
+        sections.push(body.trim().to_owned());
+    }
+    for (tag, _) in &asdoc.tags {
+        match tag {
+            AsDocTag::Param { name, description } => sections.push(format!("*@param* `{name}` — {}", description.trim())),
+            AsDocTag::Return(description) => sections.push(format!("*@return* — {}", description.trim())),
+            AsDocTag::Throws { class_reference, description } => {
+                let description = description.as_ref().map_or(String::new(), |description| format!(" — {}", description.trim()));
+                sections.push(format!("*@throws* `{}`{description}", class_reference.location().text()));
+            },
+            AsDocTag::Default(value) => sections.push(format!("*@default* `{}`", value.trim())),
+            AsDocTag::Deprecated { message } => {
+                sections.push(message.as_ref().map_or("*@deprecated*".into(), |message| format!("*@deprecated* — {}", message.trim())));
+            },
+            AsDocTag::Example(example) => sections.push(format!("*@example*\n```actionscript\n{}\n```", example.trim())),
+            AsDocTag::See { display_text: Some(display_text), .. } => sections.push(format!("*@see* {}", display_text.trim())),
+            _ => {},
+        }
+    }
+    sections.join("\n\n")
+}
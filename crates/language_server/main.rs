@@ -0,0 +1,27 @@
+//! ActionScript 3 language server.
+//!
+//! Speaks the Language Server Protocol over the standard input and output,
+//! parsing `.as`, `.mxml` and `.css` documents as they are opened and
+//! changed, and offering diagnostics, document symbols, folding ranges
+//! and hover for ASDoc comments.
+
+use std::process::ExitCode;
+use lsp_server::Connection;
+
+mod document;
+mod features;
+mod server;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    if let Err(error) = server::run(&connection) {
+        eprintln!("{error}");
+        return ExitCode::FAILURE;
+    }
+    drop(connection);
+    if let Err(error) = io_threads.join() {
+        eprintln!("{error}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
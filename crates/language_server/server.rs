@@ -0,0 +1,423 @@
+use std::error::Error;
+use as3_parser::ns::{HashMap, default};
+use serde::{de::DeserializeOwned, Serialize};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{self, Notification as _},
+    request::{self, Request as _},
+    DocumentSymbolResponse, FoldingRangeProviderCapability, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, ServerInfo, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url,
+};
+use crate::{document::*, features::*};
+
+pub type ServerResult<T> = Result<T, Box<dyn Error + Sync + Send>>;
+
+pub fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..default()
+    }
+}
+
+/// Initializes a connection and serves it until the client
+/// requests shutdown or closes the connection.
+pub fn run(connection: &Connection) -> ServerResult<()> {
+    let initialize_result = lsp_types::InitializeResult {
+        capabilities: server_capabilities(),
+        server_info: Some(ServerInfo {
+            name: "as3-language-server".into(),
+            version: Some(env!("CARGO_PKG_VERSION").into()),
+        }),
+    };
+    let (id, _) = connection.initialize_start()?;
+    connection.initialize_finish(id, serde_json::to_value(initialize_result)?)?;
+
+    let mut server = Server {
+        connection,
+        documents: HashMap::new(),
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                server.handle_request(request)?;
+            },
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {},
+        }
+    }
+    Ok(())
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server<'_> {
+    fn handle_request(&mut self, request: Request) -> ServerResult<()> {
+        let Request { id, method, params } = request;
+        let response = match method.as_str() {
+            request::DocumentSymbolRequest::METHOD => respond(id, params, |params: lsp_types::DocumentSymbolParams| {
+                let symbols = self.documents.get(&params.text_document.uri).map(document_symbols);
+                symbols.map(DocumentSymbolResponse::Nested)
+            }),
+            request::FoldingRangeRequest::METHOD => respond(id, params, |params: lsp_types::FoldingRangeParams| {
+                self.documents.get(&params.text_document.uri).map(folding_ranges)
+            }),
+            request::HoverRequest::METHOD => respond(id, params, |params: lsp_types::HoverParams| {
+                let position = params.text_document_position_params;
+                self.documents.get(&position.text_document.uri).and_then(|document| hover(document, position.position))
+            }),
+            method => method_not_found(id, method),
+        };
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> ServerResult<()> {
+        let Notification { method, params } = notification;
+        match method.as_str() {
+            notification::DidOpenTextDocument::METHOD => {
+                if let Some(params) = notification_params::<lsp_types::DidOpenTextDocumentParams>(&method, params) {
+                    let document = params.text_document;
+                    self.update_document(document.uri, document.version, document.text)?;
+                }
+            },
+            notification::DidChangeTextDocument::METHOD => {
+                if let Some(params) = notification_params::<lsp_types::DidChangeTextDocumentParams>(&method, params) {
+                    // Changes are always full, as advertised by the server capabilities.
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        self.update_document(params.text_document.uri, params.text_document.version, change.text)?;
+                    }
+                }
+            },
+            notification::DidCloseTextDocument::METHOD => {
+                if let Some(params) = notification_params::<lsp_types::DidCloseTextDocumentParams>(&method, params) {
+                    self.documents.remove(&params.text_document.uri);
+                    self.publish_diagnostics(PublishDiagnosticsParams::new(params.text_document.uri, vec![], None))?;
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn update_document(&mut self, uri: Url, version: i32, text: String) -> ServerResult<()> {
+        let document = Document::parse(uri.clone(), version, text);
+        let diagnostics = PublishDiagnosticsParams::new(uri.clone(), document.lsp_diagnostics(), Some(document.version));
+        self.documents.insert(uri, document);
+        self.publish_diagnostics(diagnostics)
+    }
+
+    fn publish_diagnostics(&self, params: PublishDiagnosticsParams) -> ServerResult<()> {
+        let notification = Notification::new(notification::PublishDiagnostics::METHOD.into(), params);
+        self.connection.sender.send(Message::Notification(notification))?;
+        Ok(())
+    }
+}
+
+fn method_not_found(id: RequestId, method: &str) -> Response {
+    Response::new_err(id, ErrorCode::MethodNotFound as i32, format!("Unsupported method '{method}'."))
+}
+
+/// Responds to a request with the result of `handler`, or with
+/// an error if the parameters of the request are invalid.
+fn respond<P: DeserializeOwned, R: Serialize>(id: RequestId, params: serde_json::Value, handler: impl FnOnce(P) -> R) -> Response {
+    match serde_json::from_value(params) {
+        Ok(params) => Response::new_ok(id, handler(params)),
+        Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, format!("Invalid parameters: {error}.")),
+    }
+}
+
+/// Deserializes the parameters of a notification. Since notifications
+/// have no response, invalid parameters are logged and ignored.
+fn notification_params<P: DeserializeOwned>(method: &str, params: serde_json::Value) -> Option<P> {
+    match serde_json::from_value(params) {
+        Ok(params) => Some(params),
+        Err(error) => {
+            eprintln!("Invalid parameters for '{method}': {error}.");
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::{self, JoinHandle};
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use lsp_types::{notification::{self, Notification as _}, request, *};
+    use serde_json::json;
+
+    /// A scripted client talking to a server running in another thread.
+    struct TestClient {
+        connection: Connection,
+        server: Option<JoinHandle<()>>,
+        next_id: i32,
+    }
+
+    impl TestClient {
+        fn start() -> Self {
+            let (client, server) = Connection::memory();
+            let server = thread::spawn(move || super::run(&server).unwrap());
+            let mut client = Self {
+                connection: client,
+                server: Some(server),
+                next_id: 0,
+            };
+            let result: InitializeResult = client.request::<request::Initialize>(InitializeParams::default());
+            assert_eq!(result.capabilities.hover_provider, Some(HoverProviderCapability::Simple(true)));
+            client.notify::<notification::Initialized>(InitializedParams {});
+            client
+        }
+
+        fn request<R: request::Request>(&mut self, params: R::Params) -> R::Result {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            self.connection.sender.send(Message::Request(Request::new(id.clone(), R::METHOD.into(), params))).unwrap();
+            loop {
+                match self.connection.receiver.recv().unwrap() {
+                    Message::Response(response) if response.id == id => {
+                        assert!(response.error.is_none(), "{:?}", response.error);
+                        return serde_json::from_value(response.result.unwrap_or_default()).unwrap();
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        fn notify<N: notification::Notification>(&self, params: N::Params) {
+            self.connection.sender.send(Message::Notification(Notification::new(N::METHOD.into(), params))).unwrap();
+        }
+
+        fn open(&self, uri: &Url, text: &str) -> PublishDiagnosticsParams {
+            self.notify::<notification::DidOpenTextDocument>(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(uri.clone(), "actionscript".into(), 1, text.into()),
+            });
+            self.receive_diagnostics()
+        }
+
+        fn receive_diagnostics(&self) -> PublishDiagnosticsParams {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification) if notification.method == notification::PublishDiagnostics::METHOD => {
+                    serde_json::from_value(notification.params).unwrap()
+                },
+                message => panic!("Unexpected message: {message:?}"),
+            }
+        }
+
+        fn shutdown(mut self) {
+            self.request::<request::Shutdown>(());
+            self.notify::<notification::Exit>(());
+            self.server.take().unwrap().join().unwrap();
+        }
+    }
+
+    fn document_id(uri: &Url) -> TextDocumentIdentifier {
+        TextDocumentIdentifier::new(uri.clone())
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let mut client = TestClient::start();
+        let uri = Url::parse("file:///project/src/Main.as").unwrap();
+        let diagnostics = client.open(&uri, "var x = ;\nvar y: ë = 0 0;");
+        assert_eq!(diagnostics.version, Some(1));
+        assert_eq!(serde_json::to_value(&diagnostics.diagnostics).unwrap(), json!([
+            {
+                "range": { "start": { "line": 0, "character": 8 }, "end": { "line": 0, "character": 9 } },
+                "severity": 1,
+                "code": 1030,
+                "source": "as3",
+                "message": "Expecting expression before semicolon.",
+            },
+            {
+                "range": { "start": { "line": 1, "character": 13 }, "end": { "line": 1, "character": 14 } },
+                "severity": 1,
+                "code": 1085,
+                "source": "as3",
+                "message": "Expecting either a semicolon or a new line here.",
+            },
+        ]));
+
+        client.notify::<notification::DidChangeTextDocument>(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+            content_changes: vec![TextDocumentContentChangeEvent { range: None, range_length: None, text: "var x = 0;".into() }],
+        });
+        let diagnostics = client.receive_diagnostics();
+        assert_eq!(diagnostics.version, Some(2));
+        assert!(diagnostics.diagnostics.is_empty());
+
+        // Closing clears the diagnostics.
+        client.notify::<notification::DidCloseTextDocument>(DidCloseTextDocumentParams { text_document: document_id(&uri) });
+        assert!(client.receive_diagnostics().diagnostics.is_empty());
+        assert!(client.request::<request::DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        }).is_none());
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let mut client = TestClient::start();
+
+        let uri = Url::parse("file:///project/src/p/C.as").unwrap();
+        client.open(&uri, "package p {\n    public class C {\n        public var x: Number;\n        public function C() {}\n        public function get y(): int { return 0 }\n    }\n}\nfunction f() {}");
+        let symbols = client.request::<request::DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        });
+        let Some(DocumentSymbolResponse::Nested(symbols)) = symbols else { panic!() };
+        assert_eq!(outline(&symbols), ["p (Package)", "  C (Class)", "    x: Number (Field)", "    C (Constructor)", "    y: get (Property)", "f (Function)"]);
+        assert_eq!(symbols[0].children.as_ref().unwrap()[0].selection_range, Range::new(Position::new(1, 17), Position::new(1, 18)));
+
+        let uri = Url::parse("file:///project/src/App.mxml").unwrap();
+        client.open(&uri, "<s:Application xmlns:s=\"library://ns.adobe.com/flex/spark\">\n    <s:Button id=\"button\"/>\n</s:Application>");
+        let Some(DocumentSymbolResponse::Nested(symbols)) = client.request::<request::DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        }) else { panic!() };
+        assert_eq!(outline(&symbols), ["s:Application (Object)", "  s:Button: button (Object)"]);
+
+        let uri = Url::parse("file:///project/src/style.css").unwrap();
+        client.open(&uri, "s|Button, .primary { color: red }\n@media (min-width: 100px) { Label { fontSize: 12 } }");
+        let Some(DocumentSymbolResponse::Nested(symbols)) = client.request::<request::DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        }) else { panic!() };
+        assert_eq!(outline(&symbols), ["s|Button, .primary (Class)", "@media (min-width: 100px) (Module)", "  Label (Class)"]);
+
+        client.shutdown();
+    }
+
+    fn outline(symbols: &[DocumentSymbol]) -> Vec<String> {
+        fn outline_into(symbols: &[DocumentSymbol], indent: usize, lines: &mut Vec<String>) {
+            for symbol in symbols {
+                let detail = symbol.detail.as_ref().map_or(String::new(), |detail| format!(": {detail}"));
+                lines.push(format!("{}{}{detail} ({:?})", "  ".repeat(indent), symbol.name, symbol.kind));
+                outline_into(symbol.children.as_deref().unwrap_or_default(), indent + 1, lines);
+            }
+        }
+        let mut lines = vec![];
+        outline_into(symbols, 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let mut client = TestClient::start();
+        let uri = Url::parse("file:///project/src/Main.as").unwrap();
+        client.open(&uri, "import p.A;\nimport p.B;\n/*\n * Comment.\n */\nfunction f() {\n    return;\n}\n");
+        let ranges = client.request::<request::FoldingRangeRequest>(FoldingRangeParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        }).unwrap();
+        let ranges: Vec<_> = ranges.iter().map(|range| (range.start_line, range.end_line, range.kind.clone())).collect();
+        assert_eq!(ranges, [
+            (0, 1, Some(FoldingRangeKind::Imports)),
+            (2, 4, Some(FoldingRangeKind::Comment)),
+            (5, 6, None),
+        ]);
+        client.shutdown();
+    }
+
+    #[test]
+    fn test_hover() {
+        let mut client = TestClient::start();
+        let uri = Url::parse("file:///project/src/Main.as").unwrap();
+        client.open(&uri, "/**\n * Adds two numbers.\n * @param a First number.\n * @return The sum.\n */\nfunction add(a: Number, b: Number): Number { return a + b }\nadd(1, 2);\nvar x: Number;\n");
+        let hover_at = |client: &mut TestClient, line, character| client.request::<request::HoverRequest>(HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(document_id(&uri), Position::new(line, character)),
+            work_done_progress_params: default(),
+        });
+
+        let markdown = "```actionscript\nfunction add\n```\n\nAdds two numbers.\n\n*@param* `a` — First number.\n\n*@return* — The sum.";
+
+        // The name of the definition.
+        let hover = hover_at(&mut client, 5, 10).unwrap();
+        assert_eq!(hover.contents, HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: markdown.into() }));
+        assert_eq!(hover.range, Some(Range::new(Position::new(5, 9), Position::new(5, 12))));
+
+        // A reference to the definition.
+        let hover = hover_at(&mut client, 6, 1).unwrap();
+        assert_eq!(hover.contents, HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: markdown.into() }));
+        assert_eq!(hover.range, Some(Range::new(Position::new(6, 0), Position::new(6, 3))));
+
+        // A definition without ASDoc.
+        let hover = hover_at(&mut client, 7, 4).unwrap();
+        assert_eq!(hover.contents, HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: "```actionscript\nvar x: Number\n```".into() }));
+
+        // Neither a definition nor a reference.
+        assert!(hover_at(&mut client, 6, 5).is_none());
+
+        client.shutdown();
+    }
+
+    #[test]
+    fn test_hover_with_included_file() {
+        let directory = std::env::temp_dir().join(format!("as3_language_server_hover_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("included.as"), "var y: Number;").unwrap();
+        let mut client = TestClient::start();
+        let uri = Url::from_file_path(directory.join("Main.as")).unwrap();
+        client.open(&uri, "include 'included.as';\nvar x: Number;\ny;");
+        let mut hover_at = |line, character| client.request::<request::HoverRequest>(HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(document_id(&uri), Position::new(line, character)),
+            work_done_progress_params: default(),
+        });
+
+        // The offsets of the definition of `y` lie within the include directive.
+        assert!(hover_at(0, 4).is_none());
+        let hover = hover_at(2, 0).unwrap();
+        assert_eq!(hover.contents, HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: "```actionscript\nvar y: Number\n```".into() }));
+        assert_eq!(hover.range, Some(Range::new(Position::new(2, 0), Position::new(2, 1))));
+
+        client.shutdown();
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    fn default<T: Default>() -> T {
+        T::default()
+    }
+
+    #[test]
+    fn test_unsupported_request() {
+        let client = TestClient::start();
+        client.connection.sender.send(Message::Request(Request::new(RequestId::from(100), "workspace/symbol".into(), json!({ "query": "" })))).unwrap();
+        let Message::Response(response) = client.connection.receiver.recv().unwrap() else { panic!() };
+        assert_eq!(response.error.unwrap().code, lsp_server::ErrorCode::MethodNotFound as i32);
+        client.shutdown();
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut client = TestClient::start();
+        client.connection.sender.send(Message::Request(Request::new(RequestId::from(100), "textDocument/hover".into(), json!({ "position": 1 })))).unwrap();
+        let Message::Response(response) = client.connection.receiver.recv().unwrap() else { panic!() };
+        assert_eq!(response.error.unwrap().code, lsp_server::ErrorCode::InvalidParams as i32);
+
+        // Notifications with invalid parameters are ignored.
+        client.connection.sender.send(Message::Notification(Notification::new(notification::DidOpenTextDocument::METHOD.into(), json!({})))).unwrap();
+        let uri = Url::parse("file:///project/src/Main.as").unwrap();
+        assert!(client.open(&uri, "var x = 0;").diagnostics.is_empty());
+        assert!(client.request::<request::FoldingRangeRequest>(FoldingRangeParams {
+            text_document: document_id(&uri),
+            work_done_progress_params: default(),
+            partial_result_params: default(),
+        }).is_some());
+        client.shutdown();
+    }
+}
@@ -0,0 +1,10 @@
+//! Defines the API model, a machine-readable description of the public API of a program.
+//!
+//! `ApiModel::extract()` collects the public and protected definitions of a `Program`
+//! with their signatures, attributes, metadata and ASDoc comments. Unlike the serialized
+//! syntactic tree, the API model follows a versioned JSON schema that does not depend
+//! on the internals of the tree.
+
+mod api_model;
+pub use api_model::*;
+mod api_extractor;
@@ -0,0 +1,415 @@
+use crate::ns::*;
+
+impl ApiModel {
+    /// Extracts the API of a program.
+    ///
+    /// The API consists of the public definitions of the packages of the program
+    /// and their public and protected members; interface and enum members are
+    /// always public. Definitions outside packages are not part of the API.
+    pub fn extract(program: &Rc<Program>) -> Self {
+        let scopes = TreeSemantics::new();
+        ScopeBuilder::new(&scopes).build_program(program);
+        let extractor = ApiExtractor {
+            scopes: &scopes,
+            printer: PrinterFacade(default()),
+        };
+        let mut definitions = vec![];
+        for package in &program.packages {
+            let name = package.name.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>().join(".");
+            let Some(scope) = scopes.get(package) else {
+                continue;
+            };
+            for symbol in scope.symbols() {
+                if symbol.definition.is_none() || Attribute::find_public(symbol.attributes()).is_none() {
+                    continue;
+                }
+                definitions.push(extractor.definition(&symbol, &name, &name, ApiAccess::Public));
+            }
+        }
+        Self {
+            schema_version: API_MODEL_SCHEMA_VERSION,
+            file: program.location.compilation_unit().file_path(),
+            definitions,
+        }
+    }
+}
+
+struct ApiExtractor<'a> {
+    scopes: &'a TreeSemantics<Rc<Scope>>,
+    printer: PrinterFacade,
+}
+
+impl ApiExtractor<'_> {
+    fn definition(&self, symbol: &Rc<Symbol>, package: &str, qualifier: &str, access: ApiAccess) -> ApiDefinition {
+        let definition = symbol.definition.as_ref().unwrap();
+        let attributes = symbol.attributes();
+        let mut api = ApiDefinition {
+            name: symbol.name.clone(),
+            package: package.to_owned(),
+            qualified_name: if qualifier.is_empty() { symbol.name.clone() } else { format!("{qualifier}.{}", symbol.name) },
+            kind: Self::kind(symbol.kind),
+            access,
+            modifiers: attributes.iter().filter_map(Self::modifier).collect(),
+            signature: String::new(),
+            type_parameters: vec![],
+            extends: vec![],
+            implements: vec![],
+            parameters: vec![],
+            result_type: None,
+            type_annotation: symbol.type_annotation.as_ref().map(|exp| self.print(exp)),
+            value: None,
+            metadata: Attribute::find_metadata(attributes).iter().map(|metadata| Self::metadata(metadata)).collect(),
+            asdoc: symbol.asdoc().map(|asdoc| self.asdoc(&asdoc)),
+            line: symbol.location.first_line_number(),
+            column: symbol.location.first_column() + 1,
+            members: vec![],
+        };
+        let (member_scope, implicitly_public) = match definition.as_ref() {
+            Directive::ClassDefinition(defn) => {
+                api.type_parameters = Self::type_parameters(&defn.type_parameters);
+                api.extends = defn.extends_clause.iter().map(|exp| self.print(exp)).collect();
+                api.implements = defn.implements_clause.iter().flatten().map(|exp| self.print(exp)).collect();
+                (self.scopes.get(definition), false)
+            },
+            Directive::InterfaceDefinition(defn) => {
+                api.type_parameters = Self::type_parameters(&defn.type_parameters);
+                api.extends = defn.extends_clause.iter().flatten().map(|exp| self.print(exp)).collect();
+                (self.scopes.get(definition), true)
+            },
+            Directive::EnumDefinition(defn) => {
+                api.type_annotation = defn.as_clause.as_ref().map(|exp| self.print(exp));
+                (self.scopes.get(&defn.block), true)
+            },
+            Directive::FunctionDefinition(defn) => {
+                api.parameters = defn.common.signature.parameters.iter().map(|parameter| self.parameter(parameter)).collect();
+                api.result_type = defn.common.signature.result_type.as_ref().map(|exp| self.print(exp));
+                (None, false)
+            },
+            Directive::VariableDefinition(defn) => {
+                if symbol.kind == SymbolKind::Constant {
+                    api.value = defn.bindings.iter()
+                        .find(|binding| binding.destructuring.destructuring.location() == symbol.location)
+                        .and_then(|binding| binding.initializer.as_ref())
+                        .map(|exp| self.print(exp));
+                }
+                (None, false)
+            },
+            Directive::NamespaceDefinition(defn) => {
+                api.value = defn.right.as_ref().map(|exp| self.print(exp));
+                (None, false)
+            },
+            Directive::TypeDefinition(defn) => {
+                api.type_annotation = Some(self.print(&defn.right));
+                (None, false)
+            },
+            _ => (None, false),
+        };
+        for member in member_scope.map_or(vec![], |scope| scope.symbols()) {
+            if member.definition.is_none() {
+                continue;
+            }
+            let member_attributes = member.attributes();
+            let access = if implicitly_public || Attribute::find_public(member_attributes).is_some() {
+                ApiAccess::Public
+            } else if Attribute::find_protected(member_attributes).is_some() {
+                ApiAccess::Protected
+            } else {
+                continue;
+            };
+            api.members.push(self.definition(&member, package, &api.qualified_name, access));
+        }
+        api.signature = Self::signature(&api, attributes);
+        api
+    }
+
+    fn kind(kind: SymbolKind) -> ApiDefinitionKind {
+        match kind {
+            SymbolKind::Class => ApiDefinitionKind::Class,
+            SymbolKind::Interface => ApiDefinitionKind::Interface,
+            SymbolKind::Enum => ApiDefinitionKind::Enum,
+            SymbolKind::Function => ApiDefinitionKind::Function,
+            SymbolKind::Getter => ApiDefinitionKind::Getter,
+            SymbolKind::Setter => ApiDefinitionKind::Setter,
+            SymbolKind::Constructor => ApiDefinitionKind::Constructor,
+            SymbolKind::Variable => ApiDefinitionKind::Variable,
+            SymbolKind::Constant => ApiDefinitionKind::Constant,
+            SymbolKind::Namespace => ApiDefinitionKind::Namespace,
+            SymbolKind::Type => ApiDefinitionKind::Type,
+            // Parameters and type parameters are not declared by definitions.
+            SymbolKind::Parameter | SymbolKind::TypeParameter => unreachable!(),
+        }
+    }
+
+    fn modifier(attribute: &Attribute) -> Option<ApiModifier> {
+        match attribute {
+            Attribute::Static(_) => Some(ApiModifier::Static),
+            Attribute::Final(_) => Some(ApiModifier::Final),
+            Attribute::Override(_) => Some(ApiModifier::Override),
+            Attribute::Native(_) => Some(ApiModifier::Native),
+            Attribute::Abstract(_) => Some(ApiModifier::Abstract),
+            Attribute::Dynamic(_) => Some(ApiModifier::Dynamic),
+            _ => None,
+        }
+    }
+
+    fn type_parameters(list: &Option<Vec<Rc<TypeParameter>>>) -> Vec<String> {
+        list.iter().flatten().map(|parameter| parameter.name.0.clone()).collect()
+    }
+
+    fn parameter(&self, parameter: &Parameter) -> ApiParameter {
+        ApiParameter {
+            name: self.print(&parameter.destructuring.destructuring),
+            kind: match parameter.kind {
+                ParameterKind::Required => ApiParameterKind::Required,
+                ParameterKind::Optional => ApiParameterKind::Optional,
+                ParameterKind::Rest => ApiParameterKind::Rest,
+            },
+            type_annotation: parameter.destructuring.type_annotation.as_ref().map(|exp| self.print(exp)),
+            default_value: parameter.default_value.as_ref().map(|exp| self.print(exp)),
+        }
+    }
+
+    fn metadata(metadata: &Metadata) -> ApiMetadata {
+        ApiMetadata {
+            name: metadata.name.0.clone(),
+            entries: metadata.entries.iter().flatten().map(|entry| ApiMetadataEntry {
+                key: entry.key.as_ref().map(|(key, _)| key.clone()),
+                value: match entry.value.as_ref() {
+                    MetadataValue::IdentifierString((value, _)) => value.clone(),
+                    MetadataValue::String((value, _)) => value.clone(),
+                },
+            }).collect(),
+        }
+    }
+
+    fn asdoc(&self, asdoc: &AsDoc) -> ApiAsDoc {
+        ApiAsDoc {
+            main_body: asdoc.main_body.as_ref().map(|(body, _)| body.trim().to_owned()),
+            tags: asdoc.tags.iter().map(|(tag, _)| self.asdoc_tag(tag)).collect(),
+        }
+    }
+
+    fn asdoc_tag(&self, tag: &AsDocTag) -> ApiAsDocTag {
+        let trim = |text: &String| text.trim().to_owned();
+        match tag {
+            AsDocTag::Author(text) => ApiAsDocTag::Author { text: trim(text) },
+            AsDocTag::Copy(reference) => ApiAsDocTag::Copy { reference: self.asdoc_reference(reference) },
+            AsDocTag::Created(text) => ApiAsDocTag::Created { text: trim(text) },
+            AsDocTag::Default(value) => ApiAsDocTag::Default { value: trim(value) },
+            AsDocTag::Deprecated { message } => ApiAsDocTag::Deprecated { message: message.as_ref().map(trim) },
+            AsDocTag::EventType(exp) => ApiAsDocTag::EventType { name: self.print(exp) },
+            AsDocTag::Example(code) => ApiAsDocTag::Example { code: trim(code) },
+            AsDocTag::InheritDoc => ApiAsDocTag::InheritDoc,
+            AsDocTag::Internal(text) => ApiAsDocTag::Internal { text: trim(text) },
+            AsDocTag::Langversion(version) => ApiAsDocTag::Langversion { version: trim(version) },
+            AsDocTag::Param { name, description } => ApiAsDocTag::Param { name: name.clone(), description: trim(description) },
+            AsDocTag::Playerversion(version) => ApiAsDocTag::Playerversion { version: trim(version) },
+            AsDocTag::Private => ApiAsDocTag::Private,
+            AsDocTag::Productversion(version) => ApiAsDocTag::Productversion { version: trim(version) },
+            AsDocTag::Return(description) => ApiAsDocTag::Return { description: trim(description) },
+            AsDocTag::See { reference, display_text } => ApiAsDocTag::See {
+                reference: self.asdoc_reference(reference),
+                display_text: display_text.as_ref().map(trim).filter(|text| !text.is_empty()),
+            },
+            AsDocTag::Throws { class_reference, description } => ApiAsDocTag::Throws {
+                class_reference: self.print(class_reference),
+                description: description.as_ref().map(trim),
+            },
+            AsDocTag::Version(version) => ApiAsDocTag::Version { version: trim(version) },
+        }
+    }
+
+    fn asdoc_reference(&self, reference: &AsDocReference) -> String {
+        let mut text = reference.base.as_ref().map_or(String::new(), |base| self.print(base));
+        if let Some(property) = &reference.instance_property {
+            text.push('#');
+            text.push_str(&property.to_identifier_name().map_or_else(|| property.location.text(), |(name, _)| name));
+        }
+        text
+    }
+
+    /// Builds the signature of a definition from its attributes,
+    /// which are written in the order they appear in the source text.
+    fn signature(api: &ApiDefinition, attributes: &[Attribute]) -> String {
+        let mut signature = String::new();
+        for attribute in attributes {
+            let keyword = match attribute {
+                Attribute::Public(_) => "public",
+                Attribute::Protected(_) => "protected",
+                Attribute::Static(_) => "static",
+                Attribute::Final(_) => "final",
+                Attribute::Override(_) => "override",
+                Attribute::Native(_) => "native",
+                Attribute::Abstract(_) => "abstract",
+                Attribute::Dynamic(_) => "dynamic",
+                _ => continue,
+            };
+            signature.push_str(keyword);
+            signature.push(' ');
+        }
+        signature.push_str(match api.kind {
+            ApiDefinitionKind::Class => "class ",
+            ApiDefinitionKind::Interface => "interface ",
+            ApiDefinitionKind::Enum => "enum ",
+            ApiDefinitionKind::Function | ApiDefinitionKind::Constructor => "function ",
+            ApiDefinitionKind::Getter => "function get ",
+            ApiDefinitionKind::Setter => "function set ",
+            ApiDefinitionKind::Variable => "var ",
+            ApiDefinitionKind::Constant => "const ",
+            ApiDefinitionKind::Namespace => "namespace ",
+            ApiDefinitionKind::Type => "type ",
+        });
+        signature.push_str(&api.name);
+        if !api.type_parameters.is_empty() {
+            signature.push_str(&format!(".<{}>", api.type_parameters.join(", ")));
+        }
+        match api.kind {
+            ApiDefinitionKind::Class => {
+                if !api.extends.is_empty() {
+                    signature.push_str(&format!(" extends {}", api.extends.join(", ")));
+                }
+                if !api.implements.is_empty() {
+                    signature.push_str(&format!(" implements {}", api.implements.join(", ")));
+                }
+            },
+            ApiDefinitionKind::Interface => {
+                if !api.extends.is_empty() {
+                    signature.push_str(&format!(" extends {}", api.extends.join(", ")));
+                }
+            },
+            ApiDefinitionKind::Enum => {
+                if let Some(type_annotation) = &api.type_annotation {
+                    signature.push_str(&format!(" as {type_annotation}"));
+                }
+            },
+            ApiDefinitionKind::Function | ApiDefinitionKind::Getter | ApiDefinitionKind::Setter | ApiDefinitionKind::Constructor => {
+                let parameters: Vec<String> = api.parameters.iter().map(|parameter| {
+                    let mut text = if parameter.kind == ApiParameterKind::Rest { format!("...{}", parameter.name) } else { parameter.name.clone() };
+                    if let Some(type_annotation) = &parameter.type_annotation {
+                        text.push_str(&format!(": {type_annotation}"));
+                    }
+                    if let Some(default_value) = &parameter.default_value {
+                        text.push_str(&format!(" = {default_value}"));
+                    }
+                    text
+                }).collect();
+                signature.push_str(&format!("({})", parameters.join(", ")));
+                if let Some(result_type) = &api.result_type {
+                    signature.push_str(&format!(": {result_type}"));
+                }
+            },
+            ApiDefinitionKind::Variable | ApiDefinitionKind::Constant => {
+                if let Some(type_annotation) = &api.type_annotation {
+                    signature.push_str(&format!(": {type_annotation}"));
+                }
+                if let Some(value) = &api.value {
+                    signature.push_str(&format!(" = {value}"));
+                }
+            },
+            ApiDefinitionKind::Namespace => {
+                if let Some(value) = &api.value {
+                    signature.push_str(&format!(" = {value}"));
+                }
+            },
+            ApiDefinitionKind::Type => {
+                if let Some(type_annotation) = &api.type_annotation {
+                    signature.push_str(&format!(" = {type_annotation}"));
+                }
+            },
+        }
+        signature
+    }
+
+    fn print(&self, exp: &Expression) -> String {
+        self.printer.print_expression(exp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn extract(source: &str) -> ApiModel {
+        let cu = CompilationUnit::new(Some("Shapes.as".into()), source.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(!cu.invalidated(), "{:?}", cu.nested_diagnostics().iter().map(|d| d.format_english()).collect::<Vec<_>>());
+        ApiModel::extract(&program)
+    }
+
+    #[test]
+    fn test_definitions() {
+        let model = extract(r#"
+            package com.example {
+                /**
+                 * A shape. Shapes have an area.
+                 * @see Circle#radius
+                 */
+                [Bindable(event="change")]
+                public class Shape implements IShape {
+                    public static const SIDES: int = 0;
+                    protected var name: String;
+                    private var cache: Number;
+                    public function Shape(name: String = "shape") {}
+                    /** @return The area. */
+                    public function area(): Number { return 0; }
+                    public function get label(): String { return name; }
+                    internal function reset(...rest): void {}
+                }
+                public interface IShape extends Object {
+                    function area(): Number;
+                }
+                internal class Hidden {}
+                public namespace shapes = "http://example.com/shapes";
+            }
+        "#);
+        assert_eq!(model.schema_version, API_MODEL_SCHEMA_VERSION);
+        assert_eq!(model.file.as_deref(), Some("Shapes.as"));
+        let names: Vec<&str> = model.definitions.iter().map(|defn| defn.qualified_name.as_str()).collect();
+        assert_eq!(names, ["com.example.Shape", "com.example.IShape", "com.example.shapes"]);
+
+        let shape = &model.definitions[0];
+        assert_eq!(shape.kind, ApiDefinitionKind::Class);
+        assert_eq!(shape.signature, "public class Shape implements IShape");
+        assert_eq!(shape.metadata, [ApiMetadata {
+            name: "Bindable".into(),
+            entries: vec![ApiMetadataEntry { key: Some("event".into()), value: "change".into() }],
+        }]);
+        let asdoc = shape.asdoc.as_ref().unwrap();
+        assert_eq!(asdoc.main_body.as_deref(), Some("A shape. Shapes have an area."));
+        assert_eq!(asdoc.tags[0], ApiAsDocTag::See { reference: "Circle#radius".into(), display_text: None });
+        assert_eq!((shape.line, shape.column), (8, 30));
+
+        let members: Vec<(&str, ApiAccess, &str)> = shape.members.iter().map(|member| (member.qualified_name.as_str(), member.access, member.signature.as_str())).collect();
+        assert_eq!(members, [
+            ("com.example.Shape.SIDES", ApiAccess::Public, "public static const SIDES: int = 0"),
+            ("com.example.Shape.name", ApiAccess::Protected, "protected var name: String"),
+            ("com.example.Shape.Shape", ApiAccess::Public, "public function Shape(name: String = \"shape\")"),
+            ("com.example.Shape.area", ApiAccess::Public, "public function area(): Number"),
+            ("com.example.Shape.label", ApiAccess::Public, "public function get label(): String"),
+        ]);
+        assert_eq!(shape.members[0].modifiers, [ApiModifier::Static]);
+        assert_eq!(shape.members[3].asdoc.as_ref().unwrap().tags, [ApiAsDocTag::Return { description: "The area.".into() }]);
+
+        let ishape = &model.definitions[1];
+        assert_eq!(ishape.signature, "public interface IShape extends Object");
+        assert_eq!(ishape.members[0].access, ApiAccess::Public);
+        assert_eq!(ishape.members[0].signature, "function area(): Number");
+
+        assert_eq!(model.definitions[2].value.as_deref(), Some("\"http://example.com/shapes\""));
+    }
+
+    #[test]
+    fn test_json() {
+        let model = extract("package { public function f(a: int, ...rest): void {} }");
+        let json = model.to_json();
+        assert!(json.contains("\"schemaVersion\": 1"));
+        assert!(json.contains("\"qualifiedName\": \"f\""));
+        assert!(json.contains("\"kind\": \"rest\""));
+        assert_eq!(ApiModel::from_json(&json).unwrap(), model);
+
+        let model = extract("package { /** @eventType flash.events.Event.CHANGE */ public var x; }");
+        let json = model.to_json();
+        assert!(json.contains("\"tag\": \"eventType\""));
+        assert_eq!(ApiModel::from_json(&json).unwrap(), model);
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Serialize, Deserialize};
+
+/// The version of the JSON schema of the API model.
+///
+/// The version is incremented whenever a field is removed, renamed or changes
+/// meaning; adding a field or an enumeration value does not increment it.
+pub const API_MODEL_SCHEMA_VERSION: u32 = 1;
+
+/// The public API of a program.
+///
+/// The model serializes as JSON with field names in camel case (such as
+/// `schemaVersion`) and enumeration values in lower camel case (such as `getter`).
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, "package q { public function f(a: int): void {} }".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let model = ApiModel::extract(&program);
+/// assert_eq!(model.definitions[0].qualified_name, "q.f");
+/// assert_eq!(model.definitions[0].signature, "public function f(a: int): void");
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiModel {
+    /// The value of `API_MODEL_SCHEMA_VERSION` at the time of extraction.
+    pub schema_version: u32,
+    /// The file path of the compilation unit of the program.
+    pub file: Option<String>,
+    /// The package-level definitions, in declaration order.
+    pub definitions: Vec<ApiDefinition>,
+}
+
+impl ApiModel {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiDefinition {
+    pub name: String,
+    /// The package name, which is empty for the top-level package.
+    pub package: String,
+    /// The fully qualified name, such as `com.example.Shape` or,
+    /// for a member, `com.example.Shape.area`.
+    pub qualified_name: String,
+    pub kind: ApiDefinitionKind,
+    pub access: ApiAccess,
+    pub modifiers: Vec<ApiModifier>,
+    /// The declaration as it would appear in ActionScript 3,
+    /// without metadata nor a body, such as `public function area(): Number`.
+    pub signature: String,
+    pub type_parameters: Vec<String>,
+    /// The base class of a class or the base interfaces of an interface.
+    pub extends: Vec<String>,
+    /// The interfaces implemented by a class.
+    pub implements: Vec<String>,
+    pub parameters: Vec<ApiParameter>,
+    /// The result type of a function.
+    pub result_type: Option<String>,
+    /// The type of a variable or constant, the aliased type of a type definition
+    /// or the representation type of an enum.
+    pub type_annotation: Option<String>,
+    /// The initializer of a constant or the URI of a namespace.
+    pub value: Option<String>,
+    pub metadata: Vec<ApiMetadata>,
+    pub asdoc: Option<ApiAsDoc>,
+    /// One based line of the declared name.
+    pub line: usize,
+    /// One based column of the declared name, in code points.
+    pub column: usize,
+    /// The members of a class, interface or enum, in declaration order.
+    pub members: Vec<ApiDefinition>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiDefinitionKind {
+    Class,
+    Interface,
+    Enum,
+    Function,
+    Getter,
+    Setter,
+    Constructor,
+    Variable,
+    Constant,
+    Namespace,
+    Type,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiAccess {
+    Public,
+    Protected,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiModifier {
+    Static,
+    Final,
+    Override,
+    Native,
+    Abstract,
+    Dynamic,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiParameter {
+    pub name: String,
+    pub kind: ApiParameterKind,
+    pub type_annotation: Option<String>,
+    pub default_value: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiParameterKind {
+    Required,
+    Optional,
+    Rest,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiMetadata {
+    pub name: String,
+    pub entries: Vec<ApiMetadataEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiMetadataEntry {
+    pub key: Option<String>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiAsDoc {
+    /// The main body, which may contain HTML as in ASDoc.
+    pub main_body: Option<String>,
+    pub tags: Vec<ApiAsDocTag>,
+}
+
+/// An ASDoc tag, serialized as an object whose `tag` field
+/// holds the tag name as written after the `@` character.
+///
+/// References are printed as in ASDoc, such as `flash.events.Event#type`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tag", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ApiAsDocTag {
+    Author { text: String },
+    Copy { reference: String },
+    Created { text: String },
+    Default { value: String },
+    Deprecated { message: Option<String> },
+    EventType { name: String },
+    Example { code: String },
+    InheritDoc,
+    Internal { text: String },
+    Langversion { version: String },
+    Param { name: String, description: String },
+    Playerversion { version: String },
+    Private,
+    Productversion { version: String },
+    Return { description: String },
+    See { reference: String, display_text: Option<String> },
+    Throws { class_reference: String, description: Option<String> },
+    Version { version: String },
+}
@@ -0,0 +1,12 @@
+//! Defines a binary encoding of syntactic trees and a cache of parsed trees on disk.
+//!
+//! The `BinaryTreeFacade` structure encodes and decodes a `Program`, `Mxml` or
+//! `CssDocument` in a compact, versioned binary format. The `TreeCache` structure
+//! uses it for skipping the parsing of unchanged files across tool runs.
+
+mod binary_encoding;
+pub use binary_encoding::BinaryTreeError;
+mod binary_tree;
+pub use binary_tree::*;
+mod tree_cache;
+pub use tree_cache::*;
@@ -0,0 +1,627 @@
+use std::fmt::Display;
+use serde::{de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, SeqAccess, VariantAccess, Visitor}, ser, Serialize};
+use crate::ns::*;
+
+/// Error of the binary tree encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryTreeError(pub String);
+
+impl Display for BinaryTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for BinaryTreeError {}
+
+impl ser::Error for BinaryTreeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for BinaryTreeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// A compact binary `serde` format, which is not self-describing.
+///
+/// Integers are variable-length, signed integers being zigzag encoded;
+/// sequences and maps are prefixed by their length; structures and tuples
+/// are written as their fields in order; enumeration variants are prefixed
+/// by their index. Strings are interned: the first occurrence of a string is
+/// written as `0` followed by its length and UTF-8 bytes, and further
+/// occurrences as the one-based index of the first occurrence.
+pub(crate) struct BinaryEncoder {
+    output: Vec<u8>,
+    strings: HashMap<String, usize>,
+}
+
+impl BinaryEncoder {
+    pub fn new() -> Self {
+        Self {
+            output: vec![],
+            strings: HashMap::new(),
+        }
+    }
+
+    pub fn into_output(self) -> Vec<u8> {
+        self.output
+    }
+
+    pub fn write_varint(&mut self, mut value: u128) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.output.push(byte);
+                break;
+            }
+            self.output.push(byte | 0x80);
+        }
+    }
+
+    fn write_signed(&mut self, value: i128) {
+        self.write_varint(((value << 1) ^ (value >> 127)) as u128);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.output.extend_from_slice(bytes);
+    }
+
+    fn write_string(&mut self, value: &str) {
+        if let Some(index) = self.strings.get(value) {
+            self.write_varint(*index as u128 + 1);
+            return;
+        }
+        self.strings.insert(value.to_owned(), self.strings.len());
+        self.write_varint(0);
+        self.write_varint(value.len() as u128);
+        self.write_bytes(value.as_bytes());
+    }
+}
+
+impl ser::Serializer for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), BinaryTreeError> {
+        self.output.push(v as u8);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), BinaryTreeError> {
+        self.write_signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), BinaryTreeError> {
+        self.write_signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), BinaryTreeError> {
+        self.write_signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), BinaryTreeError> {
+        self.write_signed(v.into());
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), BinaryTreeError> {
+        self.write_signed(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), BinaryTreeError> {
+        self.write_varint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), BinaryTreeError> {
+        self.write_varint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), BinaryTreeError> {
+        self.write_varint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), BinaryTreeError> {
+        self.write_varint(v.into());
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), BinaryTreeError> {
+        self.write_varint(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), BinaryTreeError> {
+        self.write_bytes(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), BinaryTreeError> {
+        self.write_bytes(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), BinaryTreeError> {
+        self.write_varint(u32::from(v).into());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), BinaryTreeError> {
+        self.write_string(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), BinaryTreeError> {
+        self.write_varint(v.len() as u128);
+        self.write_bytes(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), BinaryTreeError> {
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), BinaryTreeError> {
+        self.output.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str) -> Result<(), BinaryTreeError> {
+        self.write_varint(variant_index.into());
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, variant_index: u32, _variant: &'static str, value: &T) -> Result<(), BinaryTreeError> {
+        self.write_varint(variant_index.into());
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, BinaryTreeError> {
+        let len = len.ok_or_else(|| BinaryTreeError("sequences of unknown length are not supported".into()))?;
+        self.write_varint(len as u128);
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, BinaryTreeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, BinaryTreeError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, BinaryTreeError> {
+        self.write_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, BinaryTreeError> {
+        let len = len.ok_or_else(|| BinaryTreeError("maps of unknown length are not supported".into()))?;
+        self.write_varint(len as u128);
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, BinaryTreeError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, BinaryTreeError> {
+        self.write_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl ser::SerializeSeq for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), BinaryTreeError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), BinaryTreeError> {
+        Err(BinaryTreeError(format!("field `{key}` cannot be skipped")))
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut BinaryEncoder {
+    type Ok = ();
+    type Error = BinaryTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _key: &'static str, value: &T) -> Result<(), BinaryTreeError> {
+        value.serialize(&mut **self)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), BinaryTreeError> {
+        Err(BinaryTreeError(format!("field `{key}` cannot be skipped")))
+    }
+
+    fn end(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+}
+
+/// Reads the format written by `BinaryEncoder`.
+pub(crate) struct BinaryDecoder<'a> {
+    input: &'a [u8],
+    strings: Vec<String>,
+}
+
+impl<'a> BinaryDecoder<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            strings: vec![],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// The bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], BinaryTreeError> {
+        if self.input.len() < len {
+            return Err(BinaryTreeError("unexpected end of input".into()));
+        }
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, BinaryTreeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_varint(&mut self) -> Result<u128, BinaryTreeError> {
+        let mut value = 0u128;
+        let mut shift = 0u32;
+        loop {
+            let byte = self.read_byte()?;
+            if shift >= 128 {
+                return Err(BinaryTreeError("variable-length integer overflow".into()));
+            }
+            value |= u128::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_signed(&mut self) -> Result<i128, BinaryTreeError> {
+        let value = self.read_varint()?;
+        Ok(((value >> 1) as i128) ^ -((value & 1) as i128))
+    }
+
+    fn read_integer<T: TryFrom<u128>>(&mut self) -> Result<T, BinaryTreeError> {
+        T::try_from(self.read_varint()?).map_err(|_| BinaryTreeError("integer out of range".into()))
+    }
+
+    fn read_signed_integer<T: TryFrom<i128>>(&mut self) -> Result<T, BinaryTreeError> {
+        T::try_from(self.read_signed()?).map_err(|_| BinaryTreeError("integer out of range".into()))
+    }
+
+    pub fn read_len(&mut self) -> Result<usize, BinaryTreeError> {
+        self.read_integer()
+    }
+
+    fn read_string(&mut self) -> Result<String, BinaryTreeError> {
+        let index = self.read_len()?;
+        if index != 0 {
+            return self.strings.get(index - 1).cloned().ok_or_else(|| BinaryTreeError(format!("unknown string {index}")));
+        }
+        let len = self.read_len()?;
+        let string = std::str::from_utf8(self.read_bytes(len)?).map_err(|_| BinaryTreeError("invalid UTF-8 string".into()))?.to_owned();
+        self.strings.push(string.clone());
+        Ok(string)
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($method:ident, $visit:ident, $read:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+            visitor.$visit(self.$read()?)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut BinaryDecoder<'de> {
+    type Error = BinaryTreeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, BinaryTreeError> {
+        Err(BinaryTreeError("the binary tree encoding is not self-describing".into()))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        match self.read_byte()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            _ => Err(BinaryTreeError("invalid boolean".into())),
+        }
+    }
+
+    deserialize_integer!(deserialize_i8, visit_i8, read_signed_integer);
+    deserialize_integer!(deserialize_i16, visit_i16, read_signed_integer);
+    deserialize_integer!(deserialize_i32, visit_i32, read_signed_integer);
+    deserialize_integer!(deserialize_i64, visit_i64, read_signed_integer);
+    deserialize_integer!(deserialize_i128, visit_i128, read_signed);
+    deserialize_integer!(deserialize_u8, visit_u8, read_integer);
+    deserialize_integer!(deserialize_u16, visit_u16, read_integer);
+    deserialize_integer!(deserialize_u32, visit_u32, read_integer);
+    deserialize_integer!(deserialize_u64, visit_u64, read_integer);
+    deserialize_integer!(deserialize_u128, visit_u128, read_varint);
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_f32(f32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_f64(f64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        let code_point: u32 = self.read_integer()?;
+        visitor.visit_char(char::from_u32(code_point).ok_or_else(|| BinaryTreeError("invalid character".into()))?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_string(self.read_string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        let len = self.read_len()?;
+        visitor.visit_borrowed_bytes(self.read_bytes(len)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        match self.read_byte()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            _ => Err(BinaryTreeError("invalid option".into())),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        let len = self.read_len()?;
+        visitor.visit_map(Elements { decoder: self, remaining: len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_seq(Elements { decoder: self, remaining: fields.len() })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, BinaryTreeError> {
+        Err(BinaryTreeError("identifiers are not encoded".into()))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, BinaryTreeError> {
+        Err(BinaryTreeError("the binary tree encoding is not self-describing".into()))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Elements of a sequence, tuple, structure or map.
+struct Elements<'a, 'de> {
+    decoder: &'a mut BinaryDecoder<'de>,
+    remaining: usize,
+}
+
+impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = BinaryTreeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, BinaryTreeError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, 'de> {
+    type Error = BinaryTreeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, BinaryTreeError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, BinaryTreeError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut BinaryDecoder<'de> {
+    type Error = BinaryTreeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), BinaryTreeError> {
+        let variant_index: u32 = self.read_integer()?;
+        let value = seed.deserialize(IntoDeserializer::<'de, BinaryTreeError>::into_deserializer(variant_index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut BinaryDecoder<'de> {
+    type Error = BinaryTreeError;
+
+    fn unit_variant(self) -> Result<(), BinaryTreeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, BinaryTreeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_seq(Elements { decoder: self, remaining: len })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, BinaryTreeError> {
+        visitor.visit_seq(Elements { decoder: self, remaining: fields.len() })
+    }
+}
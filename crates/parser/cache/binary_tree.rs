@@ -0,0 +1,158 @@
+use serde::{de::{DeserializeOwned, DeserializeSeed}, Serialize};
+use crate::ns::*;
+use super::binary_encoding::*;
+
+/// The version of the binary tree format.
+///
+/// The version is incremented whenever the encoding or the structure of
+/// a serialized node changes, invalidating previously encoded trees.
+pub const BINARY_TREE_FORMAT_VERSION: u32 = 4;
+
+const BINARY_TREE_MAGIC: &[u8; 4] = b"AS3T";
+
+/// Encodes and decodes syntactic trees in a compact binary format.
+///
+/// An encoded tree holds the format version, the file path and a hash of
+/// the source text of the compilation unit of this facade, the compilation units nested
+/// in the compilation unit of this facade (such as included files), the comments
+/// and diagnostics of these compilation units, and the tree itself, whose
+/// locations are encoded as byte offsets and whose strings are interned.
+/// The source text of the compilation unit of this facade is not encoded.
+///
+/// Decoding a tree binds its locations to the compilation unit of this facade,
+/// which must have the same file path and source text and is expected not to have
+/// been parsed, and restores its nested compilation units, comments and diagnostics,
+/// such that it is equivalent to a fresh parse. Diagnostic arguments
+/// are restored as strings.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, "x = 10; y =".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let bytes = BinaryTreeFacade(&cu).encode_program(&program).unwrap();
+///
+/// let new_cu = CompilationUnit::new(None, "x = 10; y =".into());
+/// let decoded = BinaryTreeFacade(&new_cu).decode_program(&bytes).unwrap();
+/// assert_eq!(decoded.directives[0].location().text(), "x = 10;");
+/// assert!(new_cu.invalidated());
+/// assert_eq!(new_cu.diagnostics()[0].format_english(), cu.diagnostics()[0].format_english());
+/// ```
+pub struct BinaryTreeFacade<'a>(pub &'a Rc<CompilationUnit>);
+
+impl BinaryTreeFacade<'_> {
+    pub fn encode_program(&self, program: &Rc<Program>) -> Result<Vec<u8>, BinaryTreeError> {
+        self.encode(program)
+    }
+
+    pub fn encode_mxml(&self, document: &Rc<Mxml>) -> Result<Vec<u8>, BinaryTreeError> {
+        self.encode(document)
+    }
+
+    pub fn encode_css_document(&self, document: &Rc<CssDocument>) -> Result<Vec<u8>, BinaryTreeError> {
+        self.encode(document)
+    }
+
+    pub fn decode_program(&self, bytes: &[u8]) -> Result<Rc<Program>, BinaryTreeError> {
+        self.decode(bytes)
+    }
+
+    pub fn decode_mxml(&self, bytes: &[u8]) -> Result<Rc<Mxml>, BinaryTreeError> {
+        self.decode(bytes)
+    }
+
+    pub fn decode_css_document(&self, bytes: &[u8]) -> Result<Rc<CssDocument>, BinaryTreeError> {
+        self.decode(bytes)
+    }
+
+    fn encode<T: Serialize>(&self, tree: &T) -> Result<Vec<u8>, BinaryTreeError> {
+        let mut encoder = BinaryEncoder::new();
+        encoder.write_bytes(BINARY_TREE_MAGIC);
+        encoder.write_varint(BINARY_TREE_FORMAT_VERSION.into());
+        TreeSerializationFacade(self.0).serializable(tree).serialize(&mut encoder)?;
+        Ok(encoder.into_output())
+    }
+
+    /// Decodes a tree. The compilation unit of this facade is only
+    /// modified once decoding succeeds.
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, BinaryTreeError> {
+        let mut decoder = BinaryDecoder::new(bytes);
+        if decoder.read_bytes(BINARY_TREE_MAGIC.len()).ok() != Some(BINARY_TREE_MAGIC.as_slice()) {
+            return Err(BinaryTreeError("not an encoded tree".into()));
+        }
+        let version = decoder.read_varint()?;
+        if version != BINARY_TREE_FORMAT_VERSION.into() {
+            return Err(BinaryTreeError(format!("unsupported format version {version}")));
+        }
+        let detached: DetachedTree<T> = TreeSerializationFacade(self.0).detached_seed().deserialize(&mut decoder)?;
+        if !decoder.is_empty() {
+            return Err(BinaryTreeError("unexpected bytes after the encoded tree".into()));
+        }
+        Ok(detached.attach())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_program() {
+        let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/parser/ASDoc.as")).unwrap();
+        let cu = CompilationUnit::new(None, text.clone());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let bytes = BinaryTreeFacade(&cu).encode_program(&program).unwrap();
+        assert!(bytes.len() < serde_json::to_string(&program).unwrap().len());
+
+        let new_cu = CompilationUnit::new(None, text);
+        let decoded = BinaryTreeFacade(&new_cu).decode_program(&bytes).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), serde_json::to_string(&program).unwrap());
+        assert_eq!(TreeSerializationFacade(&new_cu).to_json(&decoded).unwrap(), TreeSerializationFacade(&cu).to_json(&program).unwrap());
+        assert_eq!(new_cu.comments().len(), cu.comments().len());
+    }
+
+    #[test]
+    fn test_mxml() {
+        let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/parser/MXML5.mxml")).unwrap();
+        let cu = CompilationUnit::new(None, text.clone());
+        let document = ParserFacade(&cu, default()).parse_mxml();
+        let bytes = BinaryTreeFacade(&cu).encode_mxml(&document).unwrap();
+
+        let new_cu = CompilationUnit::new(None, text);
+        let decoded = BinaryTreeFacade(&new_cu).decode_mxml(&bytes).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), serde_json::to_string(&document).unwrap());
+        assert_eq!(new_cu.nested_compilation_units().len(), cu.nested_compilation_units().len());
+        let diagnostics = |cu: &Rc<CompilationUnit>| cu.nested_diagnostics().iter().map(|d| d.format_english()).collect::<Vec<_>>();
+        assert_eq!(diagnostics(&new_cu), diagnostics(&cu));
+        let (root, decoded_root) = (document.root().unwrap(), decoded.root().unwrap());
+        assert_eq!(decoded_root.namespace.full_listing(), root.namespace.full_listing());
+        assert_eq!(decoded_root.child_elements()[0].namespace.full_listing(), root.child_elements()[0].namespace.full_listing());
+    }
+
+    #[test]
+    fn test_css_document() {
+        let text = "@namespace s \"library://ns.adobe.com/flex/spark\";\ns|Button { color: #ff0000; font-size: 12 }".to_owned();
+        let cu = CompilationUnit::new(None, text.clone());
+        let document = CssParserFacade(&cu, default()).parse_document();
+        let bytes = BinaryTreeFacade(&cu).encode_css_document(&document).unwrap();
+
+        let new_cu = CompilationUnit::new(None, text);
+        let decoded = BinaryTreeFacade(&new_cu).decode_css_document(&bytes).unwrap();
+        assert_eq!(CssPrinterFacade(default()).print_document(&decoded), CssPrinterFacade(default()).print_document(&document));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let cu = CompilationUnit::new(None, "x".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let bytes = BinaryTreeFacade(&cu).encode_program(&program).unwrap();
+
+        let new_cu = CompilationUnit::new(None, "x".into());
+        assert!(BinaryTreeFacade(&new_cu).decode_program(&bytes[..bytes.len() - 1]).is_err());
+        assert!(BinaryTreeFacade(&new_cu).decode_program(b"AS3T\x7F").is_err());
+        assert!(BinaryTreeFacade(&new_cu).decode_program(b"").is_err());
+        assert!(new_cu.comments().is_empty() && new_cu.diagnostics().is_empty());
+    }
+}
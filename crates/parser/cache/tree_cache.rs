@@ -0,0 +1,238 @@
+use std::{fs, path::{Path, PathBuf}};
+use serde::{Deserialize, Serialize};
+use crate::ns::*;
+use crate::util::fnv1a_hash;
+use super::binary_encoding::*;
+
+const TREE_CACHE_MAGIC: &[u8; 4] = b"AS3C";
+
+/// The kind of tree held by a cache entry.
+#[derive(Clone, Copy)]
+enum CachedTreeKind {
+    Program,
+    Mxml,
+    CssDocument,
+}
+
+impl CachedTreeKind {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Program => "program",
+            Self::Mxml => "mxml",
+            Self::CssDocument => "css",
+        }
+    }
+}
+
+/// The header of a cache entry, followed by the tree as encoded by `BinaryTreeFacade`.
+#[derive(Serialize, Deserialize)]
+struct CacheEntryHeader {
+    /// The files the tree depends on, such as included files,
+    /// with the hashes of their contents at the time of parsing.
+    dependencies: Vec<(String, u64)>,
+}
+
+/// A cache of parsed trees on disk, keyed by the hash of the path and content
+/// of a compilation unit and of the parser options.
+///
+/// A cache entry also records the content hashes of the files the tree depends on,
+/// such as included files and external `fx:Script` sources, and is ignored
+/// once any of them changes. Entries are encoded by `BinaryTreeFacade`,
+/// so that a cached tree is equivalent to a fresh parse, including
+/// nested compilation units, comments and diagnostics. Failures to read
+/// or write cache entries are ignored, falling back to parsing.
+///
+/// # Example
+///
+/// ```no_run
+/// use as3_parser::ns::*;
+///
+/// let cache = TreeCache::new("target/as3_tree_cache");
+/// let cu = CompilationUnit::new(Some("src/Main.as".into()), std::fs::read_to_string("src/Main.as").unwrap());
+/// let program = cache.parse_program(&cu, default());
+/// ```
+pub struct TreeCache {
+    directory: PathBuf,
+}
+
+impl TreeCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the cached program of a compilation unit or parses it,
+    /// caching the result.
+    pub fn parse_program(&self, compilation_unit: &Rc<CompilationUnit>, options: ParserOptions) -> Rc<Program> {
+        if let Some(program) = self.load_program(compilation_unit, &options) {
+            return program;
+        }
+        let key = Self::key(compilation_unit, &options, CachedTreeKind::Program);
+        let program = ParserFacade(compilation_unit, options).parse_program();
+        if let Ok(bytes) = BinaryTreeFacade(compilation_unit).encode_program(&program) {
+            self.store(compilation_unit, key, CachedTreeKind::Program, &bytes);
+        }
+        program
+    }
+
+    /// Returns the cached MXML document of a compilation unit or parses it,
+    /// caching the result.
+    pub fn parse_mxml(&self, compilation_unit: &Rc<CompilationUnit>, options: ParserOptions) -> Rc<Mxml> {
+        if let Some(document) = self.load_mxml(compilation_unit, &options) {
+            return document;
+        }
+        let key = Self::key(compilation_unit, &options, CachedTreeKind::Mxml);
+        let document = ParserFacade(compilation_unit, options).parse_mxml();
+        if let Ok(bytes) = BinaryTreeFacade(compilation_unit).encode_mxml(&document) {
+            self.store(compilation_unit, key, CachedTreeKind::Mxml, &bytes);
+        }
+        document
+    }
+
+    /// Returns the cached style sheet of a compilation unit or parses it,
+    /// caching the result.
+    pub fn parse_css_document(&self, compilation_unit: &Rc<CompilationUnit>, options: ParserOptions) -> Rc<CssDocument> {
+        if let Some(document) = self.load_css_document(compilation_unit, &options) {
+            return document;
+        }
+        let key = Self::key(compilation_unit, &options, CachedTreeKind::CssDocument);
+        let document = CssParserFacade(compilation_unit, options).parse_document();
+        if let Ok(bytes) = BinaryTreeFacade(compilation_unit).encode_css_document(&document) {
+            self.store(compilation_unit, key, CachedTreeKind::CssDocument, &bytes);
+        }
+        document
+    }
+
+    /// Returns the cached program of a compilation unit, if any is up to date.
+    pub fn load_program(&self, compilation_unit: &Rc<CompilationUnit>, options: &ParserOptions) -> Option<Rc<Program>> {
+        let bytes = self.load(compilation_unit, options, CachedTreeKind::Program)?;
+        BinaryTreeFacade(compilation_unit).decode_program(&bytes).ok()
+    }
+
+    /// Returns the cached MXML document of a compilation unit, if any is up to date.
+    pub fn load_mxml(&self, compilation_unit: &Rc<CompilationUnit>, options: &ParserOptions) -> Option<Rc<Mxml>> {
+        let bytes = self.load(compilation_unit, options, CachedTreeKind::Mxml)?;
+        BinaryTreeFacade(compilation_unit).decode_mxml(&bytes).ok()
+    }
+
+    /// Returns the cached style sheet of a compilation unit, if any is up to date.
+    pub fn load_css_document(&self, compilation_unit: &Rc<CompilationUnit>, options: &ParserOptions) -> Option<Rc<CssDocument>> {
+        let bytes = self.load(compilation_unit, options, CachedTreeKind::CssDocument)?;
+        BinaryTreeFacade(compilation_unit).decode_css_document(&bytes).ok()
+    }
+
+    /// Returns the encoded tree of an up-to-date cache entry.
+    fn load(&self, compilation_unit: &Rc<CompilationUnit>, options: &ParserOptions, kind: CachedTreeKind) -> Option<Vec<u8>> {
+        let bytes = fs::read(self.entry_path(Self::key(compilation_unit, options, kind), kind)).ok()?;
+        let mut decoder = BinaryDecoder::new(&bytes);
+        if decoder.read_bytes(TREE_CACHE_MAGIC.len()).ok()? != TREE_CACHE_MAGIC.as_slice() {
+            return None;
+        }
+        let header = CacheEntryHeader::deserialize(&mut decoder).ok()?;
+        for (path, hash) in header.dependencies {
+            let content = fs::read(&path).ok()?;
+            if fnv1a_hash(&[&content]) != hash {
+                return None;
+            }
+        }
+        Some(decoder.remaining().to_vec())
+    }
+
+    fn store(&self, compilation_unit: &Rc<CompilationUnit>, key: u64, kind: CachedTreeKind, tree_bytes: &[u8]) {
+        let mut dependencies = vec![];
+        collect_dependencies(compilation_unit, &mut dependencies);
+        let mut encoder = BinaryEncoder::new();
+        encoder.write_bytes(TREE_CACHE_MAGIC);
+        if (CacheEntryHeader { dependencies }).serialize(&mut encoder).is_err() {
+            return;
+        }
+        encoder.write_bytes(tree_bytes);
+
+        // Write to a temporary file first, so that readers never see a partial entry.
+        let path = self.entry_path(key, kind);
+        let temporary_path = path.with_extension(format!("{}.{}.tmp", kind.extension(), std::process::id()));
+        let _ = fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(&temporary_path, encoder.into_output()))
+            .and_then(|_| fs::rename(&temporary_path, &path));
+    }
+
+    fn entry_path(&self, key: u64, kind: CachedTreeKind) -> PathBuf {
+        self.directory.join(format!("{key:016x}.{}", kind.extension()))
+    }
+
+    fn key(compilation_unit: &Rc<CompilationUnit>, options: &ParserOptions, kind: CachedTreeKind) -> u64 {
+        let mut event_attributes: Vec<&String> = options.mxml_event_attributes.iter().flat_map(|attributes| attributes.iter()).collect();
+        event_attributes.sort();
+        let options_key = format!("{}:{:?}:{}:{:?}", kind.extension(), options.byte_range, options.ignore_xml_whitespace, options.mxml_event_attributes.as_ref().map(|_| event_attributes));
+        fnv1a_hash(&[
+            &BINARY_TREE_FORMAT_VERSION.to_le_bytes(),
+            options_key.as_bytes(),
+            compilation_unit.file_path().unwrap_or_default().as_bytes(),
+            compilation_unit.text().as_bytes(),
+        ])
+    }
+}
+
+/// Collects the files of the compilation units nested in a compilation unit,
+/// except those sharing the file of the compilation unit they are nested in,
+/// such as MXML code with character references.
+fn collect_dependencies(compilation_unit: &Rc<CompilationUnit>, output: &mut Vec<(String, u64)>) {
+    for nested in compilation_unit.nested_compilation_units() {
+        if let Some(path) = nested.file_path() {
+            if Some(&path) != compilation_unit.file_path().as_ref() {
+                output.push((path, fnv1a_hash(&[nested.text().as_bytes()])));
+            }
+        }
+        collect_dependencies(&nested, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_tree_cache() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_tree_cache_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let included_path = directory.join("included.as");
+        std::fs::write(&included_path, "y = 1").unwrap();
+        let path = directory.join("Main.as").to_string_lossy().into_owned();
+        let text = "include 'included.as'; /** Comment */ x = 10".to_owned();
+        let cache = TreeCache::new(directory.join("cache"));
+
+        let cu = CompilationUnit::new(Some(path.clone()), text.clone());
+        assert!(cache.load_program(&cu, &default()).is_none());
+        let program = cache.parse_program(&cu, default());
+        let json = serde_json::to_string(&program).unwrap();
+
+        let new_cu = CompilationUnit::new(Some(path.clone()), text.clone());
+        let cached = cache.load_program(&new_cu, &default()).unwrap();
+        assert_eq!(serde_json::to_string(&cached).unwrap(), json);
+        assert_eq!(new_cu.nested_compilation_units()[0].text(), "y = 1");
+
+        // Other parser options and other contents do not share entries
+        let new_cu = CompilationUnit::new(Some(path.clone()), text.clone());
+        assert!(cache.load_program(&new_cu, &ParserOptions { byte_range: Some((0, 4)), ..default() }).is_none());
+        let new_cu = CompilationUnit::new(Some(path.clone()), text.clone() + ";");
+        assert!(cache.load_program(&new_cu, &default()).is_none());
+
+        // Changing an included file invalidates the entry
+        std::fs::write(&included_path, "y = 2").unwrap();
+        let new_cu = CompilationUnit::new(Some(path.clone()), text.clone());
+        assert!(cache.load_program(&new_cu, &default()).is_none());
+        let program = cache.parse_program(&new_cu, default());
+        let Directive::IncludeDirective(include) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        assert_eq!(include.nested_compilation_unit.text(), "y = 2");
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,7 @@
+//! Defines code generators that turn syntactic trees into ActionScript 3 code.
+//!
+//! The `MxmlClassGenerator` structure converts an MXML document into the
+//! ActionScript 3 class it stands for, similarly to the Flex compiler.
+
+mod mxml_class_generator;
+pub use mxml_class_generator::*;
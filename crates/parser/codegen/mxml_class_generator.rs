@@ -0,0 +1,716 @@
+use std::collections::BTreeSet;
+use crate::ns::*;
+
+/// Options for the MXML class generator.
+#[derive(Clone)]
+pub struct MxmlClassGeneratorOptions {
+    /// The package of the generated class. Default is the top-level package.
+    pub package: String,
+    /// The name of the generated class. Default is `None`, which names
+    /// the class after the file stem of the MXML compilation unit.
+    pub class_name: Option<String>,
+    /// Resolves the classes of components, through the manifests of their
+    /// namespaces or package-style namespaces, such as `views.*`.
+    pub components: MxmlComponentResolver,
+    /// The property to which the child components of a component are assigned
+    /// as an array. Default is `mxmlContent`.
+    pub default_property: String,
+    /// The property to which the text content of a component is assigned.
+    /// Default is `text`.
+    pub text_property: String,
+    /// Options for printing the generated class.
+    pub printer_options: PrinterOptions,
+}
+
+impl Default for MxmlClassGeneratorOptions {
+    fn default() -> Self {
+        Self {
+            package: String::new(),
+            class_name: None,
+            components: MxmlComponentResolver::new(),
+            default_property: "mxmlContent".into(),
+            text_property: "text".into(),
+            printer_options: default(),
+        }
+    }
+}
+
+/// Generates the ActionScript 3 class equivalent to an MXML document,
+/// similarly to the Flex compiler:
+///
+/// - The class of the root element becomes the base class.
+/// - Attributes become property assignments, and event handler attributes
+///   become methods registered as event listeners.
+/// - Child components are instantiated by factory methods and assigned
+///   as an array to the default property of their parent. Child elements
+///   named after a property of their parent, such as `<s:layout>`,
+///   are assigned to that property.
+/// - Components with an `id` attribute become public bindable variables.
+/// - The directives of `fx:Script` elements are merged into the class body,
+///   and the content of `fx:Metadata` elements precedes the class.
+/// - Data bindings and `fx:Binding` elements become methods that are invoked
+///   through `mx.binding.utils.BindingUtils.bindSetter()` whenever the
+///   property chains of the bound expression change.
+///
+/// Without type information, literal attribute values that look like numbers,
+/// booleans or colors (such as `#FF0000`) are assigned as such, and every
+/// identifier of a binding expression is assumed to be a property of the class.
+/// State-specific attributes and elements, `includeIn` and `excludeFrom` attributes,
+/// style sheets and other language elements, such as `fx:Component`,
+/// are not part of the generated class.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, r#"<s:Group xmlns:s="library://ns.adobe.com/flex/spark" width="100"/>"#.into());
+/// let mxml = ParserFacade(&cu, default()).parse_mxml();
+/// let mut manifest = MxmlManifest::new();
+/// manifest.insert("Group", "spark.components.Group");
+/// let mut components = MxmlComponentResolver::new();
+/// components.add_manifest("library://ns.adobe.com/flex/spark", manifest);
+///
+/// let generator = MxmlClassGenerator(MxmlClassGeneratorOptions {
+///     class_name: Some("Main".into()),
+///     components,
+///     ..default()
+/// });
+/// let source = generator.generate_source(&mxml);
+/// assert!(source.contains("public class Main extends spark.components.Group {"));
+/// assert!(source.contains("this.width = 100;"));
+/// ```
+pub struct MxmlClassGenerator(pub MxmlClassGeneratorOptions);
+
+impl MxmlClassGenerator {
+    /// Generates the class as ActionScript 3 source text.
+    pub fn generate_source(&self, mxml: &Rc<Mxml>) -> String {
+        PrinterFacade(self.0.printer_options.clone()).print_program(&self.generate_program(mxml))
+    }
+
+    /// Generates the class as a program consisting of a package definition,
+    /// parsed from a compilation unit of its own, which holds the diagnostics
+    /// of the generated source. The generated source only has errors if
+    /// the MXML document has errors.
+    pub fn generate_program(&self, mxml: &Rc<Mxml>) -> Rc<Program> {
+        let class_name = self.0.class_name.clone().unwrap_or_else(|| {
+            mxml.location.compilation_unit().file_path()
+                .and_then(|file_path| std::path::Path::new(&file_path).file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+                .unwrap_or("Component".into())
+        });
+        let builder = MxmlClassBuilder {
+            options: &self.0,
+            class_name,
+            printer: PrinterFacade(default()),
+            imports: BTreeSet::new(),
+            metadata: vec![],
+            fields: vec![],
+            bindings: vec![],
+            methods: vec![],
+            script: vec![],
+            counters: HashMap::new(),
+            binding_count: 0,
+        };
+        let source = builder.build(mxml);
+        let compilation_unit = CompilationUnit::new(None, source);
+        let program = ParserFacade(&compilation_unit, default()).parse_program();
+        let mxml_compilation_unit = mxml.location.compilation_unit();
+        debug_assert!(
+            !compilation_unit.invalidated()
+                || mxml_compilation_unit.invalidated()
+                || mxml_compilation_unit.nested_diagnostics().iter().any(|diagnostic| diagnostic.is_error()),
+            "generated source has errors:\n{}", compilation_unit.text()
+        );
+        program
+    }
+}
+
+/// The language elements whose instances are values of a top-level class.
+const MXML_PRIMITIVES: [&str; 5] = ["Boolean", "int", "Number", "String", "uint"];
+
+struct MxmlClassBuilder<'a> {
+    options: &'a MxmlClassGeneratorOptions,
+    class_name: String,
+    printer: PrinterFacade,
+    imports: BTreeSet<String>,
+    metadata: Vec<String>,
+    fields: Vec<String>,
+    /// Statements that set up data bindings at the end of the constructor.
+    bindings: Vec<String>,
+    methods: Vec<String>,
+    script: Vec<String>,
+    /// Number of instances generated per component name.
+    counters: HashMap<String, usize>,
+    binding_count: usize,
+}
+
+impl MxmlClassBuilder<'_> {
+    fn build(mut self, mxml: &Rc<Mxml>) -> String {
+        let root = mxml.root();
+        let mut constructor = vec!["super();".to_owned()];
+        let base_class = root.as_ref().map(|root| {
+            self.initialize(root, "this", "this", &mut constructor);
+            self.class_reference(root)
+        });
+        constructor.append(&mut self.bindings);
+
+        let mut source = format!("package {} {{\n", self.options.package);
+        for import in &self.imports {
+            source.push_str(&format!("import {import};\n"));
+        }
+        for metadata in &self.metadata {
+            source.push_str(&format!("{metadata}\n"));
+        }
+        source.push_str(&format!("public class {}", self.class_name));
+        if let Some(base_class) = base_class {
+            source.push_str(&format!(" extends {base_class}"));
+        }
+        source.push_str(" {\n");
+        for field in &self.fields {
+            source.push_str(&format!("{field}\n"));
+        }
+        source.push_str(&format!("public function {}() {{\n{}\n}}\n", self.class_name, constructor.join("\n")));
+        for method in &self.methods {
+            source.push_str(&format!("{method}\n"));
+        }
+        for directive in &self.script {
+            source.push_str(&format!("{directive}\n"));
+        }
+        source.push_str("}\n}\n");
+        source
+    }
+
+    /// Generates the statements that initialize a component from its attributes
+    /// and content. `target` refers to the component within the statements and
+    /// `binding_target` refers to it from the methods of the class.
+    fn initialize(&mut self, element: &Rc<MxmlElement>, target: &str, binding_target: &str, statements: &mut Vec<String>) {
+        for attribute in &element.attributes {
+            if attribute.xmlns || attribute.name.prefix.is_some() || attribute.name.state.is_some() || Self::is_special_attribute(&attribute.name.name) {
+                continue;
+            }
+            let name = &attribute.name.name;
+            match &attribute.typed_value {
+                MxmlAttributeValue::EventHandler(directives) => {
+                    let handler = self.event_handler(element, target, name, directives);
+                    statements.push(format!("{target}.addEventListener({}, this.{handler});", escape_string_literal(name)));
+                },
+                MxmlAttributeValue::Text(segments) => {
+                    self.assign(target, binding_target, name, segments, true, statements);
+                },
+            }
+        }
+
+        let uri = element.name.resolve_prefix(&element.namespace).ok();
+        let mut children = vec![];
+        for child in element.child_elements() {
+            if child.name.state.is_some() {
+                continue;
+            }
+            if Self::is_language_element(&child) && !Self::is_value_element(&child) {
+                self.language_element(&child, statements);
+            } else if Self::is_property_element(&child, uri.as_deref()) {
+                let value = self.property_value(&child, target, binding_target, statements);
+                if let Some(value) = value {
+                    statements.push(format!("{target}.{} = {value};", child.name.name));
+                }
+            } else {
+                children.push(self.instance(&child));
+            }
+        }
+        if !children.is_empty() {
+            statements.push(format!("{target}.{} = [{}];", self.options.default_property, children.join(", ")));
+        }
+        if let Some(segments) = &element.text_value {
+            if !Self::is_blank(segments) {
+                let text_property = self.options.text_property.clone();
+                self.assign(target, binding_target, &text_property, segments, false, statements);
+            }
+        }
+    }
+
+    /// Attributes handled by the MXML language rather than assigned as properties.
+    fn is_special_attribute(name: &str) -> bool {
+        name == "id" || name == "includeIn" || name == "excludeFrom"
+    }
+
+    fn is_language_element(element: &MxmlElement) -> bool {
+        element.name.resolve_prefix(&element.namespace).is_ok_and(|uri| {
+            uri == MxmlNamespace::MXML_2009 || uri == MxmlNamespace::MXML_2006
+        })
+    }
+
+    /// Indicates whether a language element stands for a value, such as `fx:String`.
+    fn is_value_element(element: &MxmlElement) -> bool {
+        let name = element.name.name.as_str();
+        MXML_PRIMITIVES.contains(&name) || name == "Array" || name == "Object"
+    }
+
+    /// Indicates whether an element is named after a property of its parent,
+    /// such as `<s:layout>` within `<s:Group>`.
+    fn is_property_element(element: &MxmlElement, parent_uri: Option<&str>) -> bool {
+        element.name.name.starts_with(|ch: char| ch.is_lowercase())
+            && element.name.resolve_prefix(&element.namespace).ok().as_deref() == parent_uri
+            && !Self::is_value_element(element)
+    }
+
+    fn language_element(&mut self, element: &Rc<MxmlElement>, statements: &mut Vec<String>) {
+        match element.name.name.as_str() {
+            "Script" => {
+                for directive in element.script.iter().flat_map(|script| &script.directives) {
+                    self.script.push(self.printer.print_directive(directive));
+                }
+            },
+            "Metadata" => {
+                let metadata = element.inner_text();
+                if !metadata.trim().is_empty() {
+                    self.metadata.push(metadata.trim().to_owned());
+                }
+            },
+            "Declarations" => {
+                for content in element.content.iter().flatten() {
+                    if let MxmlContent::Element(child) = content.as_ref() {
+                        let instance = self.instance(child);
+                        statements.push(format!("{instance};"));
+                    }
+                }
+            },
+            "Binding" => self.binding_element(element),
+            _ => {},
+        }
+    }
+
+    /// The value of a property element: its child component, an array of
+    /// its child components or its text content.
+    fn property_value(&mut self, element: &Rc<MxmlElement>, target: &str, binding_target: &str, statements: &mut Vec<String>) -> Option<String> {
+        let children = element.child_elements();
+        if children.len() == 1 {
+            return Some(self.instance(&children[0]));
+        }
+        if !children.is_empty() {
+            let instances: Vec<String> = children.iter().map(|child| self.instance(child)).collect();
+            return Some(format!("[{}]", instances.join(", ")));
+        }
+        let segments = element.text_value.as_ref()?;
+        self.assign(target, binding_target, &element.name.name, segments, true, statements);
+        None
+    }
+
+    /// Generates a factory method that creates and initializes a component,
+    /// returning an expression that invokes it.
+    fn instance(&mut self, element: &Rc<MxmlElement>) -> String {
+        let class = self.class_reference(element);
+        let counter = self.counters.entry(element.name.name.clone()).or_insert(0);
+        *counter += 1;
+        let name = format!("_{}_{}{}", self.class_name, element.name.name, counter);
+        let id = element.attribute("id").map(|attribute| attribute.value.0.clone());
+
+        let field = if let Some(id) = id {
+            self.fields.push(format!("[Bindable]\npublic var {id}: {class};"));
+            Some(id)
+        } else if Self::has_bindings(element) {
+            self.fields.push(format!("private var {name}: {class};"));
+            Some(name.clone())
+        } else {
+            None
+        };
+        let binding_target = field.as_ref().map_or("temp".to_owned(), |field| format!("this.{field}"));
+
+        // Reserve the position of the factory method before those it depends on.
+        let method_index = self.methods.len();
+        self.methods.push(String::new());
+
+        let mut statements = vec![];
+        let is_language_element = Self::is_language_element(element);
+        if is_language_element && MXML_PRIMITIVES.contains(&element.name.name.as_str()) {
+            let text = element.text_value.as_ref().map_or(String::new(), |segments| Self::literal_text(segments));
+            let value = if class == "String" {
+                escape_string_literal(&text)
+            } else {
+                Self::literal_value("", &text).1
+            };
+            statements.push(format!("var temp: {class} = {value};"));
+            statements.extend(field.iter().map(|field| format!("this.{field} = temp;")));
+        } else if is_language_element && element.name.name == "Array" {
+            let items: Vec<String> = element.child_elements().iter().map(|child| self.instance(child)).collect();
+            statements.push(format!("var temp: Array = [{}];", items.join(", ")));
+            statements.extend(field.iter().map(|field| format!("this.{field} = temp;")));
+        } else {
+            statements.push(format!("var temp: {class} = new {class}();"));
+            statements.extend(field.iter().map(|field| format!("this.{field} = temp;")));
+            self.initialize(element, "temp", &binding_target, &mut statements);
+        }
+        statements.push("return temp;".into());
+
+        self.methods[method_index] = format!("private function {name}_i(): {class} {{\n{}\n}}", statements.join("\n"));
+        format!("this.{name}_i()")
+    }
+
+    /// Indicates whether the attributes or text content of a component
+    /// or of its property elements contain data bindings.
+    fn has_bindings(element: &MxmlElement) -> bool {
+        let has_binding = |segments: &Vec<MxmlValueSegment>| segments.iter().any(|segment| matches!(segment, MxmlValueSegment::Binding(_)));
+        element.attributes.iter().any(|attribute| matches!(&attribute.typed_value, MxmlAttributeValue::Text(segments) if has_binding(segments)))
+            || element.text_value.as_ref().is_some_and(has_binding)
+            || element.content.iter().flatten().any(|content| match content.as_ref() {
+                MxmlContent::Element(child) => child.text_value.as_ref().is_some_and(has_binding),
+                _ => false,
+            })
+    }
+
+    /// Resolves the class of a component, importing it, and returns its
+    /// fully qualified name. A component that resolves to no class
+    /// is referred to by its local name.
+    fn class_reference(&mut self, element: &MxmlElement) -> String {
+        let name = &element.name.name;
+        let Some(class) = element.name.resolve_prefix(&element.namespace).ok()
+            .and_then(|uri| self.options.components.resolve_name(&uri, name)) else {
+            return name.clone();
+        };
+        if class.contains('.') {
+            self.imports.insert(class.clone());
+        }
+        class
+    }
+
+    /// Generates an event handler method and returns its name.
+    fn event_handler(&mut self, element: &MxmlElement, target: &str, event: &str, directives: &[Rc<Directive>]) -> String {
+        self.imports.insert("flash.events.Event".into());
+        let name = if target == "this" {
+            format!("___{}_{event}", self.class_name)
+        } else {
+            let counter = self.counters.get(&element.name.name).copied().unwrap_or_default();
+            format!("___{}_{}{counter}_{event}", self.class_name, element.name.name)
+        };
+        let body: Vec<String> = directives.iter().map(|directive| self.printer.print_directive(directive)).collect();
+        self.methods.push(format!("public function {name}(event: flash.events.Event): void {{\n{}\n}}", body.join("\n")));
+        name
+    }
+
+    /// Assigns literal text and binding expressions to a property,
+    /// either immediately or through a data binding.
+    fn assign(&mut self, target: &str, binding_target: &str, name: &str, segments: &[MxmlValueSegment], infer_type: bool, statements: &mut Vec<String>) {
+        let bindings: Vec<&Rc<MxmlBinding>> = segments.iter().filter_map(|segment| match segment {
+            MxmlValueSegment::Binding(binding) => Some(binding),
+            _ => None,
+        }).collect();
+        if bindings.is_empty() {
+            let text = Self::literal_text(segments);
+            let (name, value) = if infer_type { Self::literal_value(name, &text) } else { (name.to_owned(), escape_string_literal(&text)) };
+            statements.push(format!("{target}.{name} = {value};"));
+            return;
+        }
+        let expression = self.binding_expression(segments);
+        let chains: Vec<Vec<String>> = bindings.iter().flat_map(|binding| binding_chains(&binding.expression)).collect();
+        self.data_binding(&format!("{binding_target}.{name}"), &expression, chains);
+
+        // Two-way binding, watching the property through its chain from the class
+        if let [MxmlValueSegment::Binding(binding)] = segments {
+            if binding.two_way && is_assignable(&binding.expression) {
+                let mut chain: Vec<String> = binding_target.split('.').skip(1).map(|name| name.to_owned()).collect();
+                chain.push(name.to_owned());
+                self.data_binding(&expression, &format!("{binding_target}.{name}"), vec![chain]);
+            }
+        }
+    }
+
+    /// Generates a method that assigns an expression to a destination and
+    /// the statements that invoke it whenever a property chain changes,
+    /// or once if there are no property chains.
+    fn data_binding(&mut self, destination: &str, source: &str, chains: Vec<Vec<String>>) {
+        self.binding_count += 1;
+        let name = format!("_{}_binding{}", self.class_name, self.binding_count);
+        self.methods.push(format!("private function {name}(value: *): void {{\n{destination} = {source};\n}}"));
+        if chains.is_empty() {
+            self.bindings.push(format!("this.{name}(null);"));
+            return;
+        }
+        self.imports.insert("mx.binding.utils.BindingUtils".into());
+        let mut unique_chains: Vec<Vec<String>> = vec![];
+        for chain in chains {
+            if !unique_chains.contains(&chain) {
+                unique_chains.push(chain);
+            }
+        }
+        for chain in unique_chains {
+            let chain: Vec<String> = chain.iter().map(|name| escape_string_literal(name)).collect();
+            self.bindings.push(format!("mx.binding.utils.BindingUtils.bindSetter(this.{name}, this, [{}]);", chain.join(", ")));
+        }
+    }
+
+    /// Generates the data bindings of an `fx:Binding` element.
+    fn binding_element(&mut self, element: &MxmlElement) {
+        let attribute = |name: &str| element.attribute(name).map(|attribute| attribute.value.0.trim().to_owned());
+        let (Some(source), Some(destination)) = (attribute("source"), attribute("destination")) else {
+            return;
+        };
+        let source = ParserFacade(&CompilationUnit::new(None, source), default()).parse_expression();
+        let destination = ParserFacade(&CompilationUnit::new(None, destination), default()).parse_expression();
+        self.data_binding(&self.printer.print_expression(&destination), &self.printer.print_expression(&source), binding_chains(&source));
+        if attribute("twoWay").as_deref() == Some("true") && is_assignable(&source) {
+            self.data_binding(&self.printer.print_expression(&source), &self.printer.print_expression(&destination), binding_chains(&destination));
+        }
+    }
+
+    /// The expression of a value consisting of literal text and bindings.
+    /// A single binding keeps the type of its expression; otherwise
+    /// the segments are concatenated as a string.
+    fn binding_expression(&self, segments: &[MxmlValueSegment]) -> String {
+        if let [MxmlValueSegment::Binding(binding)] = segments {
+            return self.printer.print_expression(&binding.expression);
+        }
+        let mut parts = vec![];
+        for segment in segments {
+            match segment {
+                MxmlValueSegment::Literal((text, _)) => parts.push(escape_string_literal(text)),
+                MxmlValueSegment::Binding(binding) => parts.push(format!("({})", self.printer.print_expression(&binding.expression))),
+            }
+        }
+        if matches!(segments.first(), Some(MxmlValueSegment::Binding(_))) {
+            parts.insert(0, "\"\"".into());
+        }
+        parts.join(" + ")
+    }
+
+    fn literal_text(segments: &[MxmlValueSegment]) -> String {
+        segments.iter().filter_map(|segment| match segment {
+            MxmlValueSegment::Literal((text, _)) => Some(text.as_str()),
+            _ => None,
+        }).collect()
+    }
+
+    fn is_blank(segments: &[MxmlValueSegment]) -> bool {
+        segments.iter().all(|segment| matches!(segment, MxmlValueSegment::Literal((text, _)) if text.trim().is_empty()))
+    }
+
+    /// Converts a literal attribute value into an ActionScript expression,
+    /// returning the property to assign along with it. Percentages assigned to
+    /// `width` or `height` are assigned to `percentWidth` or `percentHeight`.
+    fn literal_value(name: &str, text: &str) -> (String, String) {
+        let trimmed = text.trim();
+        if trimmed == "true" || trimmed == "false" || is_numeric_literal(trimmed) {
+            return (name.to_owned(), trimmed.to_owned());
+        }
+        if let Some(color) = trimmed.strip_prefix('#') {
+            if !color.is_empty() && color.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                return (name.to_owned(), format!("0x{color}"));
+            }
+        }
+        if let Some(percent) = trimmed.strip_suffix('%') {
+            if (name == "width" || name == "height") && is_numeric_literal(percent) {
+                let name = if name == "width" { "percentWidth" } else { "percentHeight" };
+                return (name.to_owned(), percent.to_owned());
+            }
+        }
+        (name.to_owned(), escape_string_literal(text))
+    }
+}
+
+fn is_numeric_literal(text: &str) -> bool {
+    let text = text.strip_prefix('-').unwrap_or(text);
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return !hex.is_empty() && hex.chars().all(|ch| ch.is_ascii_hexdigit());
+    }
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.chars().all(|ch| ch.is_ascii_digit());
+    let valid_exponent = exponent.is_none_or(|exponent| {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        !exponent.is_empty() && digits(exponent)
+    });
+    !(integer.is_empty() && fraction.is_empty()) && digits(integer) && digits(fraction) && valid_exponent
+}
+
+fn is_assignable(expression: &Expression) -> bool {
+    match expression {
+        Expression::QualifiedIdentifier(id) => id.is_identifier_token(),
+        Expression::Member(_) | Expression::ComputedMember(_) => true,
+        _ => false,
+    }
+}
+
+/// The property chains of a binding expression, such as `["model", "name"]`
+/// for `model.name`, relative to the class.
+fn binding_chains(expression: &Rc<Expression>) -> Vec<Vec<String>> {
+    let mut collector = BindingChainCollector { chains: vec![] };
+    let _ = walk_expression(&mut collector, expression);
+    collector.chains
+}
+
+/// The property chain of an expression consisting of property accesses
+/// from the class, such as `this.model.name` or `model.name`.
+fn property_chain(expression: &Expression) -> Option<Vec<String>> {
+    match expression {
+        Expression::ThisLiteral(_) => Some(vec![]),
+        Expression::QualifiedIdentifier(id) => id.to_identifier_name().map(|(name, _)| vec![name]),
+        Expression::Member(member) => {
+            let mut chain = property_chain(&member.base)?;
+            chain.push(member.identifier.to_identifier_name()?.0);
+            Some(chain)
+        },
+        _ => None,
+    }
+}
+
+struct BindingChainCollector {
+    chains: Vec<Vec<String>>,
+}
+
+impl Visitor for BindingChainCollector {
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        if let Some(chain) = property_chain(node) {
+            if !chain.is_empty() && !self.chains.contains(&chain) {
+                self.chains.push(chain);
+            }
+            return VisitorControl::SkipChildren;
+        }
+        // Watch the object of a method call, but not the method itself.
+        if let Expression::Call(call) = node.as_ref() {
+            match call.base.as_ref() {
+                Expression::Member(member) => {
+                    let _ = walk_expression(self, &member.base);
+                },
+                Expression::QualifiedIdentifier(_) => {},
+                _ => {
+                    let _ = walk_expression(self, &call.base);
+                },
+            }
+            let _ = walk_expressions(self, &call.arguments);
+            return VisitorControl::SkipChildren;
+        }
+        VisitorControl::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn generate(source: &str) -> String {
+        let cu = CompilationUnit::new(None, source.into());
+        let mxml = ParserFacade(&cu, default()).parse_mxml();
+        assert!(!cu.invalidated());
+        let mut manifest = MxmlManifest::new();
+        for name in ["Button", "Group", "Label", "TextInput"] {
+            manifest.insert(name, &format!("spark.components.{name}"));
+        }
+        let mut components = MxmlComponentResolver::new();
+        components.add_manifest("library://ns.adobe.com/flex/spark", manifest);
+        let generator = MxmlClassGenerator(MxmlClassGeneratorOptions {
+            package: "views".into(),
+            class_name: Some("Main".into()),
+            components,
+            ..default()
+        });
+        let program = generator.generate_program(&mxml);
+        let generated_cu = program.location.compilation_unit();
+        assert!(generated_cu.diagnostics().is_empty(), "{}", generated_cu.text());
+        PrinterFacade(generator.0.printer_options.clone()).print_program(&program)
+    }
+
+    #[test]
+    fn test_components() {
+        let source = generate(r#"<s:Group xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.adobe.com/flex/spark" width="100%" click="trace('click');">
+    <fx:Script><![CDATA[
+        private var count: int = 0;
+    ]]></fx:Script>
+    <s:Button id="button" label="Reset" enabled="false"/>
+    <s:Label>Count</s:Label>
+</s:Group>"#);
+        assert_eq!(source, r#"package views {
+    import flash.events.Event;
+    import spark.components.Button;
+    import spark.components.Group;
+    import spark.components.Label;
+
+    public class Main extends spark.components.Group {
+        [Bindable]
+        public var button:spark.components.Button;
+
+        public function Main() {
+            super();
+            this.percentWidth = 100;
+            this.addEventListener("click", this.___Main_click);
+            this.mxmlContent = [this._Main_Button1_i(), this._Main_Label1_i()];
+        }
+
+        public function ___Main_click(event:flash.events.Event):void {
+            trace("click");
+        }
+
+        private function _Main_Button1_i():spark.components.Button {
+            var temp:spark.components.Button = new spark.components.Button();
+            this.button = temp;
+            temp.label = "Reset";
+            temp.enabled = false;
+            return temp;
+        }
+
+        private function _Main_Label1_i():spark.components.Label {
+            var temp:spark.components.Label = new spark.components.Label();
+            temp.text = "Count";
+            return temp;
+        }
+
+        private var count:int = 0;
+    }
+}
+"#);
+    }
+
+    #[test]
+    fn test_bindings() {
+        let source = generate(r#"<s:Group xmlns:s="library://ns.adobe.com/flex/spark">
+    <s:TextInput id="input" text="@{model.name}"/>
+    <s:Label text="Hello, {model.name}! {format(count)}"/>
+</s:Group>"#);
+        assert_eq!(source, r#"package views {
+    import mx.binding.utils.BindingUtils;
+    import spark.components.Group;
+    import spark.components.Label;
+    import spark.components.TextInput;
+
+    public class Main extends spark.components.Group {
+        [Bindable]
+        public var input:spark.components.TextInput;
+        private var _Main_Label1:spark.components.Label;
+
+        public function Main() {
+            super();
+            this.mxmlContent = [this._Main_TextInput1_i(), this._Main_Label1_i()];
+            mx.binding.utils.BindingUtils.bindSetter(this._Main_binding1, this, ["model", "name"]);
+            mx.binding.utils.BindingUtils.bindSetter(this._Main_binding2, this, ["input", "text"]);
+            mx.binding.utils.BindingUtils.bindSetter(this._Main_binding3, this, ["model", "name"]);
+            mx.binding.utils.BindingUtils.bindSetter(this._Main_binding3, this, ["count"]);
+        }
+
+        private function _Main_TextInput1_i():spark.components.TextInput {
+            var temp:spark.components.TextInput = new spark.components.TextInput();
+            this.input = temp;
+            return temp;
+        }
+
+        private function _Main_binding1(value:*):void {
+            this.input.text = model.name;
+        }
+
+        private function _Main_binding2(value:*):void {
+            model.name = this.input.text;
+        }
+
+        private function _Main_Label1_i():spark.components.Label {
+            var temp:spark.components.Label = new spark.components.Label();
+            this._Main_Label1 = temp;
+            return temp;
+        }
+
+        private function _Main_binding3(value:*):void {
+            this._Main_Label1.text = "Hello, " + (model.name) + "! " + (format(count));
+        }
+    }
+}
+"#);
+    }
+}
@@ -1,8 +1,13 @@
 //! Defines the compilation unit, comments, and source locations.
+//!
+//! The `TreeSerializationFacade` structure serializes syntactic trees
+//! preserving the byte offsets of their locations.
 
 mod compilation_unit;
 pub use compilation_unit::*;
 mod comment;
 pub use comment::*;
 mod location;
-pub use location::*;
\ No newline at end of file
+pub use location::*;
+mod location_serialization;
+pub use location_serialization::*;
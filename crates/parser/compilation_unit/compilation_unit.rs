@@ -1,13 +1,41 @@
-use std::{any::Any, cell::RefMut};
 use crate::ns::*;
 use hydroper_source_text::SourceText;
 
+/// The source text of a compilation unit. `SourceText` caches line offsets
+/// in unsynchronized cells, so with the `sync` feature line lookups hold a lock.
+pub(crate) struct SharedSourceText {
+    source_text: SourceText,
+    #[cfg(feature = "sync")]
+    lock: std::sync::Mutex<()>,
+}
+
+// SAFETY: the line offset caches of `SourceText` are only accessed through
+// `SharedSourceText::lines()`, which holds the lock, and the contents are never mutated.
+#[cfg(feature = "sync")]
+unsafe impl Sync for SharedSourceText {}
+
+impl SharedSourceText {
+    fn new(contents: String) -> Self {
+        Self {
+            source_text: SourceText::new(contents),
+            #[cfg(feature = "sync")]
+            lock: std::sync::Mutex::new(()),
+        }
+    }
+
+    fn lines<T>(&self, lookup: impl FnOnce(&SourceText) -> T) -> T {
+        #[cfg(feature = "sync")]
+        let _guard = self.lock.lock().unwrap_or_else(|error| error.into_inner());
+        lookup(&self.source_text)
+    }
+}
+
 /// `CompilationUnit` identifies an AS3 compilation unit and contains
 /// a source text.
 pub struct CompilationUnit {
     pub(crate) file_path: Option<String>,
-    pub(crate) source_text: SourceText,
-    pub(crate) compiler_options: RefCell<Option<Rc<dyn Any>>>,
+    pub(crate) source_text: SharedSourceText,
+    pub(crate) compiler_options: RefCell<Option<Rc<CompilerOptions>>>,
     pub(crate) diagnostics: RefCell<Vec<Diagnostic>>,
     pub(crate) error_count: Cell<u32>,
     pub(crate) warning_count: Cell<u32>,
@@ -15,13 +43,17 @@ pub struct CompilationUnit {
     pub(crate) comments: RefCell<Vec<Rc<Comment>>>,
     pub(crate) included_from: RefCell<Option<Rc<CompilationUnit>>>,
     pub(crate) nested_compilation_units: RefCell<Vec<Rc<CompilationUnit>>>,
+    /// Ascending pairs of byte offsets into this compilation unit and into the
+    /// compilation unit it is nested in, from which line numbers and columns are taken.
+    /// Empty unless the source is derived from a part of the other compilation unit.
+    pub(crate) offset_table: Vec<(usize, usize)>,
 }
 
 impl Default for CompilationUnit {
     fn default() -> Self {
         Self {
             file_path: None,
-            source_text: SourceText::new("".into()),
+            source_text: SharedSourceText::new("".into()),
             compiler_options: RefCell::new(None),
             diagnostics: RefCell::new(vec![]),
             invalidated: Cell::new(false),
@@ -30,6 +62,7 @@ impl Default for CompilationUnit {
             comments: RefCell::new(vec![]),
             nested_compilation_units: RefCell::new(vec![]),
             included_from: RefCell::new(None),
+            offset_table: vec![],
         }
     }
 }
@@ -37,9 +70,17 @@ impl Default for CompilationUnit {
 impl CompilationUnit {
     /// Constructs a source file in unparsed and non verified state.
     pub fn new(file_path: Option<String>, text: String) -> Rc<Self> {
+        Self::with_offset_table(file_path, text, vec![])
+    }
+
+    /// Constructs a source derived from a part of the compilation unit it is
+    /// nested in, such as unescaped MXML text, whose line numbers and columns
+    /// are mapped back through ascending pairs of byte offsets into both
+    /// sources, starting at zero.
+    pub(crate) fn with_offset_table(file_path: Option<String>, text: String, offset_table: Vec<(usize, usize)>) -> Rc<Self> {
         Rc::new(Self {
             file_path,
-            source_text: SourceText::new(text),
+            source_text: SharedSourceText::new(text),
             compiler_options: RefCell::new(None),
             diagnostics: RefCell::new(vec![]),
             invalidated: Cell::new(false),
@@ -48,6 +89,7 @@ impl CompilationUnit {
             comments: RefCell::new(vec![]),
             nested_compilation_units: RefCell::new(vec![]),
             included_from: RefCell::new(None),
+            offset_table,
         })
     }
 
@@ -58,16 +100,17 @@ impl CompilationUnit {
 
     /// Source text.
     pub fn text(&self) -> &String {
-        &self.source_text.contents
+        &self.source_text.source_text.contents
     }
 
-    /// Compiler options.
-    pub fn compiler_options(&self) -> Option<Rc<dyn Any>> {
+    /// Compiler options, as read from project configuration files.
+    pub fn compiler_options(&self) -> Option<Rc<CompilerOptions>> {
         self.compiler_options.borrow().clone()
     }
 
-    /// Set compiler options.
-    pub fn set_compiler_options(&self, options: Option<Rc<dyn Any>>) {
+    /// Set compiler options. Compilation units nested in this one
+    /// during parsing inherit these options.
+    pub fn set_compiler_options(&self, options: Option<Rc<CompilerOptions>>) {
         self.compiler_options.replace(options);
     }
 
@@ -217,27 +260,44 @@ impl CompilationUnit {
     /// Retrieves line number from an offset. The resulting line number
     /// is counted from one.
     pub fn get_line_number(&self, offset: usize) -> usize {
-        self.source_text.get_line_number(offset)
+        if let Some((compilation_unit, offset)) = self.original_offset(offset) {
+            return compilation_unit.get_line_number(offset);
+        }
+        self.source_text.lines(|lines| lines.get_line_number(offset))
     }
 
     /// Returns the zero based column of an offset.
     pub fn get_column(&self, offset: usize) -> usize {
-        self.source_text.get_column(offset)
+        if let Some((compilation_unit, offset)) = self.original_offset(offset) {
+            return compilation_unit.get_column(offset);
+        }
+        self.source_text.lines(|lines| lines.get_column(offset))
     }
 
     /// Retrieves offset from line number (counted from one).
     pub fn get_line_offset(&self, line: usize) -> Option<usize> {
-        self.source_text.get_line_offset(line)
+        self.source_text.lines(|lines| lines.get_line_offset(line))
     }
 
     /// Retrieves the offset from the corresponding line of an offset.
     pub fn get_line_offset_from_offset(&self, offset: usize) -> usize {
-        self.source_text.get_line_offset_from_offset(offset)
+        self.source_text.lines(|lines| lines.get_line_offset_from_offset(offset))
+    }
+
+    /// Maps an offset back to the compilation unit this one is derived from,
+    /// as given by the offset table.
+    fn original_offset(&self, offset: usize) -> Option<(Rc<CompilationUnit>, usize)> {
+        if self.offset_table.is_empty() {
+            return None;
+        }
+        let compilation_unit = self.included_from()?;
+        let (derived_offset, original_offset) = self.offset_table[self.offset_table.partition_point(|(derived_offset, _)| *derived_offset <= offset) - 1];
+        Some((compilation_unit, original_offset + offset - derived_offset))
     }
 
     pub fn get_line_indent(&self, line: usize) -> usize {
         let line_offset = self.get_line_offset(line).unwrap();
-        CharacterValidator::indent_count(&self.source_text.contents[line_offset..])
+        CharacterValidator::indent_count(&self.text()[line_offset..])
     }
 }
 
@@ -1,24 +1,24 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
-use serde::{Serialize, Deserialize, Serializer};
-use std::rc::Rc;
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
 use crate::compilation_unit::*;
-use crate::util::{CharacterReader, count_first_whitespace_characters};
+use crate::util::{CharacterReader, Rc, count_first_whitespace_characters};
 
 /// Represents a source location. This location includes
 /// spanning lines and columns and the reference compilation unit.
-#[derive(Clone, Deserialize)]
+///
+/// A location serializes as line and column text, such as `"1:1-1:7"`,
+/// unless serialized through `TreeSerializationFacade`, which preserves
+/// its offsets and compilation unit.
+#[derive(Clone)]
 pub struct Location {
     /// The compilation unit that this location belongs to.
-    #[serde(skip)]
     pub(crate) compilation_unit: Rc<CompilationUnit>,
 
     /// First UTF-8 offset.
-    #[serde(skip)]
     pub(crate) first_offset: usize,
 
     /// Last UTF-8 offset.
-    #[serde(skip)]
     pub(crate) last_offset: usize,
 }
 
@@ -27,7 +27,16 @@ impl Serialize for Location {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&format!("{}:{}-{}:{}", self.first_line_number(), self.first_column() + 1, self.last_line_number(), self.last_column() + 1))
+        serialize_location(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Location {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_location(deserializer)
     }
 }
 
@@ -0,0 +1,500 @@
+use std::marker::PhantomData;
+use serde::{de::{DeserializeOwned, DeserializeSeed, Error as _, IgnoredAny, SeqAccess, Visitor}, ser::{Error as _, SerializeTuple}, Deserialize, Deserializer, Serialize, Serializer};
+use crate::ns::*;
+use crate::util::fnv1a_hash;
+
+/// How locations are serialized and deserialized on the current thread.
+enum LocationSerializationContext {
+    /// Locations serialize as a compilation unit index and byte offsets.
+    /// Compilation units are keyed by address.
+    Serializing(HashMap<*const CompilationUnit, usize>),
+    /// Locations deserialize from a compilation unit index and byte offsets,
+    /// bound to the compilation units at those indices.
+    Deserializing(Vec<Rc<CompilationUnit>>),
+}
+
+thread_local! {
+    static CONTEXT: std::cell::RefCell<Option<LocationSerializationContext>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Restores the previous location serialization context when dropped.
+struct ContextGuard(Option<LocationSerializationContext>);
+
+impl ContextGuard {
+    fn enter(context: LocationSerializationContext) -> Self {
+        Self(CONTEXT.with(|c| c.replace(Some(context))))
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        CONTEXT.with(|c| c.replace(self.0.take()));
+    }
+}
+
+pub(crate) fn serialize_location<S: Serializer>(location: &Location, serializer: S) -> Result<S::Ok, S::Error> {
+    let index = CONTEXT.with(|c| match c.borrow().as_ref() {
+        Some(LocationSerializationContext::Serializing(indices)) => Some(indices.get(&Rc::as_ptr(&location.compilation_unit)).copied()),
+        _ => None,
+    });
+    match index {
+        Some(Some(index)) => (index, location.first_offset, location.last_offset).serialize(serializer),
+        Some(None) => Err(S::Error::custom("location belongs to a compilation unit outside of the serialized tree")),
+        None => serializer.serialize_str(&format!("{}:{}-{}:{}", location.first_line_number(), location.first_column() + 1, location.last_line_number(), location.last_column() + 1)),
+    }
+}
+
+pub(crate) fn deserialize_location<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Location, D::Error> {
+    let Some(units) = deserializing_compilation_units() else {
+        // Without a context, locations deserialize to an empty compilation unit.
+        IgnoredAny::deserialize(deserializer)?;
+        return Ok(Location::with_offset(&Rc::new(CompilationUnit::default()), 0));
+    };
+    let (index, first_offset, last_offset) = <(usize, usize, usize)>::deserialize(deserializer)?;
+    let compilation_unit = units.get(index).ok_or_else(|| D::Error::custom(format!("unknown compilation unit {index}")))?;
+    let text = compilation_unit.text();
+    if first_offset > last_offset || !text.is_char_boundary(first_offset) || !text.is_char_boundary(last_offset) {
+        return Err(D::Error::custom(format!("location {first_offset}-{last_offset} is out of the source text of compilation unit {index}")));
+    }
+    Ok(Location::with_offsets(compilation_unit, first_offset, last_offset))
+}
+
+fn deserializing_compilation_units() -> Option<Vec<Rc<CompilationUnit>>> {
+    CONTEXT.with(|c| match c.borrow().as_ref() {
+        Some(LocationSerializationContext::Deserializing(units)) => Some(units.clone()),
+        _ => None,
+    })
+}
+
+/// Indicates whether locations are serialized as line and column text,
+/// in which case references to compilation units are omitted.
+pub(crate) fn serializes_locations_as_text<T>(_: &T) -> bool {
+    CONTEXT.with(|c| !matches!(c.borrow().as_ref(), Some(LocationSerializationContext::Serializing(_))))
+}
+
+/// Serializes a reference to a nested compilation unit, such as the one
+/// of an include directive, as its index within the serialized tree.
+pub(crate) mod compilation_unit_reference {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(compilation_unit: &Rc<CompilationUnit>, serializer: S) -> Result<S::Ok, S::Error> {
+        super::optional_compilation_unit_reference::serialize(&Some(compilation_unit.clone()), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rc<CompilationUnit>, D::Error> {
+        Ok(super::optional_compilation_unit_reference::deserialize(deserializer)?.unwrap_or_default())
+    }
+}
+
+pub(crate) mod optional_compilation_unit_reference {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(compilation_unit: &Option<Rc<CompilationUnit>>, serializer: S) -> Result<S::Ok, S::Error> {
+        let index = CONTEXT.with(|c| match c.borrow().as_ref() {
+            Some(LocationSerializationContext::Serializing(indices)) => compilation_unit.as_ref().and_then(|unit| indices.get(&Rc::as_ptr(unit)).copied()),
+            _ => None,
+        });
+        index.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Rc<CompilationUnit>>, D::Error> {
+        let Some(units) = deserializing_compilation_units() else {
+            IgnoredAny::deserialize(deserializer)?;
+            return Ok(None);
+        };
+        let Some(index) = Option::<usize>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        Ok(Some(units.get(index).ok_or_else(|| D::Error::custom(format!("unknown compilation unit {index}")))?.clone()))
+    }
+}
+
+/// The identity of the source of a serialized tree, that is,
+/// of the compilation unit its locations are bound to.
+#[derive(Serialize, Deserialize, PartialEq)]
+struct SerializedSource {
+    file_path: Option<String>,
+    /// FNV-1a hash of the source text.
+    text_hash: u64,
+}
+
+impl SerializedSource {
+    fn new(compilation_unit: &CompilationUnit) -> Self {
+        Self {
+            file_path: compilation_unit.file_path(),
+            text_hash: fnv1a_hash(&[compilation_unit.text().as_bytes()]),
+        }
+    }
+}
+
+/// A compilation unit nested in the compilation unit of a serialized tree,
+/// such as the one of an included file.
+#[derive(Serialize, Deserialize)]
+struct SerializedCompilationUnit {
+    /// Index of the compilation unit this one is nested in.
+    parent: usize,
+    file_path: Option<String>,
+    text: String,
+    offset_table: Vec<(usize, usize)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedComment {
+    multiline: bool,
+    content: String,
+    location: Location,
+}
+
+/// A diagnostic as serialized, with its arguments formatted as strings.
+#[derive(Serialize, Deserialize)]
+struct SerializedDiagnostic {
+    /// Index of the compilation unit holding the diagnostic.
+    compilation_unit: usize,
+    location: Location,
+    id: i32,
+    is_warning: bool,
+    is_verify_error: bool,
+    arguments: Vec<String>,
+}
+
+/// Serializes and deserializes syntactic trees along with the byte offsets
+/// of their locations, so that a deserialized tree has real locations.
+///
+/// A tree serializes as a sequence of the file path and a hash of the source text
+/// of the given compilation unit, the compilation units nested in the
+/// given compilation unit, whether each of these compilation units is invalidated,
+/// their comments, their diagnostics and the tree itself. Each location serializes
+/// as the index of its compilation unit (the given compilation unit being `0`,
+/// followed by the nested ones in pre-order), its first offset and its last offset.
+///
+/// Deserialization binds locations to the given compilation unit,
+/// which must have the same file path and source text and is expected
+/// not to have been parsed; nested compilation units, comments and
+/// diagnostics are re-created, such that the result is equivalent to
+/// a fresh parse. Diagnostic arguments are restored as strings.
+/// Outside of `TreeSerializationFacade`, locations serialize as
+/// line and column text, such as `"1:1-1:7"`, and deserialize to
+/// locations of an empty compilation unit.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(Some("Main.as".into()), "// Comment\nx = 10".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let json = TreeSerializationFacade(&cu).to_json(&program).unwrap();
+///
+/// let new_cu = CompilationUnit::new(Some("Main.as".into()), "// Comment\nx = 10".into());
+/// let loaded: Rc<Program> = TreeSerializationFacade(&new_cu).from_json(&json).unwrap();
+/// assert_eq!(loaded.directives[0].location().text(), "x = 10");
+/// assert!(Rc::ptr_eq(&loaded.location.compilation_unit(), &new_cu));
+/// assert_eq!(new_cu.comments()[0].content(), " Comment");
+/// ```
+pub struct TreeSerializationFacade<'a>(pub &'a Rc<CompilationUnit>);
+
+impl<'a> TreeSerializationFacade<'a> {
+    /// Returns a value that serializes a tree with the locations of this facade.
+    pub fn serializable<'b, T: Serialize + ?Sized>(&self, tree: &'b T) -> SerializableTree<'b, T> where 'a: 'b {
+        SerializableTree { compilation_unit: self.0, tree }
+    }
+
+    /// Returns a seed that deserializes a tree, binding its locations
+    /// to the compilation unit of this facade.
+    pub fn seed<T: DeserializeOwned>(&self) -> TreeSeed<'a, T> {
+        TreeSeed { compilation_unit: self.0, _phantom: PhantomData }
+    }
+
+    /// Returns a seed that deserializes a tree without attaching its nested
+    /// compilation units and comments to the compilation unit of this facade.
+    pub(crate) fn detached_seed<T: DeserializeOwned>(&self) -> DetachedTreeSeed<'a, T> {
+        DetachedTreeSeed { compilation_unit: self.0, _phantom: PhantomData }
+    }
+
+    pub fn to_json<T: Serialize + ?Sized>(&self, tree: &T) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.serializable(tree))
+    }
+
+    pub fn from_json<T: DeserializeOwned>(&self, json: &str) -> Result<T, serde_json::Error> {
+        self.seed().deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+}
+
+/// A tree that serializes along with the byte offsets of its locations.
+/// See `TreeSerializationFacade`.
+pub struct SerializableTree<'a, T: ?Sized> {
+    compilation_unit: &'a Rc<CompilationUnit>,
+    tree: &'a T,
+}
+
+impl<T: Serialize + ?Sized> Serialize for SerializableTree<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let units = compilation_units_in_pre_order(self.compilation_unit);
+        let indices: HashMap<*const CompilationUnit, usize> = units.iter().enumerate().map(|(i, unit)| (Rc::as_ptr(unit), i)).collect();
+        let nested: Vec<SerializedCompilationUnit> = units[1..].iter().map(|unit| SerializedCompilationUnit {
+            parent: indices[&Rc::as_ptr(&unit.included_from().unwrap())],
+            file_path: unit.file_path(),
+            text: unit.text().clone(),
+            offset_table: unit.offset_table.clone(),
+        }).collect();
+        let invalidated: Vec<bool> = units.iter().map(|unit| unit.invalidated()).collect();
+        let _guard = ContextGuard::enter(LocationSerializationContext::Serializing(indices));
+        let comments: Vec<SerializedComment> = units.iter().flat_map(|unit| unit.comments()).map(|comment| SerializedComment {
+            multiline: comment.multiline(),
+            content: comment.content(),
+            location: comment.location(),
+        }).collect();
+        let diagnostics: Vec<SerializedDiagnostic> = units.iter().enumerate().flat_map(|(i, unit)| {
+            unit.diagnostics().into_iter().map(move |diagnostic| SerializedDiagnostic {
+                compilation_unit: i,
+                location: diagnostic.location(),
+                id: diagnostic.id(),
+                is_warning: diagnostic.is_warning(),
+                is_verify_error: diagnostic.is_verify_error(),
+                arguments: diagnostic.arguments().iter().map(|argument| argument.to_string()).collect(),
+            })
+        }).collect();
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&SerializedSource::new(self.compilation_unit))?;
+        tuple.serialize_element(&nested)?;
+        tuple.serialize_element(&invalidated)?;
+        tuple.serialize_element(&comments)?;
+        tuple.serialize_element(&diagnostics)?;
+        tuple.serialize_element(self.tree)?;
+        tuple.end()
+    }
+}
+
+/// A compilation unit followed by its nested compilation units in pre-order,
+/// as indexed by serialized locations.
+pub(crate) fn compilation_units_in_pre_order(compilation_unit: &Rc<CompilationUnit>) -> Vec<Rc<CompilationUnit>> {
+    let mut units = vec![compilation_unit.clone()];
+    for nested in compilation_unit.nested_compilation_units() {
+        units.extend(compilation_units_in_pre_order(&nested));
+    }
+    units
+}
+
+/// Deserializes a tree, binding its locations to a compilation unit.
+/// See `TreeSerializationFacade`.
+pub struct TreeSeed<'a, T> {
+    compilation_unit: &'a Rc<CompilationUnit>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for TreeSeed<'_, T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        let seed = DetachedTreeSeed { compilation_unit: self.compilation_unit, _phantom: PhantomData };
+        Ok(seed.deserialize(deserializer)?.attach())
+    }
+}
+
+/// A deserialized tree whose nested compilation units, comments and diagnostics
+/// are not yet attached, so that the compilation unit it is deserialized for
+/// remains unchanged until the caller is done validating it.
+pub(crate) struct DetachedTree<T> {
+    pub tree: T,
+    /// The compilation unit of the tree followed by its nested
+    /// compilation units in pre-order.
+    compilation_units: Vec<Rc<CompilationUnit>>,
+    parents: Vec<Rc<CompilationUnit>>,
+    invalidated: Vec<bool>,
+    comments: Vec<SerializedComment>,
+    diagnostics: Vec<(Rc<CompilationUnit>, Diagnostic)>,
+}
+
+impl<T> DetachedTree<T> {
+    /// Attaches the nested compilation units, comments and diagnostics.
+    pub fn attach(self) -> T {
+        for (parent, unit) in self.parents.iter().zip(&self.compilation_units[1..]) {
+            parent.add_nested_compilation_unit(unit.clone());
+        }
+        for (unit, invalidated) in self.compilation_units.iter().zip(self.invalidated) {
+            if invalidated {
+                unit.invalidated.set(true);
+            }
+        }
+        for comment in self.comments {
+            comment.location.compilation_unit().add_comment(Rc::new(Comment::new(comment.multiline, comment.content, comment.location)));
+        }
+        for (unit, diagnostic) in self.diagnostics {
+            unit.add_diagnostic(diagnostic);
+        }
+        self.tree
+    }
+}
+
+/// Deserializes a `DetachedTree`.
+pub(crate) struct DetachedTreeSeed<'a, T> {
+    compilation_unit: &'a Rc<CompilationUnit>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for DetachedTreeSeed<'_, T> {
+    type Value = DetachedTree<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<DetachedTree<T>, D::Error> {
+        deserializer.deserialize_tuple(6, self)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for DetachedTreeSeed<'_, T> {
+    type Value = DetachedTree<T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a serialized tree")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<DetachedTree<T>, A::Error> {
+        let source: SerializedSource = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        if source != SerializedSource::new(self.compilation_unit) {
+            return Err(A::Error::custom("tree was serialized from a different file path or source text"));
+        }
+        let nested: Vec<SerializedCompilationUnit> = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let mut units = vec![self.compilation_unit.clone()];
+        let mut parents = vec![];
+        for serialized in nested {
+            let parent = units.get(serialized.parent).ok_or_else(|| A::Error::custom(format!("unknown compilation unit {}", serialized.parent)))?.clone();
+            let unit = CompilationUnit::with_offset_table(serialized.file_path, serialized.text, serialized.offset_table);
+            unit.set_compiler_options(parent.compiler_options());
+            parents.push(parent);
+            units.push(unit);
+        }
+        let invalidated: Vec<bool> = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(2, &self))?;
+        if invalidated.len() != units.len() {
+            return Err(A::Error::custom("invalidation flags do not match the compilation units"));
+        }
+        let tree: T;
+        let comments: Vec<SerializedComment>;
+        let serialized_diagnostics: Vec<SerializedDiagnostic>;
+        {
+            let _guard = ContextGuard::enter(LocationSerializationContext::Deserializing(units.clone()));
+            comments = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(3, &self))?;
+            serialized_diagnostics = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(4, &self))?;
+            tree = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(5, &self))?;
+        }
+        let mut diagnostics = vec![];
+        for diagnostic in serialized_diagnostics {
+            let kind = DiagnosticKind::from_id(diagnostic.id).ok_or_else(|| A::Error::custom(format!("unknown diagnostic {}", diagnostic.id)))?;
+            let unit = units.get(diagnostic.compilation_unit).ok_or_else(|| A::Error::custom(format!("unknown compilation unit {}", diagnostic.compilation_unit)))?.clone();
+            let arguments: Vec<Rc<dyn DiagnosticArgument>> = diagnostic.arguments.into_iter().map(|argument| Rc::new(argument) as Rc<dyn DiagnosticArgument>).collect();
+            diagnostics.push((unit, if diagnostic.is_warning {
+                Diagnostic::new_warning(&diagnostic.location, kind, arguments)
+            } else if diagnostic.is_verify_error {
+                Diagnostic::new_verify_error(&diagnostic.location, kind, arguments)
+            } else {
+                Diagnostic::new_syntax_error(&diagnostic.location, kind, arguments)
+            }));
+        }
+        Ok(DetachedTree { tree, compilation_units: units, parents, invalidated, comments, diagnostics })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_round_trip() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_location_serialization_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("included.as"), "/* Included */ y = 'é'").unwrap();
+        let path = directory.join("Main.as").to_string_lossy().into_owned();
+        let text = "include 'included.as'; // Comment\nx = 10".to_owned();
+        let cu = CompilationUnit::new(Some(path.clone()), text.clone());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let json = TreeSerializationFacade(&cu).to_json(&program).unwrap();
+
+        let new_cu = CompilationUnit::new(Some(path), text);
+        let loaded: Rc<Program> = TreeSerializationFacade(&new_cu).from_json(&json).unwrap();
+        assert_eq!(TreeSerializationFacade(&new_cu).to_json(&loaded).unwrap(), json);
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), serde_json::to_string(&program).unwrap());
+        assert_eq!(new_cu.nested_compilation_units().len(), cu.nested_compilation_units().len());
+        assert_eq!(new_cu.comments().len(), cu.comments().len());
+
+        // Included directives are bound to the nested compilation unit
+        let Directive::IncludeDirective(include) = loaded.directives[0].as_ref() else {
+            panic!();
+        };
+        assert!(Rc::ptr_eq(&include.nested_compilation_unit, &new_cu.nested_compilation_units()[0]));
+        assert!(Rc::ptr_eq(&include.nested_directives[0].location().compilation_unit(), &include.nested_compilation_unit));
+        assert_eq!(include.nested_directives[0].location().text(), "y = 'é'");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_source_mismatch() {
+        let cu = CompilationUnit::new(Some("Main.as".into()), "// Comment\nx = 10".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let json = TreeSerializationFacade(&cu).to_json(&program).unwrap();
+
+        for (file_path, text) in [(Some("Other.as"), "// Comment\nx = 10"), (None, "// Comment\nx = 10"), (Some("Main.as"), "// Comment\nx = 20")] {
+            let new_cu = CompilationUnit::new(file_path.map(|path| path.into()), text.into());
+            assert!(TreeSerializationFacade(&new_cu).from_json::<Rc<Program>>(&json).is_err());
+            assert!(new_cu.comments().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_location() {
+        let cu = CompilationUnit::new(None, "x = 10".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let json = TreeSerializationFacade(&cu).to_json(&program).unwrap();
+        assert!(json.contains("[0,0,6]"));
+        let json = json.replacen("[0,0,6]", "[0,0,60]", 1);
+        assert!(TreeSerializationFacade(&cu).from_json::<Rc<Program>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let text = "x = 10; y =".to_owned();
+        let cu = CompilationUnit::new(None, text.clone());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(cu.invalidated());
+        let json = TreeSerializationFacade(&cu).to_json(&program).unwrap();
+
+        let new_cu = CompilationUnit::new(None, text.clone());
+        let _: Rc<Program> = TreeSerializationFacade(&new_cu).from_json(&json).unwrap();
+        assert!(new_cu.invalidated());
+        let diagnostics = |cu: &Rc<CompilationUnit>| cu.diagnostics().iter().map(|d| d.format_english()).collect::<Vec<_>>();
+        assert_eq!(diagnostics(&new_cu), diagnostics(&cu));
+
+        // A diagnostic that does not deserialize leaves the compilation unit unchanged.
+        let id = format!("\"id\":{}", cu.diagnostics()[0].id());
+        assert!(json.contains(&id));
+        let json = json.replacen(&id, "\"id\":-1", 1);
+        let new_cu = CompilationUnit::new(None, text);
+        assert!(TreeSerializationFacade(&new_cu).from_json::<Rc<Program>>(&json).is_err());
+        assert!(!new_cu.invalidated() && new_cu.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_mxml_namespaces() {
+        let text = "<s:Application xmlns:fx=\"http://ns.adobe.com/mxml/2009\" xmlns:s=\"library://ns.adobe.com/flex/spark\"><s:Button xmlns=\"ns\"/></s:Application>".to_owned();
+        let cu = CompilationUnit::new(None, text.clone());
+        let document = ParserFacade(&cu, default()).parse_mxml();
+        let json = TreeSerializationFacade(&cu).to_json(&document).unwrap();
+
+        let new_cu = CompilationUnit::new(None, text);
+        let loaded: Rc<Mxml> = TreeSerializationFacade(&new_cu).from_json(&json).unwrap();
+        let (root, loaded_root) = (document.root().unwrap(), loaded.root().unwrap());
+        assert_eq!(loaded_root.namespace.full_listing(), root.namespace.full_listing());
+        assert_eq!(loaded_root.child_elements()[0].namespace.full_listing(), root.child_elements()[0].namespace.full_listing());
+    }
+
+    #[test]
+    fn test_offset_table() {
+        let text = "<s:Label xmlns:s=\"library://ns.adobe.com/flex/spark\"\n  text=\"{a &lt; b c}\"/>".to_owned();
+        let cu = CompilationUnit::new(None, text.clone());
+        let document = ParserFacade(&cu, default()).parse_mxml();
+        let json = TreeSerializationFacade(&cu).to_json(&document).unwrap();
+
+        let new_cu = CompilationUnit::new(None, text);
+        let _: Rc<Mxml> = TreeSerializationFacade(&new_cu).from_json(&json).unwrap();
+        let diagnostics = |cu: &Rc<CompilationUnit>| cu.nested_diagnostics().iter().map(|d| d.format_english()).collect::<Vec<_>>();
+        assert_eq!(diagnostics(&new_cu), ["2:19: Syntax error #1028: Expecting end-of-file before identifier."]);
+        assert_eq!(diagnostics(&new_cu), diagnostics(&cu));
+    }
+}
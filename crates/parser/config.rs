@@ -0,0 +1,10 @@
+//! Defines the reading of project configuration files.
+//!
+//! The `CompilerOptions` structure holds the options of a build that affect
+//! parsing and semantic passes, such as conditional compilation constants.
+//! It is read from `asconfig.json` files and from Flex configuration files,
+//! and attached to compilation units through `CompilationUnit::set_compiler_options()`.
+
+mod compiler_options;
+pub use compiler_options::*;
+mod config_reader;
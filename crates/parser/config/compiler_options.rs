@@ -0,0 +1,80 @@
+use std::{collections::{BTreeMap, BTreeSet}, path::PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// The options of a build that affect parsing and semantic passes,
+/// as configured by `asconfig.json` and Flex configuration files.
+///
+/// Compiler options are attached to a compilation unit through
+/// `CompilationUnit::set_compiler_options()`, which compilation units
+/// nested in it, such as included files, inherit.
+///
+/// # Example
+///
+/// ```no_run
+/// use as3_parser::ns::*;
+///
+/// let compiler_options = CompilerOptions::read_asconfig("asconfig.json".as_ref()).unwrap();
+/// let project = Project::parse(&ProjectOptions {
+///     source_roots: compiler_options.source_paths.clone(),
+///     compiler_options: Some(Rc::new(compiler_options)),
+///     ..default()
+/// }).unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompilerOptions {
+    /// Directories holding the sources of the project, as with `-source-path`.
+    pub source_paths: Vec<PathBuf>,
+    /// SWC files and directories of SWC files linked into the output,
+    /// as with `-library-path`.
+    pub library_paths: Vec<PathBuf>,
+    /// SWC files and directories of SWC files that are not linked into the output,
+    /// as with `-external-library-path`.
+    pub external_library_paths: Vec<PathBuf>,
+    /// Conditional compilation constants by qualified name, such as `"CONFIG::debug"`,
+    /// with their values as ActionScript expressions, as with `-define`.
+    pub defines: BTreeMap<String, String>,
+    /// The targeted Flash Player version, such as `"11.1"`, as with `-target-player`.
+    pub target_player: Option<String>,
+    /// Whether type incompatibilities are reported, as with `-strict`.
+    /// Overrides `VerifierOptions::strict` for the programs of the compilation unit.
+    pub strict: Option<bool>,
+    /// Warning switches by option name, such as `"warnings"` or `"warn-no-type-decl"`.
+    /// A lint rule whose `LintRule::warning_switch()` is switched off is not run
+    /// over the programs of the compilation unit.
+    pub warnings: BTreeMap<String, bool>,
+}
+
+impl CompilerOptions {
+    /// The value of the conditional compilation constant `namespace::name`.
+    pub fn define(&self, namespace: &str, name: &str) -> Option<&str> {
+        self.defines.get(&format!("{namespace}::{name}")).map(|value| value.as_str())
+    }
+
+    /// The namespaces of the conditional compilation constants, such as `CONFIG`.
+    pub fn configuration_namespaces(&self) -> BTreeSet<String> {
+        self.defines.keys().filter_map(|name| name.split_once("::").map(|(namespace, _)| namespace.to_owned())).collect()
+    }
+
+    /// Indicates whether a warning switch, such as `"warn-no-type-decl"`,
+    /// is enabled. Warnings are enabled unless switched off, either
+    /// individually or through the `"warnings"` switch.
+    pub fn is_warning_enabled(&self, name: &str) -> bool {
+        self.warnings.get("warnings").copied().unwrap_or(true) && self.warnings.get(name).copied().unwrap_or(true)
+    }
+}
+
+/// An error reading a configuration file.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
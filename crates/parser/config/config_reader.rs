@@ -0,0 +1,405 @@
+use std::{fs, path::{Path, PathBuf}};
+use serde_json::Value;
+use crate::ns::*;
+
+impl CompilerOptions {
+    /// Reads an `asconfig.json` file, as used by the ActionScript & MXML
+    /// extension for Visual Studio Code.
+    ///
+    /// The `compilerOptions` and `additionalOptions` properties are read,
+    /// after the file referred by the `extends` property and the Flex
+    /// configuration files referred by the `load-config` compiler option.
+    /// Comments are allowed. Relative paths are resolved against the
+    /// directory of the file referring to them.
+    pub fn read_asconfig(path: &Path) -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::default();
+        reader.read_file(path, ConfigReader::parse_asconfig)?;
+        Ok(reader.options)
+    }
+
+    /// Parses the content of an `asconfig.json` file located at `path`.
+    pub fn parse_asconfig(path: &Path, text: &str) -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::default();
+        reader.parse_asconfig(path, text)?;
+        Ok(reader.options)
+    }
+
+    /// Reads a Flex configuration file, as loaded through the `-load-config`
+    /// option of the Flex compiler, such as `flex-config.xml`.
+    ///
+    /// The options within the `compiler` element and at the top level are read,
+    /// where lists are replaced unless their element has an `append="true"`
+    /// attribute. The files referred by `load-config` elements are read
+    /// in place. Relative paths are resolved against the directory of the file
+    /// referring to them, except those containing tokens such as `${flexlib}`.
+    pub fn read_flex_config(path: &Path) -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::default();
+        reader.read_file(path, ConfigReader::parse_flex_config)?;
+        Ok(reader.options)
+    }
+
+    /// Parses the content of a Flex configuration file located at `path`.
+    pub fn parse_flex_config(path: &Path, text: &str) -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::default();
+        reader.parse_flex_config(path, text)?;
+        Ok(reader.options)
+    }
+}
+
+#[derive(Default)]
+struct ConfigReader {
+    options: CompilerOptions,
+    /// The configuration files being read, for detecting circular references.
+    reading: Vec<PathBuf>,
+}
+
+impl ConfigReader {
+    fn read_file(&mut self, path: &Path, parse: fn(&mut Self, &Path, &str) -> Result<(), ConfigError>) -> Result<(), ConfigError> {
+        let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        if self.reading.contains(&canonical_path) {
+            return Err(config_error(path, "circular reference to the configuration file"));
+        }
+        let text = fs::read_to_string(path).map_err(|error| config_error(path, &error.to_string()))?;
+        self.reading.push(canonical_path);
+        let result = parse(self, path, &text);
+        self.reading.pop();
+        result
+    }
+
+    fn parse_asconfig(&mut self, path: &Path, text: &str) -> Result<(), ConfigError> {
+        let json: Value = serde_json::from_str(&strip_json_comments(text)).map_err(|error| config_error(path, &error.to_string()))?;
+        if let Some(extends) = json.get("extends").and_then(Value::as_str) {
+            self.read_file(&resolve_path(path, extends), Self::parse_asconfig)?;
+        }
+        match json.get("compilerOptions") {
+            Some(Value::Object(compiler_options)) => {
+                // Options from loaded configuration files come first, so that
+                // they are overriden by the other compiler options.
+                if let Some(load_config) = compiler_options.get("load-config") {
+                    self.apply(path, "load-config", json_values(load_config), true)?;
+                }
+                for (name, value) in compiler_options.iter().filter(|(name, _)| *name != "load-config") {
+                    // Lists extend those of loaded configuration files.
+                    self.apply(path, name, json_values(value), true)?;
+                }
+            },
+            Some(_) => return Err(config_error(path, "compilerOptions must be an object")),
+            None => {},
+        }
+        if let Some(additional_options) = json.get("additionalOptions").and_then(Value::as_str) {
+            self.parse_arguments(path, additional_options)?;
+        }
+        Ok(())
+    }
+
+    fn parse_flex_config(&mut self, path: &Path, text: &str) -> Result<(), ConfigError> {
+        let compilation_unit = CompilationUnit::new(Some(path.to_string_lossy().into_owned()), text.to_owned());
+        let document = ParserFacade(&compilation_unit, default()).parse_mxml();
+        if let Some(diagnostic) = compilation_unit.nested_diagnostics().iter().find(|diagnostic| diagnostic.is_error()) {
+            return Err(config_error(path, &diagnostic.format_english()));
+        }
+        let Some(root) = document.root() else {
+            return Err(config_error(path, "missing root element"));
+        };
+        for element in root.child_elements() {
+            if element.name.name == "compiler" {
+                for option in element.child_elements() {
+                    self.apply_xml_option(path, &option)?;
+                }
+            } else {
+                self.apply_xml_option(path, &element)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_xml_option(&mut self, path: &Path, element: &Rc<MxmlElement>) -> Result<(), ConfigError> {
+        let children = element.child_elements();
+        let values: Vec<String> = if children.is_empty() {
+            Some(element.inner_text().trim().to_owned()).filter(|value| !value.is_empty()).into_iter().collect()
+        } else {
+            children.iter().map(|child| child.inner_text().trim().to_owned()).collect()
+        };
+        // Each `define` element adds a constant.
+        let append = element.name.name == "define" || element.attribute("append").is_some_and(|attribute| attribute.value.0 == "true");
+        self.apply(path, &element.name.name, values, append)
+    }
+
+    /// Parses command-line options, such as `-define+=CONFIG::debug,true`
+    /// or `-source-path src lib`.
+    fn parse_arguments(&mut self, path: &Path, text: &str) -> Result<(), ConfigError> {
+        let arguments = split_arguments(text);
+        let mut i = 0;
+        while i < arguments.len() {
+            let argument = &arguments[i];
+            i += 1;
+            // Arguments other than options, such as source files, are ignored.
+            let Some(option) = argument.strip_prefix("--").or_else(|| argument.strip_prefix('-')) else {
+                continue;
+            };
+            if let Some(equals) = option.find('=') {
+                let (name, append) = match option[..equals].strip_suffix('+') {
+                    Some(name) => (name, true),
+                    None => (&option[..equals], false),
+                };
+                let value = &option[(equals + 1)..];
+                let values = if name.trim_start_matches("compiler.") == "define" {
+                    value.splitn(2, ',').map(|value| value.to_owned()).collect()
+                } else {
+                    value.split(',').filter(|value| !value.is_empty()).map(|value| value.to_owned()).collect()
+                };
+                self.apply(path, name, values, append)?;
+            } else {
+                let mut values = vec![];
+                while i < arguments.len() && !arguments[i].starts_with('-') {
+                    values.push(arguments[i].clone());
+                    i += 1;
+                }
+                self.apply(path, option, values, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a compiler option read from the configuration file at `path`.
+    /// Unsupported options are ignored.
+    fn apply(&mut self, path: &Path, name: &str, values: Vec<String>, append: bool) -> Result<(), ConfigError> {
+        let name = name.strip_prefix("compiler.").unwrap_or(name);
+        match name {
+            "source-path" | "library-path" | "external-library-path" => {
+                let paths = match name {
+                    "source-path" => &mut self.options.source_paths,
+                    "library-path" => &mut self.options.library_paths,
+                    _ => &mut self.options.external_library_paths,
+                };
+                if !append {
+                    paths.clear();
+                }
+                paths.extend(values.iter().map(|value| resolve_path(path, value)));
+            },
+            "define" => {
+                if !append {
+                    self.options.defines.clear();
+                }
+                for pair in values.chunks(2) {
+                    let [name, value] = pair else {
+                        return Err(config_error(path, "define expects a name and a value"));
+                    };
+                    self.options.defines.insert(name.clone(), value.clone());
+                }
+            },
+            "target-player" => {
+                self.options.target_player = values.last().cloned();
+            },
+            "load-config" => {
+                for value in values {
+                    self.read_file(&resolve_path(path, &value), Self::parse_flex_config)?;
+                }
+            },
+            "strict" => {
+                self.options.strict = Some(parse_boolean(path, name, &values)?);
+            },
+            _ if name == "warnings" || name.starts_with("warn-") || name.ends_with("-warnings") => {
+                let enabled = parse_boolean(path, name, &values)?;
+                self.options.warnings.insert(name.to_owned(), enabled);
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+}
+
+/// Parses the value of a boolean option, which defaults to true
+/// when omitted, as in `-strict`.
+fn parse_boolean(path: &Path, name: &str, values: &[String]) -> Result<bool, ConfigError> {
+    match values.last().map(|value| value.as_str()) {
+        None | Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        Some(_) => Err(config_error(path, &format!("{name} expects true or false"))),
+    }
+}
+
+fn config_error(path: &Path, message: &str) -> ConfigError {
+    ConfigError {
+        path: path.to_owned(),
+        message: message.to_owned(),
+    }
+}
+
+/// Resolves a path relative to the directory of the configuration file at `config_path`,
+/// unless it contains a token such as `${flexlib}`.
+fn resolve_path(config_path: &Path, path: &str) -> PathBuf {
+    if path.contains("${") {
+        return PathBuf::from(path);
+    }
+    config_path.parent().unwrap_or(Path::new("")).join(path)
+}
+
+/// The values of an `asconfig.json` compiler option as command-line values,
+/// where a define such as `{ "name": "CONFIG::debug", "value": true }`
+/// results into its name and value.
+fn json_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::Null => vec![],
+        Value::String(value) => vec![value.clone()],
+        Value::Array(items) => items.iter().flat_map(json_values).collect(),
+        Value::Object(object) => ["name", "value"].iter().filter_map(|key| object.get(*key)).flat_map(json_values).collect(),
+        value => vec![value.to_string()],
+    }
+}
+
+/// Removes `//` and `/* */` comments outside of strings, preserving line breaks.
+fn strip_json_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                result.push(ch);
+                while let Some(ch) = chars.next() {
+                    result.push(ch);
+                    if ch == '\\' {
+                        result.extend(chars.next());
+                    } else if ch == '"' {
+                        break;
+                    }
+                }
+            },
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|ch| *ch != '\n').is_some() {}
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        result.push(ch);
+                    } else if previous == '*' && ch == '/' {
+                        break;
+                    }
+                    previous = ch;
+                }
+            },
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+/// Splits command-line arguments at whitespace, except within quotes,
+/// which are removed.
+fn split_arguments(text: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut argument: Option<String> = None;
+    let mut quote: Option<char> = None;
+    for ch in text.chars() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => argument.get_or_insert_with(String::new).push(ch),
+            None if ch == '"' || ch == '\'' => {
+                quote = Some(ch);
+                argument.get_or_insert_with(String::new);
+            },
+            None if ch.is_whitespace() => arguments.extend(argument.take()),
+            None => argument.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    arguments.extend(argument);
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::ns::*;
+
+    #[test]
+    fn test_flex_config() {
+        let path = Path::new("config/flex-config.xml");
+        let options = CompilerOptions::parse_flex_config(path, r#"<?xml version="1.0"?>
+            <flex-config xmlns="http://www.adobe.com/2006/flex-config">
+                <target-player>11.1</target-player>
+                <compiler>
+                    <source-path append="true">
+                        <path-element>src</path-element>
+                        <path-element>${flexlib}/projects/framework/src</path-element>
+                    </source-path>
+                    <external-library-path>
+                        <path-element>libs/playerglobal.swc</path-element>
+                    </external-library-path>
+                    <define>
+                        <name>CONFIG::debug</name>
+                        <value>true</value>
+                    </define>
+                    <define>
+                        <name>CONFIG::version</name>
+                        <value>'1.0'</value>
+                    </define>
+                    <warn-no-type-decl>false</warn-no-type-decl>
+                    <show-unused-type-selector-warnings>false</show-unused-type-selector-warnings>
+                    <strict>true</strict>
+                </compiler>
+            </flex-config>"#).unwrap();
+        assert_eq!(options.target_player.as_deref(), Some("11.1"));
+        assert_eq!(options.source_paths, [Path::new("config/src"), Path::new("${flexlib}/projects/framework/src")]);
+        assert_eq!(options.external_library_paths, [Path::new("config/libs/playerglobal.swc")]);
+        assert_eq!(options.define("CONFIG", "debug"), Some("true"));
+        assert_eq!(options.define("CONFIG", "version"), Some("'1.0'"));
+        assert!(!options.is_warning_enabled("warn-no-type-decl"));
+        assert!(!options.is_warning_enabled("show-unused-type-selector-warnings"));
+        assert!(options.is_warning_enabled("warn-duplicate-variable-def"));
+        assert_eq!(options.strict, Some(true));
+
+        assert!(CompilerOptions::parse_flex_config(path, "<flex-config><compiler></flex-config>").is_err());
+    }
+
+    #[test]
+    fn test_asconfig() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_asconfig_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("build")).unwrap();
+        std::fs::write(directory.join("build/base-config.xml"), r#"<flex-config>
+            <target-player>10.0</target-player>
+            <compiler>
+                <source-path><path-element>../shared</path-element></source-path>
+                <define><name>CONFIG::debug</name><value>false</value></define>
+            </compiler>
+        </flex-config>"#).unwrap();
+        std::fs::write(directory.join("asconfig.json"), r#"{
+            // The release configuration
+            "config": "flex",
+            "compilerOptions": {
+                "source-path": ["src"],
+                "library-path": ["libs"],
+                "define": [
+                    { "name": "CONFIG::debug", "value": true },
+                    { "name": "COMPILE::version", "value": 2 } /* a number */
+                ],
+                "target-player": "11.1",
+                "load-config": ["build/base-config.xml"],
+                "warnings": true,
+                "strict": false
+            },
+            "additionalOptions": "-warn-no-constructor=false -define+=CONFIG::name,\"'a b'\" -compiler.library-path+=swcs"
+        }"#).unwrap();
+
+        let options = CompilerOptions::read_asconfig(&directory.join("asconfig.json")).unwrap();
+        assert_eq!(options.target_player.as_deref(), Some("11.1"));
+        assert_eq!(options.source_paths, [directory.join("build/../shared"), directory.join("src")]);
+        assert_eq!(options.library_paths, [directory.join("libs"), directory.join("swcs")]);
+        assert_eq!(options.define("CONFIG", "debug"), Some("true"));
+        assert_eq!(options.define("COMPILE", "version"), Some("2"));
+        assert_eq!(options.define("CONFIG", "name"), Some("'a b'"));
+        assert_eq!(options.configuration_namespaces().into_iter().collect::<Vec<_>>(), ["COMPILE", "CONFIG"]);
+        assert!(options.is_warning_enabled("warnings"));
+        assert!(!options.is_warning_enabled("warn-no-constructor"));
+        assert_eq!(options.strict, Some(false));
+
+        // Circular references are reported
+        std::fs::write(directory.join("extended.json"), r#"{ "extends": "asconfig2.json" }"#).unwrap();
+        std::fs::write(directory.join("asconfig2.json"), r#"{ "extends": "extended.json" }"#).unwrap();
+        assert!(CompilerOptions::read_asconfig(&directory.join("extended.json")).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,25 @@
+//! Defines the lossless concrete syntax tree.
+//!
+//! The concrete syntax tree covers every token of the source text. Whitespace,
+//! line terminators and comments are attached as trivia to the token that follows
+//! them, so that the source text can be reconstructed exactly.
+//!
+//! The tree consists of an immutable green tree (`GreenNode`, `GreenToken`), whose
+//! elements store lengths rather than offsets, and of red tree cursors (`CstNode`,
+//! `CstToken`), which compute source locations on demand. Concrete syntax tree nodes
+//! correspond to the syntactic nodes of the `tree` module and can be mapped back
+//! to them by location, as in `CstNode::find_nodes()`. Syntactic nodes parsed from
+//! within comments, such as ASDoc tag references, are part of the trivia and have
+//! no corresponding concrete syntax tree node.
+//!
+//! Using `ParserFacade::parse_program_cst()` is the most common way of producing
+//! a concrete syntax tree.
+
+mod trivia;
+pub use trivia::*;
+mod green;
+pub use green::*;
+mod red;
+pub use red::*;
+mod cst_builder;
+pub use cst_builder::*;
@@ -0,0 +1,279 @@
+use crate::ns::*;
+
+/// Builds a concrete syntax tree from a syntactic tree and the tokens
+/// consumed by a parser in lossless mode.
+///
+/// Every syntactic node becomes a concrete syntax tree node that contains the
+/// tokens within its location. Source text not covered by any consumed token
+/// or recorded trivia is attached as `TriviaKind::Skipped` trivia, so that the
+/// resulting tree always reproduces the source text between `first_offset`
+/// and `last_offset`.
+pub struct CstBuilder {
+    compilation_unit: Rc<CompilationUnit>,
+    tokens: Vec<LosslessToken>,
+    /// Token ranges of syntactic nodes, sorted by their first token.
+    ranges: Vec<(usize, usize, CstKind)>,
+    next_token: usize,
+    next_range: usize,
+    /// Offset up to which the source text has been covered.
+    cursor: usize,
+    last_offset: usize,
+}
+
+impl CstBuilder {
+    pub fn new(compilation_unit: &Rc<CompilationUnit>, tokens: Vec<LosslessToken>, first_offset: usize, last_offset: usize) -> Self {
+        // Discard tokens that overlap a preceding token.
+        let mut monotonic_tokens: Vec<LosslessToken> = vec![];
+        for token in tokens {
+            if monotonic_tokens.last().is_none_or(|last| last.location.last_offset() <= token.location.first_offset()) {
+                monotonic_tokens.push(token);
+            }
+        }
+        Self {
+            compilation_unit: compilation_unit.clone(),
+            tokens: monotonic_tokens,
+            ranges: vec![],
+            next_token: 0,
+            next_range: 0,
+            cursor: first_offset,
+            last_offset,
+        }
+    }
+
+    /// Builds the concrete syntax tree of a program.
+    pub fn build_program(mut self, program: &Rc<Program>) -> CstNode {
+        let mut collector = RangeCollector(vec![]);
+        for package in &program.packages {
+            let _ = walk_package_definition(&mut collector, package);
+        }
+        let _ = walk_directives(&mut collector, &program.directives);
+        self.set_ranges(collector.0);
+
+        let first_offset = self.cursor;
+        let green = self.build_node(CstKind::Program, self.tokens.len());
+        CstNode::new_root(Rc::new(green), &self.compilation_unit, first_offset)
+    }
+
+    /// Converts node locations into ranges of token indices.
+    fn set_ranges(&mut self, locations: Vec<(Location, CstKind)>) {
+        let token_offsets: Vec<usize> = self.tokens.iter().map(|token| token.location.first_offset()).collect();
+        let mut ranges = vec![];
+        for (location, kind) in locations {
+            let first = token_offsets.partition_point(|offset| *offset < location.first_offset());
+            let last = token_offsets.partition_point(|offset| *offset < location.last_offset());
+            if first < last {
+                ranges.push((first, last, kind));
+            }
+        }
+        // Outer nodes precede inner nodes; nodes sharing a range keep their traversal order.
+        ranges.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        self.ranges = ranges;
+    }
+
+    fn build_node(&mut self, kind: CstKind, last_token: usize) -> GreenNode {
+        let mut children = vec![];
+        while self.next_token < last_token {
+            if let Some(&(first, last, kind)) = self.ranges.get(self.next_range) {
+                if first <= self.next_token {
+                    self.next_range += 1;
+                    // Ignore nodes starting at a consumed token or
+                    // overlapping the end of the enclosing node.
+                    if first == self.next_token && last <= last_token {
+                        children.push(GreenElement::Node(Rc::new(self.build_node(kind, last))));
+                    }
+                    continue;
+                }
+            }
+            let token = self.build_token(self.next_token);
+            children.push(GreenElement::Token(Rc::new(token)));
+            self.next_token += 1;
+        }
+        // Attach remaining source text to a final end-of-file token.
+        if kind == CstKind::Program && self.cursor < self.last_offset {
+            let trivia = vec![GreenTrivia::new(TriviaKind::Skipped, self.text(self.cursor, self.last_offset))];
+            children.push(GreenElement::Token(Rc::new(GreenToken::new(Token::Eof, String::new(), trivia))));
+            self.cursor = self.last_offset;
+        }
+        GreenNode::new(kind, children)
+    }
+
+    fn build_token(&mut self, index: usize) -> GreenToken {
+        let LosslessToken { token, location, leading_trivia } = self.tokens[index].clone();
+        let mut green_trivia = vec![];
+        for trivia in leading_trivia {
+            if trivia.location.first_offset() < self.cursor || trivia.location.last_offset() > location.first_offset() {
+                continue;
+            }
+            self.skip_until(trivia.location.first_offset(), &mut green_trivia);
+            green_trivia.push(GreenTrivia::new(trivia.kind, trivia.location.text()));
+            self.cursor = trivia.location.last_offset();
+        }
+        self.skip_until(location.first_offset(), &mut green_trivia);
+        self.cursor = location.last_offset();
+        GreenToken::new(token, location.text(), green_trivia)
+    }
+
+    /// Covers the source text up to `offset` with skipped trivia.
+    fn skip_until(&mut self, offset: usize, trivia: &mut Vec<GreenTrivia>) {
+        if self.cursor < offset {
+            trivia.push(GreenTrivia::new(TriviaKind::Skipped, self.text(self.cursor, offset)));
+            self.cursor = offset;
+        }
+    }
+
+    fn text(&self, first_offset: usize, last_offset: usize) -> String {
+        self.compilation_unit.text()[first_offset..last_offset].to_owned()
+    }
+}
+
+/// Collects the locations of syntactic nodes.
+struct RangeCollector(Vec<(Location, CstKind)>);
+
+impl RangeCollector {
+    fn add(&mut self, location: Location, kind: CstKind) -> VisitorControl {
+        self.0.push((location, kind));
+        VisitorControl::Continue
+    }
+}
+
+impl Visitor for RangeCollector {
+    fn visit_package_definition(&mut self, node: &Rc<PackageDefinition>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::PackageDefinition)
+    }
+
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        self.add(node.location(), CstKind::Directive)
+    }
+
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        self.add(node.location(), CstKind::Expression)
+    }
+
+    fn visit_block(&mut self, node: &Block) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::Block)
+    }
+
+    fn visit_qualified_identifier(&mut self, node: &QualifiedIdentifier) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::QualifiedIdentifier)
+    }
+
+    fn visit_metadata(&mut self, node: &Rc<Metadata>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::Metadata)
+    }
+
+    // ASDoc comments are trivia in the concrete syntax tree.
+    fn visit_asdoc(&mut self, _node: &Rc<AsDoc>) -> VisitorControl {
+        VisitorControl::SkipChildren
+    }
+
+    fn visit_type_parameter(&mut self, node: &Rc<TypeParameter>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::TypeParameter)
+    }
+
+    fn visit_function_common(&mut self, node: &Rc<FunctionCommon>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::FunctionCommon)
+    }
+
+    fn visit_parameter(&mut self, node: &Rc<Parameter>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::Parameter)
+    }
+
+    fn visit_function_type_parameter(&mut self, node: &Rc<FunctionTypeParameter>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::FunctionTypeParameter)
+    }
+
+    fn visit_typed_destructuring(&mut self, node: &TypedDestructuring) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::TypedDestructuring)
+    }
+
+    fn visit_variable_binding(&mut self, node: &Rc<VariableBinding>) -> VisitorControl {
+        self.add(node.location(), CstKind::VariableBinding)
+    }
+
+    fn visit_simple_variable_definition(&mut self, node: &Rc<SimpleVariableDefinition>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::SimpleVariableDefinition)
+    }
+
+    fn visit_initializer_field(&mut self, node: &Rc<InitializerField>) -> VisitorControl {
+        self.add(node.location(), CstKind::InitializerField)
+    }
+
+    fn visit_case(&mut self, node: &Case) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::Case)
+    }
+
+    fn visit_type_case(&mut self, node: &TypeCase) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::TypeCase)
+    }
+
+    fn visit_catch_clause(&mut self, node: &CatchClause) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::CatchClause)
+    }
+
+    fn visit_finally_clause(&mut self, node: &FinallyClause) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::FinallyClause)
+    }
+
+    fn visit_xml_element(&mut self, node: &Rc<XmlElement>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::XmlElement)
+    }
+
+    fn visit_xml_attribute(&mut self, node: &Rc<XmlAttribute>) -> VisitorControl {
+        self.add(node.location.clone(), CstKind::XmlAttribute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_lossless_round_trip() {
+        let source = "package p {\r\n    /** Doc. */\r\n    public class C {\r\n        // Comment\r\n        var x = /a+/g, y = <a b=\"c\">{x} text</a>; /* Trailing */\r\n    }\r\n}\r\n  ";
+        let compilation_unit = CompilationUnit::new(None, source.into());
+        let (program, cst) = ParserFacade(&compilation_unit, default()).parse_program_cst();
+        assert!(compilation_unit.nested_diagnostics().is_empty());
+        assert_eq!(cst.text(), source);
+        assert_eq!(cst.kind(), CstKind::Program);
+
+        let mut comments = vec![];
+        for token in cst.tokens() {
+            assert_eq!(token.text(), token.location().text());
+            assert!(matches!(token.token(), Token::Eof) || !token.text().is_empty());
+            for trivia in token.leading_trivia() {
+                assert_ne!(trivia.kind, TriviaKind::Skipped);
+                if matches!(trivia.kind, TriviaKind::SingleLineComment | TriviaKind::MultiLineComment) {
+                    comments.push(trivia.location.text());
+                }
+            }
+        }
+        assert_eq!(comments, ["/** Doc. */", "// Comment", "/* Trailing */"]);
+
+        // Concrete syntax tree nodes map to syntactic nodes by location.
+        let package = &program.packages[0];
+        let node = cst.find_node(&package.location, CstKind::PackageDefinition).unwrap();
+        assert_eq!(node.location(), package.location);
+        let Directive::ClassDefinition(class) = package.block.directives[0].as_ref() else {
+            panic!();
+        };
+        let node = cst.find_node(&class.location, CstKind::Directive).unwrap();
+        assert_eq!(node.location(), class.location);
+        assert!(node.text().starts_with(" class C {\r\n        // Comment\r\n"));
+        let Directive::VariableDefinition(definition) = class.block.directives[0].as_ref() else {
+            panic!();
+        };
+        let initializer = definition.bindings[1].initializer.as_ref().unwrap();
+        let node = cst.find_node(&initializer.location(), CstKind::Expression).unwrap();
+        assert_eq!(node.to_string(), " <a b=\"c\">{x} text</a>");
+        assert_eq!(node.parent().unwrap().kind(), CstKind::VariableBinding);
+    }
+
+    #[test]
+    fn test_lossless_invalid_source() {
+        let source = "x = ;\n#y\n}";
+        let compilation_unit = CompilationUnit::new(None, source.into());
+        let (_, cst) = ParserFacade(&compilation_unit, default()).parse_program_cst();
+        assert!(compilation_unit.invalidated());
+        assert_eq!(cst.text(), source);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::ns::*;
+use std::fmt;
+
+/// Kind of a concrete syntax tree node, corresponding to a type of syntactic node.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CstKind {
+    Program,
+    PackageDefinition,
+    Directive,
+    Expression,
+    Block,
+    QualifiedIdentifier,
+    Metadata,
+    TypeParameter,
+    FunctionCommon,
+    Parameter,
+    FunctionTypeParameter,
+    TypedDestructuring,
+    VariableBinding,
+    SimpleVariableDefinition,
+    InitializerField,
+    Case,
+    TypeCase,
+    CatchClause,
+    FinallyClause,
+    XmlElement,
+    XmlAttribute,
+}
+
+/// Immutable trivia of the green tree.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GreenTrivia {
+    kind: TriviaKind,
+    text: String,
+}
+
+impl GreenTrivia {
+    pub fn new(kind: TriviaKind, text: String) -> Self {
+        Self { kind, text }
+    }
+
+    pub fn kind(&self) -> TriviaKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Immutable token of the green tree.
+///
+/// A green token holds its source text and the trivia preceding it,
+/// but not its position in the source text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GreenToken {
+    token: Token,
+    text: String,
+    leading_trivia: Vec<GreenTrivia>,
+}
+
+impl GreenToken {
+    pub fn new(token: Token, text: String, leading_trivia: Vec<GreenTrivia>) -> Self {
+        Self { token, text, leading_trivia }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// The source text of the token, excluding its leading trivia.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn leading_trivia(&self) -> &[GreenTrivia] {
+        &self.leading_trivia
+    }
+
+    /// Length in bytes of the leading trivia.
+    pub fn leading_trivia_length(&self) -> usize {
+        self.leading_trivia.iter().map(|trivia| trivia.text.len()).sum()
+    }
+
+    /// Length in bytes of the token, including its leading trivia.
+    pub fn full_length(&self) -> usize {
+        self.leading_trivia_length() + self.text.len()
+    }
+}
+
+impl fmt::Display for GreenToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading_trivia {
+            write!(f, "{}", trivia.text)?;
+        }
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Child of a green node.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    /// Length in bytes of the element, including leading trivia.
+    pub fn full_length(&self) -> usize {
+        match self {
+            Self::Node(node) => node.full_length(),
+            Self::Token(token) => token.full_length(),
+        }
+    }
+}
+
+impl fmt::Display for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(node) => node.fmt(f),
+            Self::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+/// Immutable node of the green tree.
+///
+/// Green nodes are position independent: equal subtrees are equal
+/// regardless of where they appear in the source text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GreenNode {
+    kind: CstKind,
+    children: Vec<GreenElement>,
+    full_length: usize,
+}
+
+impl GreenNode {
+    pub fn new(kind: CstKind, children: Vec<GreenElement>) -> Self {
+        let full_length = children.iter().map(|child| child.full_length()).sum();
+        Self { kind, children, full_length }
+    }
+
+    pub fn kind(&self) -> CstKind {
+        self.kind
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// Length in bytes of the node, including the leading trivia of its first token.
+    pub fn full_length(&self) -> usize {
+        self.full_length
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            child.fmt(f)?;
+        }
+        Ok(())
+    }
+}
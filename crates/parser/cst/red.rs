@@ -0,0 +1,209 @@
+use crate::ns::*;
+use std::fmt;
+
+/// Node of the concrete syntax tree, positioned in the source text.
+///
+/// A `CstNode` is a cursor over a `GreenNode` that knows its parent and its
+/// offset in the source text. Cloning a `CstNode` is cheap.
+#[derive(Clone)]
+pub struct CstNode(Rc<CstNodeData>);
+
+struct CstNodeData {
+    green: Rc<GreenNode>,
+    parent: Option<CstNode>,
+    /// Offset at which the node starts, including leading trivia.
+    offset: usize,
+    compilation_unit: Rc<CompilationUnit>,
+}
+
+impl CstNode {
+    /// Constructs the root of a concrete syntax tree
+    /// whose text starts at `offset` in the compilation unit.
+    pub fn new_root(green: Rc<GreenNode>, compilation_unit: &Rc<CompilationUnit>, offset: usize) -> Self {
+        Self(Rc::new(CstNodeData {
+            green,
+            parent: None,
+            offset,
+            compilation_unit: compilation_unit.clone(),
+        }))
+    }
+
+    pub fn kind(&self) -> CstKind {
+        self.0.green.kind()
+    }
+
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<CstNode> {
+        self.0.parent.clone()
+    }
+
+    pub fn compilation_unit(&self) -> &Rc<CompilationUnit> {
+        &self.0.compilation_unit
+    }
+
+    /// Location of the node, including the leading trivia of its first token.
+    pub fn full_location(&self) -> Location {
+        Location::with_offsets(&self.0.compilation_unit, self.0.offset, self.0.offset + self.0.green.full_length())
+    }
+
+    /// Location of the node from its first token to its last token, excluding
+    /// trivia. This location equals that of the corresponding syntactic node.
+    pub fn location(&self) -> Location {
+        let tokens: Vec<CstToken> = self.tokens().into_iter().filter(|token| !token.text().is_empty()).collect();
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => first.location().combine_with(last.location()),
+            _ => {
+                let offset = self.full_location().last_offset();
+                Location::with_offset(&self.0.compilation_unit, offset)
+            },
+        }
+    }
+
+    /// The children of the node.
+    pub fn children(&self) -> Vec<CstElement> {
+        let mut offset = self.0.offset;
+        let mut children = vec![];
+        for child in self.0.green.children() {
+            children.push(match child {
+                GreenElement::Node(green) => CstElement::Node(Self(Rc::new(CstNodeData {
+                    green: green.clone(),
+                    parent: Some(self.clone()),
+                    offset,
+                    compilation_unit: self.0.compilation_unit.clone(),
+                }))),
+                GreenElement::Token(green) => CstElement::Token(CstToken {
+                    green: green.clone(),
+                    parent: self.clone(),
+                    offset,
+                }),
+            });
+            offset += child.full_length();
+        }
+        children
+    }
+
+    /// The child nodes of the node, excluding tokens.
+    pub fn child_nodes(&self) -> Vec<CstNode> {
+        self.children().into_iter().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        }).collect()
+    }
+
+    /// All tokens within the node, in source order.
+    pub fn tokens(&self) -> Vec<CstToken> {
+        let mut tokens = vec![];
+        for child in self.children() {
+            match child {
+                CstElement::Node(node) => tokens.extend(node.tokens()),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    /// The source text of the node, including leading trivia.
+    pub fn text(&self) -> String {
+        self.0.green.to_string()
+    }
+
+    /// Returns the nodes, from the outermost to the innermost, whose location equals `location`.
+    /// Several nodes share a location when, for instance, an expression statement
+    /// consists of an expression only.
+    pub fn find_nodes(&self, location: &Location) -> Vec<CstNode> {
+        let mut nodes = vec![];
+        let mut node = Some(self.clone());
+        while let Some(node_1) = node {
+            if node_1.location() == *location {
+                nodes.push(node_1.clone());
+            }
+            node = node_1.child_nodes().into_iter().find(|child| {
+                let child_location = child.location();
+                child_location.first_offset() <= location.first_offset() && location.last_offset() <= child_location.last_offset()
+            });
+        }
+        nodes
+    }
+
+    /// Returns the node of a given kind whose location equals `location`.
+    pub fn find_node(&self, location: &Location, kind: CstKind) -> Option<CstNode> {
+        self.find_nodes(location).into_iter().find(|node| node.kind() == kind)
+    }
+
+    /// Returns the token whose text, excluding trivia, contains `offset`.
+    pub fn token_at_offset(&self, offset: usize) -> Option<CstToken> {
+        self.tokens().into_iter().find(|token| {
+            let location = token.location();
+            location.first_offset() <= offset && offset < location.last_offset()
+        })
+    }
+}
+
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.green.fmt(f)
+    }
+}
+
+/// Child of a concrete syntax tree node.
+#[derive(Clone)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+/// Token of the concrete syntax tree, positioned in the source text.
+#[derive(Clone)]
+pub struct CstToken {
+    green: Rc<GreenToken>,
+    parent: CstNode,
+    /// Offset at which the token starts, including leading trivia.
+    offset: usize,
+}
+
+impl CstToken {
+    pub fn token(&self) -> &Token {
+        self.green.token()
+    }
+
+    pub fn green(&self) -> &Rc<GreenToken> {
+        &self.green
+    }
+
+    pub fn parent(&self) -> CstNode {
+        self.parent.clone()
+    }
+
+    /// The source text of the token, excluding trivia.
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    /// Location of the token, excluding trivia.
+    pub fn location(&self) -> Location {
+        let first_offset = self.offset + self.green.leading_trivia_length();
+        Location::with_offsets(self.parent.compilation_unit(), first_offset, first_offset + self.green.text().len())
+    }
+
+    /// Location of the token, including its leading trivia.
+    pub fn full_location(&self) -> Location {
+        Location::with_offsets(self.parent.compilation_unit(), self.offset, self.offset + self.green.full_length())
+    }
+
+    /// The whitespace, line terminators and comments preceding the token.
+    pub fn leading_trivia(&self) -> Vec<Trivia> {
+        let mut offset = self.offset;
+        let mut trivia = vec![];
+        for green in self.green.leading_trivia() {
+            trivia.push(Trivia {
+                kind: green.kind(),
+                location: Location::with_offsets(self.parent.compilation_unit(), offset, offset + green.text().len()),
+            });
+            offset += green.text().len();
+        }
+        trivia
+    }
+}
@@ -0,0 +1,34 @@
+use crate::ns::*;
+use serde::{Serialize, Deserialize};
+
+/// Kind of trivia.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum TriviaKind {
+    /// A run of whitespace characters.
+    Whitespace,
+    /// A single line terminator, such as `\n` or `\r\n`.
+    LineTerminator,
+    /// A comment starting with `//`, excluding the line terminator that follows it.
+    SingleLineComment,
+    /// A comment delimited by `/*` and `*/`, including ASDoc comments.
+    MultiLineComment,
+    /// Characters that do not belong to any consumed token,
+    /// such as those skipped on a syntax error.
+    Skipped,
+}
+
+/// Whitespace, line terminator or comment recorded in lossless mode.
+#[derive(Clone)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub location: Location,
+}
+
+/// Token consumed by the parser in lossless mode,
+/// along with the trivia preceding it.
+#[derive(Clone)]
+pub struct LosslessToken {
+    pub token: Token,
+    pub location: Location,
+    pub leading_trivia: Vec<Trivia>,
+}
@@ -1,4 +1,8 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
 #[repr(i32)]
+#[derive(FromPrimitive)]
 #[derive(Eq, PartialEq, Clone, Copy)]
 pub enum DiagnosticKind {
     InvalidEscapeValue = 1024,
@@ -68,10 +72,52 @@ pub enum DiagnosticKind {
     ExpectingStatement = 1088,
     Unexpected = 1089,
     XmlClosingTagNameMustBeEquals = 1090,
+    UndefinedProperty = 1091,
+    TypeNotFound = 1092,
+    DefinitionNotFound = 1093,
+    PackageNotFound = 1094,
+    AmbiguousReference = 1095,
+    IncompatibleTypes = 1096,
+    IncorrectArgumentCount = 1097,
+    TooManyArguments = 1098,
+    UndefinedPropertyThroughReference = 1099,
+    ReturnValueMustBeUndefined = 1100,
+    NotAType = 1101,
+    IncorrectTypeArgumentCount = 1102,
+    EmptyStatementBody = 1103,
+    AssignmentWithinConditional = 1104,
+    IllogicalComparisonWithNaN = 1105,
+    MissingTypeDeclaration = 1106,
+    MissingReturnTypeDeclaration = 1107,
+    DuplicateVariableDefinition = 1108,
+    TaskComment = 1109,
+    LintMessage = 1110,
+    // 1111 is retired; it identified character references in embedded MXML code.
+    UnresolvedMxmlComponent = 1112,
+    UnknownMxmlState = 1113,
 }
 
 impl DiagnosticKind {
     pub fn id(&self) -> i32 {
         *self as i32
     }
-}
\ No newline at end of file
+
+    /// The diagnostic kind of an identifier, as returned by `id()`.
+    pub fn from_id(id: i32) -> Option<Self> {
+        FromPrimitive::from_i32(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiagnosticKind;
+
+    #[test]
+    fn test_from_id() {
+        for kind in [DiagnosticKind::InvalidEscapeValue, DiagnosticKind::UnresolvedMxmlComponent, DiagnosticKind::UnknownMxmlState] {
+            assert!(DiagnosticKind::from_id(kind.id()) == Some(kind));
+        }
+        // Retired identifiers are not reused.
+        assert!(DiagnosticKind::from_id(1111).is_none());
+    }
+}
@@ -16,7 +16,7 @@ pub struct Diagnostic {
     pub(crate) is_warning: bool,
     pub(crate) is_verify_error: bool,
     pub(crate) arguments: Vec<Rc<dyn DiagnosticArgument>>,
-    pub(crate) custom_kind: RefCell<Option<Rc<dyn Any>>>,
+    pub(crate) custom_kind: RefCell<Option<Rc<DynAny>>>,
 }
 
 impl Eq for Diagnostic {}
@@ -106,11 +106,11 @@ impl Diagnostic {
         self.kind.id()
     }
 
-    pub fn custom_kind(&self) -> Option<Rc<dyn Any>> {
+    pub fn custom_kind(&self) -> Option<Rc<DynAny>> {
         self.custom_kind.borrow().clone()
     }
 
-    pub fn set_custom_kind(&self, id: Option<Rc<dyn Any>>) {
+    pub fn set_custom_kind(&self, id: Option<Rc<DynAny>>) {
         self.custom_kind.replace(id);
     }
 
@@ -167,9 +167,14 @@ pub macro diagarg {
     ($($value:expr),*) => { vec![ $(Rc::new($value)),* ] },
 }
 
+#[cfg(not(feature = "sync"))]
 pub trait DiagnosticArgument: Any + ToString + 'static {
 }
 
+#[cfg(feature = "sync")]
+pub trait DiagnosticArgument: Any + ToString + Send + Sync + 'static {
+}
+
 impl DiagnosticArgument for String {}
 
 impl DiagnosticArgument for Token {}
\ No newline at end of file
@@ -71,6 +71,28 @@ lazy_static! {
         DiagnosticKind::ExpectingStatement.id() => "Expecting statement before {1}.".into(),
         DiagnosticKind::Unexpected.id() => "Unexpected {1}.".into(),
         DiagnosticKind::XmlClosingTagNameMustBeEquals.id() => "Closing tag name must be equals '{1}'.".into(),
+        DiagnosticKind::UndefinedProperty.id() => "Access of undefined property '{1}'.".into(),
+        DiagnosticKind::TypeNotFound.id() => "Type was not found: '{1}'.".into(),
+        DiagnosticKind::DefinitionNotFound.id() => "Definition was not found: '{1}'.".into(),
+        DiagnosticKind::PackageNotFound.id() => "Package was not found: '{1}'.".into(),
+        DiagnosticKind::AmbiguousReference.id() => "Ambiguous reference to '{1}'.".into(),
+        DiagnosticKind::IncompatibleTypes.id() => "Implicit coercion of a value of type '{1}' to an unrelated type '{2}'.".into(),
+        DiagnosticKind::IncorrectArgumentCount.id() => "Incorrect number of arguments. Expected {1}.".into(),
+        DiagnosticKind::TooManyArguments.id() => "Incorrect number of arguments. Expected no more than {1}.".into(),
+        DiagnosticKind::UndefinedPropertyThroughReference.id() => "Access of possibly undefined property '{1}' through a reference with static type '{2}'.".into(),
+        DiagnosticKind::ReturnValueMustBeUndefined.id() => "Return value must be undefined.".into(),
+        DiagnosticKind::NotAType.id() => "'{1}' is not a type.".into(),
+        DiagnosticKind::IncorrectTypeArgumentCount.id() => "Incorrect number of type arguments. Expected {1}.".into(),
+        DiagnosticKind::EmptyStatementBody.id() => "Empty statement used as the body of a statement.".into(),
+        DiagnosticKind::AssignmentWithinConditional.id() => "Assignment within conditional. Did you mean == instead of =?".into(),
+        DiagnosticKind::IllogicalComparisonWithNaN.id() => "Illogical comparison with NaN. Any comparison operation involving NaN will evaluate to false because NaN != NaN.".into(),
+        DiagnosticKind::MissingTypeDeclaration.id() => "'{1}' has no type declaration.".into(),
+        DiagnosticKind::MissingReturnTypeDeclaration.id() => "Return value for function '{1}' has no type declaration.".into(),
+        DiagnosticKind::DuplicateVariableDefinition.id() => "Duplicate variable definition '{1}'.".into(),
+        DiagnosticKind::TaskComment.id() => "Comment contains the task marker '{1}'.".into(),
+        DiagnosticKind::LintMessage.id() => "{1}".into(),
+        DiagnosticKind::UnknownMxmlState.id() => "Unknown state or state group '{1}'.".into(),
+        DiagnosticKind::UnresolvedMxmlComponent.id() => "Could not resolve <{1}> to a component implementation.".into(),
         // DiagnosticKind::K.id() => ".".into(),
     };
 }
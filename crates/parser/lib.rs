@@ -1,11 +1,29 @@
+//! ActionScript 3 parser, including MXML and CSS.
+//!
+//! # Features
+//!
+//! - `sync`: makes syntactic trees and compilation units `Send` and `Sync`,
+//!   so that `Project` parses source files in parallel. Without it, `Project`
+//!   reads source files in parallel, but parses them one after another
+//!   in the calling thread.
+
 #![feature(decl_macro)]
 #![feature(try_blocks)]
 
 pub mod tree;
+pub mod cst;
 pub mod compilation_unit;
 pub mod diagnostics;
 pub mod operator;
 pub mod parser;
+pub mod printer;
+pub mod semantics;
+pub mod lint;
+pub mod api;
+pub mod codegen;
+pub mod cache;
+pub mod project;
+pub mod config;
 pub mod util;
 
 pub mod ns;
\ No newline at end of file
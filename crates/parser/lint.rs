@@ -0,0 +1,13 @@
+//! Defines the lint framework, which reports warnings over the syntactic tree.
+//!
+//! A `LintRule` checks a `Program` and the comments of its compilation unit,
+//! reporting diagnostics through a `LintContext`. The `Linter` structure holds
+//! a registry of rules, including the built-in rules and any rule registered by
+//! another crate, along with the severity of each rule.
+
+mod lint_rule;
+pub use lint_rule::*;
+mod linter;
+pub use linter::*;
+mod builtin_rules;
+pub use builtin_rules::*;
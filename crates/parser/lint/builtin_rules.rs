@@ -0,0 +1,310 @@
+use crate::ns::*;
+
+/// Returns the built-in lint rules.
+pub fn builtin_lint_rules() -> Vec<Rc<dyn LintRule>> {
+    vec![
+        Rc::new(EmptyStatementBodyRule),
+        Rc::new(AssignmentWithinConditionalRule),
+        Rc::new(NanComparisonRule),
+        Rc::new(MissingTypeDeclarationRule),
+        Rc::new(DuplicateVariableDefinitionRule),
+        Rc::new(TaskCommentRule::default()),
+    ]
+}
+
+/// Reports an empty statement used as the body of an `if`, `while`,
+/// `for`, `for..in` or `with` statement, as in `if (x);`.
+pub struct EmptyStatementBodyRule;
+
+impl LintRule for EmptyStatementBodyRule {
+    fn name(&self) -> &str {
+        "empty-statement-body"
+    }
+
+    fn description(&self) -> &str {
+        "Reports an empty statement used as the body of a statement."
+    }
+
+    fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+        let _ = walk_program(&mut EmptyStatementBodyWalker(context), program);
+    }
+}
+
+struct EmptyStatementBodyWalker<'a, 'b>(&'a LintContext<'b>);
+
+impl EmptyStatementBodyWalker<'_, '_> {
+    fn check_body(&self, body: &Rc<Directive>) {
+        if let Directive::EmptyStatement(stmt) = body.as_ref() {
+            self.0.report(&stmt.location, DiagnosticKind::EmptyStatementBody, diagarg![]);
+        }
+    }
+}
+
+impl Visitor for EmptyStatementBodyWalker<'_, '_> {
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        match node.as_ref() {
+            Directive::IfStatement(stmt) => {
+                self.check_body(&stmt.consequent);
+                if let Some(alternative) = &stmt.alternative {
+                    self.check_body(alternative);
+                }
+            },
+            Directive::WhileStatement(stmt) => self.check_body(&stmt.body),
+            Directive::ForStatement(stmt) => self.check_body(&stmt.body),
+            Directive::ForInStatement(stmt) => self.check_body(&stmt.body),
+            Directive::WithStatement(stmt) => self.check_body(&stmt.body),
+            _ => {},
+        }
+        VisitorControl::Continue
+    }
+}
+
+/// Reports an unparenthesized assignment used as the test of a
+/// conditional statement or expression, as in `if (x = 0)`.
+///
+/// Wrapping the assignment in parentheses, as in `while ((x = next()))`,
+/// suppresses the warning.
+pub struct AssignmentWithinConditionalRule;
+
+impl LintRule for AssignmentWithinConditionalRule {
+    fn name(&self) -> &str {
+        "assignment-within-conditional"
+    }
+
+    fn warning_switch(&self) -> Option<&str> {
+        Some("warn-assignment-within-conditional")
+    }
+
+    fn description(&self) -> &str {
+        "Reports an assignment used as the test of a conditional."
+    }
+
+    fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+        let _ = walk_program(&mut AssignmentWithinConditionalWalker(context), program);
+    }
+}
+
+struct AssignmentWithinConditionalWalker<'a, 'b>(&'a LintContext<'b>);
+
+impl AssignmentWithinConditionalWalker<'_, '_> {
+    fn check_test(&self, test: &Rc<Expression>) {
+        if let Expression::Assignment(assignment) = test.as_ref() {
+            if assignment.compound.is_none() {
+                self.0.report(&assignment.location, DiagnosticKind::AssignmentWithinConditional, diagarg![]);
+            }
+        }
+    }
+}
+
+impl Visitor for AssignmentWithinConditionalWalker<'_, '_> {
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        match node.as_ref() {
+            Directive::IfStatement(stmt) => self.check_test(&stmt.test),
+            Directive::WhileStatement(stmt) => self.check_test(&stmt.test),
+            Directive::DoStatement(stmt) => self.check_test(&stmt.test),
+            Directive::ForStatement(stmt) => {
+                if let Some(test) = &stmt.test {
+                    self.check_test(test);
+                }
+            },
+            _ => {},
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        if let Expression::Conditional(conditional) = node.as_ref() {
+            self.check_test(&conditional.test);
+        }
+        VisitorControl::Continue
+    }
+}
+
+/// Reports an equality or relational comparison one of whose operands
+/// is the `NaN` identifier, which always evaluates to false
+/// (or true for `!=` and `!==`). `isNaN()` is to be used instead.
+pub struct NanComparisonRule;
+
+impl LintRule for NanComparisonRule {
+    fn name(&self) -> &str {
+        "illogical-comparison-with-nan"
+    }
+
+    fn warning_switch(&self) -> Option<&str> {
+        Some("warn-bad-nan-comparison")
+    }
+
+    fn description(&self) -> &str {
+        "Reports a comparison against NaN."
+    }
+
+    fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+        let _ = walk_program(&mut NanComparisonWalker(context), program);
+    }
+}
+
+struct NanComparisonWalker<'a, 'b>(&'a LintContext<'b>);
+
+impl Visitor for NanComparisonWalker<'_, '_> {
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        let Expression::Binary(binary) = node.as_ref() else {
+            return VisitorControl::Continue;
+        };
+        let comparison = matches!(binary.operator,
+            Operator::Equals | Operator::NotEquals | Operator::StrictEquals | Operator::StrictNotEquals |
+            Operator::Lt | Operator::Gt | Operator::Le | Operator::Ge);
+        if comparison && (is_nan_identifier(&binary.left) || is_nan_identifier(&binary.right)) {
+            self.0.report(&binary.location, DiagnosticKind::IllogicalComparisonWithNaN, diagarg![]);
+        }
+        VisitorControl::Continue
+    }
+}
+
+fn is_nan_identifier(exp: &Rc<Expression>) -> bool {
+    match exp.as_ref() {
+        Expression::QualifiedIdentifier(id) => {
+            id.qualifier.is_none() && !id.attribute && id.to_identifier_name().is_some_and(|(name, _)| name == "NaN")
+        },
+        Expression::Paren(paren) => is_nan_identifier(&paren.expression),
+        _ => false,
+    }
+}
+
+/// Reports a variable or parameter without a type annotation, and a
+/// function definition without a result type other than a constructor.
+///
+/// Destructuring patterns and rest parameters are not reported.
+pub struct MissingTypeDeclarationRule;
+
+impl LintRule for MissingTypeDeclarationRule {
+    fn name(&self) -> &str {
+        "missing-type-declaration"
+    }
+
+    fn warning_switch(&self) -> Option<&str> {
+        Some("warn-no-type-decl")
+    }
+
+    fn description(&self) -> &str {
+        "Reports a variable, parameter or function result without a type declaration."
+    }
+
+    fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+        let _ = walk_program(&mut MissingTypeDeclarationWalker(context), program);
+    }
+}
+
+struct MissingTypeDeclarationWalker<'a, 'b>(&'a LintContext<'b>);
+
+impl MissingTypeDeclarationWalker<'_, '_> {
+    fn check_destructuring(&self, destructuring: &TypedDestructuring) {
+        if destructuring.type_annotation.is_some() {
+            return;
+        }
+        if let Expression::QualifiedIdentifier(id) = destructuring.destructuring.as_ref() {
+            if let Some((name, location)) = id.to_identifier_name() {
+                self.0.report(&location, DiagnosticKind::MissingTypeDeclaration, diagarg![name]);
+            }
+        }
+    }
+}
+
+impl Visitor for MissingTypeDeclarationWalker<'_, '_> {
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        if let Directive::FunctionDefinition(defn) = node.as_ref() {
+            if !defn.is_constructor() && defn.common.signature.result_type.is_none() {
+                let (name, location) = defn.name_identifier();
+                self.0.report(&location, DiagnosticKind::MissingReturnTypeDeclaration, diagarg![name]);
+            }
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_variable_binding(&mut self, node: &Rc<VariableBinding>) -> VisitorControl {
+        self.check_destructuring(&node.destructuring);
+        VisitorControl::Continue
+    }
+
+    fn visit_parameter(&mut self, node: &Rc<Parameter>) -> VisitorControl {
+        if node.kind != ParameterKind::Rest {
+            self.check_destructuring(&node.destructuring);
+        }
+        VisitorControl::Continue
+    }
+}
+
+/// Reports a variable or constant declared more than once within
+/// the same scope, as in `var x; var x;`.
+pub struct DuplicateVariableDefinitionRule;
+
+impl LintRule for DuplicateVariableDefinitionRule {
+    fn name(&self) -> &str {
+        "duplicate-variable-definition"
+    }
+
+    fn warning_switch(&self) -> Option<&str> {
+        Some("warn-duplicate-variable-def")
+    }
+
+    fn description(&self) -> &str {
+        "Reports a variable declared more than once within the same scope."
+    }
+
+    fn check_program(&self, _program: &Rc<Program>, context: &LintContext) {
+        self.check_scope(&context.program_scope(), context);
+    }
+}
+
+impl DuplicateVariableDefinitionRule {
+    fn check_scope(&self, scope: &Rc<Scope>, context: &LintContext) {
+        let mut names = HashSet::<String>::new();
+        for symbol in scope.symbols() {
+            if !matches!(symbol.kind, SymbolKind::Variable | SymbolKind::Constant) {
+                continue;
+            }
+            if !names.insert(symbol.name.clone()) {
+                context.report(&symbol.location, DiagnosticKind::DuplicateVariableDefinition, diagarg![symbol.name.clone()]);
+            }
+        }
+        for child in scope.children() {
+            self.check_scope(&child, context);
+        }
+    }
+}
+
+/// Reports a comment containing a task marker, such as `TODO` or `FIXME`.
+///
+/// This rule is off by default.
+pub struct TaskCommentRule {
+    pub markers: Vec<String>,
+}
+
+impl Default for TaskCommentRule {
+    fn default() -> Self {
+        Self {
+            markers: vec!["TODO".into(), "FIXME".into()],
+        }
+    }
+}
+
+impl LintRule for TaskCommentRule {
+    fn name(&self) -> &str {
+        "task-comment"
+    }
+
+    fn description(&self) -> &str {
+        "Reports a comment containing a task marker."
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Off
+    }
+
+    fn check_comment(&self, comment: &Rc<Comment>, context: &LintContext) {
+        let content = comment.content();
+        let words: Vec<&str> = content.split(|ch: char| !(ch.is_alphanumeric() || ch == '_')).collect();
+        if let Some(marker) = self.markers.iter().find(|marker| words.contains(&marker.as_str())) {
+            context.report(&comment.location(), DiagnosticKind::TaskComment, diagarg![marker.clone()]);
+        }
+    }
+}
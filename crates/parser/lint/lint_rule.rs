@@ -0,0 +1,104 @@
+use crate::ns::*;
+
+/// A rule that checks programs for suspicious or non-idiomatic code.
+///
+/// Rules report diagnostics through the `LintContext` they are given,
+/// which applies the severity configured for the rule in the `Linter`.
+/// A rule defined outside of this crate reports its diagnostics through
+/// `LintContext::report_message()`, since it has no `DiagnosticKind` of its own.
+#[allow(unused_variables)]
+pub trait LintRule {
+    /// The unique name of the rule, in kebab case, such as `"assignment-within-conditional"`.
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    /// The warning switch of the ActionScript compiler corresponding to the rule,
+    /// such as `"warn-no-type-decl"`. See `Linter` for how it applies.
+    fn warning_switch(&self) -> Option<&str> {
+        None
+    }
+
+    /// Checks a program.
+    fn check_program(&self, program: &Rc<Program>, context: &LintContext) {}
+
+    /// Checks a comment of the compilation unit of the program or of
+    /// a compilation unit nested in it, such as an included file.
+    fn check_comment(&self, comment: &Rc<Comment>, context: &LintContext) {}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LintSeverity {
+    /// The rule is not run.
+    Off,
+    /// Diagnostics of the rule are reported as warnings.
+    Warning,
+    /// Diagnostics of the rule are reported as verify errors.
+    Error,
+}
+
+/// The context in which a lint rule checks a program.
+pub struct LintContext<'a> {
+    rule: &'a str,
+    severity: LintSeverity,
+    scopes: &'a TreeSemantics<Rc<Scope>>,
+    program_scope: Rc<Scope>,
+}
+
+impl<'a> LintContext<'a> {
+    pub(crate) fn new(rule: &'a str, severity: LintSeverity, scopes: &'a TreeSemantics<Rc<Scope>>, program_scope: Rc<Scope>) -> Self {
+        Self {
+            rule,
+            severity,
+            scopes,
+            program_scope,
+        }
+    }
+
+    /// The scopes of the program, as built by `ScopeBuilder`.
+    pub fn scopes(&self) -> &TreeSemantics<Rc<Scope>> {
+        self.scopes
+    }
+
+    /// The outermost scope of the program.
+    pub fn program_scope(&self) -> Rc<Scope> {
+        self.program_scope.clone()
+    }
+
+    /// Reports a diagnostic to the compilation unit of `location`, unless
+    /// the rule has reported a diagnostic of the same kind at the same offset.
+    ///
+    /// The custom kind of the diagnostic is set to the name of
+    /// the rule, as a `String`.
+    pub fn report(&self, location: &Location, kind: DiagnosticKind, arguments: Vec<Rc<dyn DiagnosticArgument>>) {
+        let cu = location.compilation_unit();
+        if self.has_reported(&cu, location, kind) {
+            return;
+        }
+        let diagnostic = match self.severity {
+            LintSeverity::Off => return,
+            LintSeverity::Warning => Diagnostic::new_warning(location, kind, arguments),
+            LintSeverity::Error => Diagnostic::new_verify_error(location, kind, arguments),
+        };
+        diagnostic.set_custom_kind(Some(Rc::new(self.rule.to_owned())));
+        cu.add_diagnostic(diagnostic);
+    }
+
+    fn has_reported(&self, cu: &Rc<CompilationUnit>, location: &Location, kind: DiagnosticKind) -> bool {
+        cu.diagnostics.borrow().iter().any(|diagnostic| {
+            diagnostic.location.first_offset == location.first_offset
+                && diagnostic.kind == kind
+                && diagnostic.custom_kind.borrow().as_ref().and_then(|rule| rule.downcast_ref::<String>()).is_some_and(|rule| rule == self.rule)
+        })
+    }
+
+    /// Reports a diagnostic whose message is the given text, as
+    /// a `DiagnosticKind::LintMessage` diagnostic.
+    pub fn report_message(&self, location: &Location, message: impl Into<String>) {
+        self.report(location, DiagnosticKind::LintMessage, diagarg![message.into()]);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::ns::*;
+
+/// Runs a registry of lint rules over programs.
+///
+/// `Linter::default()` registers the built-in rules, as returned by
+/// `builtin_lint_rules()`, whereas `Linter::new()` registers no rules.
+/// Each rule runs with its default severity unless overriden through
+/// `set_severity()`.
+///
+/// A rule that runs as a warning without an overriden severity is skipped for
+/// a program whose compilation unit has compiler options switching its warning off,
+/// either through its `LintRule::warning_switch()` or through `"warnings"`.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, "if (x = 0) {}".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let linter = Linter::default();
+/// linter.lint_program(&program);
+/// assert_eq!(cu.diagnostics()[0].format_english(), "1:5: Warning #1104: Assignment within conditional. Did you mean == instead of =?");
+/// ```
+pub struct Linter {
+    rules: Vec<Rc<dyn LintRule>>,
+    severities: HashMap<String, LintSeverity>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        let mut linter = Self::new();
+        for rule in builtin_lint_rules() {
+            linter.register(rule);
+        }
+        linter
+    }
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            severities: HashMap::new(),
+        }
+    }
+
+    /// Registers a rule, replacing any registered rule of the same name.
+    pub fn register(&mut self, rule: Rc<dyn LintRule>) {
+        self.rules.retain(|registered| registered.name() != rule.name());
+        self.rules.push(rule);
+    }
+
+    /// The registered rules, in registration order.
+    pub fn rules(&self) -> Vec<Rc<dyn LintRule>> {
+        self.rules.clone()
+    }
+
+    pub fn rule(&self, name: &str) -> Option<Rc<dyn LintRule>> {
+        self.rules.iter().find(|rule| rule.name() == name).cloned()
+    }
+
+    /// The severity of the rule named `name`, or `LintSeverity::Off`
+    /// if no such rule is registered.
+    pub fn severity(&self, name: &str) -> LintSeverity {
+        if let Some(severity) = self.severities.get(name) {
+            return *severity;
+        }
+        self.rule(name).map_or(LintSeverity::Off, |rule| rule.default_severity())
+    }
+
+    pub fn set_severity(&mut self, name: &str, severity: LintSeverity) {
+        self.severities.insert(name.to_owned(), severity);
+    }
+
+    /// Runs the rules whose severity is not `LintSeverity::Off` over a program
+    /// and the comments of its compilation unit and of the compilation units
+    /// nested in it, such as included files.
+    pub fn lint_program(&self, program: &Rc<Program>) {
+        let scopes = TreeSemantics::new();
+        let program_scope = ScopeBuilder::new(&scopes).build_program(program);
+        let compilation_unit = program.location.compilation_unit();
+        let comments: Vec<Rc<Comment>> = compilation_units_in_pre_order(&compilation_unit).iter().flat_map(|unit| unit.comments()).collect();
+        let compiler_options = compilation_unit.compiler_options();
+        for rule in &self.rules {
+            let severity = self.severity(rule.name());
+            if severity == LintSeverity::Off {
+                continue;
+            }
+            let switched_off = compiler_options.as_ref().is_some_and(|options| {
+                !options.is_warning_enabled(rule.warning_switch().unwrap_or("warnings"))
+            });
+            if severity == LintSeverity::Warning && switched_off && !self.severities.contains_key(rule.name()) {
+                continue;
+            }
+            let context = LintContext::new(rule.name(), severity, &scopes, program_scope.clone());
+            rule.check_program(program, &context);
+            for comment in &comments {
+                rule.check_comment(comment, &context);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn lint(linter: &Linter, text: &str) -> Vec<String> {
+        let cu = CompilationUnit::new(None, text.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(cu.nested_diagnostics().is_empty(), "{text}");
+        linter.lint_program(&program);
+        cu.sort_diagnostics();
+        cu.diagnostics().iter().map(|diagnostic| {
+            let rule = diagnostic.custom_kind().unwrap().downcast::<String>().unwrap();
+            format!("{rule} {} {}", diagnostic.location().text(), diagnostic.format_english())
+        }).collect()
+    }
+
+    #[test]
+    fn test_builtin_rules() {
+        let source = r#"
+            // TODO: remove this.
+            var a = 0, b: Number;
+            function f(c, d: Number) {
+                var b: int;
+                var b: int;
+                if (a = 1);
+                while ((a = 2)) {}
+                for (var i: int = 0; a == NaN; i++);
+            }
+            function g(): void {}
+            var h: Function = function() {};
+        "#;
+        let mut linter = Linter::default();
+        linter.set_severity("task-comment", LintSeverity::Warning);
+        linter.set_severity("missing-type-declaration", LintSeverity::Error);
+        assert_eq!(lint(&linter, source), [
+            "task-comment // TODO: remove this. 2:13: Warning #1109: Comment contains the task marker 'TODO'.",
+            "missing-type-declaration a 3:17: Verify error #1106: 'a' has no type declaration.",
+            "missing-type-declaration f 4:22: Verify error #1107: Return value for function 'f' has no type declaration.",
+            "missing-type-declaration c 4:24: Verify error #1106: 'c' has no type declaration.",
+            "duplicate-variable-definition b 6:21: Warning #1108: Duplicate variable definition 'b'.",
+            "assignment-within-conditional a = 1 7:21: Warning #1104: Assignment within conditional. Did you mean == instead of =?",
+            "empty-statement-body ; 7:27: Warning #1103: Empty statement used as the body of a statement.",
+            "illogical-comparison-with-nan a == NaN 9:38: Warning #1105: Illogical comparison with NaN. Any comparison operation involving NaN will evaluate to false because NaN != NaN.",
+            "empty-statement-body ; 9:52: Warning #1103: Empty statement used as the body of a statement.",
+        ]);
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        struct NoWithRule;
+        impl LintRule for NoWithRule {
+            fn name(&self) -> &str {
+                "no-with"
+            }
+            fn description(&self) -> &str {
+                "Disallows the with statement."
+            }
+            fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+                for directive in &program.directives {
+                    if let Directive::WithStatement(stmt) = directive.as_ref() {
+                        context.report_message(&stmt.location, "Unexpected with statement.");
+                    }
+                }
+            }
+        }
+        let mut linter = Linter::new();
+        linter.register(Rc::new(NoWithRule));
+        assert_eq!(linter.severity("no-with"), LintSeverity::Warning);
+        assert_eq!(linter.severity("assignment-within-conditional"), LintSeverity::Off);
+        assert_eq!(lint(&linter, "with (o) {} if (a = b) {}"), ["no-with with (o) {} 1:1: Warning #1110: Unexpected with statement."]);
+
+        linter.set_severity("no-with", LintSeverity::Off);
+        assert!(lint(&linter, "with (o) {}").is_empty());
+    }
+
+    #[test]
+    fn test_warning_switches() {
+        let source = "var a = 0; if (a = 1) {}";
+        let cu = CompilationUnit::new(None, source.into());
+        let mut warnings = std::collections::BTreeMap::new();
+        warnings.insert("warn-no-type-decl".to_owned(), false);
+        cu.set_compiler_options(Some(Rc::new(CompilerOptions { warnings, ..default() })));
+        let program = ParserFacade(&cu, default()).parse_program();
+        Linter::default().lint_program(&program);
+        let rules = |cu: &Rc<CompilationUnit>| cu.diagnostics().iter().map(|diagnostic| diagnostic.custom_kind().unwrap().downcast::<String>().unwrap().to_string()).collect::<Vec<_>>();
+        assert_eq!(rules(&cu), ["assignment-within-conditional"]);
+
+        // An overriden severity takes precedence, and "warnings" switches off every warning.
+        let cu = CompilationUnit::new(None, source.into());
+        let mut warnings = std::collections::BTreeMap::new();
+        warnings.insert("warnings".to_owned(), false);
+        cu.set_compiler_options(Some(Rc::new(CompilerOptions { warnings, ..default() })));
+        let program = ParserFacade(&cu, default()).parse_program();
+        let mut linter = Linter::default();
+        linter.set_severity("missing-type-declaration", LintSeverity::Warning);
+        linter.lint_program(&program);
+        assert_eq!(rules(&cu), ["missing-type-declaration"]);
+    }
+
+    #[test]
+    fn test_equal_offset_diagnostics() {
+        struct RepeatingRule(&'static str);
+        impl LintRule for RepeatingRule {
+            fn name(&self) -> &str {
+                self.0
+            }
+            fn description(&self) -> &str {
+                "Reports the first directive twice."
+            }
+            fn check_program(&self, program: &Rc<Program>, context: &LintContext) {
+                for message in ["First.", "Second."] {
+                    context.report_message(&program.directives[0].location(), message);
+                }
+                context.report(&program.directives[0].location(), DiagnosticKind::EmptyStatementBody, vec![]);
+            }
+        }
+        let mut linter = Linter::new();
+        linter.register(Rc::new(RepeatingRule("first")));
+        linter.register(Rc::new(RepeatingRule("second")));
+        assert_eq!(lint(&linter, "x;"), [
+            "first x; 1:1: Warning #1110: First.",
+            "first x; 1:1: Warning #1103: Empty statement used as the body of a statement.",
+            "second x; 1:1: Warning #1110: First.",
+            "second x; 1:1: Warning #1103: Empty statement used as the body of a statement.",
+        ]);
+    }
+
+    #[test]
+    fn test_included_comments() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_linter_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("included.as"), "// TODO: included.\ny = 1;").unwrap();
+        let path = directory.join("Main.as").to_string_lossy().into_owned();
+        let cu = CompilationUnit::new(Some(path), "include 'included.as';".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let mut linter = Linter::new();
+        linter.register(Rc::new(TaskCommentRule::default()));
+        linter.set_severity("task-comment", LintSeverity::Warning);
+        linter.lint_program(&program);
+        assert!(cu.diagnostics().is_empty());
+        let included = &cu.nested_compilation_units()[0];
+        let diagnostics: Vec<(String, i32)> = included.diagnostics().iter().map(|diagnostic| (diagnostic.location().text(), diagnostic.id())).collect();
+        assert_eq!(diagnostics, [("// TODO: included.".to_owned(), DiagnosticKind::TaskComment.id())]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
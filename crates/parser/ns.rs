@@ -1,8 +1,17 @@
 //! The `ns` module is an union of all of the parser modules.
 
 pub use crate::tree::*;
+pub use crate::cst::*;
 pub use crate::compilation_unit::*;
 pub use crate::diagnostics::*;
 pub use crate::operator::*;
 pub use crate::parser::*;
+pub use crate::printer::*;
+pub use crate::semantics::*;
+pub use crate::lint::*;
+pub use crate::api::*;
+pub use crate::codegen::*;
+pub use crate::cache::*;
+pub use crate::project::*;
+pub use crate::config::*;
 pub use crate::util::*;
\ No newline at end of file
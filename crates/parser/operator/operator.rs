@@ -51,6 +51,57 @@ pub enum Operator {
     NullCoalescing,
 }
 
+impl Operator {
+    /// Returns the operator as it appears in ActionScript source,
+    /// such as `"+"`, `"typeof"` or `"not in"`.
+    pub fn source_text(&self) -> &'static str {
+        match self {
+            Self::PostIncrement | Self::PreIncrement => "++",
+            Self::PostDecrement | Self::PreDecrement => "--",
+            Self::NonNull | Self::LogicalNot => "!",
+            Self::Delete => "delete",
+            Self::Void => "void",
+            Self::Typeof => "typeof",
+            Self::Await => "await",
+            Self::Yield => "yield",
+            Self::Positive => "+",
+            Self::Negative => "-",
+            Self::BitwiseNot => "~",
+
+            Self::Power => "**",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Remainder => "%",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::ShiftRightUnsigned => ">>>",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::Le => "<=",
+            Self::Ge => ">=",
+            Self::Instanceof => "instanceof",
+            Self::In => "in",
+            Self::NotIn => "not in",
+            Self::Is => "is",
+            Self::IsNot => "is not",
+            Self::As => "as",
+            Self::Equals => "==",
+            Self::NotEquals => "!=",
+            Self::StrictEquals => "===",
+            Self::StrictNotEquals => "!==",
+            Self::BitwiseAnd => "&",
+            Self::BitwiseXor => "^",
+            Self::BitwiseOr => "|",
+            Self::LogicalAnd => "&&",
+            Self::LogicalXor => "^^",
+            Self::LogicalOr => "||",
+            Self::NullCoalescing => "??",
+        }
+    }
+}
+
 /// Represents binary operator associativity.
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum BinaryAssociativity {
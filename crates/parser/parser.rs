@@ -11,6 +11,8 @@ mod reserved_word;
 pub use reserved_word::*;
 mod parser;
 pub use parser::*;
+mod incremental_parser;
+pub use incremental_parser::*;
 mod css_parser;
 pub use css_parser::*;
 mod css_tokenizer;
@@ -0,0 +1,705 @@
+use crate::ns::*;
+
+/// Replacement of the characters within a byte range of a source text.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TextEdit {
+    /// The first and last byte indices of the replaced characters.
+    pub range: (usize, usize),
+    /// The replacement text.
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: (usize, usize), text: &str) -> Self {
+        Self {
+            range,
+            text: text.to_owned(),
+        }
+    }
+
+    /// Applies a sequence of edits to a text, each edit referring
+    /// to the text resulting from the previous edits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of an edit is out of bounds or does not
+    /// lie on character boundaries.
+    pub fn apply_all(text: &str, edits: &[TextEdit]) -> String {
+        let mut text = text.to_owned();
+        for edit in edits {
+            text.replace_range(edit.range.0..edit.range.1, &edit.text);
+        }
+        text
+    }
+}
+
+/// Package or directive of a previously parsed list.
+struct TopLevelItem {
+    /// Offset of the first token.
+    first_offset: usize,
+    /// Offset at which the last token ends.
+    last_offset: usize,
+    package: bool,
+}
+
+/// Packages and directives parsed again in part: the top level
+/// of a program, or the directives of a package or class block.
+struct DirectiveList<'a> {
+    packages: &'a [Rc<PackageDefinition>],
+    directives: &'a [Rc<Directive>],
+    context: ParserDirectiveContext,
+    /// Offset at which the list begins.
+    first_offset: usize,
+    /// Offset of the `}` closing the block, if any.
+    close_offset: Option<usize>,
+}
+
+/// Result of parsing a `DirectiveList` again.
+struct ReparsedList {
+    packages: Vec<Rc<PackageDefinition>>,
+    directives: Vec<Rc<Directive>>,
+    /// Index of the first item parsed again.
+    start: usize,
+    /// Location of the first token parsed again.
+    first_token: Location,
+    /// Location of the last token parsed again.
+    last_token: Location,
+    /// Whether the items following the region were reused.
+    resumed: bool,
+    /// Number of reused items.
+    reused: usize,
+}
+
+/// Package or class definition whose block encloses the edits.
+enum EnclosingDefinition {
+    Package(Rc<PackageDefinition>),
+    Class(Rc<Directive>),
+}
+
+impl EnclosingDefinition {
+    fn location(&self) -> Location {
+        match self {
+            Self::Package(defn) => defn.location.clone(),
+            Self::Class(directive) => directive.location(),
+        }
+    }
+
+    fn block(&self) -> &Rc<Block> {
+        match self {
+            Self::Package(defn) => &defn.block,
+            Self::Class(directive) => {
+                let Directive::ClassDefinition(defn) = directive.as_ref() else {
+                    unreachable!();
+                };
+                &defn.block
+            },
+        }
+    }
+
+    fn context(&self) -> ParserDirectiveContext {
+        match self {
+            Self::Package(_) => ParserDirectiveContext::PackageBlock,
+            Self::Class(directive) => {
+                let Directive::ClassDefinition(defn) = directive.as_ref() else {
+                    unreachable!();
+                };
+                ParserDirectiveContext::ClassBlock {
+                    name: defn.name.0.clone(),
+                }
+            },
+        }
+    }
+
+    /// Rebinds the definition, replacing the directives of its block
+    /// and moving the end of the definition by `delta` bytes.
+    fn rebind(&self, rebinder: &mut LocationRebinder, new_cu: &Rc<CompilationUnit>, delta: isize, directives: Vec<Rc<Directive>>) -> Self {
+        let move_end = |location: &Location| Location::with_offsets(new_cu, location.first_offset, location.last_offset.saturating_add_signed(delta));
+        let block = self.block();
+        let empty_block = Rc::new(Block {
+            location: block.location.clone(),
+            directives: vec![],
+        });
+        let new_block = Rc::new(Block {
+            location: move_end(&block.location),
+            directives,
+        });
+        match self {
+            Self::Package(defn) => {
+                let mut new_defn = Rc::new(PackageDefinition {
+                    block: empty_block,
+                    ..defn.as_ref().clone()
+                });
+                rebinder.rebind_package_definition(&mut new_defn);
+                let new_defn_mut = Rc::make_mut(&mut new_defn);
+                new_defn_mut.location = move_end(&defn.location);
+                new_defn_mut.block = new_block;
+                Self::Package(new_defn)
+            },
+            Self::Class(directive) => {
+                let Directive::ClassDefinition(defn) = directive.as_ref() else {
+                    unreachable!();
+                };
+                let mut new_directive = Rc::new(Directive::ClassDefinition(ClassDefinition {
+                    block: empty_block,
+                    ..defn.clone()
+                }));
+                rebinder.rebind_directive(&mut new_directive);
+                if let Directive::ClassDefinition(new_defn) = Rc::make_mut(&mut new_directive) {
+                    new_defn.location = move_end(&defn.location);
+                    new_defn.block = new_block;
+                }
+                Self::Class(new_directive)
+            },
+        }
+    }
+}
+
+impl<'input> ParserFacade<'input> {
+    /// Parses `Program` after applying text edits to the compilation unit,
+    /// given the program previously parsed from the compilation unit.
+    ///
+    /// Only the packages and directives affected by the edits are parsed again,
+    /// within the innermost package or class block enclosing the edits; the
+    /// remaining ones are reused and have their locations rebound to the new
+    /// compilation unit. The result, including the diagnostics and comments of
+    /// the new compilation unit, is identical to that of parsing the edited text
+    /// with `ParserFacade::parse_program()`.
+    ///
+    /// Returns the new compilation unit along with the new program.
+    ///
+    /// ```
+    /// use as3_parser::ns::*;
+    ///
+    /// let cu = CompilationUnit::new(None, "x = 1;\ny = 2;".into());
+    /// let program = ParserFacade(&cu, default()).parse_program();
+    ///
+    /// let (new_cu, new_program) = ParserFacade(&cu, default()).reparse_program(&program, &[TextEdit::new((11, 12), "20")]);
+    /// assert_eq!(new_cu.text(), "x = 1;\ny = 20;");
+    /// assert_eq!(new_program.directives[1].location().text(), "y = 20;");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of an edit is out of bounds or does not
+    /// lie on character boundaries.
+    pub fn reparse_program(&self, program: &Rc<Program>, edits: &[TextEdit]) -> (Rc<CompilationUnit>, Rc<Program>) {
+        let (new_cu, new_program, _) = self.reparse_program_counting_reuse(program, edits);
+        (new_cu, new_program)
+    }
+
+    /// Same as `reparse_program()`, additionally returning the number of
+    /// packages and directives reused from the previous program.
+    pub(crate) fn reparse_program_counting_reuse(&self, program: &Rc<Program>, edits: &[TextEdit]) -> (Rc<CompilationUnit>, Rc<Program>, usize) {
+        let old_cu = self.0;
+        let new_text = TextEdit::apply_all(old_cu.text(), edits);
+        let new_compilation_unit = || {
+            let new_cu = CompilationUnit::new(old_cu.file_path(), new_text.clone());
+            new_cu.set_compiler_options(old_cu.compiler_options());
+            new_cu
+        };
+
+        // Included compilation units and partial parses are always parsed again.
+        if old_cu.nested_compilation_units().is_empty() && self.1.byte_range.is_none() {
+            let new_cu = new_compilation_unit();
+            if let Some((new_program, reused)) = self.reparse_program_region(program, &new_cu) {
+                return (new_cu, new_program, reused);
+            }
+        }
+        let new_cu = new_compilation_unit();
+        let new_program = ParserFacade(&new_cu, ParserOptions {
+            ignore_xml_whitespace: self.1.ignore_xml_whitespace,
+            ..default()
+        }).parse_program();
+        (new_cu, new_program, 0)
+    }
+
+    fn reparse_program_region(&self, program: &Rc<Program>, new_cu: &Rc<CompilationUnit>) -> Option<(Rc<Program>, usize)> {
+        let old_cu = self.0;
+        let old_text = old_cu.text();
+        let delta = new_cu.text().len() as isize - old_text.len() as isize;
+        let edit_range = changed_range(old_text, new_cu.text());
+
+        // Parse again within the innermost package or class block enclosing
+        // the edits, provided that no error was reported within the outermost one.
+        let mut enclosing = enclosing_definitions(program, old_text, edit_range);
+        if let Some((_, outermost)) = enclosing.first() {
+            let location = outermost.location();
+            if old_cu.diagnostics().iter().any(|diagnostic| (location.first_offset..=location.last_offset).contains(&diagnostic.location.first_offset)) {
+                enclosing.clear();
+            }
+        }
+        let Some((mut index, innermost)) = enclosing.last() else {
+            return self.reparse_top_level(program, new_cu, delta, edit_range);
+        };
+
+        let block = innermost.block();
+        let reparsed = self.reparse_directive_list(DirectiveList {
+            packages: &[],
+            directives: &block.directives,
+            context: innermost.context(),
+            first_offset: block.location.first_offset + 1,
+            close_offset: Some(block.location.last_offset - 1),
+        }, new_cu, delta, edit_range)?;
+        let mut reused = reparsed.reused;
+
+        // Rebuild the enclosing definitions, which end `delta` bytes further.
+        let mut prefix_rebinder = LocationRebinder::new(old_cu, new_cu, 0);
+        let mut suffix_rebinder = LocationRebinder::new(old_cu, new_cu, delta);
+        let mut definition = innermost.rebind(&mut prefix_rebinder, new_cu, delta, reparsed.directives);
+        for (outer_index, outer) in enclosing.iter().rev().skip(1) {
+            let EnclosingDefinition::Class(class) = definition else {
+                unreachable!();
+            };
+            let outer_directives = &outer.block().directives;
+            let directives = replace_and_rebind(outer_directives, index, class, |directive, follows| {
+                if follows {
+                    suffix_rebinder.rebind_directive(directive);
+                } else {
+                    prefix_rebinder.rebind_directive(directive);
+                }
+            });
+            reused += outer_directives.len() - 1;
+            definition = outer.rebind(&mut prefix_rebinder, new_cu, delta, directives);
+            index = *outer_index;
+        }
+
+        let package_count = program.packages.len();
+        let (packages, directives) = match definition {
+            EnclosingDefinition::Package(package) => {
+                let packages = replace_and_rebind(&program.packages, index, package, |package, follows| {
+                    if follows {
+                        suffix_rebinder.rebind_package_definition(package);
+                    } else {
+                        prefix_rebinder.rebind_package_definition(package);
+                    }
+                });
+                let mut directives = program.directives.clone();
+                for directive in &mut directives {
+                    suffix_rebinder.rebind_directive(directive);
+                }
+                (packages, directives)
+            },
+            EnclosingDefinition::Class(class) => {
+                let mut packages = program.packages.clone();
+                for package in &mut packages {
+                    prefix_rebinder.rebind_package_definition(package);
+                }
+                let directives = replace_and_rebind(&program.directives, index - package_count, class, |directive, follows| {
+                    if follows {
+                        suffix_rebinder.rebind_directive(directive);
+                    } else {
+                        prefix_rebinder.rebind_directive(directive);
+                    }
+                });
+                (packages, directives)
+            },
+        };
+        reused += package_count + program.directives.len() - 1;
+
+        Some((Rc::new(Program {
+            location: Location::with_offsets(new_cu, program.location.first_offset, program.location.last_offset.checked_add_signed(delta)?),
+            packages,
+            directives,
+        }), reused))
+    }
+
+    fn reparse_top_level(&self, program: &Rc<Program>, new_cu: &Rc<CompilationUnit>, delta: isize, edit_range: (usize, usize)) -> Option<(Rc<Program>, usize)> {
+        if program.packages.is_empty() && program.directives.is_empty() {
+            return None;
+        }
+        let reparsed = self.reparse_directive_list(DirectiveList {
+            packages: &program.packages,
+            directives: &program.directives,
+            context: ParserDirectiveContext::TopLevel,
+            first_offset: 0,
+            close_offset: None,
+        }, new_cu, delta, edit_range)?;
+
+        let first_offset = if reparsed.start == 0 { reparsed.first_token.first_offset } else { program.location.first_offset };
+        let last_offset = if reparsed.resumed { program.location.last_offset.checked_add_signed(delta)? } else { reparsed.last_token.last_offset };
+        let location = if reparsed.start == 0 && reparsed.packages.is_empty() && reparsed.directives.is_empty() {
+            reparsed.first_token
+        } else {
+            Location::with_offsets(new_cu, first_offset, last_offset)
+        };
+        Some((Rc::new(Program {
+            location,
+            packages: reparsed.packages,
+            directives: reparsed.directives,
+        }), reparsed.reused))
+    }
+
+    fn reparse_directive_list(&self, list: DirectiveList, new_cu: &Rc<CompilationUnit>, delta: isize, (edit_first_offset, edit_last_offset): (usize, usize)) -> Option<ReparsedList> {
+        let old_cu = self.0;
+        let items = list_items(list.packages, list.directives)?;
+        let old_diagnostics = old_cu.diagnostics();
+        let has_diagnostic_within = |first_offset: usize, last_offset: usize| {
+            old_diagnostics.iter().any(|diagnostic| {
+                let offset = diagnostic.location.first_offset;
+                first_offset <= offset && offset <= last_offset
+            })
+        };
+        // Whether the errors preceding `offset` were all reported before the remaining errors.
+        let splits_diagnostics = |offset: usize| {
+            old_diagnostics.iter().skip_while(|diagnostic| diagnostic.location.first_offset < offset).all(|diagnostic| diagnostic.location.first_offset >= offset)
+        };
+
+        // Parse again starting with the item preceding the first affected item,
+        // whose extent depends on the token that follows it, and continue backwards
+        // while an error precedes the first token of the item or errors of
+        // preceding items were reported after errors of the region.
+        let first_affected = items.iter().position(|item| item.last_offset >= edit_first_offset).unwrap_or(items.len());
+        let mut start = first_affected.saturating_sub(1);
+        while start > 0 && (has_diagnostic_within(items[start - 1].last_offset, items[start].first_offset) || !splits_diagnostics(items[start - 1].last_offset)) {
+            start -= 1;
+        }
+        let region_offset = if start == 0 { list.first_offset } else { items[start - 1].last_offset };
+
+        // Items before the region are unchanged.
+        let mut prefix_rebinder = LocationRebinder::new(old_cu, new_cu, 0);
+        for comment in old_cu.comments.borrow().iter() {
+            if comment.location.borrow().first_offset < region_offset {
+                new_cu.comments.borrow_mut().push(prefix_rebinder.rebind_comment(comment));
+            }
+        }
+        for diagnostic in old_diagnostics.iter().filter(|diagnostic| diagnostic.location.first_offset < region_offset) {
+            new_cu.add_diagnostic(prefix_rebinder.rebind_diagnostic(diagnostic));
+        }
+
+        let package_count = list.packages.len();
+        let mut parser = Parser::new(new_cu, &ParserOptions {
+            ignore_xml_whitespace: self.1.ignore_xml_whitespace,
+            byte_range: Some((region_offset, new_cu.text().len())),
+            ..default()
+        });
+
+        // Stop at an item following the edits whose preceding gap is unchanged
+        // and free of errors.
+        let mut resumption: Option<usize> = None;
+        let region = parser.parse_program_region(list.context, list.close_offset.is_none() && start <= package_count, |previous_token, token, packages| {
+            let Some(old_offset) = token.first_offset.checked_add_signed(-delta) else {
+                return false;
+            };
+            let Ok(index) = items.binary_search_by_key(&old_offset, |item| item.first_offset) else {
+                return false;
+            };
+            if index <= start || items[index].package != packages {
+                return false;
+            }
+            let gap_offset = items[index - 1].last_offset;
+            if gap_offset < edit_last_offset || previous_token.last_offset.checked_add_signed(-delta) != Some(gap_offset) {
+                return false;
+            }
+            if has_diagnostic_within(gap_offset, old_offset) || !splits_diagnostics(gap_offset) || new_cu.diagnostics.borrow().iter().any(|diagnostic| diagnostic.location.first_offset >= previous_token.last_offset) {
+                return false;
+            }
+            resumption = Some(index);
+            true
+        });
+
+        // Without resuming, a block must still be closed by the same `}`.
+        let gap_offset = match (resumption, list.close_offset) {
+            (Some(resumption), _) => Some(items[resumption - 1].last_offset),
+            (None, Some(close_offset)) => {
+                if region.next_token.first_offset.checked_add_signed(-delta) != Some(close_offset) || !splits_diagnostics(close_offset) || new_cu.diagnostics.borrow().iter().any(|diagnostic| diagnostic.location.first_offset >= region.last_token.last_offset) {
+                    return None;
+                }
+                Some(close_offset)
+            },
+            (None, None) => None,
+        };
+
+        let mut packages: Vec<Rc<PackageDefinition>> = list.packages[..start.min(package_count)].to_vec();
+        let mut directives: Vec<Rc<Directive>> = list.directives[..start.saturating_sub(package_count)].to_vec();
+        for package in &mut packages {
+            prefix_rebinder.rebind_package_definition(package);
+        }
+        for directive in &mut directives {
+            prefix_rebinder.rebind_directive(directive);
+        }
+        packages.extend(region.packages);
+        directives.extend(region.directives);
+        let mut reused = start;
+
+        // Items after the region are unchanged, except for their offsets.
+        let mut suffix_rebinder = LocationRebinder::new(old_cu, new_cu, delta);
+        if let Some(gap_offset) = gap_offset {
+            let new_gap_offset = gap_offset.checked_add_signed(delta)?;
+            new_cu.comments.borrow_mut().retain(|comment| comment.location.borrow().first_offset < new_gap_offset);
+            for comment in old_cu.comments.borrow().iter() {
+                if comment.location.borrow().first_offset >= gap_offset {
+                    new_cu.comments.borrow_mut().push(suffix_rebinder.rebind_comment(comment));
+                }
+            }
+            for diagnostic in old_diagnostics.iter().filter(|diagnostic| diagnostic.location.first_offset >= gap_offset) {
+                new_cu.add_diagnostic(suffix_rebinder.rebind_diagnostic(diagnostic));
+            }
+        }
+        if let Some(resumption) = resumption {
+            for package in &list.packages[resumption.min(package_count)..] {
+                let mut package = package.clone();
+                suffix_rebinder.rebind_package_definition(&mut package);
+                packages.push(package);
+            }
+            for directive in &list.directives[resumption.saturating_sub(package_count)..] {
+                let mut directive = directive.clone();
+                suffix_rebinder.rebind_directive(&mut directive);
+                directives.push(directive);
+            }
+            reused += items.len() - resumption;
+        }
+
+        Some(ReparsedList {
+            packages,
+            directives,
+            start,
+            first_token: region.first_token,
+            last_token: region.last_token,
+            resumed: resumption.is_some(),
+            reused,
+        })
+    }
+}
+
+/// Returns the package definition and class definition whose blocks enclose
+/// the edits, from the outermost to the innermost one, each along with its
+/// index among the items of the enclosing list.
+fn enclosing_definitions(program: &Program, old_text: &str, (edit_first_offset, edit_last_offset): (usize, usize)) -> Vec<(usize, EnclosingDefinition)> {
+    let bytes = old_text.as_bytes();
+    let encloses = |block: &Block| {
+        let location = &block.location;
+        location.first_offset < edit_first_offset && edit_last_offset < location.last_offset
+            && bytes.get(location.first_offset) == Some(&b'{') && bytes.get(location.last_offset - 1) == Some(&b'}')
+    };
+    let mut enclosing = vec![];
+    if let Some(index) = program.packages.iter().position(|defn| encloses(&defn.block)) {
+        enclosing.push((index, EnclosingDefinition::Package(program.packages[index].clone())));
+    }
+    let (first_index, directives) = match enclosing.first() {
+        Some((_, EnclosingDefinition::Package(defn))) => (0, &defn.block.directives),
+        _ => (program.packages.len(), &program.directives),
+    };
+    let class = directives.iter().position(|directive| matches!(directive.as_ref(), Directive::ClassDefinition(defn) if encloses(&defn.block)));
+    if let Some(index) = class {
+        enclosing.push((first_index + index, EnclosingDefinition::Class(directives[index].clone())));
+    }
+    enclosing
+}
+
+/// Returns a copy of `nodes` with the node at `index` replaced by `node`,
+/// calling `rebind` for the remaining nodes along with whether they follow it.
+fn replace_and_rebind<T>(nodes: &[Rc<T>], index: usize, node: Rc<T>, mut rebind: impl FnMut(&mut Rc<T>, bool)) -> Vec<Rc<T>> {
+    let mut nodes = nodes.to_vec();
+    for (i, node) in nodes.iter_mut().enumerate() {
+        if i != index {
+            rebind(node, i > index);
+        }
+    }
+    nodes[index] = node;
+    nodes
+}
+
+/// Returns the first and last byte indices of the characters of `old_text`
+/// that differ from `new_text`, excluding their common prefix and suffix.
+fn changed_range(old_text: &str, new_text: &str) -> (usize, usize) {
+    let mut prefix = old_text.bytes().zip(new_text.bytes()).take_while(|(a, b)| a == b).count();
+    while !old_text.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = old_text.len().min(new_text.len()) - prefix;
+    let mut suffix = old_text.bytes().rev().zip(new_text.bytes().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    while !old_text.is_char_boundary(old_text.len() - suffix) {
+        suffix -= 1;
+    }
+    (prefix, old_text.len() - suffix)
+}
+
+/// Returns the extents of a list of packages and directives, or `None`
+/// if they overlap.
+fn list_items(packages: &[Rc<PackageDefinition>], directives: &[Rc<Directive>]) -> Option<Vec<TopLevelItem>> {
+    let mut items = vec![];
+    for package in packages {
+        items.push(TopLevelItem {
+            first_offset: package.location.first_offset,
+            last_offset: package.location.last_offset,
+            package: true,
+        });
+    }
+    for directive in directives {
+        let location = directive.location();
+        let first_offset = directive_attributes(directive).first().map_or(location.first_offset, |attribute| attribute.location().first_offset.min(location.first_offset));
+        items.push(TopLevelItem {
+            first_offset,
+            last_offset: location.last_offset,
+            package: false,
+        });
+    }
+    let mut last_offset = 0;
+    for item in &items {
+        if item.first_offset < last_offset || item.last_offset < item.first_offset {
+            return None;
+        }
+        last_offset = item.last_offset;
+    }
+    Some(items)
+}
+
+fn directive_attributes(directive: &Directive) -> &[Attribute] {
+    match directive {
+        Directive::VariableDefinition(defn) => &defn.attributes,
+        Directive::FunctionDefinition(defn) => &defn.attributes,
+        Directive::ClassDefinition(defn) => &defn.attributes,
+        Directive::EnumDefinition(defn) => &defn.attributes,
+        Directive::InterfaceDefinition(defn) => &defn.attributes,
+        Directive::TypeDefinition(defn) => &defn.attributes,
+        Directive::NamespaceDefinition(defn) => &defn.attributes,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use super::{changed_range, list_items};
+
+    const SOURCE: &str = r#"
+/** Package. */
+package p {
+    import q.*;
+
+    /**
+     * Class.
+     */
+    [Meta(x = "y")]
+    public class C extends B {
+        // Comment
+        protected var x: Number = 10, y = /x+/g;
+
+        function C() {
+            super();
+            if (x) { trace(<a b="c">{y}</a>); } else return;
+        }
+    }
+}
+package q { public namespace ns = "ns" }
+
+/* Top-level */
+x = a
+(b)
+const z: * = [1, , ...w];
+function f(a: int, ...rest): void {
+    for each (var v in rest) continue;
+}
+y = { a: 1, "b": 2 }
+switch (x) { case 1: break; default: }
+"#;
+
+    /// Snippets inserted by random edits.
+    const SNIPPETS: [&str; 24] = [
+        "", " ", "\n", ";", "{", "}", "(", ")", "x", "10", "/*", "*/", "//", "\"",
+        "/** Doc. */", "package r {}", "function g() {}", "[M]", "public ", "<a/>",
+        "var w = 1", "CONFIG::debug ", "\r\n", "é",
+    ];
+
+    /// Xorshift pseudo-random number generator.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+    }
+
+    fn random_edit(text: &str, random: &mut Random) -> TextEdit {
+        let boundary = |offset: usize| {
+            let mut offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        let first_offset = boundary(random.next(text.len() + 1));
+        let last_offset = boundary(first_offset + random.next(6)).max(first_offset);
+        TextEdit::new((first_offset, last_offset), SNIPPETS[random.next(SNIPPETS.len())])
+    }
+
+    fn assert_equal_parse(compilation_unit: &Rc<CompilationUnit>, program: &Rc<Program>, expected_compilation_unit: &Rc<CompilationUnit>, expected_program: &Rc<Program>) {
+        let text = compilation_unit.text();
+        assert_eq!(serde_json::to_string(program).unwrap(), serde_json::to_string(expected_program).unwrap(), "{text:?}");
+        assert_eq!(program.location, Location::with_offsets(compilation_unit, expected_program.location.first_offset, expected_program.location.last_offset), "{text:?}");
+
+        let diagnostics = |compilation_unit: &Rc<CompilationUnit>| compilation_unit.diagnostics().iter().map(|diagnostic| {
+            (diagnostic.location().first_offset, diagnostic.location().last_offset, diagnostic.format_english())
+        }).collect::<Vec<_>>();
+        assert_eq!(diagnostics(compilation_unit), diagnostics(expected_compilation_unit), "{text:?}");
+        assert_eq!(compilation_unit.invalidated(), expected_compilation_unit.invalidated());
+
+        let comments = |compilation_unit: &Rc<CompilationUnit>| compilation_unit.comments().iter().map(|comment| {
+            (comment.location().first_offset, comment.location().last_offset, comment.content())
+        }).collect::<Vec<_>>();
+        assert_eq!(comments(compilation_unit), comments(expected_compilation_unit), "{text:?}");
+
+        // Every location of the program refers to the new compilation unit.
+        assert_eq!(Rc::strong_count(compilation_unit), Rc::strong_count(expected_compilation_unit), "{text:?}");
+    }
+
+    #[test]
+    fn test_reparse_equals_full_parse() {
+        let mut random = Random(0x2545F4914F6CDD1D);
+        let mut reused_prefixes = 0;
+        let mut reused_suffixes = 0;
+        for _ in 0..40 {
+            let mut compilation_unit = CompilationUnit::new(None, SOURCE.into());
+            let mut program = ParserFacade(&compilation_unit, default()).parse_program();
+            for _ in 0..25 {
+                let mut text = compilation_unit.text().clone();
+                let mut edits = vec![];
+                for _ in 0..1 + random.next(2) {
+                    let edit = random_edit(&text, &mut random);
+                    text = TextEdit::apply_all(&text, std::slice::from_ref(&edit));
+                    edits.push(edit);
+                }
+                let (new_compilation_unit, new_program, reused) = ParserFacade(&compilation_unit, default()).reparse_program_counting_reuse(&program, &edits);
+
+                let expected_compilation_unit = CompilationUnit::new(None, new_compilation_unit.text().clone());
+                let expected_program = ParserFacade(&expected_compilation_unit, default()).parse_program();
+                assert_equal_parse(&new_compilation_unit, &new_program, &expected_compilation_unit, &expected_program);
+
+                // Without errors before the edits, the items preceding the edits are reused,
+                // except for the last one, whose extent depends on the token that follows it.
+                let (edit_first_offset, _) = changed_range(compilation_unit.text(), new_compilation_unit.text());
+                let items = list_items(&program.packages, &program.directives).unwrap_or_default();
+                if compilation_unit.diagnostics().iter().all(|diagnostic| diagnostic.location().first_offset >= edit_first_offset) {
+                    let untouched = items.iter().take_while(|item| item.last_offset < edit_first_offset).count().saturating_sub(1);
+                    assert!(reused >= untouched, "{:?}", new_compilation_unit.text());
+                    reused_prefixes += usize::from(untouched != 0);
+                    reused_suffixes += usize::from(reused > untouched);
+                }
+
+                compilation_unit = new_compilation_unit;
+                program = new_program;
+            }
+        }
+        assert!(reused_prefixes > 0 && reused_suffixes > 0);
+    }
+
+    #[test]
+    fn test_reparse_class_members() {
+        let compilation_unit = CompilationUnit::new(None, SOURCE.into());
+        let program = ParserFacade(&compilation_unit, default()).parse_program();
+        let offset = SOURCE.find("10, y").unwrap();
+        let edits = [TextEdit::new((offset, offset + 2), "20")];
+        let (new_compilation_unit, new_program, reused) = ParserFacade(&compilation_unit, default()).reparse_program_counting_reuse(&program, &edits);
+
+        let expected_compilation_unit = CompilationUnit::new(None, new_compilation_unit.text().clone());
+        let expected_program = ParserFacade(&expected_compilation_unit, default()).parse_program();
+        assert_equal_parse(&new_compilation_unit, &new_program, &expected_compilation_unit, &expected_program);
+
+        // Only the variable definition is parsed again; the constructor, the import,
+        // the second package and the top-level directives are reused.
+        assert_eq!(reused, 1 + 1 + program.packages.len() + program.directives.len() - 1);
+    }
+}
@@ -8,7 +8,10 @@ pub struct Parser<'input> {
     locations: Vec<Location>,
     activations: Vec<ParserActivation>,
     ignore_xml_whitespace: bool,
+    mxml_event_attributes: Option<Rc<HashSet<String>>>,
     expecting_token_error: bool,
+    /// Tokens consumed so far, recorded only in lossless mode.
+    lossless_tokens: Option<Vec<LosslessToken>>,
 }
 
 impl<'input> Parser<'input> {
@@ -21,13 +24,16 @@ impl<'input> Parser<'input> {
             locations: vec![],
             activations: vec![],
             ignore_xml_whitespace: options.ignore_xml_whitespace,
+            mxml_event_attributes: options.mxml_event_attributes.clone(),
             expecting_token_error: false,
+            lossless_tokens: if options.lossless { Some(vec![]) } else { None },
         }
     }
 
     fn options(&self) -> ParserOptions {
         ParserOptions {
             ignore_xml_whitespace: self.ignore_xml_whitespace,
+            mxml_event_attributes: self.mxml_event_attributes.clone(),
             ..default()
         }
     }
@@ -83,20 +89,50 @@ impl<'input> Parser<'input> {
     */
 
     fn next(&mut self) {
+        self.record_lossless_token();
         self.previous_token = self.token.clone();
         self.token = self.tokenizer.scan_ie_div();
     }
 
     fn next_ie_xml_tag(&mut self) {
+        self.record_lossless_token();
         self.previous_token = self.token.clone();
         self.token = self.tokenizer.scan_ie_xml_tag();
     }
 
     fn next_ie_xml_content(&mut self) {
+        self.record_lossless_token();
         self.previous_token = self.token.clone();
         self.token = self.tokenizer.scan_ie_xml_content();
     }
 
+    /// In lossless mode, records the current token as consumed.
+    fn record_lossless_token(&mut self) {
+        // The initial token is a placeholder; the end-of-file token
+        // is recorded by `take_lossless_tokens()`.
+        if self.lossless_tokens.is_none() || self.token.0 == Token::Eof {
+            return;
+        }
+        let leading_trivia = self.tokenizer.take_trivia();
+        self.lossless_tokens.as_mut().unwrap().push(LosslessToken {
+            token: self.token.0.clone(),
+            location: self.token.1.clone(),
+            leading_trivia,
+        });
+    }
+
+    /// Returns the tokens consumed in lossless mode (`ParserOptions::lossless`),
+    /// followed by the current token, each with its leading trivia.
+    pub fn take_lossless_tokens(&mut self) -> Vec<LosslessToken> {
+        let mut tokens = self.lossless_tokens.take().unwrap_or_default();
+        tokens.push(LosslessToken {
+            token: self.token.0.clone(),
+            location: self.token.1.clone(),
+            leading_trivia: self.tokenizer.take_trivia(),
+        });
+        tokens
+    }
+
     fn peek(&self, token: Token) -> bool {
         self.token.0 == token
     }
@@ -2015,7 +2051,9 @@ impl<'input> Parser<'input> {
         // Parenthesized
         if self.peek(Token::ParenOpen) {
             self.mark_location();
+            self.next();
             let expression = self.parse_type_expression();
+            self.non_greedy_expect(Token::ParenClose);
             (Rc::new(Expression::Paren(ParenExpression {
                 location: self.pop_location(),
                 expression,
@@ -2163,7 +2201,7 @@ impl<'input> Parser<'input> {
             if arguments.is_some() {
                 semicolon = self.parse_semicolon();
             }
-            if !semicolon && (self.peek(Token::Dot) || self.peek(Token::SquareOpen)) {
+            if !semicolon && (arguments.is_none() || self.peek(Token::Dot) || self.peek(Token::SquareOpen)) {
                 if !(self.peek(Token::Dot) || self.peek(Token::SquareOpen)) {
                     self.non_greedy_expect(Token::Dot);
                 }
@@ -2268,14 +2306,14 @@ impl<'input> Parser<'input> {
             self.mark_location();
 
             // Store offset for patching error
-            let i = self.tokenizer.characters().index();
+            let i = self.token.1.first_offset();
 
             let exp = self.parse_expression(ParserExpressionContext {
                 allow_in: true, min_precedence: OperatorPrecedence::List, ..default()
             });
 
             // Patch error
-            if i == self.tokenizer.characters().index() {
+            if i == self.token.1.first_offset() {
                 self.patch_syntax_error(DiagnosticKind::ExpectingExpression, DiagnosticKind::ExpectingStatement, diagarg![self.token.0.clone()]);
             }
 
@@ -2359,6 +2397,7 @@ impl<'input> Parser<'input> {
 
         // Parse CONFIG::VAR_NAME
         if let Some(result) = self.parse_opt_normal_config(&exp, asdoc.clone(), context.clone()) {
+            self.pop_location();
             return result;
         }
 
@@ -2510,7 +2549,7 @@ impl<'input> Parser<'input> {
     fn parse_case_elements(&mut self, context: ParserDirectiveContext) -> Vec<Case> {
         let mut cases = vec![];
         let mut semicolon = false;
-        while !self.peek(Token::BlockClose) {
+        while !self.peek(Token::BlockClose) && !self.peek(Token::Eof) {
             if !cases.is_empty() && !semicolon {
                 self.non_greedy_expect_virtual_semicolon();
             }
@@ -2541,7 +2580,7 @@ impl<'input> Parser<'input> {
             }
             let mut directives = vec![];
             semicolon = false;
-            while !(self.peek(Token::BlockClose) || self.peek(Token::Case) || self.peek(Token::Default)) {
+            while !(self.peek(Token::BlockClose) || self.peek(Token::Case) || self.peek(Token::Default) || self.peek(Token::Eof)) {
                 if !directives.is_empty() && !semicolon {
                     self.non_greedy_expect_virtual_semicolon();
                 }
@@ -3104,6 +3143,29 @@ impl<'input> Parser<'input> {
 
     fn parse_directive(&mut self, context: ParserDirectiveContext) -> (Rc<Directive>, bool) {
         let asdoc: Option<Rc<AsDoc>> = if self.peek(Token::SquareOpen) { None } else { self.parse_asdoc() };
+        let (directive, semicolon) = self.parse_directive_with_asdoc(context, asdoc.clone());
+
+        // An ASDoc comment preceding a directive that is not annotatable
+        // is not part of the tree; keep it as an ordinary comment.
+        if let Some(asdoc) = asdoc {
+            if !directive_asdoc(&directive).is_some_and(|asdoc_1| Rc::ptr_eq(&asdoc, asdoc_1)) {
+                self.restore_asdoc_comment(&asdoc);
+            }
+        }
+
+        (directive, semicolon)
+    }
+
+    fn restore_asdoc_comment(&mut self, asdoc: &AsDoc) {
+        let location = asdoc.location.clone();
+        let text = location.text();
+        let content = text[2..text.len() - 2].to_owned();
+        let mut comments = self.compilation_unit().comments_mut();
+        let i = comments.iter().position(|comment| comment.location.borrow().first_offset > location.first_offset).unwrap_or(comments.len());
+        comments.insert(i, Rc::new(Comment::new(true, content, location)));
+    }
+
+    fn parse_directive_with_asdoc(&mut self, context: ParserDirectiveContext, asdoc: Option<Rc<AsDoc>>) -> (Rc<Directive>, bool) {
         // ConfigurationDirective or Statement
         if let Token::Identifier(id) = &self.token.0 {
             let id = (id.clone(), self.token_location());
@@ -3152,6 +3214,7 @@ impl<'input> Parser<'input> {
 
                         // Parse CONFIG::VAR_NAME
                         if let Some(result) = self.parse_opt_normal_config(&first_attr_expr, asdoc.clone(), context.clone()) {
+                            self.pop_location();
                             return result;
                         }
 
@@ -3250,9 +3313,9 @@ impl<'input> Parser<'input> {
         } else if self.peek(Token::Use) {
             self.parse_use_namespace_directive()
         } else {
-            let i = self.tokenizer.characters().index();
+            let i = self.token.1.first_offset();
             let r = self.parse_statement(context);
-            if i == self.tokenizer.characters().index() {
+            if i == self.token.1.first_offset() {
                 self.patch_syntax_error(DiagnosticKind::ExpectingStatement, DiagnosticKind::ExpectingDirective, diagarg![self.token.0.clone()]);
             }
             r
@@ -3581,6 +3644,38 @@ impl<'input> Parser<'input> {
         self.next();
         let semicolon = self.parse_semicolon();
 
+        let (nested_compilation_unit, nested_packages, nested_directives) = self.parse_included_source(&source, &source_path_location, context);
+
+        let node = Rc::new(Directive::IncludeDirective(IncludeDirective {
+            location: self.pop_location(),
+            source,
+            nested_packages,
+            nested_directives,
+            nested_compilation_unit,
+        }));
+
+        (node, semicolon)
+    }
+
+    /// Loads a file referred to by an include directive or by the `source` attribute
+    /// of an MXML script as a nested compilation unit and parses its directives.
+    fn parse_included_source(&mut self, source: &str, source_path_location: &Location, context: ParserDirectiveContext) -> (Rc<CompilationUnit>, Vec<Rc<PackageDefinition>>, Vec<Rc<Directive>>) {
+        let nested_compilation_unit = self.load_included_source(source, source_path_location);
+
+        // Parse directives from replacement source
+        let (nested_packages, nested_directives) = parse_include_directive_source(nested_compilation_unit.clone(), context);
+
+        // Delegate sub compilation unit errors to super compilation unit
+        if nested_compilation_unit.invalidated() {
+            self.tokenizer.compilation_unit().invalidated.set(true);
+        }
+
+        (nested_compilation_unit, nested_packages, nested_directives)
+    }
+
+    /// Loads a file referred to relative to the current compilation unit
+    /// as a nested compilation unit, which is empty if the file cannot be read.
+    fn load_included_source(&mut self, source: &str, source_path_location: &Location) -> Rc<CompilationUnit> {
         let nested_compilation_unit: Rc<CompilationUnit>;
 
         // Select origin file path
@@ -3592,7 +3687,7 @@ impl<'input> Parser<'input> {
 
         // Resolve source
         if let Some(origin_file_path) = origin_file_path {
-            let sub_file_path = file_paths::FlexPath::from_n_native([origin_file_path.as_ref(), "..", source.as_ref()]).to_string_with_flex_separator();
+            let sub_file_path = file_paths::FlexPath::from_n_native([origin_file_path.as_ref(), "..", source]).to_string_with_flex_separator();
             if self.tokenizer.compilation_unit().include_directive_is_circular(&sub_file_path) {
                 self.add_syntax_error(&source_path_location.clone(), DiagnosticKind::CircularIncludeDirective, vec![]);
 
@@ -3621,23 +3716,7 @@ impl<'input> Parser<'input> {
         // Add sub compilation unit to super compilation unit
         self.tokenizer.compilation_unit().add_nested_compilation_unit(nested_compilation_unit.clone());
 
-        // Parse directives from replacement source
-        let (nested_packages, nested_directives) = parse_include_directive_source(nested_compilation_unit.clone(), context);
-
-        // Delegate sub compilation unit errors to super compilation unit
-        if nested_compilation_unit.invalidated() {
-            self.tokenizer.compilation_unit().invalidated.set(true);
-        }
-
-        let node = Rc::new(Directive::IncludeDirective(IncludeDirective {
-            location: self.pop_location(),
-            source,
-            nested_packages,
-            nested_directives,
-            nested_compilation_unit: nested_compilation_unit.clone(),
-        }));
-
-        (node, semicolon)
+        nested_compilation_unit
     }
 
     fn parse_use_namespace_directive(&mut self) -> (Rc<Directive>, bool) {
@@ -4361,6 +4440,51 @@ impl<'input> Parser<'input> {
         })
     }
 
+    /// Parses a region of the top-level packages and directives of a program,
+    /// or of the directives of a package or class block given by `context`,
+    /// starting with the next token, as part of an incremental reparse.
+    ///
+    /// `packages` indicates whether the region starts with package definitions.
+    /// Parsing stops at end-of-file, at the `}` closing the block, or before a package or directive for which
+    /// `synchronize` returns true, given the location of the previous token,
+    /// the location of the current token and whether package definitions are
+    /// still being parsed. `synchronize` is only called where a full parse would
+    /// not report an error before the current token.
+    pub(crate) fn parse_program_region(&mut self, context: ParserDirectiveContext, mut packages: bool, mut synchronize: impl FnMut(&Location, &Location, bool) -> bool) -> ProgramRegion {
+        self.next();
+        let mut region = ProgramRegion {
+            packages: vec![],
+            directives: vec![],
+            first_token: self.token_location(),
+            last_token: self.token_location(),
+            next_token: self.token_location(),
+        };
+        let block = !matches!(context, ParserDirectiveContext::TopLevel);
+        let mut semicolon = true;
+        let mut first = true;
+        while !self.peek(Token::Eof) && !(block && self.peek(Token::BlockClose)) {
+            packages = packages && self.peek(Token::Package);
+            let clean = !first && (semicolon || (!self.peek(Token::Semicolon) && self.parse_semicolon()));
+            if clean && synchronize(&self.previous_token.1, &self.token.1, packages) {
+                break;
+            }
+            first = false;
+            if packages {
+                region.packages.push(self.parse_package_definition());
+                continue;
+            }
+            if !semicolon {
+                self.non_greedy_expect_virtual_semicolon();
+            }
+            let (directive, semicolon_1) = self.parse_directive(context.clone());
+            region.directives.push(directive);
+            semicolon = semicolon_1;
+        }
+        region.last_token = self.previous_token.1.clone();
+        region.next_token = self.token_location();
+        region
+    }
+
     pub fn parse_asdoc(&mut self) -> Option<Rc<AsDoc>> {
         let comments = self.compilation_unit().comments.borrow();
         let last_comment = comments.last().map(|last_comment| last_comment.clone());
@@ -4840,13 +4964,237 @@ impl<'input> Parser<'input> {
             self.filter_mxml_whitespace_out(content);
         }
 
-        MxmlElement {
+        let mut element = MxmlElement {
             location: self.pop_location(),
             name,
             attributes,
             content,
             closing_name,
             namespace,
+            script: None,
+            style: None,
+            text_value: None,
+        };
+        if element.is_language_element("Script") {
+            element.script = Some(Rc::new(self.parse_mxml_script(&element)));
+        } else if element.is_language_element("Style") {
+            element.style = Some(Rc::new(self.parse_mxml_style(&element)));
+        } else {
+            element.text_value = self.parse_mxml_text_content(&element);
+        }
+        element
+    }
+
+    /// Parses the content of an element whose content consists of text only
+    /// into literal text and binding expressions. CDATA sections are literal text.
+    fn parse_mxml_text_content(&mut self, element: &MxmlElement) -> Option<Vec<MxmlValueSegment>> {
+        let content = element.content.as_ref()?;
+        if content.iter().any(|node| matches!(node.as_ref(), MxmlContent::Element(_))) {
+            return None;
+        }
+        let mut segments = vec![];
+        let mut has_text = false;
+        for node in content {
+            match node.as_ref() {
+                MxmlContent::Characters((_, location)) => {
+                    segments.extend(self.parse_mxml_text_segments((location.first_offset(), location.last_offset())));
+                    has_text = true;
+                },
+                MxmlContent::CData((_, location)) => {
+                    segments.push(MxmlValueSegment::Literal((node.inner_text(), location.clone())));
+                    has_text = true;
+                },
+                _ => {},
+            }
+        }
+        if has_text { Some(segments) } else { None }
+    }
+
+    /// Parses the ActionScript code of an `fx:Script` element in class block context,
+    /// from the file referred to by its `source` attribute and from its content.
+    fn parse_mxml_script(&mut self, element: &MxmlElement) -> MxmlScript {
+        let class_name = self.compilation_unit().file_path.as_ref()
+            .and_then(|file_path| std::path::Path::new(file_path).file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let context = ParserDirectiveContext::ClassBlock { name: class_name };
+        let mut script = MxmlScript {
+            source: None,
+            nested_compilation_unit: None,
+            directives: vec![],
+        };
+
+        if let Some(attribute) = mxml_source_attribute(element) {
+            let (nested_compilation_unit, _, nested_directives) = self.parse_included_source(&attribute.value.0, &attribute.value.1, context.clone());
+            script.source = Some(attribute.value.0.clone());
+            script.nested_compilation_unit = Some(nested_compilation_unit);
+            script.directives.extend(nested_directives);
+        }
+
+        for (compilation_unit, byte_range) in self.mxml_embedded_code_sources(element) {
+            let parser_options = ParserOptions {
+                byte_range: Some(byte_range),
+                ..self.options()
+            };
+            script.directives.extend(ParserFacade(&compilation_unit, parser_options).parse_directives(context.clone()));
+            self.delegate_invalidation(&compilation_unit);
+        }
+
+        script
+    }
+
+    /// Parses the style sheets of an `fx:Style` element, from the file referred to
+    /// by its `source` attribute and from its content.
+    fn parse_mxml_style(&mut self, element: &MxmlElement) -> MxmlStyle {
+        let mut style = MxmlStyle {
+            source: None,
+            nested_compilation_unit: None,
+            documents: vec![],
+        };
+
+        if let Some(attribute) = mxml_source_attribute(element) {
+            let nested_compilation_unit = self.load_included_source(&attribute.value.0, &attribute.value.1);
+            style.documents.push(CssParserFacade(&nested_compilation_unit, default()).parse_document());
+            self.delegate_invalidation(&nested_compilation_unit);
+            style.source = Some(attribute.value.0.clone());
+            style.nested_compilation_unit = Some(nested_compilation_unit);
+        }
+
+        for (compilation_unit, byte_range) in self.mxml_embedded_code_sources(element) {
+            let parser_options = ParserOptions {
+                byte_range: Some(byte_range),
+                ..self.options()
+            };
+            style.documents.push(CssParserFacade(&compilation_unit, parser_options).parse_document());
+            self.delegate_invalidation(&compilation_unit);
+        }
+
+        style
+    }
+
+    /// The sources of the code within the character and CDATA content of an
+    /// `fx:Script` or `fx:Style` element, excluding whitespace-only chunks,
+    /// as returned by `mxml_embedded_code_source()`.
+    fn mxml_embedded_code_sources(&mut self, element: &MxmlElement) -> Vec<(Rc<CompilationUnit>, (usize, usize))> {
+        let mut sources = vec![];
+        for node in element.content.iter().flatten() {
+            match node.as_ref() {
+                MxmlContent::CData((_, location)) => {
+                    let byte_range = (location.first_offset() + "<![CDATA[".len(), location.last_offset() - "]]>".len());
+                    sources.push((self.compilation_unit().clone(), byte_range));
+                },
+                MxmlContent::Characters((text, location)) => {
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    sources.push(self.mxml_embedded_code_source((location.first_offset(), location.last_offset())));
+                },
+                _ => {},
+            }
+        }
+        sources
+    }
+
+    /// Returns the compilation unit and byte range from which to parse ActionScript
+    /// or CSS code embedded in MXML text, given its byte range in the MXML file.
+    ///
+    /// Code containing character references, such as `&lt;`, is unescaped into a
+    /// nested compilation unit whose line numbers and columns are mapped back
+    /// to those of the MXML file through an offset table.
+    fn mxml_embedded_code_source(&mut self, byte_range: (usize, usize)) -> (Rc<CompilationUnit>, (usize, usize)) {
+        let (unescaped_code, offset_table) = unescape_xml_with_offset_table(&self.compilation_unit().text()[byte_range.0..byte_range.1]);
+        if offset_table.len() == 1 {
+            return (self.compilation_unit().clone(), byte_range);
+        }
+        let offset_table = offset_table.into_iter().map(|(offset, code_offset)| (offset, byte_range.0 + code_offset)).collect();
+        let last_offset = unescaped_code.len();
+        let nested_compilation_unit = CompilationUnit::with_offset_table(self.compilation_unit().file_path.clone(), unescaped_code, offset_table);
+        nested_compilation_unit.set_compiler_options(self.compilation_unit().compiler_options());
+        self.compilation_unit().add_nested_compilation_unit(nested_compilation_unit.clone());
+        (nested_compilation_unit, (0, last_offset))
+    }
+
+    /// Delegates the errors of a nested compilation unit to the current compilation unit.
+    fn delegate_invalidation(&self, compilation_unit: &Rc<CompilationUnit>) {
+        if compilation_unit.invalidated() {
+            self.compilation_unit().invalidated.set(true);
+        }
+    }
+
+    /// Parses MXML text within a byte range of the MXML file into literal
+    /// text and binding expressions.
+    fn parse_mxml_text_segments(&mut self, byte_range: (usize, usize)) -> Vec<MxmlValueSegment> {
+        let cu = self.compilation_unit().clone();
+        let chars: Vec<(usize, char)> = cu.text()[byte_range.0..byte_range.1].char_indices().map(|(i, ch)| (byte_range.0 + i, ch)).collect();
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut literal_start = byte_range.0;
+        let mut i = 0;
+        while i < chars.len() {
+            let (offset, ch) = chars[i];
+            let next = chars.get(i + 1).map(|(_, ch)| *ch);
+
+            // Escaped brace
+            if ch == '\\' && matches!(next, Some('{' | '}')) {
+                literal.push(next.unwrap());
+                i += 2;
+                continue;
+            }
+
+            let two_way = ch == '@' && next == Some('{');
+            let open = if two_way { i + 1 } else { i };
+            if ch == '{' || two_way {
+                let Some(close) = find_mxml_binding_close(&chars, open) else {
+                    // The rest of the text is kept as literal.
+                    self.add_syntax_error(&Location::with_offsets(&cu, byte_range.1, byte_range.1), DiagnosticKind::Expecting, diagarg![Token::BlockClose, Token::Eof]);
+                    literal.push_str(&cu.text()[offset..byte_range.1]);
+                    break;
+                };
+                if !literal.is_empty() {
+                    segments.push(MxmlValueSegment::Literal((unescape_xml(&literal), Location::with_offsets(&cu, literal_start, offset))));
+                    literal.clear();
+                }
+                let (compilation_unit, code_range) = self.mxml_embedded_code_source((chars[open].0 + 1, chars[close].0));
+                let parser_options = ParserOptions {
+                    byte_range: Some(code_range),
+                    ..self.options()
+                };
+                let expression = ParserFacade(&compilation_unit, parser_options).parse_expression();
+                self.delegate_invalidation(&compilation_unit);
+                segments.push(MxmlValueSegment::Binding(Rc::new(MxmlBinding {
+                    location: Location::with_offsets(&cu, offset, chars[close].0 + 1),
+                    two_way,
+                    expression,
+                })));
+                i = close + 1;
+                literal_start = chars.get(i).map_or(byte_range.1, |(offset, _)| *offset);
+                continue;
+            }
+
+            literal.push(ch);
+            i += 1;
+        }
+        if !literal.is_empty() {
+            segments.push(MxmlValueSegment::Literal((unescape_xml(&literal), Location::with_offsets(&cu, literal_start, byte_range.1))));
+        }
+        segments
+    }
+
+    /// Parses the value of an event handler attribute as directives.
+    fn parse_mxml_event_handler(&mut self, byte_range: (usize, usize)) -> Vec<Rc<Directive>> {
+        let (compilation_unit, code_range) = self.mxml_embedded_code_source(byte_range);
+        let parser_options = ParserOptions {
+            byte_range: Some(code_range),
+            ..self.options()
+        };
+        let directives = ParserFacade(&compilation_unit, parser_options).parse_directives(ParserDirectiveContext::Default);
+        self.delegate_invalidation(&compilation_unit);
+        directives
+    }
+
+    fn is_mxml_event_attribute(&self, name: &str) -> bool {
+        match &self.mxml_event_attributes {
+            Some(names) => names.contains(name),
+            None => DEFAULT_MXML_EVENT_ATTRIBUTES.contains(&name),
         }
     }
 
@@ -4890,7 +5238,9 @@ impl<'input> Parser<'input> {
                     location: attribute.name.1.clone(),
                     prefix: None,
                     name: "xmlns".into(),
+                    state: None,
                 },
+                typed_value: self.mxml_literal_attribute_value(attribute, &attribute_value),
                 value: (attribute_value, attribute.value.1.clone()),
                 xmlns: true,
             }));
@@ -4907,13 +5257,20 @@ impl<'input> Parser<'input> {
                     location: attribute.name.1.clone(),
                     prefix: Some("xmlns".into()),
                     name: attribute.name.0[6..].to_owned(),
+                    state: None,
                 },
+                typed_value: self.mxml_literal_attribute_value(attribute, &attribute_value),
                 value: (attribute_value, attribute.value.1.clone()),
                 xmlns: true,
             }));
         }
     }
 
+    fn mxml_literal_attribute_value(&self, attribute: &PlainMxmlAttribute, value: &str) -> MxmlAttributeValue {
+        let (first_offset, last_offset) = mxml_attribute_value_range(attribute);
+        MxmlAttributeValue::Text(vec![MxmlValueSegment::Literal((value.to_owned(), Location::with_offsets(self.compilation_unit(), first_offset, last_offset)))])
+    }
+
     fn process_mxml_attribute(&mut self, output: &mut Vec<Rc<MxmlAttribute>>, attribute: &PlainMxmlAttribute, namespace: &Rc<MxmlNamespace>) {
         // attrib="value"
         if !(attribute.name.0 == "xmlns" || attribute.name.0.starts_with("xmlns:")) {
@@ -4927,15 +5284,23 @@ impl<'input> Parser<'input> {
             } else {
                 None
             };
-            let name = split.last().unwrap();
+            let (name, state) = split_mxml_state(split.last().unwrap(), &attribute.name.1);
+            let byte_range = mxml_attribute_value_range(attribute);
+            let typed_value = if prefix.is_none() && self.is_mxml_event_attribute(&name) {
+                MxmlAttributeValue::EventHandler(self.parse_mxml_event_handler(byte_range))
+            } else {
+                MxmlAttributeValue::Text(self.parse_mxml_text_segments(byte_range))
+            };
             let attrib = Rc::new(MxmlAttribute {
                 location: attribute.location.clone(),
                 name: MxmlName {
                     location: attribute.name.1.clone(),
                     prefix,
-                    name: (*name).to_owned(),
+                    name,
+                    state,
                 },
                 value: (attribute_value, attribute.value.1.clone()),
+                typed_value,
                 xmlns: false,
             });
             match attrib.name.resolve_prefix(namespace) {
@@ -4964,11 +5329,12 @@ impl<'input> Parser<'input> {
         } else {
             None
         };
-        let name_str = split.last().unwrap();
+        let (name_str, state) = split_mxml_state(split.last().unwrap(), &name.1);
         let name = MxmlName {
             location: name.1.clone(),
             prefix,
-            name: (*name_str).to_owned(),
+            name: name_str,
+            state,
         };
         match name.resolve_prefix(namespace) {
             Ok(_) => {},
@@ -5030,9 +5396,20 @@ impl<'input> Parser<'input> {
                         data: if data.is_empty() { None } else { Some(data) },
                     }));
                 }
-            } else if let Token::XmlText(text) = self.token.0.clone() {
-                let location = self.token_location();
-                self.next_ie_xml_content();
+            } else if matches!(self.token.0, Token::XmlText(_) | Token::BlockOpen) {
+                // Unlike in E4X, braces do not start an interpolation in MXML,
+                // so a brace and the text around it form a single chunk.
+                let mut location = self.token_location();
+                let mut text = String::new();
+                loop {
+                    match &self.token.0 {
+                        Token::XmlText(chunk) => text.push_str(chunk),
+                        Token::BlockOpen => text.push('{'),
+                        _ => break,
+                    }
+                    location = location.combine_with(self.token_location());
+                    self.next_ie_xml_content();
+                }
                 content.push(Rc::new(MxmlContent::Characters((unescape_xml(&text), location))));
             } else if self.consume_and_ie_xml_tag(Token::Lt) {
                 let start = self.token_location();
@@ -5080,12 +5457,48 @@ impl<'input> Parser<'input> {
         if element_count != 1 || character_count != 0 {
             self.add_syntax_error(&location, DiagnosticKind::XmlMustConsistOfExactly1Element, vec![]);
         }
-        Rc::new(Mxml {
+        let mxml = Rc::new(Mxml {
             location,
             version: XmlVersion::Version10,
             encoding,
             content,
-        })
+        });
+        self.verify_mxml_states(&mxml);
+        mxml
+    }
+
+    /// Reports the states referenced by state-specific names and by `includeIn`
+    /// and `excludeFrom` attributes that are neither declared states nor state groups.
+    fn verify_mxml_states(&self, mxml: &Mxml) {
+        let mut names: HashSet<String> = HashSet::new();
+        for state in mxml.states() {
+            names.insert(state.name.0);
+            names.extend(state.state_groups.into_iter().map(|(group, _)| group));
+        }
+        if let Some(root) = mxml.root() {
+            self.verify_mxml_element_states(&root, &names);
+        }
+    }
+
+    fn verify_mxml_element_states(&self, element: &MxmlElement, names: &HashSet<String>) {
+        let mut references: Vec<(String, Location)> = element.name.state.iter().cloned().collect();
+        for attribute in &element.attributes {
+            if attribute.xmlns {
+                continue;
+            }
+            references.extend(attribute.name.state.iter().cloned());
+            if attribute.name.prefix.is_none() && attribute.name.state.is_none() && (attribute.name.name == "includeIn" || attribute.name.name == "excludeFrom") {
+                references.extend(attribute.list_items());
+            }
+        }
+        for (name, location) in references {
+            if !names.contains(&name) {
+                location.compilation_unit().add_diagnostic(Diagnostic::new_verify_error(&location, DiagnosticKind::UnknownMxmlState, diagarg![name]));
+            }
+        }
+        for child in element.child_elements() {
+            self.verify_mxml_element_states(&child, names);
+        }
     }
 }
 
@@ -5103,6 +5516,55 @@ fn parse_include_directive_source(nested_compilation_unit: Rc<CompilationUnit>,
     (packages, parser.parse_directives(context))
 }
 
+/// Finds the brace closing the brace at index `open` of a list of characters,
+/// skipping the braces within string literals.
+fn find_mxml_binding_close(chars: &[(usize, char)], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut i = open;
+    while i < chars.len() {
+        let ch = chars[i].1;
+        if let Some(delim) = quote {
+            if ch == '\\' {
+                i += 1;
+            } else if ch == delim {
+                quote = None;
+            }
+        } else if ch == '\'' || ch == '"' {
+            quote = Some(ch);
+        } else if ch == '{' {
+            depth += 1;
+        } else if ch == '}' {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Splits the state from a state-specific MXML name, such as `label.over`.
+fn split_mxml_state(name: &str, location: &Location) -> (String, Option<(String, Location)>) {
+    let Some((name, state)) = name.split_once('.') else {
+        return (name.to_owned(), None);
+    };
+    let state_location = Location::with_offsets(&location.compilation_unit(), location.last_offset() - state.len(), location.last_offset());
+    (name.to_owned(), Some((state.to_owned(), state_location)))
+}
+
+/// The byte range of the value of an MXML attribute, excluding the quotes.
+fn mxml_attribute_value_range(attribute: &PlainMxmlAttribute) -> (usize, usize) {
+    let first_offset = attribute.value.1.first_offset() + if attribute.value.0.is_empty() { 0 } else { 1 };
+    (first_offset, first_offset + attribute.value.0.len())
+}
+
+/// The `source` attribute of an `fx:Script` or `fx:Style` element.
+fn mxml_source_attribute(element: &MxmlElement) -> Option<&Rc<MxmlAttribute>> {
+    element.attributes.iter().find(|attribute| !attribute.xmlns && attribute.name.prefix.is_none() && attribute.name.name == "source")
+}
+
 fn join_asdoc_content(content: &Vec<(String, Location)>) -> (String, Location) {
     // Ignore first empty lines
     let mut i = 0usize;
@@ -5181,7 +5643,22 @@ fn process_xml_pi(cu: &Rc<CompilationUnit>, byte_range: (usize, usize), name: &s
     errors
 }
 
-fn is_flex_documentable_meta_data(name: &str) -> bool {
+/// Returns the ASDoc comment attached to a directive, if any.
+fn directive_asdoc(directive: &Directive) -> Option<&Rc<AsDoc>> {
+    match directive {
+        Directive::VariableDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::FunctionDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::ClassDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::InterfaceDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::EnumDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::NamespaceDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::TypeDefinition(defn) => defn.asdoc.as_ref(),
+        Directive::NormalConfigurationDirective(config) => directive_asdoc(&config.directive),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_flex_documentable_meta_data(name: &str) -> bool {
     ["Event", "SkinState"].contains(&name)
 }
 
@@ -5237,9 +5714,22 @@ struct PlainMxmlAttribute {
     pub value: (String, Location),
 }
 
+/// Region of a program parsed by `Parser::parse_program_region()`.
+pub(crate) struct ProgramRegion {
+    pub packages: Vec<Rc<PackageDefinition>>,
+    pub directives: Vec<Rc<Directive>>,
+    /// Location of the first token of the region.
+    pub first_token: Location,
+    /// Location of the last token consumed by the region.
+    pub last_token: Location,
+    /// Location of the token following the region.
+    pub next_token: Location,
+}
+
 /// A simplified interface for executing the parser.
 pub struct ParserFacade<'input>(pub &'input Rc<CompilationUnit>, pub ParserOptions);
 
+#[derive(Clone)]
 pub struct ParserOptions {
     /// For MXML, indicates whether to ignore XML whitespace chunks when at
     /// least one element appears. Default: true.
@@ -5247,6 +5737,14 @@ pub struct ParserOptions {
     /// Indicates the range of characters that shall be parsed,
     /// the first and last byte indices respectively.
     pub byte_range: Option<(usize, usize)>,
+    /// Indicates whether the tokenizer and parser record every consumed token
+    /// along with the whitespace, line terminators and comments preceding it,
+    /// as required for building a concrete syntax tree. Default: false.
+    pub lossless: bool,
+    /// For MXML, the names of the attributes whose values are parsed as event
+    /// handlers rather than as text with binding expressions. Default: `None`,
+    /// standing for `DEFAULT_MXML_EVENT_ATTRIBUTES`.
+    pub mxml_event_attributes: Option<Rc<HashSet<String>>>,
 }
 
 impl Default for ParserOptions {
@@ -5254,10 +5752,23 @@ impl Default for ParserOptions {
         Self {
             ignore_xml_whitespace: true,
             byte_range: None,
+            lossless: false,
+            mxml_event_attributes: None,
         }
     }
 }
 
+/// The events of the Flex framework whose attributes are parsed as event
+/// handlers in MXML, unless overriden by `ParserOptions::mxml_event_attributes`.
+pub const DEFAULT_MXML_EVENT_ATTRIBUTES: [&str; 44] = [
+    "activate", "add", "added", "addedToStage", "applicationComplete", "change", "changing",
+    "click", "close", "complete", "creationComplete", "dataChange", "deactivate", "doubleClick",
+    "enter", "fault", "focusIn", "focusOut", "hide", "initialize", "invoke", "ioError", "itemClick",
+    "keyDown", "keyUp", "mouseDown", "mouseMove", "mouseOut", "mouseOver", "mouseUp", "mouseWheel",
+    "open", "preinitialize", "progress", "remove", "removedFromStage", "resize", "result", "rollOut",
+    "rollOver", "securityError", "show", "updateComplete", "valueCommit",
+];
+
 impl<'input> ParserFacade<'input> {
     fn create_parser(&self) -> Parser<'input> {
         Parser::new(self.0, &self.1)
@@ -5270,6 +5781,24 @@ impl<'input> ParserFacade<'input> {
         parser.parse_program()
     }
 
+    /// Parses `Program` until end-of-file in lossless mode, returning the program
+    /// along with a concrete syntax tree that covers the entire source text.
+    pub fn parse_program_cst(&self) -> (Rc<Program>, CstNode) {
+        let byte_range = self.1.byte_range;
+        let mut parser = Parser::new(self.0, &ParserOptions {
+            ignore_xml_whitespace: self.1.ignore_xml_whitespace,
+            byte_range,
+            lossless: true,
+            mxml_event_attributes: self.1.mxml_event_attributes.clone(),
+        });
+        parser.next();
+        let program = parser.parse_program();
+        let tokens = parser.take_lossless_tokens();
+        let (first_offset, last_offset) = byte_range.unwrap_or((0, self.0.text().len()));
+        let cst = CstBuilder::new(self.0, tokens, first_offset, last_offset).build_program(&program);
+        (program, cst)
+    }
+
     /// Parses `ListExpression^allowIn` and expects end-of-file.
     pub fn parse_expression(&self) -> Rc<Expression> {
         let mut parser = self.create_parser();
@@ -5313,3 +5842,69 @@ impl<'input> ParserFacade<'input> {
         parser.parse_mxml()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn parse(text: &str) -> (Rc<CompilationUnit>, Rc<Program>) {
+        let cu = CompilationUnit::new(None, text.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        (cu, program)
+    }
+
+    fn diagnostics(cu: &Rc<CompilationUnit>) -> Vec<String> {
+        cu.diagnostics().iter().map(|diagnostic| diagnostic.format_english()).collect()
+    }
+
+    #[test]
+    fn test_parenthesized_type_expression() {
+        let (cu, program) = parse("var x: (int) = 1;");
+        assert!(diagnostics(&cu).is_empty());
+        let Directive::VariableDefinition(defn) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        assert_eq!(defn.bindings[0].destructuring.type_annotation.as_ref().unwrap().location().text(), "(int)");
+    }
+
+    #[test]
+    fn test_super_statement_without_arguments() {
+        let (cu, _) = parse("class C { function C() { super } }");
+        assert_eq!(diagnostics(&cu), ["1:32: Syntax error #1028: Expecting dot before block-close."]);
+    }
+
+    #[test]
+    fn test_switch_at_end_of_file() {
+        let (cu, _) = parse("switch (x) { case 1: x");
+        assert_eq!(diagnostics(&cu), ["1:23: Syntax error #1028: Expecting block-close before end-of-file."]);
+    }
+
+    #[test]
+    fn test_configuration_directive_locations() {
+        let (_, program) = parse("x = 1; CONFIG::debug var y;");
+        assert_eq!(program.location.text(), "x = 1; CONFIG::debug var y;");
+        let (_, program) = parse("{ CONFIG::debug var y }");
+        assert_eq!(program.directives[0].location().text(), "{ CONFIG::debug var y }");
+    }
+
+    #[test]
+    fn test_expecting_statement_patch() {
+        let (cu, _) = parse("x = 1; )");
+        assert_eq!(diagnostics(&cu), ["1:8: Syntax error #1088: Expecting statement before paren-close."]);
+    }
+
+    #[test]
+    fn test_mxml_character_reference_locations() {
+        let cu = CompilationUnit::new(None, "<s:Label xmlns:s=\"library://ns.adobe.com/flex/spark\">\n  {a &lt; b &amp;&amp; c d}</s:Label>".into());
+        ParserFacade(&cu, default()).parse_mxml();
+        let nested = cu.nested_diagnostics().iter().map(|diagnostic| diagnostic.format_english()).collect::<Vec<_>>();
+        assert_eq!(nested, ["2:26: Syntax error #1028: Expecting end-of-file before identifier."]);
+    }
+
+    #[test]
+    fn test_mxml_unclosed_binding() {
+        let cu = CompilationUnit::new(None, "<s:Label xmlns:s=\"library://ns.adobe.com/flex/spark\" text=\"a {b\"/>".into());
+        ParserFacade(&cu, default()).parse_mxml();
+        assert_eq!(diagnostics(&cu), ["1:64: Syntax error #1028: Expecting block-close before end-of-file."]);
+    }
+}
@@ -3,6 +3,9 @@ use crate::ns::*;
 pub struct Tokenizer<'input> {
     compilation_unit: Rc<CompilationUnit>,
     characters: CharacterReader<'input>,
+    /// Trivia scanned since the last call to `take_trivia()`,
+    /// recorded only in lossless mode.
+    trivia: Option<Vec<Trivia>>,
 }
 
 impl<'input> Tokenizer<'input> {
@@ -19,6 +22,7 @@ impl<'input> Tokenizer<'input> {
         Self {
             compilation_unit,
             characters,
+            trivia: if options.lossless { Some(vec![]) } else { None },
         }
     }
 
@@ -37,14 +41,44 @@ impl<'input> Tokenizer<'input> {
         self.compilation_unit.add_diagnostic(Diagnostic::new_syntax_error(location, kind, arguments));
     }
 
+    /// Returns the whitespace, line terminators and comments scanned since the
+    /// last call to this method. Trivia is only recorded in lossless mode
+    /// (`ParserOptions::lossless`); otherwise this method returns an empty collection.
+    pub fn take_trivia(&mut self) -> Vec<Trivia> {
+        self.trivia.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn add_trivia(&mut self, kind: TriviaKind, first_offset: usize) {
+        let last_offset = self.characters.index();
+        let Some(trivia) = self.trivia.as_mut() else {
+            return;
+        };
+        // Join adjacent whitespace characters into a single run.
+        if let Some(last) = trivia.last_mut() {
+            if kind == TriviaKind::Whitespace && last.kind == kind && last.location.last_offset() == first_offset {
+                last.location = Location::with_offsets(&self.compilation_unit, last.location.first_offset(), last_offset);
+                return;
+            }
+        }
+        trivia.push(Trivia {
+            kind,
+            location: Location::with_offsets(&self.compilation_unit, first_offset, last_offset),
+        });
+    }
+
     /// Scans for an *InputElementDiv* token.
     pub fn scan_ie_div(&mut self) -> (Token, Location) {
         loop {
             let ch = self.characters.peek_or_zero();
+            let first_offset = self.characters.index();
             if CharacterValidator::is_whitespace(ch) {
                 self.characters.next();
-            } else if self.consume_line_terminator() || self.consume_comment() {
-                // Consumed line terminator or comment
+                self.add_trivia(TriviaKind::Whitespace, first_offset);
+            } else if self.consume_line_terminator() {
+                self.add_trivia(TriviaKind::LineTerminator, first_offset);
+            } else if self.consume_comment() {
+                let kind = if self.compilation_unit.text()[first_offset..].starts_with("//") { TriviaKind::SingleLineComment } else { TriviaKind::MultiLineComment };
+                self.add_trivia(kind, first_offset);
             } else {
                 break;
             }
@@ -536,7 +570,6 @@ impl<'input> Tokenizer<'input> {
                 self.characters.skip_in_place();
             }
             let location = start.combine_with(self.cursor_location());
-            self.consume_line_terminator();
 
             self.compilation_unit.add_comment(Rc::new(Comment {
                 multiline: false,
@@ -0,0 +1,15 @@
+//! Defines printers that turn syntactic nodes back into source text.
+//!
+//! Using the methods of the `PrinterFacade` structure is the most common way of
+//! printing a `Program`, `Directive` or `Expression` as ActionScript 3.
+//! The `Formatter` structure re-emits a source file preserving its comments.
+//!
+//! The `CssPrinterFacade` structure prints a `CssDocument` as a style sheet,
+//! either pretty-printed or minified.
+
+mod as3_printer;
+pub use as3_printer::*;
+mod formatter;
+pub use formatter::*;
+mod css_printer;
+pub use css_printer::*;
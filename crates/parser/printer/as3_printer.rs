@@ -0,0 +1,2094 @@
+use crate::ns::*;
+use serde::{Serialize, Deserialize};
+
+/// Options for the ActionScript printer.
+///
+/// The options may be deserialized from a configuration file,
+/// where field names are in camel case (such as `indentWidth`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct PrinterOptions {
+    /// Number of spaces emitted per indentation level. Default is `4`.
+    pub indent_width: usize,
+    /// Placement of the opening brace of a block. Default is `BracePlacement::SameLine`.
+    pub brace_placement: BracePlacement,
+    /// Maximum line length before argument lists, parameter lists,
+    /// array literals and object initializers are split across lines.
+    /// A value of zero disables line wrapping. Default is `120`.
+    pub max_line_length: usize,
+}
+
+impl Default for PrinterOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            brace_placement: BracePlacement::SameLine,
+            max_line_length: 120,
+        }
+    }
+}
+
+/// Placement of the opening brace of a block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BracePlacement {
+    /// The opening brace follows the header on the same line, as in `if (x) {`.
+    SameLine,
+    /// The opening brace is placed on its own line.
+    NextLine,
+}
+
+/// Printer facade.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+/// let cu = CompilationUnit::new(None, "x = a + b * c".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// assert_eq!(PrinterFacade(default()).print_program(&program), "x = a + b * c;\n");
+/// ```
+pub struct PrinterFacade(pub PrinterOptions);
+
+impl PrinterFacade {
+    /// Prints a program.
+    pub fn print_program(&self, program: &Program) -> String {
+        let mut printer = Printer::new(self.0.clone());
+        printer.print_program(program);
+        printer.into_output()
+    }
+
+    /// Prints a package definition.
+    pub fn print_package_definition(&self, package: &PackageDefinition) -> String {
+        let mut printer = Printer::new(self.0.clone());
+        printer.print_package_definition(package);
+        printer.into_output()
+    }
+
+    /// Prints a directive without a trailing line terminator.
+    pub fn print_directive(&self, directive: &Directive) -> String {
+        let mut printer = Printer::new(self.0.clone());
+        printer.print_directive(directive);
+        printer.into_output()
+    }
+
+    /// Prints an expression, including type expressions.
+    pub fn print_expression(&self, expression: &Expression) -> String {
+        let mut printer = Printer::new(self.0.clone());
+        printer.print_expression(expression);
+        printer.into_output()
+    }
+}
+
+/// ActionScript 3 printer.
+///
+/// The printer emits parentheses only where required by operator precedence,
+/// apart from explicit `Expression::Paren` nodes, which are always kept.
+///
+/// A printer constructed through `Printer::with_comments()` reattaches the comments
+/// of a compilation unit to the printed nodes and preserves ASDoc comments
+/// and blank lines as they appear in the source text.
+pub struct Printer {
+    options: PrinterOptions,
+    output: String,
+    indent_level: usize,
+    line_start: bool,
+    compilation_unit: Option<Rc<CompilationUnit>>,
+    comments: Vec<Rc<Comment>>,
+    next_comment: usize,
+    /// End offset of the last source node or comment printed.
+    last_offset: usize,
+    /// Number of single-line comments printed.
+    line_comment_count: usize,
+    /// Greater than zero while a list is tentatively printed on a single line.
+    flat_depth: usize,
+}
+
+/// Printer state saved before tentatively printing a list on a single line.
+struct PrinterState {
+    output_length: usize,
+    line_start: bool,
+    next_comment: usize,
+    last_offset: usize,
+    line_comment_count: usize,
+}
+
+impl Printer {
+    pub fn new(options: PrinterOptions) -> Self {
+        Self {
+            options,
+            output: String::new(),
+            indent_level: 0,
+            line_start: true,
+            compilation_unit: None,
+            comments: vec![],
+            next_comment: 0,
+            last_offset: 0,
+            line_comment_count: 0,
+            flat_depth: 0,
+        }
+    }
+
+    /// Constructs a printer that reattaches the comments of
+    /// `compilation_unit` to the nodes printed from it.
+    pub fn with_comments(options: PrinterOptions, compilation_unit: &Rc<CompilationUnit>) -> Self {
+        let mut comments = compilation_unit.comments();
+        comments.sort_by_key(|comment| comment.location().first_offset());
+        Self {
+            compilation_unit: Some(compilation_unit.clone()),
+            comments,
+            ..Self::new(options)
+        }
+    }
+
+    /// Returns the text printed so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.line_start {
+            self.output.push_str(&" ".repeat(self.indent_level * self.options.indent_width));
+            self.line_start = false;
+        }
+        self.output.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        self.output.push('\n');
+        self.line_start = true;
+    }
+
+    fn indent(&mut self) {
+        self.indent_level += 1;
+    }
+
+    fn dedent(&mut self) {
+        self.indent_level -= 1;
+    }
+
+    /// Terminates the current line, if any, and emits an empty line
+    /// unless at the beginning of the output or of a block.
+    fn blank_line(&mut self) {
+        if !self.line_start {
+            self.newline();
+        }
+        if self.output.is_empty() || self.output.ends_with("\n\n") || self.output.ends_with("{\n") {
+            return;
+        }
+        self.newline();
+    }
+
+    /// Indicates whether the source text contains an empty line
+    /// between two offsets.
+    fn has_blank_line(&self, from: usize, to: usize) -> bool {
+        let Some(cu) = &self.compilation_unit else {
+            return false;
+        };
+        from < to && cu.text()[from..to].chars().filter(|ch| *ch == '\n').count() > 1
+    }
+
+    /// Prints the pending comments that precede `offset` in the source text.
+    fn comments_before(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            let location = comment.location();
+            if location.first_offset() >= offset {
+                break;
+            }
+            self.next_comment += 1;
+            if self.line_start {
+                if self.has_blank_line(self.last_offset, location.first_offset()) {
+                    self.blank_line();
+                }
+                self.comment(&comment);
+                // Keep a multi-line comment on the line of the code that follows it.
+                if comment.multiline() && !self.has_line_break_after_comments(location.last_offset()) {
+                    self.write(" ");
+                } else {
+                    self.newline();
+                }
+            } else {
+                if !self.output.ends_with([' ', '(', '[', '{']) {
+                    self.write(" ");
+                }
+                self.comment(&comment);
+                if comment.multiline() {
+                    self.write(" ");
+                } else {
+                    self.newline();
+                }
+            }
+        }
+    }
+
+    /// Prints the pending comments that follow `offset` in the same line
+    /// of the source text, separated only by whitespace, commas or semicolons.
+    fn trailing_comments(&mut self, offset: usize) {
+        let Some(cu) = self.compilation_unit.clone() else {
+            return;
+        };
+        let mut offset = offset;
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            let location = comment.location();
+            if location.first_offset() < offset || !cu.text()[offset..location.first_offset()].chars().all(|ch| matches!(ch, ' ' | '\t' | ',' | ';')) {
+                break;
+            }
+            // A multi-line comment followed by code in the same line belongs to that code.
+            if comment.multiline() && !self.has_line_break_after_comments(location.last_offset()) {
+                break;
+            }
+            self.next_comment += 1;
+            self.write(" ");
+            self.comment(&comment);
+            offset = location.last_offset();
+        }
+    }
+
+    fn has_line_break_after(&self, offset: usize) -> bool {
+        let text = &self.compilation_unit.as_ref().unwrap().text()[offset..];
+        let whitespace_length = text.len() - text.trim_start_matches([' ', '\t']).len();
+        matches!(text[whitespace_length..].chars().next(), None | Some('\r' | '\n'))
+    }
+
+    /// Indicates whether the source text contains a line break after `offset`,
+    /// preceded only by whitespace and pending multi-line comments, such as
+    /// `/* b */` in `/* a */ /* b */`.
+    fn has_line_break_after_comments(&self, offset: usize) -> bool {
+        let text = self.compilation_unit.as_ref().unwrap().text();
+        let mut offset = offset;
+        for comment in &self.comments[self.next_comment..] {
+            if self.has_line_break_after(offset) {
+                return true;
+            }
+            let location = comment.location();
+            if location.first_offset() < offset {
+                continue;
+            }
+            if !comment.multiline() || !text[offset..location.first_offset()].chars().all(|ch| matches!(ch, ' ' | '\t')) {
+                break;
+            }
+            offset = location.last_offset();
+        }
+        self.has_line_break_after(offset)
+    }
+
+    /// Returns the offset of the token following `offset` in the source text,
+    /// skipping whitespace and pending comments.
+    fn next_token_offset(&self, offset: usize) -> usize {
+        let Some(cu) = &self.compilation_unit else {
+            return offset;
+        };
+        let text = cu.text();
+        let mut offset = offset;
+        for comment in &self.comments[self.next_comment..] {
+            offset = text.len() - text[offset..].trim_start().len();
+            let location = comment.location();
+            if location.first_offset() > offset {
+                break;
+            }
+            offset = offset.max(location.last_offset());
+        }
+        text.len() - text[offset..].trim_start().len()
+    }
+
+    /// Prints the pending comments between `offset` and the following token,
+    /// such as an operator or a comma, after the token preceding them.
+    fn comments_before_next_token(&mut self, offset: usize) {
+        if !self.has_comments_before(usize::MAX) {
+            return;
+        }
+        let token_offset = self.next_token_offset(offset);
+        while let Some(comment) = self.comments.get(self.next_comment).cloned() {
+            if comment.location().first_offset() >= token_offset {
+                break;
+            }
+            self.next_comment += 1;
+            if !self.line_start && !self.output.ends_with([' ', '(', '[', '{']) {
+                self.write(" ");
+            }
+            self.comment(&comment);
+            if !comment.multiline() {
+                self.newline();
+            }
+        }
+    }
+
+    /// Prints the comments between the end of a statement, such as a closing
+    /// brace, and a following keyword, such as `else`, keeping those that
+    /// are on their own lines in the source text on their own lines.
+    fn comments_before_keyword(&mut self, offset: usize) {
+        if !self.has_comments_before(usize::MAX) {
+            return;
+        }
+        let keyword_offset = self.next_token_offset(offset);
+        let line_comment_count = self.line_comment_count;
+        self.trailing_comments(offset);
+        if self.line_comment_count != line_comment_count {
+            self.newline();
+        }
+        if let Some(comment) = self.comments.get(self.next_comment).filter(|comment| comment.location().first_offset() < keyword_offset) {
+            let from = self.last_offset.max(offset);
+            let comment_offset = comment.location().first_offset();
+            if !self.line_start && self.compilation_unit.as_ref().unwrap().text()[from..comment_offset].contains('\n') {
+                self.newline();
+            }
+        }
+        self.last_offset = self.last_offset.max(offset);
+        self.comments_before(keyword_offset);
+    }
+
+    /// Writes a space separating an operator from the preceding operand,
+    /// unless a comment terminated the line.
+    fn operator_space(&mut self) {
+        if !self.line_start {
+            self.write(" ");
+        }
+    }
+
+    fn comment(&mut self, comment: &Comment) {
+        let location = comment.location();
+        if comment.multiline() {
+            self.multiline_comment(&location);
+        } else {
+            self.write("//");
+            self.write(comment.content().trim_end());
+            self.line_comment_count += 1;
+        }
+        self.last_offset = location.last_offset();
+    }
+
+    /// Prints a multi-line comment from the source text, re-indenting its lines.
+    fn multiline_comment(&mut self, location: &Location) {
+        let column = location.first_column();
+        for (i, line) in location.text().lines().enumerate() {
+            let line = line.trim_end();
+            if i != 0 {
+                self.newline();
+                let trimmed_line = line.trim_start();
+                if trimmed_line.starts_with('*') {
+                    self.write(" ");
+                    self.write(trimmed_line);
+                } else {
+                    let indent_length = line.len() - trimmed_line.len();
+                    let mut chars = line.chars();
+                    for _ in 0..column.min(indent_length) {
+                        chars.next();
+                    }
+                    self.write(chars.as_str());
+                }
+            } else {
+                self.write(line);
+            }
+        }
+    }
+
+    /// Prints the opening brace of a block following a header such as `if (x)`.
+    fn open_brace(&mut self) {
+        match self.options.brace_placement {
+            BracePlacement::SameLine => self.write(" {"),
+            BracePlacement::NextLine => {
+                self.newline();
+                self.write("{");
+            },
+        }
+    }
+
+    /// Prints a block following a header such as `if (x)`.
+    fn header_block(&mut self, block: &Block) {
+        match self.options.brace_placement {
+            BracePlacement::SameLine => self.write(" "),
+            BracePlacement::NextLine => self.newline(),
+        }
+        self.block(block);
+    }
+
+    /// Separates a closing brace from a following keyword such as `else`.
+    fn after_closing_brace(&mut self) {
+        match self.options.brace_placement {
+            BracePlacement::SameLine => self.write(" "),
+            BracePlacement::NextLine => self.newline(),
+        }
+    }
+
+    fn save_state(&self) -> PrinterState {
+        PrinterState {
+            output_length: self.output.len(),
+            line_start: self.line_start,
+            next_comment: self.next_comment,
+            last_offset: self.last_offset,
+            line_comment_count: self.line_comment_count,
+        }
+    }
+
+    fn restore_state(&mut self, state: &PrinterState) {
+        self.output.truncate(state.output_length);
+        self.line_start = state.line_start;
+        self.next_comment = state.next_comment;
+        self.last_offset = state.last_offset;
+        self.line_comment_count = state.line_comment_count;
+    }
+
+    /// Indicates whether the text printed since a saved state fits
+    /// within the maximum line length and contains no single-line comment.
+    fn fits_since(&self, state: &PrinterState) -> bool {
+        if self.line_comment_count != state.line_comment_count {
+            return false;
+        }
+        let max_line_length = self.options.max_line_length;
+        let first_line_start = self.output[..state.output_length].rfind('\n').map_or(0, |i| i + 1);
+        let first_line_end = self.output[state.output_length..].find('\n').map_or(self.output.len(), |i| state.output_length + i);
+        let last_line_start = self.output.rfind('\n').map_or(0, |i| i + 1).max(first_line_start);
+        self.output[first_line_start..first_line_end].chars().count() <= max_line_length
+            && self.output[last_line_start..].chars().count() <= max_line_length
+    }
+
+    /// Prints a comma-separated list surrounded by `open` and `close`, either on
+    /// a single line or, if it does not fit, with one item per line.
+    fn list<T>(&mut self, open: &str, close: &str, items: &[T], item_offsets: impl Fn(&T) -> Option<(usize, usize)>, print_item: impl Fn(&mut Self, &T)) {
+        self.write(open);
+        if items.is_empty() {
+            self.write(close);
+            return;
+        }
+        let state = self.save_state();
+        self.flat_depth += 1;
+        for (i, item) in items.iter().enumerate() {
+            if i != 0 {
+                self.write(", ");
+            }
+            print_item(self, item);
+            if let (true, Some((_, last_offset))) = (i + 1 != items.len(), item_offsets(item)) {
+                self.comments_before_next_token(last_offset);
+            }
+        }
+        self.write(close);
+        self.flat_depth -= 1;
+        if self.flat_depth != 0 || self.options.max_line_length == 0 || self.fits_since(&state) {
+            return;
+        }
+        self.restore_state(&state);
+        // A single item, such as in `f(g(...))`, may break its own lists instead.
+        if let [item] = items {
+            print_item(self, item);
+            self.write(close);
+            if self.fits_since(&state) {
+                return;
+            }
+            self.restore_state(&state);
+        }
+        self.newline();
+        self.indent();
+        for (i, item) in items.iter().enumerate() {
+            let offsets = item_offsets(item);
+            if let Some((first_offset, _)) = offsets {
+                self.comments_before(first_offset);
+            }
+            print_item(self, item);
+            if i + 1 != items.len() {
+                if let Some((_, last_offset)) = offsets {
+                    self.comments_before_next_token(last_offset);
+                }
+                self.write(",");
+            }
+            if let Some((_, last_offset)) = offsets {
+                self.trailing_comments(last_offset);
+            }
+            self.newline();
+        }
+        self.dedent();
+        self.write(close);
+    }
+
+    pub fn print_program(&mut self, program: &Program) {
+        let mut first = true;
+        for package in &program.packages {
+            if !first {
+                self.blank_line();
+            }
+            self.print_package_definition(package);
+            self.trailing_comments(package.location.last_offset());
+            self.newline();
+            first = false;
+        }
+        if !first && program.directives.iter().any(|d| !matches!(d.as_ref(), Directive::Invalidated(_))) {
+            self.blank_line();
+        }
+        self.directive_list(&program.directives);
+        self.comments_before(usize::MAX);
+        if !self.line_start {
+            self.newline();
+        }
+    }
+
+    pub fn print_package_definition(&mut self, package: &PackageDefinition) {
+        let first_offset = package.asdoc.as_ref().map_or(package.location.first_offset(), |asdoc| asdoc.location.first_offset());
+        self.comments_before(first_offset);
+        if self.has_blank_line(self.last_offset, first_offset) {
+            self.blank_line();
+        }
+        if let Some(asdoc) = &package.asdoc {
+            self.asdoc(asdoc);
+        }
+        self.write("package");
+        if !package.name.is_empty() {
+            self.write(" ");
+            self.write(&package.name.iter().map(|name| name.0.clone()).collect::<Vec<_>>().join("."));
+        }
+        self.header_block(&package.block);
+        self.last_offset = package.location.last_offset();
+    }
+
+    /// Prints a list of directives, each followed by a line terminator.
+    fn directive_list(&mut self, list: &[Rc<Directive>]) {
+        let mut previous: Option<&Rc<Directive>> = None;
+        for directive in list {
+            if matches!(directive.as_ref(), Directive::Invalidated(_)) {
+                continue;
+            }
+            if let Some(previous) = previous {
+                if is_separated_directive(previous) || is_separated_directive(directive) {
+                    self.blank_line();
+                }
+            }
+            let first_offset = directive_first_offset(directive);
+            self.comments_before(first_offset);
+            if self.has_blank_line(self.last_offset, first_offset) {
+                self.blank_line();
+            }
+            self.print_directive(directive);
+            self.trailing_comments(directive.location().last_offset());
+            self.newline();
+            previous = Some(directive);
+        }
+    }
+
+    /// Indicates whether there are pending comments before `offset`.
+    fn has_comments_before(&self, offset: usize) -> bool {
+        self.comments.get(self.next_comment).is_some_and(|comment| comment.location().first_offset() < offset)
+    }
+
+    fn block(&mut self, block: &Block) {
+        let last_offset = block.location.last_offset();
+        if block.directives.iter().all(|d| matches!(d.as_ref(), Directive::Invalidated(_))) && !self.has_comments_before(last_offset) {
+            if self.options.brace_placement == BracePlacement::NextLine {
+                self.write("{");
+                self.newline();
+                self.write("}");
+            } else {
+                self.write("{}");
+            }
+            return;
+        }
+        // Lists inside the block are measured on their own.
+        let flat_depth = std::mem::take(&mut self.flat_depth);
+        self.write("{");
+        self.last_offset = block.location.first_offset() + 1;
+        self.trailing_comments(self.last_offset);
+        self.newline();
+        self.indent();
+        self.directive_list(&block.directives);
+        self.comments_before(last_offset);
+        if !self.line_start {
+            self.newline();
+        }
+        self.dedent();
+        self.write("}");
+        self.flat_depth = flat_depth;
+    }
+
+    /// Prints the body of a statement such as `if` or `while`.
+    fn substatement(&mut self, directive: &Directive) {
+        match directive {
+            Directive::Block(block) => {
+                self.header_block(block);
+            },
+            Directive::EmptyStatement(_) => {
+                self.write(";");
+            },
+            _ => {
+                self.newline();
+                self.indent();
+                self.print_directive(directive);
+                self.dedent();
+            },
+        }
+    }
+
+    /// Prints a substatement surrounded by braces.
+    fn braced_substatement(&mut self, directive: &Directive) {
+        self.open_brace();
+        self.newline();
+        self.indent();
+        self.print_directive(directive);
+        self.newline();
+        self.dedent();
+        self.write("}");
+    }
+
+    pub fn print_directive(&mut self, directive: &Directive) {
+        self.comments_before(directive_first_offset(directive));
+        self.directive(directive);
+        self.last_offset = self.last_offset.max(directive.location().last_offset());
+    }
+
+    fn directive(&mut self, directive: &Directive) {
+        match directive {
+            Directive::EmptyStatement(_) => {
+                self.write(";");
+            },
+            Directive::ExpressionStatement(stmt) => {
+                self.statement_expression(&stmt.expression);
+                self.write(";");
+            },
+            Directive::SuperStatement(stmt) => {
+                self.write("super");
+                self.arguments(&stmt.arguments);
+                self.write(";");
+            },
+            Directive::Block(block) => {
+                self.block(block);
+            },
+            Directive::LabeledStatement(stmt) => {
+                self.write(&stmt.label.0);
+                self.write(": ");
+                self.print_directive(&stmt.substatement);
+            },
+            Directive::IfStatement(stmt) => {
+                self.if_statement(stmt);
+            },
+            Directive::SwitchStatement(stmt) => {
+                self.switch_statement(stmt);
+            },
+            Directive::SwitchTypeStatement(stmt) => {
+                self.switch_type_statement(stmt);
+            },
+            Directive::DoStatement(stmt) => {
+                self.write("do");
+                self.substatement(&stmt.body);
+                if matches!(stmt.body.as_ref(), Directive::Block(_)) {
+                    self.after_closing_brace();
+                } else {
+                    self.newline();
+                }
+                self.write("while (");
+                self.expression(&stmt.test, OperatorPrecedence::List, true);
+                self.write(");");
+            },
+            Directive::WhileStatement(stmt) => {
+                self.write("while (");
+                self.expression(&stmt.test, OperatorPrecedence::List, true);
+                self.write(")");
+                self.substatement(&stmt.body);
+            },
+            Directive::ForStatement(stmt) => {
+                self.write("for (");
+                match &stmt.init {
+                    Some(ForInitializer::Expression(init)) => {
+                        self.expression(init, OperatorPrecedence::List, false);
+                    },
+                    Some(ForInitializer::VariableDefinition(init)) => {
+                        self.variable_bindings(&init.kind.0, &init.bindings, false);
+                    },
+                    None => {},
+                }
+                self.write(";");
+                if let Some(test) = &stmt.test {
+                    self.write(" ");
+                    self.expression(test, OperatorPrecedence::List, true);
+                }
+                self.write(";");
+                if let Some(update) = &stmt.update {
+                    self.write(" ");
+                    self.expression(update, OperatorPrecedence::List, true);
+                }
+                self.write(")");
+                self.substatement(&stmt.body);
+            },
+            Directive::ForInStatement(stmt) => {
+                self.write(if stmt.each { "for each (" } else { "for (" });
+                match &stmt.left {
+                    ForInBinding::Expression(left) => {
+                        self.expression(left, OperatorPrecedence::Postfix, false);
+                    },
+                    ForInBinding::VariableDefinition(left) => {
+                        self.variable_bindings(&left.kind.0, &left.bindings, false);
+                    },
+                }
+                self.write(" in ");
+                self.expression(&stmt.right, OperatorPrecedence::List, true);
+                self.write(")");
+                self.substatement(&stmt.body);
+            },
+            Directive::BreakStatement(stmt) => {
+                self.write("break");
+                if let Some(label) = &stmt.label {
+                    self.write(" ");
+                    self.write(&label.0);
+                }
+                self.write(";");
+            },
+            Directive::ContinueStatement(stmt) => {
+                self.write("continue");
+                if let Some(label) = &stmt.label {
+                    self.write(" ");
+                    self.write(&label.0);
+                }
+                self.write(";");
+            },
+            Directive::WithStatement(stmt) => {
+                self.write("with (");
+                self.expression(&stmt.object, OperatorPrecedence::List, true);
+                self.write(")");
+                self.substatement(&stmt.body);
+            },
+            Directive::ReturnStatement(stmt) => {
+                self.write("return");
+                if let Some(expression) = &stmt.expression {
+                    self.write(" ");
+                    self.expression(expression, OperatorPrecedence::List, true);
+                }
+                self.write(";");
+            },
+            Directive::ThrowStatement(stmt) => {
+                self.write("throw ");
+                self.expression(&stmt.expression, OperatorPrecedence::List, true);
+                self.write(";");
+            },
+            Directive::DefaultXmlNamespaceStatement(stmt) => {
+                self.write("default xml namespace = ");
+                self.expression(&stmt.right, OperatorPrecedence::AssignmentAndOther, true);
+                self.write(";");
+            },
+            Directive::TryStatement(stmt) => {
+                self.write("try");
+                self.header_block(&stmt.block);
+                for catch_clause in &stmt.catch_clauses {
+                    self.after_closing_brace();
+                    self.write("catch (");
+                    self.typed_destructuring(&catch_clause.parameter);
+                    self.write(")");
+                    self.header_block(&catch_clause.block);
+                }
+                if let Some(finally_clause) = &stmt.finally_clause {
+                    self.after_closing_brace();
+                    self.write("finally");
+                    self.header_block(&finally_clause.block);
+                }
+            },
+            Directive::Invalidated(_) => {},
+            Directive::ConfigurationDirective(defn) => {
+                self.write("configuration {");
+                self.newline();
+                self.indent();
+                self.print_directive(&defn.directive);
+                self.newline();
+                self.dedent();
+                self.write("}");
+            },
+            Directive::ImportDirective(defn) => {
+                self.write("import ");
+                if let Some(alias) = &defn.alias {
+                    self.write(&alias.0);
+                    self.write(" = ");
+                }
+                self.import_name(&defn.package_name, &defn.import_specifier);
+                self.write(";");
+            },
+            Directive::UseNamespaceDirective(defn) => {
+                self.write("use namespace ");
+                self.expression(&defn.expression, OperatorPrecedence::List, true);
+                self.write(";");
+            },
+            Directive::IncludeDirective(defn) => {
+                self.write("include ");
+                self.string_literal(&defn.source);
+                self.write(";");
+            },
+            Directive::NormalConfigurationDirective(defn) => {
+                self.write(&defn.namespace.0);
+                self.write("::");
+                self.write(&defn.constant_name.0);
+                if let Directive::Block(block) = defn.directive.as_ref() {
+                    self.header_block(block);
+                } else {
+                    self.newline();
+                    self.print_directive(&defn.directive);
+                }
+            },
+            Directive::PackageConcatDirective(defn) => {
+                self.write("public += ");
+                self.import_name(&defn.package_name, &defn.import_specifier);
+                self.write(";");
+            },
+            Directive::DirectiveInjection(injection) => {
+                let directives = injection.directives.borrow();
+                let directives: Vec<&Rc<Directive>> = directives.iter().filter(|d| !matches!(d.as_ref(), Directive::Invalidated(_))).collect();
+                for (i, directive) in directives.iter().enumerate() {
+                    if i != 0 {
+                        self.newline();
+                    }
+                    self.print_directive(directive);
+                }
+            },
+            Directive::VariableDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.variable_bindings(&defn.kind.0, &defn.bindings, true);
+                self.write(";");
+            },
+            Directive::FunctionDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("function ");
+                match &defn.name {
+                    FunctionName::Getter(_) => self.write("get "),
+                    FunctionName::Setter(_) => self.write("set "),
+                    _ => {},
+                }
+                self.write(&defn.name_identifier().0);
+                self.function_common(&defn.common);
+                if !defn.common.has_block_body() {
+                    self.write(";");
+                }
+            },
+            Directive::ClassDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("class ");
+                self.write(&defn.name.0);
+                self.type_parameters(&defn.type_parameters);
+                if let Some(extends_clause) = &defn.extends_clause {
+                    self.write(" extends ");
+                    self.type_expression(extends_clause);
+                }
+                if let Some(implements_clause) = &defn.implements_clause {
+                    self.write(" implements ");
+                    self.type_expression_list(implements_clause);
+                }
+                self.header_block(&defn.block);
+            },
+            Directive::EnumDefinition(defn) => {
+                if defn.is_set {
+                    self.write("[Set]");
+                    self.newline();
+                }
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("enum ");
+                self.write(&defn.name.0);
+                if let Some(as_clause) = &defn.as_clause {
+                    self.write(" as ");
+                    self.type_expression(as_clause);
+                }
+                self.header_block(&defn.block);
+            },
+            Directive::InterfaceDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("interface ");
+                self.write(&defn.name.0);
+                self.type_parameters(&defn.type_parameters);
+                if let Some(extends_clause) = &defn.extends_clause {
+                    self.write(" extends ");
+                    self.type_expression_list(extends_clause);
+                }
+                self.header_block(&defn.block);
+            },
+            Directive::TypeDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("type ");
+                self.write(&defn.left.0);
+                self.write(" = ");
+                self.type_expression(&defn.right);
+                self.write(";");
+            },
+            Directive::NamespaceDefinition(defn) => {
+                self.annotations(&defn.asdoc, &defn.attributes);
+                self.write("namespace ");
+                self.write(&defn.left.0);
+                if let Some(right) = &defn.right {
+                    self.write(" = ");
+                    self.expression(right, OperatorPrecedence::AssignmentAndOther, true);
+                }
+                self.write(";");
+            },
+        }
+    }
+
+    fn if_statement(&mut self, stmt: &IfStatement) {
+        self.write("if (");
+        self.expression(&stmt.test, OperatorPrecedence::List, true);
+        self.write(")");
+        let Some(alternative) = &stmt.alternative else {
+            self.substatement(&stmt.consequent);
+            return;
+        };
+        // Braces prevent a nested `if` from taking over the `else` clause.
+        let braced = ends_with_if_without_else(&stmt.consequent);
+        if braced {
+            self.braced_substatement(&stmt.consequent);
+        } else {
+            self.substatement(&stmt.consequent);
+        }
+        self.comments_before_keyword(stmt.consequent.location().last_offset());
+        if self.line_start || self.output.ends_with(' ') {
+            // A comment precedes `else`.
+        } else if braced || matches!(stmt.consequent.as_ref(), Directive::Block(_)) {
+            self.after_closing_brace();
+        } else {
+            self.newline();
+        }
+        self.write("else");
+        if let Directive::IfStatement(alternative) = alternative.as_ref() {
+            self.write(" ");
+            self.if_statement(alternative);
+        } else {
+            self.substatement(alternative);
+        }
+    }
+
+    fn switch_statement(&mut self, stmt: &SwitchStatement) {
+        self.write("switch (");
+        self.expression(&stmt.discriminant, OperatorPrecedence::List, true);
+        self.write(")");
+        self.open_brace();
+        self.newline();
+        self.indent();
+        for case in &stmt.cases {
+            for label in &case.labels {
+                self.comments_before(label.location().first_offset());
+                match label {
+                    CaseLabel::Case((exp, _)) => {
+                        self.write("case ");
+                        self.expression(exp, OperatorPrecedence::List, true);
+                        self.write(":");
+                    },
+                    CaseLabel::Default(_) => {
+                        self.write("default:");
+                    },
+                }
+                self.last_offset = label.location().last_offset();
+                self.trailing_comments(self.last_offset);
+                self.newline();
+            }
+            self.indent();
+            self.directive_list(&case.directives);
+            self.dedent();
+        }
+        self.comments_before(stmt.location.last_offset());
+        self.dedent();
+        self.write("}");
+    }
+
+    fn switch_type_statement(&mut self, stmt: &SwitchTypeStatement) {
+        self.write("switch type (");
+        self.expression(&stmt.discriminant, OperatorPrecedence::List, true);
+        self.write(")");
+        self.open_brace();
+        self.newline();
+        self.indent();
+        for case in &stmt.cases {
+            self.comments_before(case.location.first_offset());
+            if let Some(parameter) = &case.parameter {
+                self.write("case (");
+                self.typed_destructuring(parameter);
+                self.write(")");
+            } else {
+                self.write("default");
+            }
+            self.header_block(&case.block);
+            self.trailing_comments(case.location.last_offset());
+            self.newline();
+        }
+        self.comments_before(stmt.location.last_offset());
+        self.dedent();
+        self.write("}");
+    }
+
+    fn import_name(&mut self, package_name: &[(String, Location)], specifier: &ImportSpecifier) {
+        for name in package_name {
+            self.write(&name.0);
+            self.write(".");
+        }
+        match specifier {
+            ImportSpecifier::Wildcard(_) => self.write("*"),
+            ImportSpecifier::Recursive(_) => self.write("**"),
+            ImportSpecifier::Identifier(name) => self.write(&name.0),
+        }
+    }
+
+    fn variable_bindings(&mut self, kind: &VariableDefinitionKind, bindings: &[Rc<VariableBinding>], allow_in: bool) {
+        self.write(match kind {
+            VariableDefinitionKind::Var => "var ",
+            VariableDefinitionKind::Const => "const ",
+        });
+        for (i, binding) in bindings.iter().enumerate() {
+            if i != 0 {
+                self.write(", ");
+            }
+            self.typed_destructuring(&binding.destructuring);
+            if let Some(initializer) = &binding.initializer {
+                self.write(" = ");
+                self.expression(initializer, OperatorPrecedence::AssignmentAndOther, allow_in);
+            }
+        }
+    }
+
+    fn typed_destructuring(&mut self, destructuring: &TypedDestructuring) {
+        self.expression(&destructuring.destructuring, OperatorPrecedence::Postfix, true);
+        if let Some(type_annotation) = &destructuring.type_annotation {
+            self.write(":");
+            self.type_expression(type_annotation);
+        }
+    }
+
+    fn type_parameters(&mut self, type_parameters: &Option<Vec<Rc<TypeParameter>>>) {
+        if let Some(type_parameters) = type_parameters {
+            self.write(".<");
+            for (i, type_parameter) in type_parameters.iter().enumerate() {
+                if i != 0 {
+                    self.write(", ");
+                }
+                self.write(&type_parameter.name.0);
+            }
+            self.write(">");
+        }
+    }
+
+    fn function_common(&mut self, common: &FunctionCommon) {
+        let parameters = &common.signature.parameters;
+        self.list("(", ")", parameters, |parameter| Some((parameter.location.first_offset(), parameter.location.last_offset())), |printer, parameter| {
+            if matches!(parameter.kind, ParameterKind::Rest) {
+                printer.write("...");
+            }
+            printer.typed_destructuring(&parameter.destructuring);
+            if let Some(default_value) = &parameter.default_value {
+                printer.write(" = ");
+                printer.expression(default_value, OperatorPrecedence::AssignmentAndOther, true);
+            }
+        });
+        if let Some(result_type) = &common.signature.result_type {
+            self.write(":");
+            self.type_expression(result_type);
+        }
+        match &common.body {
+            Some(FunctionBody::Block(block)) => {
+                self.header_block(block);
+            },
+            Some(FunctionBody::Expression(exp)) => {
+                self.write(" ");
+                if starts_with_brace(exp) {
+                    self.write("(");
+                    self.expression(exp, OperatorPrecedence::List, true);
+                    self.write(")");
+                } else {
+                    self.expression(exp, OperatorPrecedence::AssignmentAndOther, true);
+                }
+            },
+            None => {},
+        }
+    }
+
+    /// Prints the ASDoc comment, meta-data and modifiers of an annotatable directive.
+    fn annotations(&mut self, asdoc: &Option<Rc<AsDoc>>, attributes: &[Attribute]) {
+        // The parser delegates an ASDoc comment preceding meta-data to the
+        // annotatable directive, except for certain Flex meta-data.
+        let asdoc_first = match attributes.first() {
+            Some(Attribute::Metadata(metadata)) => metadata.asdoc.is_none() && !is_flex_documentable_meta_data(&metadata.name.0),
+            _ => true,
+        };
+        let metadata_list: Vec<&Rc<Metadata>> = attributes.iter().filter_map(|attribute| match attribute {
+            Attribute::Metadata(metadata) => Some(metadata),
+            _ => None,
+        }).collect();
+        // When printing from the source text, the ASDoc comment keeps its position among the meta-data.
+        let asdoc_index = match asdoc {
+            Some(asdoc) if self.compilation_unit.is_some() => {
+                metadata_list.iter().take_while(|metadata| metadata.location.first_offset() < asdoc.location.first_offset()).count()
+            },
+            _ => if asdoc_first { 0 } else { metadata_list.len() },
+        };
+        for (i, metadata) in metadata_list.iter().enumerate() {
+            if i == asdoc_index {
+                if let Some(asdoc) = asdoc {
+                    self.asdoc(asdoc);
+                }
+            }
+            self.metadata(metadata);
+        }
+        if asdoc_index == metadata_list.len() {
+            if let Some(asdoc) = asdoc {
+                self.asdoc(asdoc);
+            }
+        }
+        for attribute in attributes {
+            let keyword = match attribute {
+                Attribute::Metadata(_) => continue,
+                Attribute::Expression(exp) => {
+                    self.expression(exp, OperatorPrecedence::Postfix, true);
+                    self.write(" ");
+                    continue;
+                },
+                Attribute::Public(_) => "public",
+                Attribute::Private(_) => "private",
+                Attribute::Protected(_) => "protected",
+                Attribute::Internal(_) => "internal",
+                Attribute::Final(_) => "final",
+                Attribute::Native(_) => "native",
+                Attribute::Static(_) => "static",
+                Attribute::Abstract(_) => "abstract",
+                Attribute::Override(_) => "override",
+                Attribute::Dynamic(_) => "dynamic",
+            };
+            self.write(keyword);
+            self.write(" ");
+        }
+    }
+
+    fn metadata(&mut self, metadata: &Metadata) {
+        if let Some(asdoc) = &metadata.asdoc {
+            self.asdoc(asdoc);
+        }
+        self.write("[");
+        self.write(&metadata.name.0);
+        if let Some(entries) = &metadata.entries {
+            self.write("(");
+            for (i, entry) in entries.iter().enumerate() {
+                if i != 0 {
+                    self.write(", ");
+                }
+                if let Some(key) = &entry.key {
+                    self.write(&key.0);
+                    self.write("=");
+                }
+                match entry.value.as_ref() {
+                    MetadataValue::IdentifierString((value, _)) => self.write(value),
+                    MetadataValue::String((value, _)) => self.string_literal(value),
+                }
+            }
+            self.write(")");
+        }
+        self.write("]");
+        self.last_offset = metadata.location.last_offset();
+        self.trailing_comments(self.last_offset);
+        self.newline();
+    }
+
+    fn asdoc(&mut self, asdoc: &AsDoc) {
+        // Preserve the ASDoc comment as written when printing from the source text.
+        if self.compilation_unit.is_some() {
+            self.comments_before(asdoc.location.first_offset());
+            self.multiline_comment(&asdoc.location);
+            self.newline();
+            return;
+        }
+        let mut lines: Vec<String> = vec![];
+        if let Some((main_body, _)) = &asdoc.main_body {
+            lines.extend(main_body.lines().map(|line| line.to_owned()));
+        }
+        if !lines.is_empty() && !asdoc.tags.is_empty() {
+            lines.push(String::new());
+        }
+        for (tag, _) in &asdoc.tags {
+            lines.extend(self.asdoc_tag(tag).lines().map(|line| line.to_owned()));
+        }
+        self.write("/**");
+        self.newline();
+        for line in lines {
+            if line.is_empty() {
+                self.write(" *");
+            } else {
+                self.write(" * ");
+                self.write(&line);
+            }
+            self.newline();
+        }
+        self.write(" */");
+        self.newline();
+    }
+
+    /// Prints an ASDoc comment that precedes a bracketed expression,
+    /// which is only kept when printing from the source text.
+    fn inline_asdoc(&mut self, asdoc: &AsDoc) {
+        if self.compilation_unit.is_some() {
+            self.multiline_comment(&asdoc.location);
+            self.write(" ");
+        }
+    }
+
+    fn asdoc_tag(&self, tag: &AsDocTag) -> String {
+        let (name, content): (&str, String) = match tag {
+            AsDocTag::Author(text) => ("author", text.clone()),
+            AsDocTag::Copy(reference) => ("copy", self.asdoc_reference(reference)),
+            AsDocTag::Created(text) => ("created", text.clone()),
+            AsDocTag::Default(text) => ("default", text.clone()),
+            AsDocTag::Deprecated { message } => ("deprecated", message.clone().unwrap_or_default()),
+            AsDocTag::EventType(exp) => ("eventType", PrinterFacade(self.options.clone()).print_expression(exp)),
+            AsDocTag::Example(text) => ("example", text.clone()),
+            AsDocTag::InheritDoc => ("inheritDoc", String::new()),
+            AsDocTag::Internal(text) => ("internal", text.clone()),
+            AsDocTag::Langversion(text) => ("langversion", text.clone()),
+            AsDocTag::Param { name, description } => ("param", format!("{name} {description}").trim_end().to_owned()),
+            AsDocTag::Playerversion(text) => ("playerversion", text.clone()),
+            AsDocTag::Private => ("private", String::new()),
+            AsDocTag::Productversion(text) => ("productversion", text.clone()),
+            AsDocTag::Return(text) => ("return", text.clone()),
+            AsDocTag::See { reference, display_text } => {
+                let mut content = self.asdoc_reference(reference);
+                if let Some(display_text) = display_text {
+                    content.push(' ');
+                    content.push_str(display_text);
+                }
+                ("see", content)
+            },
+            AsDocTag::Throws { class_reference, description } => {
+                let mut content = PrinterFacade(self.options.clone()).print_expression(class_reference);
+                if let Some(description) = description {
+                    content.push(' ');
+                    content.push_str(description);
+                }
+                ("throws", content)
+            },
+            AsDocTag::Version(text) => ("version", text.clone()),
+        };
+        if content.is_empty() {
+            format!("@{name}")
+        } else {
+            format!("@{name} {content}")
+        }
+    }
+
+    fn asdoc_reference(&self, reference: &AsDocReference) -> String {
+        let mut printer = Printer::new(self.options.clone());
+        if let Some(base) = &reference.base {
+            printer.print_expression(base);
+        }
+        if let Some(instance_property) = &reference.instance_property {
+            printer.write("#");
+            printer.qualified_identifier(instance_property);
+        }
+        printer.into_output()
+    }
+
+    /// Prints the expression of an expression statement, parenthesizing it
+    /// where it would otherwise be read as another directive.
+    fn statement_expression(&mut self, exp: &Expression) {
+        let leftmost = leftmost_expression(exp);
+        if matches!(leftmost, Expression::ObjectInitializer(_) | Expression::Function(_) | Expression::ImportMeta(_)) {
+            self.write("(");
+            self.expression(exp, OperatorPrecedence::List, true);
+            self.write(")");
+        } else {
+            self.expression(exp, OperatorPrecedence::List, true);
+        }
+    }
+
+    pub fn print_expression(&mut self, expression: &Expression) {
+        self.expression(expression, OperatorPrecedence::List, true);
+    }
+
+    fn type_expression(&mut self, exp: &Expression) {
+        self.expression(exp, OperatorPrecedence::Postfix, true);
+    }
+
+    fn type_expression_list(&mut self, list: &[Rc<Expression>]) {
+        for (i, exp) in list.iter().enumerate() {
+            if i != 0 {
+                self.write(", ");
+            }
+            self.type_expression(exp);
+        }
+    }
+
+    /// Prints an expression, parenthesizing it if its precedence is lower
+    /// than `min_precedence` or if it is an `in` operation where `allow_in` is false.
+    fn expression(&mut self, exp: &Expression, min_precedence: OperatorPrecedence, allow_in: bool) {
+        let location = exp.location();
+        self.comments_before(location.first_offset());
+        self.expression_1(exp, min_precedence, allow_in);
+        self.last_offset = self.last_offset.max(location.last_offset());
+    }
+
+    fn expression_1(&mut self, exp: &Expression, min_precedence: OperatorPrecedence, allow_in: bool) {
+        let in_operation = matches!(exp, Expression::Binary(BinaryExpression { operator: Operator::In | Operator::NotIn, .. }));
+        if !min_precedence.includes(&expression_precedence(exp)) || (!allow_in && in_operation) {
+            self.write("(");
+            self.expression_without_parens(exp, true);
+            self.write(")");
+        } else {
+            self.expression_without_parens(exp, allow_in);
+        }
+    }
+
+    /// Prints the base of a postfix operation such as a property access or call.
+    fn postfix_base(&mut self, base: &Expression) {
+        // `new C().x` and `new <T>[].x` need no parentheses,
+        // although `new` is not allowed at postfix precedence.
+        match base {
+            Expression::New(NewExpression { arguments: Some(_), .. }) |
+            Expression::VectorLiteral(_) => self.expression_without_parens(base, true),
+            _ => self.expression(base, OperatorPrecedence::Postfix, true),
+        }
+    }
+
+    /// Prints the base of a `.` or `..` operator.
+    fn member_base(&mut self, base: &Expression) {
+        // `(1).x` is not `1.x`, which begins with the numeric literal `1.`.
+        if is_integer_literal_without_suffix(base) {
+            self.write("(");
+            self.expression_without_parens(base, true);
+            self.write(")");
+        } else {
+            self.postfix_base(base);
+        }
+    }
+
+    fn expression_without_parens(&mut self, exp: &Expression, allow_in: bool) {
+        match exp {
+            Expression::QualifiedIdentifier(id) => {
+                self.qualified_identifier(id);
+            },
+            Expression::Paren(exp) => {
+                self.write("(");
+                self.expression(&exp.expression, OperatorPrecedence::List, true);
+                self.write(")");
+            },
+            Expression::NullLiteral(_) => {
+                self.write("null");
+            },
+            Expression::BooleanLiteral(literal) => {
+                self.write(if literal.value { "true" } else { "false" });
+            },
+            Expression::NumericLiteral(literal) => {
+                self.write(&literal.value);
+                if literal.suffix == NumberSuffix::F {
+                    self.write("f");
+                }
+            },
+            Expression::StringLiteral(literal) => {
+                self.string_literal(&literal.value);
+            },
+            Expression::ThisLiteral(_) => {
+                self.write("this");
+            },
+            Expression::RegExpLiteral(literal) => {
+                self.write("/");
+                self.write(&literal.body);
+                self.write("/");
+                self.write(&literal.flags);
+            },
+            Expression::Xml(exp) => {
+                self.xml_element(&exp.element);
+            },
+            Expression::XmlMarkup(exp) => {
+                self.write(&exp.markup);
+            },
+            Expression::XmlList(exp) => {
+                self.write("<>");
+                self.xml_content(&exp.content);
+                self.write("</>");
+            },
+            Expression::ArrayLiteral(exp) => {
+                if let Some(asdoc) = &exp.asdoc {
+                    self.inline_asdoc(asdoc);
+                }
+                self.elements(&exp.elements);
+            },
+            Expression::VectorLiteral(exp) => {
+                self.write("new <");
+                self.type_expression(&exp.element_type);
+                self.write(">");
+                self.elements(&exp.elements);
+            },
+            Expression::ObjectInitializer(exp) => {
+                self.object_initializer(exp);
+            },
+            Expression::Function(exp) => {
+                self.write("function");
+                if let Some(name) = &exp.name {
+                    self.write(" ");
+                    self.write(&name.0);
+                }
+                self.function_common(&exp.common);
+            },
+            Expression::ImportMeta(_) => {
+                self.write("import.meta");
+            },
+            Expression::New(exp) => {
+                self.write("new ");
+                let base_needs_parens = !is_new_subexpression(&exp.base) || (exp.arguments.is_some() && is_new_expression_without_arguments(&exp.base));
+                if base_needs_parens {
+                    self.write("(");
+                    self.expression(&exp.base, OperatorPrecedence::List, true);
+                    self.write(")");
+                } else {
+                    self.expression_without_parens(&exp.base, true);
+                }
+                if let Some(arguments) = &exp.arguments {
+                    self.arguments(arguments);
+                }
+            },
+            Expression::Member(exp) => {
+                self.member_base(&exp.base);
+                if !matches!(exp.base.as_ref(), Expression::OptionalChainingPlaceholder(_)) {
+                    self.write(".");
+                }
+                self.qualified_identifier(&exp.identifier);
+            },
+            Expression::ComputedMember(exp) => {
+                self.postfix_base(&exp.base);
+                if let Some(asdoc) = &exp.asdoc {
+                    self.inline_asdoc(asdoc);
+                }
+                self.write("[");
+                self.expression(&exp.key, OperatorPrecedence::List, true);
+                self.write("]");
+            },
+            Expression::Descendants(exp) => {
+                self.member_base(&exp.base);
+                self.write("..");
+                self.qualified_identifier(&exp.identifier);
+            },
+            Expression::Filter(exp) => {
+                self.postfix_base(&exp.base);
+                self.write(".(");
+                self.expression(&exp.test, OperatorPrecedence::List, true);
+                self.write(")");
+            },
+            Expression::Super(exp) => {
+                self.write("super");
+                if let Some(object) = &exp.object {
+                    self.arguments(object);
+                }
+            },
+            Expression::Call(exp) => {
+                self.postfix_base(&exp.base);
+                self.arguments(&exp.arguments);
+            },
+            Expression::WithTypeArguments(exp) => {
+                self.postfix_base(&exp.base);
+                self.write(".<");
+                self.type_expression_list(&exp.arguments);
+                self.write(">");
+            },
+            Expression::Unary(exp) => {
+                self.unary_expression(exp, allow_in);
+            },
+            Expression::OptionalChaining(exp) => {
+                self.postfix_base(&exp.base);
+                self.write("?.");
+                self.expression_without_parens(&exp.expression, true);
+            },
+            Expression::OptionalChainingPlaceholder(_) => {},
+            Expression::Binary(exp) => {
+                let operator = BinaryOperator::try_from(exp.operator).unwrap();
+                let left_precedence = if operator.associativity() == BinaryAssociativity::LeftToRight {
+                    operator.precedence()
+                } else {
+                    operator.precedence().add(1).unwrap()
+                };
+                self.expression(&exp.left, left_precedence, allow_in);
+                self.comments_before_next_token(exp.left.location().last_offset());
+                self.operator_space();
+                self.write(exp.operator.source_text());
+                self.write(" ");
+                self.expression(&exp.right, operator.right_precedence(), allow_in);
+            },
+            Expression::Conditional(exp) => {
+                self.expression(&exp.test, OperatorPrecedence::LogicalOrAndOther, allow_in);
+                self.comments_before_next_token(exp.test.location().last_offset());
+                self.operator_space();
+                self.write("? ");
+                self.expression(&exp.consequent, OperatorPrecedence::AssignmentAndOther, allow_in);
+                self.comments_before_next_token(exp.consequent.location().last_offset());
+                self.operator_space();
+                self.write(": ");
+                self.expression(&exp.alternative, OperatorPrecedence::AssignmentAndOther, allow_in);
+            },
+            Expression::Assignment(exp) => {
+                self.postfix_base(&exp.left);
+                self.comments_before_next_token(exp.left.location().last_offset());
+                self.operator_space();
+                if let Some(compound) = exp.compound {
+                    self.write(compound.source_text());
+                }
+                self.write("= ");
+                self.expression(&exp.right, OperatorPrecedence::AssignmentAndOther, allow_in);
+            },
+            Expression::Sequence(exp) => {
+                self.expression(&exp.left, OperatorPrecedence::List, allow_in);
+                self.write(", ");
+                self.expression(&exp.right, OperatorPrecedence::AssignmentAndOther, allow_in);
+            },
+            Expression::NullableType(exp) => {
+                self.write("?");
+                if matches!(exp.base.as_ref(), Expression::NullableType(_)) {
+                    self.write("(");
+                    self.type_expression(&exp.base);
+                    self.write(")");
+                } else {
+                    self.type_expression(&exp.base);
+                }
+            },
+            Expression::NonNullableType(exp) => {
+                // The result type of a function type would take over the `!` operator.
+                if matches!(exp.base.as_ref(), Expression::FunctionType(_)) {
+                    self.write("(");
+                    self.type_expression(&exp.base);
+                    self.write(")");
+                } else {
+                    self.type_expression(&exp.base);
+                }
+                self.write("!");
+            },
+            Expression::AnyType(_) => {
+                self.write("*");
+            },
+            Expression::VoidType(_) => {
+                self.write("void");
+            },
+            Expression::ArrayType(exp) => {
+                self.write("[");
+                self.type_expression(&exp.expression);
+                self.write("]");
+            },
+            Expression::TupleType(exp) => {
+                self.write("[");
+                self.type_expression_list(&exp.expressions);
+                self.write("]");
+            },
+            Expression::FunctionType(exp) => {
+                self.write("function(");
+                for (i, parameter) in exp.parameters.iter().enumerate() {
+                    if i != 0 {
+                        self.write(", ");
+                    }
+                    if matches!(parameter.kind, ParameterKind::Rest) {
+                        self.write("...");
+                    }
+                    if let Some(type_expression) = &parameter.type_expression {
+                        self.type_expression(type_expression);
+                    }
+                    if matches!(parameter.kind, ParameterKind::Optional) {
+                        self.write("=");
+                    }
+                }
+                self.write(")");
+                if let Some(result_type) = &exp.result_type {
+                    self.write(":");
+                    self.type_expression(result_type);
+                }
+            },
+            Expression::Invalidated(_) => {},
+            Expression::ReservedNamespace(exp) => {
+                self.write(&exp.to_string());
+            },
+        }
+    }
+
+    fn unary_expression(&mut self, exp: &UnaryExpression, allow_in: bool) {
+        match exp.operator {
+            Operator::PostIncrement | Operator::PostDecrement | Operator::NonNull => {
+                self.postfix_base(&exp.expression);
+                self.write(exp.operator.source_text());
+            },
+            Operator::Yield => {
+                self.write("yield ");
+                self.expression(&exp.expression, OperatorPrecedence::AssignmentAndOther, allow_in);
+            },
+            Operator::Delete | Operator::PreIncrement | Operator::PreDecrement => {
+                self.write(exp.operator.source_text());
+                if exp.operator == Operator::Delete {
+                    self.write(" ");
+                }
+                // Operands at postfix precedence cannot begin with `new`.
+                let leftmost = leftmost_postfix_expression(&exp.expression);
+                if matches!(leftmost, Expression::New(_) | Expression::VectorLiteral(_)) {
+                    self.write("(");
+                    self.expression(&exp.expression, OperatorPrecedence::List, true);
+                    self.write(")");
+                } else {
+                    self.expression(&exp.expression, OperatorPrecedence::Postfix, allow_in);
+                }
+            },
+            _ => {
+                let operator = exp.operator.source_text();
+                self.write(operator);
+                if operator.chars().all(|ch| ch.is_ascii_alphabetic()) {
+                    self.write(" ");
+                }
+                // Prevent `- -x` from being printed as `--x`.
+                let operand_offset = self.output.len();
+                self.expression(&exp.expression, OperatorPrecedence::Unary, allow_in);
+                let operand_first = self.output[operand_offset..].chars().next();
+                if matches!((exp.operator, operand_first), (Operator::Positive, Some('+')) | (Operator::Negative, Some('-'))) {
+                    self.output.insert(operand_offset, ' ');
+                }
+            },
+        }
+    }
+
+    fn qualified_identifier(&mut self, id: &QualifiedIdentifier) {
+        if id.attribute {
+            self.write("@");
+        }
+        if let Some(qualifier) = &id.qualifier {
+            match qualifier.as_ref() {
+                Expression::QualifiedIdentifier(_) |
+                Expression::ReservedNamespace(_) |
+                Expression::Paren(_) => self.expression_without_parens(qualifier, true),
+                _ => {
+                    self.write("(");
+                    self.expression(qualifier, OperatorPrecedence::List, true);
+                    self.write(")");
+                },
+            }
+            self.write("::");
+        }
+        match &id.id {
+            QualifiedIdentifierIdentifier::Id((name, _)) => {
+                self.write(name);
+            },
+            QualifiedIdentifierIdentifier::Brackets(exp) => {
+                self.write("[");
+                self.expression(exp, OperatorPrecedence::List, true);
+                self.write("]");
+            },
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Rc<Expression>]) {
+        self.list("(", ")", arguments, |argument| Some((argument.location().first_offset(), argument.location().last_offset())), |printer, argument| {
+            printer.expression(argument, OperatorPrecedence::AssignmentAndOther, true);
+        });
+    }
+
+    /// Prints the elements of an array or vector literal, including the brackets.
+    fn elements(&mut self, elements: &[Element]) {
+        // A trailing elision requires an explicit comma.
+        let close = if matches!(elements.last(), Some(Element::Elision)) { ",]" } else { "]" };
+        self.list("[", close, elements, |element| match element {
+            Element::Elision => None,
+            Element::Expression(exp) => Some((exp.location().first_offset(), exp.location().last_offset())),
+            Element::Rest((exp, location)) => Some((location.first_offset(), exp.location().last_offset())),
+        }, |printer, element| {
+            match element {
+                Element::Elision => {},
+                Element::Expression(exp) => {
+                    printer.expression(exp, OperatorPrecedence::AssignmentAndOther, true);
+                },
+                Element::Rest((exp, _)) => {
+                    printer.write("...");
+                    printer.expression(exp, OperatorPrecedence::AssignmentAndOther, true);
+                },
+            }
+        });
+    }
+
+    fn object_initializer(&mut self, exp: &ObjectInitializer) {
+        self.list("{", "}", &exp.fields, |field| Some((field.location().first_offset(), field.location().last_offset())), |printer, field| {
+            match field.as_ref() {
+                InitializerField::Field { name, non_null, value } => {
+                    match &name.0 {
+                        FieldName::Identifier(id) => printer.qualified_identifier(id),
+                        FieldName::Brackets(exp) => {
+                            printer.write("[");
+                            printer.expression(exp, OperatorPrecedence::List, true);
+                            printer.write("]");
+                        },
+                        FieldName::StringLiteral(exp) |
+                        FieldName::NumericLiteral(exp) => printer.expression_without_parens(exp, true),
+                    }
+                    if *non_null {
+                        printer.write("!");
+                    }
+                    if let Some(value) = value {
+                        printer.write(": ");
+                        printer.expression(value, OperatorPrecedence::AssignmentAndOther, true);
+                    }
+                },
+                InitializerField::Rest((exp, _)) => {
+                    printer.write("...");
+                    printer.expression(exp, OperatorPrecedence::AssignmentAndOther, true);
+                },
+            }
+        });
+    }
+
+    fn xml_element(&mut self, element: &XmlElement) {
+        self.write("<");
+        self.xml_tag_name(&element.name);
+        for attribute in &element.attributes {
+            self.write(" ");
+            self.write(&attribute.name.0);
+            self.write("=");
+            match &attribute.value {
+                XmlAttributeValue::Value((value, _)) => {
+                    let delimiter = if value.contains('"') { "'" } else { "\"" };
+                    self.write(delimiter);
+                    self.write(value);
+                    self.write(delimiter);
+                },
+                XmlAttributeValue::Expression(exp) => {
+                    self.write("{");
+                    self.expression(exp, OperatorPrecedence::List, true);
+                    self.write("}");
+                },
+            }
+        }
+        if let Some(exp) = &element.attribute_expression {
+            self.write(" {");
+            self.expression(exp, OperatorPrecedence::List, true);
+            self.write("}");
+        }
+        if let Some(content) = &element.content {
+            self.write(">");
+            self.xml_content(content);
+            self.write("</");
+            self.xml_tag_name(element.closing_name.as_ref().unwrap_or(&element.name));
+            self.write(">");
+        } else {
+            self.write("/>");
+        }
+    }
+
+    fn xml_tag_name(&mut self, name: &XmlTagName) {
+        match name {
+            XmlTagName::Name((name, _)) => self.write(name),
+            XmlTagName::Expression(exp) => {
+                self.write("{");
+                self.expression(exp, OperatorPrecedence::List, true);
+                self.write("}");
+            },
+        }
+    }
+
+    fn xml_content(&mut self, content: &[Rc<XmlContent>]) {
+        for node in content {
+            match node.as_ref() {
+                XmlContent::Characters((text, _)) |
+                XmlContent::Markup((text, _)) => self.write(text),
+                XmlContent::Element(element) => self.xml_element(element),
+                XmlContent::Expression(exp) => {
+                    self.write("{");
+                    self.expression(exp, OperatorPrecedence::List, true);
+                    self.write("}");
+                },
+            }
+        }
+    }
+
+    fn string_literal(&mut self, value: &str) {
+        self.write(&escape_string_literal(value));
+    }
+}
+
+/// Returns the first offset of a directive, including its ASDoc comment.
+fn directive_first_offset(directive: &Directive) -> usize {
+    let mut offset = directive.location().first_offset();
+    let (asdoc, attributes) = match directive {
+        Directive::VariableDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::FunctionDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::ClassDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::EnumDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::InterfaceDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::TypeDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        Directive::NamespaceDefinition(defn) => (&defn.asdoc, &defn.attributes),
+        _ => return offset,
+    };
+    if let Some(asdoc) = asdoc {
+        offset = offset.min(asdoc.location.first_offset());
+    }
+    for attribute in attributes {
+        if let Attribute::Metadata(metadata) = attribute {
+            if let Some(asdoc) = &metadata.asdoc {
+                offset = offset.min(asdoc.location.first_offset());
+            }
+        }
+    }
+    offset
+}
+
+/// Returns the precedence of an expression, used to determine whether
+/// it must be parenthesized in a given context.
+fn expression_precedence(exp: &Expression) -> OperatorPrecedence {
+    match exp {
+        Expression::Unary(exp) => match exp.operator {
+            Operator::PostIncrement | Operator::PostDecrement | Operator::NonNull => OperatorPrecedence::Postfix,
+            Operator::Yield => OperatorPrecedence::AssignmentAndOther,
+            _ => OperatorPrecedence::Unary,
+        },
+        Expression::Binary(exp) => BinaryOperator::try_from(exp.operator).unwrap().precedence(),
+        Expression::New(_) |
+        Expression::VectorLiteral(_) => OperatorPrecedence::Unary,
+        Expression::Function(_) |
+        Expression::Conditional(_) |
+        Expression::Assignment(_) => OperatorPrecedence::AssignmentAndOther,
+        Expression::Sequence(_) => OperatorPrecedence::List,
+        _ => OperatorPrecedence::Postfix,
+    }
+}
+
+/// Returns the expression at which the printed form of an expression begins.
+fn leftmost_expression(exp: &Expression) -> &Expression {
+    match exp {
+        Expression::Binary(exp) => leftmost_expression(&exp.left),
+        Expression::Conditional(exp) => leftmost_expression(&exp.test),
+        Expression::Assignment(exp) => leftmost_expression(&exp.left),
+        Expression::Sequence(exp) => leftmost_expression(&exp.left),
+        Expression::Unary(UnaryExpression { operator: Operator::PostIncrement | Operator::PostDecrement | Operator::NonNull, expression, .. }) => leftmost_expression(expression),
+        _ => {
+            let leftmost = leftmost_postfix_expression(exp);
+            if std::ptr::eq(leftmost, exp) { exp } else { leftmost_expression(leftmost) }
+        },
+    }
+}
+
+/// Returns the innermost base of a chain of postfix operations.
+fn leftmost_postfix_expression(exp: &Expression) -> &Expression {
+    match exp {
+        Expression::Member(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::ComputedMember(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::Descendants(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::Filter(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::Call(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::WithTypeArguments(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::OptionalChaining(exp) => leftmost_postfix_expression(&exp.base),
+        Expression::Unary(UnaryExpression { operator: Operator::PostIncrement | Operator::PostDecrement | Operator::NonNull, expression, .. }) => leftmost_postfix_expression(expression),
+        _ => exp,
+    }
+}
+
+/// Determines whether an expression begins with an object initializer.
+fn starts_with_brace(exp: &Expression) -> bool {
+    matches!(leftmost_expression(exp), Expression::ObjectInitializer(_))
+}
+
+/// Determines whether an expression is a decimal numeric literal with neither
+/// a decimal point, an exponent nor a suffix, which a following dot would extend.
+fn is_integer_literal_without_suffix(exp: &Expression) -> bool {
+    matches!(exp, Expression::NumericLiteral(literal) if literal.suffix == NumberSuffix::None && literal.value.chars().all(|ch| ch.is_ascii_digit() || ch == '_'))
+}
+
+/// Determines whether an expression may appear unparenthesized
+/// as the base of a `new` expression.
+fn is_new_subexpression(exp: &Expression) -> bool {
+    match exp {
+        Expression::Member(exp) => is_new_subexpression(&exp.base),
+        Expression::ComputedMember(exp) => is_new_subexpression(&exp.base),
+        Expression::WithTypeArguments(exp) => is_new_subexpression(&exp.base),
+        Expression::New(_) |
+        Expression::VectorLiteral(_) |
+        Expression::QualifiedIdentifier(_) |
+        Expression::Paren(_) |
+        Expression::NullLiteral(_) |
+        Expression::BooleanLiteral(_) |
+        Expression::NumericLiteral(_) |
+        Expression::StringLiteral(_) |
+        Expression::ThisLiteral(_) |
+        Expression::RegExpLiteral(_) |
+        Expression::Xml(_) |
+        Expression::XmlMarkup(_) |
+        Expression::XmlList(_) |
+        Expression::ArrayLiteral(_) |
+        Expression::ObjectInitializer(_) => true,
+        _ => false,
+    }
+}
+
+/// Determines whether an expression is a `new` expression lacking arguments,
+/// which as the base of another `new` expression would take over its arguments.
+fn is_new_expression_without_arguments(exp: &Expression) -> bool {
+    matches!(exp, Expression::New(NewExpression { arguments: None, .. }))
+}
+
+/// Determines whether a substatement ends with an `if` statement
+/// lacking an `else` clause.
+fn ends_with_if_without_else(directive: &Directive) -> bool {
+    match directive {
+        Directive::IfStatement(stmt) => stmt.alternative.as_ref().is_none_or(|alternative| ends_with_if_without_else(alternative)),
+        Directive::WhileStatement(stmt) => ends_with_if_without_else(&stmt.body),
+        Directive::ForStatement(stmt) => ends_with_if_without_else(&stmt.body),
+        Directive::ForInStatement(stmt) => ends_with_if_without_else(&stmt.body),
+        Directive::WithStatement(stmt) => ends_with_if_without_else(&stmt.body),
+        Directive::LabeledStatement(stmt) => ends_with_if_without_else(&stmt.substatement),
+        _ => false,
+    }
+}
+
+/// Determines whether a directive is separated from its
+/// neighbours by an empty line.
+fn is_separated_directive(directive: &Directive) -> bool {
+    match directive {
+        Directive::FunctionDefinition(_) |
+        Directive::ClassDefinition(_) |
+        Directive::EnumDefinition(_) |
+        Directive::InterfaceDefinition(_) => true,
+        Directive::NormalConfigurationDirective(defn) => is_separated_directive(&defn.directive),
+        _ => false,
+    }
+}
+
+/// Escapes a string as an ActionScript string literal delimited by double quotes.
+pub fn escape_string_literal(value: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\x08' => result.push_str("\\b"),
+            '\x0C' => result.push_str("\\f"),
+            '\x0B' => result.push_str("\\v"),
+            '\x00'..='\x1F' | '\x7F' | '\u{2028}' | '\u{2029}' => {
+                result.push_str(&format!("\\u{:04X}", ch as u32));
+            },
+            _ => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    const SOURCE: &str = r#"
+package com.example {
+    import flash.events.*;
+    import q = com.example.other.Q;
+    /**
+     * Main class.
+     * @see flash.display.Sprite#x Sprite
+     */
+    [Event(name="change", type="flash.events.Event")]
+    public dynamic class C.<T> extends B implements I1, I2 {
+        [Bindable]
+        private static const X:Vector.<int> = new <int>[1, 2, ...r];
+        public function get x():?T {
+            return a?.b?.[k]?.(1) ?? y!;
+        }
+        CONFIG::DEBUG
+        function f({b, c: [d, e]}, a:int = 1, ...rest):function(int, String=, ...):void {
+            var xml:XML = <a b="1" c={v}>text {x} <!-- c --><d/></a>;
+            var list:XMLList = <><x/></>;
+            for (var i:int = ("k" in o) ? 1 : 0; i < 10; i++) {
+                if (a) if (b) f(); else g();
+                else h();
+            }
+            for each (var v in o) continue;
+            x.@attr = o..y.(@z == 1)[0].ns::w;
+            delete o.p;
+            (function():void {})();
+            ({}).toString();
+            x = (a, b);
+            y = -(-z) + (a - (b - c)) * d ** e ** f;
+            s = "q\"\n\\";
+            t = /a\/b/gi;
+            switch (x) {
+                case 1:
+                case 2:
+                    break;
+                default:
+                    throw new Error("e");
+            }
+            switch type (v) {
+                case (s:String) {
+                    trace(s);
+                }
+                default {
+                }
+            }
+            try {
+                throw e;
+            } catch (e:Error) {
+            } finally {
+            }
+            do x++; while (x < 10);
+            while (true) break;
+            default xml namespace = ns;
+            use namespace ns;
+            return new C.<String>().m() as T;
+        }
+    }
+    enum E as uint {
+        const A = 1;
+    }
+    type F = [Number, String];
+    namespace ns = "http://x";
+}
+configuration {
+    if (CONFIG::DEBUG) {
+        trace(1);
+    } else {
+        trace(2);
+    }
+}
+"#;
+
+    fn print(source: &str) -> (String, Vec<String>) {
+        let cu = CompilationUnit::new(None, source.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let diagnostics = cu.nested_diagnostics().iter().map(|d| d.format_english()).collect();
+        (PrinterFacade(default()).print_program(&program), diagnostics)
+    }
+
+    #[test]
+    fn test_print_round_trip() {
+        let (first, diagnostics) = print(SOURCE);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        let (second, diagnostics) = print(&first);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}\n{first}");
+        assert_eq!(first, second);
+    }
+
+    struct ParenRemover;
+
+    impl VisitorMut for ParenRemover {
+        fn visit_expression(&mut self, node: &mut Rc<Expression>) -> VisitorControl {
+            while let Expression::Paren(paren) = node.as_ref() {
+                *node = paren.expression.clone();
+            }
+            VisitorControl::Continue
+        }
+    }
+
+    #[test]
+    fn test_minimal_parentheses() {
+        for (source, expected) in [
+            ("(a + b) * (c - (d - e))", "(a + b) * (c - (d - e))"),
+            ("(a * b) + ((c - d) - e)", "a * b + (c - d - e)"),
+            ("(a ** b) ** (c ** d)", "(a ** b) ** c ** d"),
+            ("(-a) ** b", "-a ** b"),
+            ("-(-a)", "- -a"),
+            ("(new C).x", "(new C).x"),
+            ("(new C()).x", "new C().x"),
+            ("new (f())", "new (f())"),
+            ("(a ? b : c) ? (d, e) : f = g", "(a ? b : c) ? (d, e) : f = g"),
+            ("(function() {})()", "(function() {})()"),
+            ("a = (b = c)", "a = b = c"),
+            ("(a || b) ?? c", "a || b ?? c"),
+            ("a ?? (b || c)", "a ?? (b || c)"),
+            ("(1).toString()", "(1).toString()"),
+            ("(1_000)..x", "(1_000)..x"),
+            ("(1.5).toString() + (1e3).x + (0x1F).x + (1)[0]", "1.5.toString() + 1e3.x + 0x1F.x + 1[0]"),
+        ] {
+            let cu = CompilationUnit::new(None, source.into());
+            let mut exp = ParserFacade(&cu, default()).parse_expression();
+            assert!(!cu.invalidated());
+            let _ = walk_expression_mut(&mut ParenRemover, &mut exp);
+            let printed = PrinterFacade(default()).print_expression(&exp);
+            assert_eq!(printed, expected);
+
+            // The output parses without diagnostics.
+            let printed_cu = CompilationUnit::new(None, printed.clone());
+            ParserFacade(&printed_cu, default()).parse_expression();
+            assert!(printed_cu.diagnostics().is_empty(), "{printed}");
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use crate::ns::*;
+use serde::{Serialize, Deserialize};
+
+/// Options for the CSS printer.
+///
+/// The options may be deserialized from a configuration file,
+/// where field names are in camel case (such as `indentWidth`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct CssPrinterOptions {
+    /// Number of spaces emitted per indentation level. Default is `4`.
+    pub indent_width: usize,
+    /// Emits the style sheet without optional whitespace and with
+    /// the shortest form of colors and numbers. Default is `false`.
+    pub minify: bool,
+}
+
+impl Default for CssPrinterOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            minify: false,
+        }
+    }
+}
+
+/// CSS printer facade.
+///
+/// Property names are printed as they are spelled in the source text,
+/// such as `font-size`, rather than in the camel case of `CssProperty`.
+/// Invalidated nodes are omitted.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+/// let cu = CompilationUnit::new(None, "s|Button { font-size: 12; color: #ff0000 }".into());
+/// let document = CssParserFacade(&cu, default()).parse_document();
+/// assert_eq!(CssPrinterFacade(default()).print_document(&document), "s|Button {\n    font-size: 12;\n    color: #ff0000;\n}\n");
+///
+/// let minified = CssPrinterFacade(CssPrinterOptions { minify: true, ..default() }).print_document(&document);
+/// assert_eq!(minified, "s|Button{font-size:12;color:#f00}");
+/// ```
+pub struct CssPrinterFacade(pub CssPrinterOptions);
+
+impl CssPrinterFacade {
+    /// Prints a style sheet.
+    pub fn print_document(&self, document: &CssDocument) -> String {
+        let mut printer = CssPrinter::new(self.0.clone());
+        printer.print_document(document);
+        printer.into_output()
+    }
+
+    /// Prints a directive, including a trailing line terminator in pretty mode.
+    pub fn print_directive(&self, directive: &CssDirective) -> String {
+        let mut printer = CssPrinter::new(self.0.clone());
+        printer.print_directive(directive);
+        printer.into_output()
+    }
+
+    /// Prints a selector.
+    pub fn print_selector(&self, selector: &CssSelector) -> String {
+        let mut printer = CssPrinter::new(self.0.clone());
+        printer.print_selector(selector);
+        printer.into_output()
+    }
+
+    /// Prints a property value.
+    pub fn print_property_value(&self, value: &CssPropertyValue) -> String {
+        let mut printer = CssPrinter::new(self.0.clone());
+        printer.print_property_value(value);
+        printer.into_output()
+    }
+}
+
+/// CSS printer.
+pub struct CssPrinter {
+    options: CssPrinterOptions,
+    output: String,
+    indent_level: usize,
+}
+
+impl CssPrinter {
+    pub fn new(options: CssPrinterOptions) -> Self {
+        Self {
+            options,
+            output: String::new(),
+            indent_level: 0,
+        }
+    }
+
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    /// Writes text that is omitted in minified mode, such as a space.
+    fn write_pretty(&mut self, text: &str) {
+        if !self.options.minify {
+            self.output.push_str(text);
+        }
+    }
+
+    fn write_indent(&mut self) {
+        if !self.options.minify {
+            self.output.push_str(&" ".repeat(self.indent_level * self.options.indent_width));
+        }
+    }
+
+    pub fn print_document(&mut self, document: &CssDocument) {
+        let directives: Vec<&Rc<CssDirective>> = document.directives.iter().filter(|directive| !matches!(directive.as_ref(), CssDirective::Invalidated(_))).collect();
+        let mut previous: Option<&CssDirective> = None;
+        for directive in directives {
+            // Separate directives by a blank line, except for consecutive namespace definitions.
+            if previous.is_some_and(|previous| !(matches!(previous, CssDirective::NamespaceDefinition(_)) && matches!(directive.as_ref(), CssDirective::NamespaceDefinition(_)))) {
+                self.write_pretty("\n");
+            }
+            self.print_directive(directive);
+            previous = Some(directive);
+        }
+    }
+
+    pub fn print_directive(&mut self, directive: &CssDirective) {
+        match directive {
+            CssDirective::Invalidated(_) => {},
+            CssDirective::Rule(rule) => self.print_rule(rule),
+            CssDirective::FontFace(font_face) => {
+                self.write_indent();
+                self.write("@font-face");
+                self.print_properties(&font_face.properties);
+            },
+            CssDirective::MediaQuery(media_query) => self.print_media_query(media_query),
+            CssDirective::NamespaceDefinition(defn) => {
+                self.write_indent();
+                self.write("@namespace ");
+                self.write(&defn.prefix.0);
+                self.write_pretty(" ");
+                self.print_string(&defn.uri.0);
+                self.write(";");
+                self.write_pretty("\n");
+            },
+        }
+    }
+
+    pub fn print_rule(&mut self, rule: &CssRule) {
+        self.write_indent();
+        let selectors: Vec<&Rc<CssSelector>> = rule.selectors.iter().filter(|selector| !matches!(selector.as_ref(), CssSelector::Invalidated(_))).collect();
+        for (i, selector) in selectors.iter().enumerate() {
+            if i != 0 {
+                self.write(",");
+                self.write_pretty(" ");
+            }
+            self.print_selector(selector);
+        }
+        self.print_properties(&rule.properties);
+    }
+
+    /// Prints a block of properties, preceded by a space in pretty mode.
+    fn print_properties(&mut self, properties: &[Rc<CssProperty>]) {
+        let properties: Vec<&Rc<CssProperty>> = properties.iter().filter(|property| !matches!(property.value.as_ref(), CssPropertyValue::Invalidated(_))).collect();
+        self.write_pretty(" ");
+        self.write("{");
+        self.write_pretty("\n");
+        self.indent_level += 1;
+        for (i, property) in properties.iter().enumerate() {
+            self.write_indent();
+            self.print_property(property);
+            // The last semicolon is optional
+            if !(self.options.minify && i + 1 == properties.len()) {
+                self.write(";");
+            }
+            self.write_pretty("\n");
+        }
+        self.indent_level -= 1;
+        self.write_indent();
+        self.write("}");
+        self.write_pretty("\n");
+    }
+
+    pub fn print_property(&mut self, property: &CssProperty) {
+        self.write(&Self::property_name(property));
+        self.write(":");
+        self.write_pretty(" ");
+        self.print_property_value(&property.value);
+    }
+
+    /// The name of a property as spelled in the source text. A property without
+    /// source text has its camel-case name hyphenated, such as `font-size` for `fontSize`.
+    fn property_name(property: &CssProperty) -> String {
+        let text = property.name.1.text();
+        if !text.is_empty() {
+            return text;
+        }
+        let mut name = String::new();
+        for ch in property.name.0.chars() {
+            if ch.is_uppercase() {
+                name.push('-');
+                name.extend(ch.to_lowercase());
+            } else {
+                name.push(ch);
+            }
+        }
+        name
+    }
+
+    pub fn print_media_query(&mut self, media_query: &CssMediaQuery) {
+        self.write_indent();
+        self.write("@media ");
+        let conditions: Vec<&Rc<CssMediaQueryCondition>> = media_query.conditions.iter().filter(|condition| !matches!(condition.as_ref(), CssMediaQueryCondition::Invalidated(_))).collect();
+        for (i, condition) in conditions.iter().enumerate() {
+            if i != 0 {
+                self.write(",");
+                self.write_pretty(" ");
+            }
+            self.print_media_query_condition(condition);
+        }
+        self.write_pretty(" ");
+        self.write("{");
+        self.write_pretty("\n");
+        self.indent_level += 1;
+        for (i, rule) in media_query.rules.iter().enumerate() {
+            if i != 0 {
+                self.write_pretty("\n");
+            }
+            self.print_rule(rule);
+        }
+        self.indent_level -= 1;
+        self.write_indent();
+        self.write("}");
+        self.write_pretty("\n");
+    }
+
+    pub fn print_media_query_condition(&mut self, condition: &CssMediaQueryCondition) {
+        match condition {
+            CssMediaQueryCondition::Invalidated(_) => {},
+            CssMediaQueryCondition::Id((id, _)) => self.write(id),
+            CssMediaQueryCondition::OnlyId { id, .. } => {
+                self.write("only ");
+                self.write(&id.0);
+            },
+            CssMediaQueryCondition::ParenProperty((property, _)) => {
+                self.write("(");
+                self.print_property(property);
+                self.write(")");
+            },
+            CssMediaQueryCondition::And { left, right, .. } => {
+                self.print_media_query_condition(left);
+                self.write(" and ");
+                self.print_media_query_condition(right);
+            },
+        }
+    }
+
+    pub fn print_selector(&mut self, selector: &CssSelector) {
+        match selector {
+            CssSelector::Invalidated(_) => {},
+            CssSelector::Base(base) => {
+                if let Some((prefix, _)) = &base.namespace_prefix {
+                    self.write(prefix);
+                    self.write("|");
+                }
+                if let Some((name, _)) = &base.element_name {
+                    self.write(name);
+                } else if base.conditions.is_empty() {
+                    self.write("*");
+                }
+                for condition in &base.conditions {
+                    self.print_selector_condition(condition);
+                }
+            },
+            CssSelector::Combinator(combinator) => {
+                self.print_selector(&combinator.left);
+                if combinator.combinator_type == CssCombinatorType::Descendant {
+                    self.write(" ");
+                } else {
+                    self.write_pretty(" ");
+                    self.write(&combinator.combinator_type.to_string());
+                    self.write_pretty(" ");
+                }
+                self.print_selector(&combinator.right);
+            },
+        }
+    }
+
+    pub fn print_selector_condition(&mut self, condition: &CssSelectorCondition) {
+        match condition {
+            CssSelectorCondition::Invalidated(_) => {},
+            CssSelectorCondition::Class((name, _)) => {
+                self.write(".");
+                self.write(name);
+            },
+            CssSelectorCondition::Id((name, _)) => {
+                self.write("#");
+                self.write(name);
+            },
+            CssSelectorCondition::Pseudo((name, _)) => {
+                self.write(":");
+                self.write(name);
+            },
+            CssSelectorCondition::PseudoElement((name, _)) => {
+                self.write("::");
+                self.write(name);
+            },
+            CssSelectorCondition::Not { condition, .. } => {
+                self.write(":not(");
+                self.print_selector_condition(condition);
+                self.write(")");
+            },
+            CssSelectorCondition::Attribute { name, operator, value, .. } => {
+                self.write("[");
+                self.write(&name.0);
+                if let Some(operator) = operator {
+                    self.write(&operator.to_string());
+                }
+                if let Some((value, _)) = value {
+                    self.print_string(value);
+                }
+                self.write("]");
+            },
+        }
+    }
+
+    pub fn print_property_value(&mut self, value: &CssPropertyValue) {
+        match value {
+            CssPropertyValue::Invalidated(_) => {},
+            CssPropertyValue::Array(array) => {
+                for (i, element) in array.elements.iter().enumerate() {
+                    if i != 0 {
+                        self.write(",");
+                        self.write_pretty(" ");
+                    }
+                    self.print_property_value(element);
+                }
+            },
+            CssPropertyValue::MultiValue(multi_value) => {
+                for (i, value) in multi_value.values.iter().enumerate() {
+                    if i != 0 {
+                        self.write(" ");
+                    }
+                    self.print_property_value(value);
+                }
+            },
+            CssPropertyValue::Color(color) => {
+                let text = color.text();
+                // Named colors, such as `yellow`, are kept as written
+                if text.is_empty() || (self.options.minify && text.starts_with('#')) {
+                    self.write(&Self::hex_color(color.color_int, self.options.minify));
+                } else {
+                    self.write(&text);
+                }
+            },
+            CssPropertyValue::Number(number) => {
+                self.write(&Self::number(number.value, self.options.minify));
+                if let Some(unit) = &number.unit {
+                    self.write(unit);
+                }
+            },
+            CssPropertyValue::RgbColor(color) => {
+                if self.options.minify {
+                    self.write(&Self::hex_color(color.color_int, true));
+                } else {
+                    let (r, g, b) = ((color.color_int >> 16) & 0xFF, (color.color_int >> 8) & 0xFF, color.color_int & 0xFF);
+                    self.write(&format!("rgb({r}, {g}, {b})"));
+                }
+            },
+            CssPropertyValue::String(string) => self.print_string(&string.value),
+            CssPropertyValue::Identifier(identifier) => self.write(&identifier.value),
+            CssPropertyValue::ClassReference(reference) => {
+                self.write("ClassReference(");
+                if reference.name.0 == "null" {
+                    self.write("null");
+                } else {
+                    self.print_string(&reference.name.0);
+                }
+                self.write(")");
+            },
+            CssPropertyValue::PropertyReference(reference) => {
+                self.write("PropertyReference(");
+                self.print_string(&reference.name.0);
+                self.write(")");
+            },
+            CssPropertyValue::Url(url) => {
+                self.write("url(");
+                self.print_string(&url.url.0);
+                self.write(")");
+                if let Some((format, _)) = &url.format {
+                    self.write(" format(");
+                    self.print_string(format);
+                    self.write(")");
+                }
+            },
+            CssPropertyValue::Local(local) => {
+                self.write("local(");
+                self.print_string(&local.name.0);
+                self.write(")");
+            },
+            CssPropertyValue::Embed(embed) => {
+                self.write("Embed(");
+                for (i, entry) in embed.entries.iter().enumerate() {
+                    if i != 0 {
+                        self.write(",");
+                        self.write_pretty(" ");
+                    }
+                    if let Some((key, _)) = &entry.key {
+                        self.write(key);
+                        self.write("=");
+                    }
+                    self.print_string(&entry.value.0);
+                }
+                self.write(")");
+            },
+        }
+    }
+
+    /// Prints a quoted string, preferring double quotes. Characters that cannot
+    /// appear within the quotes are written as six-digit hexadecimal escapes.
+    fn print_string(&mut self, value: &str) {
+        let delim = if value.contains('"') && !value.contains('\'') { '\'' } else { '"' };
+        let mut result = String::new();
+        result.push(delim);
+        for ch in value.chars() {
+            if ch == delim || ch == '\\' || CharacterValidator::is_line_terminator(ch) {
+                result.push_str(&format!("\\{:06x}", ch as u32));
+            } else {
+                result.push(ch);
+            }
+        }
+        result.push(delim);
+        self.write(&result);
+    }
+
+    /// Formats a color as `#rrggbb`, or as `#rgb` in its shortest form.
+    fn hex_color(color: u32, shortest: bool) -> String {
+        let hex = format!("{:06x}", color & 0xFFFFFF);
+        let bytes = hex.as_bytes();
+        if shortest && bytes[0] == bytes[1] && bytes[2] == bytes[3] && bytes[4] == bytes[5] {
+            format!("#{}{}{}", bytes[0] as char, bytes[2] as char, bytes[4] as char)
+        } else {
+            format!("#{hex}")
+        }
+    }
+
+    /// Formats a number, without its leading zero in its shortest form, such as `.5`.
+    fn number(value: f64, shortest: bool) -> String {
+        let text = value.to_string();
+        if !shortest {
+            return text;
+        }
+        if let Some(fraction) = text.strip_prefix("0.") {
+            format!(".{fraction}")
+        } else if let Some(fraction) = text.strip_prefix("-0.") {
+            format!("-.{fraction}")
+        } else {
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    const STYLE_SHEET: &str = r#"@namespace s "library://ns.adobe.com/flex/spark";
+@namespace mx "library://ns.adobe.com/flex/mx";
+@font-face {
+    src: url("fonts/a.ttf") format("truetype"), local("Arial");
+    font-family: myFont;
+}
+s|Button.primary:over, #main s|Label::first-line, s|Group:not(.a)[data-x^="y"] {
+    color: #FF0000;
+    background-color: yellow;
+    border-color: rgb(10%, 20%, 30%);
+    padding-left: 0.5em;
+    fill-colors: #FFFFFF, #CCCCCC;
+    border: 1px solid #00ff00;
+    skin-class: ClassReference("spark.skins.ButtonSkin");
+    focus-skin: ClassReference(null);
+    icon: Embed(source="a.png", symbol="x");
+    prop-ref: PropertyReference("x");
+    content: 'say "hi"';
+}
+@media only screen, screen and (application-dpi: 240), print {
+    s|Button { font-size: 12; }
+    s|Label { fontSize: 10 }
+}"#;
+
+    fn parse(text: &str) -> Rc<CssDocument> {
+        let cu = CompilationUnit::new(None, text.into());
+        let document = CssParserFacade(&cu, default()).parse_document();
+        assert!(!cu.invalidated());
+        document
+    }
+
+    #[test]
+    fn test_pretty() {
+        let printed = CssPrinterFacade(default()).print_document(&parse(STYLE_SHEET));
+        assert_eq!(printed, r#"@namespace s "library://ns.adobe.com/flex/spark";
+@namespace mx "library://ns.adobe.com/flex/mx";
+
+@font-face {
+    src: url("fonts/a.ttf") format("truetype"), local("Arial");
+    font-family: myFont;
+}
+
+s|Button.primary:over, #main s|Label::first-line, s|Group:not(.a)[data-x^="y"] {
+    color: #FF0000;
+    background-color: yellow;
+    border-color: rgb(26, 51, 77);
+    padding-left: 0.5em;
+    fill-colors: #FFFFFF, #CCCCCC;
+    border: 1px solid #00ff00;
+    skin-class: ClassReference("spark.skins.ButtonSkin");
+    focus-skin: ClassReference(null);
+    icon: Embed(source="a.png", symbol="x");
+    prop-ref: PropertyReference("x");
+    content: 'say "hi"';
+}
+
+@media only screen, screen and (application-dpi: 240), print {
+    s|Button {
+        font-size: 12;
+    }
+
+    s|Label {
+        fontSize: 10;
+    }
+}
+"#);
+        // Printing is stable
+        assert_eq!(CssPrinterFacade(default()).print_document(&parse(&printed)), printed);
+    }
+
+    #[test]
+    fn test_minified() {
+        let printed = CssPrinterFacade(CssPrinterOptions { minify: true, ..default() }).print_document(&parse(STYLE_SHEET));
+        assert_eq!(printed, concat!(
+            r#"@namespace s"library://ns.adobe.com/flex/spark";@namespace mx"library://ns.adobe.com/flex/mx";"#,
+            r#"@font-face{src:url("fonts/a.ttf") format("truetype"),local("Arial");font-family:myFont}"#,
+            r#"s|Button.primary:over,#main s|Label::first-line,s|Group:not(.a)[data-x^="y"]{color:#f00;background-color:yellow;"#,
+            r#"border-color:#1a334d;padding-left:.5em;fill-colors:#fff,#ccc;border:1px solid #0f0;"#,
+            r#"skin-class:ClassReference("spark.skins.ButtonSkin");focus-skin:ClassReference(null);icon:Embed(source="a.png",symbol="x");"#,
+            r#"prop-ref:PropertyReference("x");content:'say "hi"'}"#,
+            r#"@media only screen,screen and (application-dpi:240),print{s|Button{font-size:12}s|Label{fontSize:10}}"#,
+        ));
+        // The minified style sheet parses back to the same style sheet
+        assert_eq!(CssPrinterFacade(CssPrinterOptions { minify: true, ..default() }).print_document(&parse(&printed)), printed);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::ns::*;
+use std::{fmt, fs, io, path::Path};
+
+/// ActionScript 3 formatter.
+///
+/// The formatter re-emits a source file with consistent indentation, brace style,
+/// spacing and line wrapping, reattaching its comments and ASDoc comments
+/// where they appear in the source text. Formatting a formatted source
+/// produces the same text.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+/// let formatted = Formatter(default()).format_text("x=a+b // sum").unwrap();
+/// assert_eq!(formatted, "x = a + b; // sum\n");
+/// ```
+pub struct Formatter(pub PrinterOptions);
+
+impl Formatter {
+    /// Reads formatter options from a JSON configuration file, such as:
+    ///
+    /// ```json
+    /// {
+    ///     "indentWidth": 4,
+    ///     "bracePlacement": "next-line",
+    ///     "maxLineLength": 100
+    /// }
+    /// ```
+    ///
+    /// Options that are omitted take their default value.
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, FormatterError> {
+        let text = fs::read_to_string(path).map_err(FormatterError::Io)?;
+        Self::from_config_text(&text)
+    }
+
+    /// Reads formatter options from the text of a JSON configuration file.
+    pub fn from_config_text(text: &str) -> Result<Self, FormatterError> {
+        let options: PrinterOptions = serde_json::from_str(text).map_err(|error| FormatterError::Config(error.to_string()))?;
+        Ok(Self(options))
+    }
+
+    /// Parses and formats a compilation unit.
+    ///
+    /// Fails with `FormatterError::Invalidated` if the source text contains errors.
+    /// Windows line terminators are preserved if the source text uses them.
+    pub fn format(&self, compilation_unit: &Rc<CompilationUnit>) -> Result<String, FormatterError> {
+        let program = ParserFacade(compilation_unit, default()).parse_program();
+        if compilation_unit.invalidated() {
+            compilation_unit.sort_diagnostics();
+            return Err(FormatterError::Invalidated(compilation_unit.nested_diagnostics()));
+        }
+        let mut printer = Printer::with_comments(self.0.clone(), compilation_unit);
+        printer.print_program(&program);
+        let output = printer.into_output();
+        if compilation_unit.text().contains("\r\n") {
+            Ok(output.replace("\r\n", "\n").replace('\n', "\r\n"))
+        } else {
+            Ok(output)
+        }
+    }
+
+    /// Parses and formats source text.
+    pub fn format_text(&self, text: &str) -> Result<String, FormatterError> {
+        self.format(&CompilationUnit::new(None, text.to_owned()))
+    }
+}
+
+/// Error produced by the `Formatter`.
+pub enum FormatterError {
+    /// The source text contains errors, which are described by the given diagnostics.
+    Invalidated(Vec<Diagnostic>),
+    /// A configuration file could not be read.
+    Io(io::Error),
+    /// A configuration file contains invalid options.
+    Config(String),
+}
+
+impl fmt::Display for FormatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalidated(diagnostics) => {
+                write!(f, "Source text contains errors; refusing to format")?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic.format_english())?;
+                }
+                Ok(())
+            },
+            Self::Io(error) => write!(f, "Failed to read configuration file: {error}"),
+            Self::Config(message) => write!(f, "Invalid configuration file: {message}"),
+        }
+    }
+}
+
+// `Diagnostic` does not implement `Debug`; describe the error as in `Display`.
+impl fmt::Debug for FormatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for FormatterError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    const SOURCE: &str = r#"
+// Leading comment
+
+package  com.example   {
+    import flash.events.Event ; // trailing import comment
+
+    /*
+     * Block comment.
+     */
+    [Bindable]
+    /**
+     * The main class.
+     *
+     * @author Someone
+     */
+    public class Main extends Object
+    {
+        /** Count. */ private var count : int = 0 ;
+
+        public function Main( a : int , /* inline */ b : String = "x" ) { // after brace
+            if(a>0){trace( a )}else{ trace(b); }
+            // before switch
+            switch( a ){
+                case 1: // one
+                    break;
+                default:
+                    trace( [1,2,3], {x:1, y:2} );
+            }
+
+            /** Not attached. */
+            trace(veryLongFunctionName(firstArgumentWithLongName, secondArgumentWithLongName, thirdArgumentWithLongName, fourth));
+            // end of constructor
+        }
+    }
+}
+
+/* Trailing block comment */
+"#;
+
+    fn assert_comments_preserved(source: &str, formatted: &str) {
+        let cu = CompilationUnit::new(None, source.into());
+        ParserFacade(&cu, default()).parse_program();
+        let formatted_cu = CompilationUnit::new(None, formatted.into());
+        ParserFacade(&formatted_cu, default()).parse_program();
+        let contents = |cu: &CompilationUnit| cu.comments().iter().map(|comment| comment.content().lines().map(|line| line.trim().to_owned()).collect::<Vec<_>>()).collect::<Vec<_>>();
+        assert_eq!(contents(&cu), contents(&formatted_cu));
+    }
+
+    #[test]
+    fn test_format_idempotence() {
+        for options in [
+            PrinterOptions::default(),
+            PrinterOptions { indent_width: 2, brace_placement: BracePlacement::NextLine, max_line_length: 60 },
+        ] {
+            let formatter = Formatter(options);
+            let formatted = formatter.format_text(SOURCE).unwrap();
+            assert_comments_preserved(SOURCE, &formatted);
+            assert_eq!(formatter.format_text(&formatted).unwrap(), formatted);
+        }
+    }
+
+    #[test]
+    fn test_format_output() {
+        let formatted = Formatter(default()).format_text("/** Doc. */\nfunction f(a:int,b:int):int{return a+b // sum\n}\n\n\n\nf(1,2);").unwrap();
+        assert_eq!(formatted, "/** Doc. */\nfunction f(a:int, b:int):int {\n    return a + b; // sum\n}\n\nf(1, 2);\n");
+
+        let formatted = Formatter(PrinterOptions { max_line_length: 20, ..default() }).format_text("f(first, second, third);").unwrap();
+        assert_eq!(formatted, "f(\n    first,\n    second,\n    third\n);\n");
+
+        let formatted = Formatter(PrinterOptions { brace_placement: BracePlacement::NextLine, ..default() }).format_text("if (x) { f() } else { g() }").unwrap();
+        assert_eq!(formatted, "if (x)\n{\n    f();\n}\nelse\n{\n    g();\n}\n");
+    }
+
+    #[test]
+    fn test_format_comment_positions() {
+        for source in [
+            "x = 1; /* a */ /* b */\n",
+            "if (x) {\n    f();\n} // c\nelse {\n    g();\n}\n",
+            "if (x) {\n    f();\n} /* c */ else {\n    g();\n}\n",
+            "if (x) {\n    f();\n}\n// c\nelse {\n    g();\n}\n",
+            "x = a /* mid */ + b;\n",
+            "f(a /* x */, b);\n",
+        ] {
+            assert_eq!(Formatter(default()).format_text(source).unwrap(), source);
+        }
+    }
+
+    #[test]
+    fn test_format_invalidated() {
+        assert!(matches!(Formatter(default()).format_text("x = ;"), Err(FormatterError::Invalidated(_))));
+    }
+
+    #[test]
+    fn test_config() {
+        let formatter = Formatter::from_config_text(r#"{ "bracePlacement": "next-line", "maxLineLength": 80 }"#).unwrap();
+        assert_eq!(formatter.0, PrinterOptions { indent_width: 4, brace_placement: BracePlacement::NextLine, max_line_length: 80 });
+        assert!(matches!(Formatter::from_config_text(r#"{ "indent": 2 }"#), Err(FormatterError::Config(_))));
+    }
+}
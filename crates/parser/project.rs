@@ -0,0 +1,11 @@
+//! Defines the parsing of projects consisting of several source files.
+//!
+//! The `Project` structure discovers the `.as`, `.mxml` and `.css` files under
+//! source roots, optionally filtered by globs, and parses each of them in its own
+//! `CompilationUnit`. Files are read in parallel and, with the `sync` feature,
+//! also parsed in parallel.
+
+mod project;
+pub use project::*;
+mod source_glob;
+pub(crate) use source_glob::*;
@@ -0,0 +1,372 @@
+use std::{fs, io, path::{Path, PathBuf}};
+use crate::ns::*;
+
+/// Options for discovering and parsing the source files of a `Project`.
+#[derive(Clone, Default)]
+pub struct ProjectOptions {
+    /// Directories searched recursively for source files, or source files.
+    /// A source file given as a root is parsed regardless of globs.
+    pub source_roots: Vec<PathBuf>,
+    /// Globs relative to a source root that discovered files must match,
+    /// such as `"**/*.as"`. Default: empty, matching every file.
+    pub include: Vec<String>,
+    /// Globs relative to a source root that discovered files and directories
+    /// must not match, such as `"test/**"`.
+    pub exclude: Vec<String>,
+    pub parser_options: ParserOptions,
+    /// The compiler options attached to the compilation unit of every source file.
+    pub compiler_options: Option<Rc<CompilerOptions>>,
+    /// The number of threads reading source files and, with the `sync` feature,
+    /// parsing them. Default: `None`, standing for the available parallelism.
+    pub threads: Option<usize>,
+}
+
+/// The syntax of a source file, according to its extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectFileKind {
+    ActionScript,
+    Mxml,
+    Css,
+}
+
+impl ProjectFileKind {
+    /// Returns the kind of a path ending with `.as`, `.mxml` or `.css`,
+    /// ignoring case.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_string_lossy().to_lowercase().as_str() {
+            "as" => Some(Self::ActionScript),
+            "mxml" => Some(Self::Mxml),
+            "css" => Some(Self::Css),
+            _ => None,
+        }
+    }
+}
+
+/// The syntactic tree of a source file.
+#[derive(Clone)]
+pub enum ProjectTree {
+    Program(Rc<Program>),
+    Mxml(Rc<Mxml>),
+    CssDocument(Rc<CssDocument>),
+}
+
+/// A parsed source file of a `Project`.
+#[derive(Clone)]
+pub struct ProjectFile {
+    pub path: PathBuf,
+    pub compilation_unit: Rc<CompilationUnit>,
+    pub tree: ProjectTree,
+}
+
+/// The progress of parsing a `Project`, reported once a file is parsed
+/// or fails to be read.
+pub struct ProjectProgress<'a> {
+    /// The number of parsed files, including this one.
+    pub completed: usize,
+    pub total: usize,
+    pub path: &'a Path,
+}
+
+/// An error reading a source root or a source file.
+#[derive(Debug)]
+pub struct ProjectError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl std::fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to read {}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// The source files of a project, each parsed in its own compilation unit.
+///
+/// Source files are discovered under the source roots in path order. Files
+/// ending with `.mxml` are parsed as MXML, those ending with `.css` as CSS and
+/// any other as ActionScript. Files that cannot be read are reported by
+/// `Project::errors()` and skipped, while an unreadable source root or directory
+/// fails the whole project.
+///
+/// Source files are read by a pool of threads. With the `sync` feature, they are
+/// parsed by the same pool; otherwise, since trees cannot be sent across threads,
+/// they are parsed one after another in the calling thread. Either way,
+/// files and diagnostics are ordered by path, regardless of which file
+/// finishes parsing first.
+///
+/// # Example
+///
+/// ```no_run
+/// use as3_parser::ns::*;
+///
+/// let options = ProjectOptions {
+///     source_roots: vec!["src".into()],
+///     exclude: vec!["test/**".into()],
+///     ..default()
+/// };
+/// let project = Project::parse_with_progress(&options, |progress| {
+///     println!("[{}/{}] {}", progress.completed, progress.total, progress.path.display());
+/// }).unwrap();
+/// for error in project.errors() {
+///     println!("{error}");
+/// }
+/// for diagnostic in project.diagnostics() {
+///     println!("{}", diagnostic.format_english());
+/// }
+/// ```
+pub struct Project {
+    files: Vec<ProjectFile>,
+    errors: Vec<ProjectError>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Project {
+    /// Discovers and parses the source files of a project.
+    pub fn parse(options: &ProjectOptions) -> Result<Self, ProjectError> {
+        Self::parse_with_progress(options, |_| {})
+    }
+
+    /// Discovers and parses the source files of a project, calling `progress`
+    /// in the calling thread whenever a file is parsed or fails to be read.
+    pub fn parse_with_progress(options: &ProjectOptions, mut progress: impl FnMut(ProjectProgress)) -> Result<Self, ProjectError> {
+        let paths = Self::discover(options)?;
+        let mut files = vec![];
+        let mut errors = vec![];
+        for result in parse_files(&paths, options, &mut progress) {
+            match result {
+                Ok(file) => files.push(file),
+                Err(error) => errors.push(error),
+            }
+        }
+        let mut diagnostics = vec![];
+        for file in &files {
+            file.compilation_unit.sort_diagnostics();
+            diagnostics.extend(file.compilation_unit.nested_diagnostics());
+        }
+        Ok(Self {
+            files,
+            errors,
+            diagnostics,
+        })
+    }
+
+    /// Returns the source files of a project in path order, without parsing them.
+    pub fn discover(options: &ProjectOptions) -> Result<Vec<PathBuf>, ProjectError> {
+        let include: Vec<SourceGlob> = options.include.iter().map(|pattern| SourceGlob::new(pattern)).collect();
+        let exclude: Vec<SourceGlob> = options.exclude.iter().map(|pattern| SourceGlob::new(pattern)).collect();
+        let mut paths = vec![];
+        for root in &options.source_roots {
+            if root.is_dir() {
+                discover_directory(root, root, &include, &exclude, &mut paths)?;
+            } else {
+                paths.push(root.clone());
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// The parsed source files, in path order.
+    pub fn files(&self) -> &[ProjectFile] {
+        &self.files
+    }
+
+    pub fn file(&self, path: &Path) -> Option<&ProjectFile> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    /// The programs of the ActionScript source files, in path order.
+    pub fn programs(&self) -> Vec<Rc<Program>> {
+        self.files.iter().filter_map(|file| match &file.tree {
+            ProjectTree::Program(program) => Some(program.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    /// The errors reading source files, in path order.
+    pub fn errors(&self) -> &[ProjectError] {
+        &self.errors
+    }
+
+    /// The diagnostics of every source file, including those of the compilation
+    /// units nested in them, ordered by path and then by location.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.clone()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count()
+    }
+}
+
+fn discover_directory(root: &Path, directory: &Path, include: &[SourceGlob], exclude: &[SourceGlob], output: &mut Vec<PathBuf>) -> Result<(), ProjectError> {
+    let mut entries = fs::read_dir(directory)
+        .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<_>>>())
+        .map_err(|error| ProjectError { path: directory.to_owned(), error })?;
+    entries.sort();
+    for entry in entries {
+        let relative_path = entry.strip_prefix(root).unwrap_or(&entry).to_string_lossy().into_owned();
+        if exclude.iter().any(|glob| glob.matches(&relative_path)) {
+            continue;
+        }
+        if entry.is_dir() {
+            discover_directory(root, &entry, include, exclude, output)?;
+        } else if ProjectFileKind::from_path(&entry).is_some() && (include.is_empty() || include.iter().any(|glob| glob.matches(&relative_path))) {
+            output.push(entry);
+        }
+    }
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<String, ProjectError> {
+    fs::read_to_string(path).map_err(|error| ProjectError { path: path.to_owned(), error })
+}
+
+fn parse_file(path: &Path, text: String, project_options: &ProjectOptions) -> ProjectFile {
+    let compilation_unit = CompilationUnit::new(Some(path.to_string_lossy().into_owned()), text);
+    compilation_unit.set_compiler_options(project_options.compiler_options.clone());
+    let options = &project_options.parser_options;
+    let tree = match ProjectFileKind::from_path(path) {
+        Some(ProjectFileKind::Mxml) => ProjectTree::Mxml(ParserFacade(&compilation_unit, options.clone()).parse_mxml()),
+        Some(ProjectFileKind::Css) => ProjectTree::CssDocument(CssParserFacade(&compilation_unit, options.clone()).parse_document()),
+        _ => ProjectTree::Program(ParserFacade(&compilation_unit, options.clone()).parse_program()),
+    };
+    ProjectFile {
+        path: path.to_owned(),
+        compilation_unit,
+        tree,
+    }
+}
+
+/// Reads files in a pool of threads and parses them in the calling thread,
+/// since trees are not `Send` without the `sync` feature.
+#[cfg(not(feature = "sync"))]
+fn parse_files(paths: &[PathBuf], options: &ProjectOptions, progress: &mut dyn FnMut(ProjectProgress)) -> Vec<Result<ProjectFile, ProjectError>> {
+    let texts = run_in_pool(paths, options, read_file, |_, _| {});
+    paths.iter().zip(texts).enumerate().map(|(i, (path, text))| {
+        let result = text.map(|text| parse_file(path, text, options));
+        progress(ProjectProgress { completed: i + 1, total: paths.len(), path });
+        result
+    }).collect()
+}
+
+/// Reads and parses files in a pool of threads.
+#[cfg(feature = "sync")]
+fn parse_files(paths: &[PathBuf], options: &ProjectOptions, progress: &mut dyn FnMut(ProjectProgress)) -> Vec<Result<ProjectFile, ProjectError>> {
+    run_in_pool(paths, options, |path| read_file(path).map(|text| parse_file(path, text, options)), |completed, path| {
+        progress(ProjectProgress { completed, total: paths.len(), path });
+    })
+}
+
+/// Runs `job` for every path in a pool of threads, each taking the next
+/// pending path, and collects the results in path order. `completed` is called
+/// in the calling thread with the number of completed jobs whenever a job completes.
+fn run_in_pool<T: Send>(paths: &[PathBuf], options: &ProjectOptions, job: impl Fn(&Path) -> T + Sync, mut completed: impl FnMut(usize, &Path)) -> Vec<T> {
+    use std::sync::{atomic::{AtomicUsize, Ordering}, mpsc};
+
+    let threads = options.threads
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |threads| threads.get()))
+        .clamp(1, paths.len().max(1));
+    let next_path = AtomicUsize::new(0);
+    let mut results: Vec<Option<T>> = paths.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let (next_path, job) = (&next_path, &job);
+            scope.spawn(move || {
+                loop {
+                    let i = next_path.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(i) else {
+                        break;
+                    };
+                    if sender.send((i, job(path))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        for (count, (i, result)) in receiver.into_iter().enumerate() {
+            completed(count + 1, &paths[i]);
+            results[i] = Some(result);
+        }
+    });
+    results.into_iter().map(|result| result.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::ns::*;
+
+    #[test]
+    fn test_project() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_project_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        for subdirectory in ["src/com/example", "src/styles", "src/test"] {
+            std::fs::create_dir_all(directory.join(subdirectory)).unwrap();
+        }
+        std::fs::write(directory.join("src/Main.as"), "package { public class Main {} }").unwrap();
+        std::fs::write(directory.join("src/com/example/Util.as"), "x = ;\ny = ;").unwrap();
+        std::fs::write(directory.join("src/com/example/App.MXML"), "<?xml version=\"1.0\"?>\n<s:Application xmlns:s=\"library://ns.adobe.com/flex/spark\"/>").unwrap();
+        std::fs::write(directory.join("src/styles/main.css"), "s|Button { color: }").unwrap();
+        std::fs::write(directory.join("src/test/MainTest.as"), "x = ;").unwrap();
+        std::fs::write(directory.join("src/README.md"), "").unwrap();
+        let options = ProjectOptions {
+            source_roots: vec![directory.join("src"), directory.join("src/Main.as")],
+            exclude: vec!["test/**".into()],
+            threads: Some(3),
+            ..default()
+        };
+
+        let mut progress = vec![];
+        let project = Project::parse_with_progress(&options, |p| progress.push((p.completed, p.total))).unwrap();
+        let paths: Vec<String> = project.files().iter().map(|file| file.path.strip_prefix(&directory).unwrap().to_string_lossy().replace('\\', "/")).collect();
+        assert_eq!(paths, ["src/Main.as", "src/com/example/App.MXML", "src/com/example/Util.as", "src/styles/main.css"]);
+        assert_eq!(progress, [(1, 4), (2, 4), (3, 4), (4, 4)]);
+        assert!(matches!(project.files()[1].tree, ProjectTree::Mxml(_)));
+        assert!(matches!(project.files()[3].tree, ProjectTree::CssDocument(_)));
+        assert_eq!(project.programs().len(), 2);
+
+        let diagnostics: Vec<(String, usize)> = project.diagnostics().iter().map(|diagnostic| {
+            (diagnostic.location().compilation_unit().file_path().unwrap(), diagnostic.location().first_line_number())
+        }).collect();
+        let util_path = directory.join("src/com/example/Util.as").to_string_lossy().into_owned();
+        let css_path = directory.join("src/styles/main.css").to_string_lossy().into_owned();
+        assert_eq!(diagnostics, [(util_path.clone(), 1), (util_path, 2), (css_path, 1)]);
+        assert_eq!(project.error_count(), 3);
+
+        // The order of files and diagnostics does not depend on scheduling
+        let format = |project: &Project| project.diagnostics().iter().map(|diagnostic| diagnostic.format_english()).collect::<Vec<_>>();
+        let single_threaded = Project::parse(&ProjectOptions { threads: Some(1), ..options.clone() }).unwrap();
+        assert_eq!(format(&single_threaded), format(&project));
+
+        let only_as = Project::discover(&ProjectOptions { include: vec!["**/*.as".into()], ..options.clone() }).unwrap();
+        assert_eq!(only_as.len(), 2);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_project_error() {
+        let directory = std::env::temp_dir().join(format!("as3_parser_project_error_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("Main.as"), "x = ;").unwrap();
+        let options = ProjectOptions {
+            source_roots: vec![directory.join("A.as"), directory.join("Main.as"), directory.join("Z.as")],
+            ..default()
+        };
+
+        // Every unreadable file is reported and the remaining files are still parsed.
+        let project = Project::parse(&options).unwrap();
+        let errors: Vec<&Path> = project.errors().iter().map(|error| error.path.as_path()).collect();
+        assert_eq!(errors, [directory.join("A.as"), directory.join("Z.as")]);
+        assert_eq!(project.files().len(), 1);
+        assert_eq!(project.error_count(), 1);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
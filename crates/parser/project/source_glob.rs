@@ -0,0 +1,67 @@
+/// A glob matching paths relative to a source root, whose components
+/// are separated by slashes.
+///
+/// A `*` matches any sequence of characters within a path component,
+/// a `?` matches any single character within a path component and
+/// a `**` component matches any sequence of path components,
+/// including none.
+pub(crate) struct SourceGlob {
+    components: Vec<String>,
+}
+
+impl SourceGlob {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.replace('\\', "/");
+        Self {
+            components: pattern.split('/').filter(|component| !component.is_empty() && *component != ".").map(|component| component.to_owned()).collect(),
+        }
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let path: Vec<&str> = path.split('/').filter(|component| !component.is_empty() && *component != ".").collect();
+        let pattern: Vec<&str> = self.components.iter().map(|component| component.as_str()).collect();
+        match_components(&pattern, &path)
+    }
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_components(rest, &path[i..])),
+        Some((component, rest)) => {
+            path.first().is_some_and(|name| {
+                let pattern: Vec<char> = component.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                match_component(&pattern, &name)
+            }) && match_components(rest, &path[1..])
+        },
+    }
+}
+
+fn match_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| match_component(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && match_component(rest, &name[1..]),
+        Some((ch, rest)) => name.first() == Some(ch) && match_component(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceGlob;
+
+    #[test]
+    fn test_source_glob() {
+        assert!(SourceGlob::new("*.as").matches("Main.as"));
+        assert!(!SourceGlob::new("*.as").matches("com/Main.as"));
+        assert!(SourceGlob::new("**/*.as").matches("Main.as"));
+        assert!(SourceGlob::new("**/*.as").matches("com/example/Main.as"));
+        assert!(!SourceGlob::new("**/*.as").matches("com/example/Main.mxml"));
+        assert!(SourceGlob::new("./com/**").matches("com"));
+        assert!(SourceGlob::new("com/**").matches("com\\example\\Main.as"));
+        assert!(SourceGlob::new("com/*/Ma?n.as").matches("com/example/Main.as"));
+        assert!(!SourceGlob::new("com/*/Ma?n.as").matches("com/Main.as"));
+    }
+}
@@ -0,0 +1,36 @@
+//! Defines semantic passes over the syntactic tree.
+//!
+//! The `ScopeBuilder` structure builds the lexical scopes of a `Program`
+//! and the symbols declared within them, attaching each scope to the node
+//! that introduces it through a `TreeSemantics`.
+//!
+//! The `NameResolver` structure resolves the names of several programs,
+//! including their imports, to the definitions they refer to.
+//!
+//! The `Verifier` structure verifies the types of several programs,
+//! reporting incompatibilities as verify errors.
+//!
+//! The `MxmlComponentResolver` structure resolves the elements of MXML documents
+//! to the classes of the components they stand for, through Flex manifests
+//! and package-style namespaces.
+//!
+//! The `CssSelectorMatcher` structure matches CSS selectors against descriptions
+//! of elements, computing the effective style of Flex components through
+//! `CssSpecificity`.
+
+mod scope;
+pub use scope::*;
+mod symbol;
+pub use symbol::*;
+mod scope_builder;
+pub use scope_builder::*;
+mod name_resolver;
+pub use name_resolver::*;
+mod types;
+pub use types::*;
+mod verifier;
+pub use verifier::*;
+mod mxml_component_resolver;
+pub use mxml_component_resolver::*;
+mod css_selector_matcher;
+pub use css_selector_matcher::*;
@@ -0,0 +1,484 @@
+use std::collections::BTreeMap;
+use crate::ns::*;
+
+/// The specificity of a CSS selector, as the number of identifier conditions,
+/// the number of class, attribute and pseudo-class conditions, and the number
+/// of type names and pseudo-elements.
+///
+/// Specificities compare in that order, such that `#a` is more specific
+/// than `s|Button.b:over`.
+///
+/// See also: [CSS3 selectors: specificity](http://www.w3.org/TR/css3-selectors/#specificity).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct CssSpecificity(pub u32, pub u32, pub u32);
+
+impl CssSpecificity {
+    /// Computes the specificity of a selector. Invalidated selectors have no specificity;
+    /// the universal selectors `*` and `global` count as no type name.
+    pub fn of(selector: &CssSelector) -> Self {
+        match selector {
+            CssSelector::Invalidated(_) => Self::default(),
+            CssSelector::Base(base) => {
+                let mut specificity = Self::default();
+                if base.element_name.as_ref().is_some_and(|(name, _)| !is_universal_type_name(name)) {
+                    specificity.2 += 1;
+                }
+                for condition in &base.conditions {
+                    specificity = specificity + Self::of_condition(condition);
+                }
+                specificity
+            },
+            CssSelector::Combinator(combinator) => Self::of(&combinator.left) + Self::of(&combinator.right),
+        }
+    }
+
+    /// Computes the specificity of a selector condition. The specificity of
+    /// a negation is the specificity of its argument.
+    pub fn of_condition(condition: &CssSelectorCondition) -> Self {
+        match condition {
+            CssSelectorCondition::Invalidated(_) => Self::default(),
+            CssSelectorCondition::Id(_) => Self(1, 0, 0),
+            CssSelectorCondition::Class(_) |
+            CssSelectorCondition::Pseudo(_) |
+            CssSelectorCondition::Attribute { .. } => Self(0, 1, 0),
+            CssSelectorCondition::PseudoElement(_) => Self(0, 0, 1),
+            CssSelectorCondition::Not { condition, .. } => Self::of_condition(condition),
+        }
+    }
+}
+
+impl std::ops::Add for CssSpecificity {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl std::fmt::Display for CssSpecificity {
+    /// Comma-separated components, such as `0,1,1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.0, self.1, self.2)
+    }
+}
+
+fn is_universal_type_name(name: &str) -> bool {
+    name == "*" || name == "global"
+}
+
+/// A type name qualified by a namespace URI, such as
+/// `library://ns.adobe.com/flex/spark` and `Button`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CssTypeName {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl CssTypeName {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// The description of an element against which CSS selectors are matched,
+/// such as a Flex component instance.
+#[derive(Clone, Debug, Default)]
+pub struct CssElement {
+    pub type_name: CssTypeName,
+    /// The types the element's type derives from. Type selectors
+    /// match these as well, as in Flex.
+    pub supertypes: Vec<CssTypeName>,
+    /// The style names of the element, matched by class conditions.
+    pub style_names: Vec<String>,
+    pub id: Option<String>,
+    /// The current states of the element, matched by pseudo-class conditions
+    /// such as `:over`.
+    pub states: Vec<String>,
+    /// The attributes of the element, matched by attribute conditions.
+    pub attributes: BTreeMap<String, String>,
+    /// The parent element, matched by the descendant and child combinators.
+    pub parent: Option<Rc<CssElement>>,
+    /// The preceding siblings, nearest first, matched by the `+` and `~` combinators.
+    /// Siblings share the parent of this element; their own `parent` and
+    /// `previous_siblings` fields are ignored.
+    pub previous_siblings: Vec<Rc<CssElement>>,
+}
+
+impl CssElement {
+    pub fn new(type_name: CssTypeName) -> Self {
+        Self {
+            type_name,
+            ..default()
+        }
+    }
+}
+
+/// An element within its tree: the element, its parent and its preceding siblings.
+#[derive(Clone, Copy)]
+struct CssElementPosition<'a> {
+    element: &'a CssElement,
+    parent: Option<&'a Rc<CssElement>>,
+    previous_siblings: &'a [Rc<CssElement>],
+}
+
+impl<'a> CssElementPosition<'a> {
+    fn of(element: &'a CssElement) -> Self {
+        Self {
+            element,
+            parent: element.parent.as_ref(),
+            previous_siblings: &element.previous_siblings,
+        }
+    }
+
+    fn parent(&self) -> Option<Self> {
+        self.parent.map(|parent| Self::of(parent))
+    }
+
+    fn previous_sibling(&self, index: usize) -> Option<Self> {
+        Some(Self {
+            element: self.previous_siblings.get(index)?,
+            parent: self.parent,
+            previous_siblings: &self.previous_siblings[(index + 1)..],
+        })
+    }
+}
+
+/// Matches the selectors of a style sheet against element descriptions.
+///
+/// Namespace prefixes of type selectors are resolved through the `@namespace`
+/// definitions of the style sheet; a type selector without a prefix matches
+/// a type of any namespace. Pseudo-element conditions match no element.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+/// let cu = CompilationUnit::new(None, r#"
+///     @namespace s "library://ns.adobe.com/flex/spark";
+///     s|Button { color: #000000 }
+///     s|Group s|Button.primary:over { color: #ff0000 }
+/// "#.into());
+/// let document = CssParserFacade(&cu, default()).parse_document();
+/// let matcher = CssSelectorMatcher::new(&document);
+///
+/// let spark = "library://ns.adobe.com/flex/spark";
+/// let group = Rc::new(CssElement::new(CssTypeName::new(spark, "Group")));
+/// let button = CssElement {
+///     style_names: vec!["primary".into()],
+///     states: vec!["over".into()],
+///     parent: Some(group),
+///     ..CssElement::new(CssTypeName::new(spark, "Button"))
+/// };
+/// let style = matcher.effective_style(&button);
+/// assert_eq!(style["color"].value.location().text(), "#ff0000");
+/// ```
+pub struct CssSelectorMatcher {
+    document: Rc<CssDocument>,
+    namespaces: HashMap<String, String>,
+}
+
+impl CssSelectorMatcher {
+    pub fn new(document: &Rc<CssDocument>) -> Self {
+        let mut namespaces = HashMap::new();
+        for directive in &document.directives {
+            if let CssDirective::NamespaceDefinition(defn) = directive.as_ref() {
+                namespaces.insert(defn.prefix.0.clone(), defn.uri.0.clone());
+            }
+        }
+        Self {
+            document: document.clone(),
+            namespaces,
+        }
+    }
+
+    /// The namespace URI a prefix is bound to by the style sheet.
+    pub fn namespace_uri(&self, prefix: &str) -> Option<String> {
+        self.namespaces.get(prefix).cloned()
+    }
+
+    /// Determines whether a selector matches an element.
+    pub fn matches(&self, selector: &CssSelector, element: &CssElement) -> bool {
+        let mut compounds = vec![];
+        if !Self::flatten(selector, None, &mut compounds) {
+            return false;
+        }
+        self.matches_compounds(&compounds, CssElementPosition::of(element))
+    }
+
+    /// The rules of the style sheet that match an element, with the specificity
+    /// of their most specific matching selector, in cascade order: by ascending
+    /// specificity and then by source order.
+    ///
+    /// Rules within media queries are not considered, as media features
+    /// depend on the runtime environment.
+    pub fn matching_rules(&self, element: &CssElement) -> Vec<(CssSpecificity, &CssRule)> {
+        let mut rules = vec![];
+        for directive in &self.document.directives {
+            let CssDirective::Rule(rule) = directive.as_ref() else {
+                continue;
+            };
+            let specificity = rule.selectors.iter()
+                .filter(|selector| self.matches(selector, element))
+                .map(|selector| CssSpecificity::of(selector))
+                .max();
+            if let Some(specificity) = specificity {
+                rules.push((specificity, rule));
+            }
+        }
+        // The sort is stable, preserving source order among equal specificities
+        rules.sort_by_key(|(specificity, _)| *specificity);
+        rules
+    }
+
+    /// The effective style of an element, mapping the camel-case names of
+    /// properties (as in `CssProperty`) to the properties that win the cascade.
+    pub fn effective_style(&self, element: &CssElement) -> BTreeMap<String, Rc<CssProperty>> {
+        let mut style = BTreeMap::new();
+        for (_, rule) in self.matching_rules(element) {
+            for property in &rule.properties {
+                if matches!(property.value.as_ref(), CssPropertyValue::Invalidated(_)) {
+                    continue;
+                }
+                style.insert(property.name.0.clone(), property.clone());
+            }
+        }
+        style
+    }
+
+    /// Flattens a selector into its base selectors in source order, each with the
+    /// combinator that precedes it. Returns `false` for an invalidated selector.
+    fn flatten<'a>(selector: &'a CssSelector, combinator_type: Option<CssCombinatorType>, output: &mut Vec<(Option<CssCombinatorType>, &'a CssBaseSelector)>) -> bool {
+        match selector {
+            CssSelector::Invalidated(_) => false,
+            CssSelector::Base(base) => {
+                output.push((combinator_type, base));
+                true
+            },
+            CssSelector::Combinator(combinator) => {
+                Self::flatten(&combinator.left, combinator_type, output)
+                    && Self::flatten(&combinator.right, Some(combinator.combinator_type), output)
+            },
+        }
+    }
+
+    /// Matches the last base selector against an element and the preceding
+    /// ones against its ancestors and siblings, according to their combinators.
+    fn matches_compounds(&self, compounds: &[(Option<CssCombinatorType>, &CssBaseSelector)], position: CssElementPosition) -> bool {
+        let Some(((combinator_type, base), preceding)) = compounds.split_last() else {
+            return true;
+        };
+        if !self.matches_base(base, position.element) {
+            return false;
+        }
+        let Some(combinator_type) = combinator_type else {
+            return true;
+        };
+        match combinator_type {
+            CssCombinatorType::Descendant => {
+                let mut ancestor = position.parent();
+                while let Some(position) = ancestor {
+                    if self.matches_compounds(preceding, position) {
+                        return true;
+                    }
+                    ancestor = position.parent();
+                }
+                false
+            },
+            CssCombinatorType::Child => {
+                position.parent().is_some_and(|parent| self.matches_compounds(preceding, parent))
+            },
+            CssCombinatorType::Preceded => {
+                position.previous_sibling(0).is_some_and(|sibling| self.matches_compounds(preceding, sibling))
+            },
+            CssCombinatorType::Sibling => {
+                (0..position.previous_siblings.len()).any(|i| self.matches_compounds(preceding, position.previous_sibling(i).unwrap()))
+            },
+        }
+    }
+
+    fn matches_base(&self, base: &CssBaseSelector, element: &CssElement) -> bool {
+        if let Some((name, _)) = &base.element_name {
+            if !is_universal_type_name(name) {
+                let namespace = match &base.namespace_prefix {
+                    Some((prefix, _)) => match self.namespaces.get(prefix) {
+                        Some(uri) => Some(uri.as_str()),
+                        None => return false,
+                    },
+                    None => None,
+                };
+                let matches_type = |type_name: &CssTypeName| {
+                    type_name.name == *name && namespace.is_none_or(|uri| type_name.namespace == uri)
+                };
+                if !(matches_type(&element.type_name) || element.supertypes.iter().any(matches_type)) {
+                    return false;
+                }
+            }
+        }
+        base.conditions.iter().all(|condition| Self::matches_condition(condition, element))
+    }
+
+    fn matches_condition(condition: &CssSelectorCondition, element: &CssElement) -> bool {
+        match condition {
+            CssSelectorCondition::Invalidated(_) => false,
+            CssSelectorCondition::Class((name, _)) => element.style_names.contains(name),
+            CssSelectorCondition::Id((name, _)) => element.id.as_ref() == Some(name),
+            CssSelectorCondition::Pseudo((name, _)) => element.states.contains(name),
+            CssSelectorCondition::PseudoElement(_) => false,
+            CssSelectorCondition::Not { condition, .. } => !Self::matches_condition(condition, element),
+            CssSelectorCondition::Attribute { name, operator, value, .. } => {
+                let Some(actual) = element.attributes.get(&name.0) else {
+                    return false;
+                };
+                let (Some(operator), Some((value, _))) = (operator, value) else {
+                    return true;
+                };
+                match operator {
+                    CssAttributeOperator::Equals => actual == value,
+                    CssAttributeOperator::BeginsWith => !value.is_empty() && actual.starts_with(value.as_str()),
+                    CssAttributeOperator::EndsWith => !value.is_empty() && actual.ends_with(value.as_str()),
+                    CssAttributeOperator::Contains => !value.is_empty() && actual.contains(value.as_str()),
+                    CssAttributeOperator::ListMatch => actual.split_whitespace().any(|item| item == value),
+                    CssAttributeOperator::HreflangMatch => actual == value || actual.starts_with(&format!("{value}-")),
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    const SPARK: &str = "library://ns.adobe.com/flex/spark";
+
+    fn parse(text: &str) -> Rc<CssDocument> {
+        let cu = CompilationUnit::new(None, text.into());
+        let document = CssParserFacade(&cu, default()).parse_document();
+        assert!(!cu.invalidated());
+        document
+    }
+
+    fn selector(document: &CssDocument, index: usize) -> Rc<CssSelector> {
+        let CssDirective::Rule(rule) = document.directives[index].as_ref() else {
+            panic!();
+        };
+        rule.selectors[0].clone()
+    }
+
+    #[test]
+    fn test_specificity() {
+        let document = parse(r#"
+            global {}
+            s|Button {}
+            s|Button.primary:over {}
+            #main s|Label::first-line {}
+            s|Group:not(.a)[data-x^="y"] {}
+        "#);
+        let specificities: Vec<String> = (0..5).map(|i| CssSpecificity::of(&selector(&document, i)).to_string()).collect();
+        assert_eq!(specificities, ["0,0,0", "0,0,1", "0,2,1", "1,0,2", "0,2,1"]);
+        assert!(CssSpecificity(1, 0, 0) > CssSpecificity(0, 5, 5));
+    }
+
+    #[test]
+    fn test_matching() {
+        let document = parse(r#"
+            @namespace s "library://ns.adobe.com/flex/spark";
+            @namespace mx "library://ns.adobe.com/flex/mx";
+            s|Button {}
+            mx|Button {}
+            Button.primary:over {}
+            s|Group s|Button {}
+            #main s|Label {}
+            s|Button:not(.primary) {}
+            s|Button[label|="en"] {}
+            s|Label::first-line {}
+        "#);
+        let matcher = CssSelectorMatcher::new(&document);
+        let group = Rc::new(CssElement {
+            id: Some("main".into()),
+            ..CssElement::new(CssTypeName::new(SPARK, "Group"))
+        });
+        let button = CssElement {
+            style_names: vec!["primary".into()],
+            states: vec!["over".into()],
+            attributes: [("label".to_owned(), "en-US".to_owned())].into(),
+            parent: Some(Rc::new(CssElement {
+                parent: Some(group.clone()),
+                ..CssElement::new(CssTypeName::new(SPARK, "VGroup"))
+            })),
+            ..CssElement::new(CssTypeName::new(SPARK, "Button"))
+        };
+        let label = CssElement {
+            supertypes: vec![CssTypeName::new(SPARK, "Label")],
+            parent: Some(group.clone()),
+            ..CssElement::new(CssTypeName::new("com.example.*", "Heading"))
+        };
+        let matches: Vec<bool> = (2..10).map(|i| matcher.matches(&selector(&document, i), &button)).collect();
+        assert_eq!(matches, [true, false, true, true, false, false, true, false]);
+        let matches: Vec<bool> = (2..10).map(|i| matcher.matches(&selector(&document, i), &label)).collect();
+        assert_eq!(matches, [false, false, false, false, true, false, false, false]);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let document = parse("s|Label {}");
+        let matcher = CssSelectorMatcher::new(&document);
+        let base = |name: &str| Rc::new(CssSelector::Base(CssBaseSelector {
+            location: document.location.clone(),
+            namespace_prefix: None,
+            element_name: Some((name.into(), document.location.clone())),
+            conditions: vec![],
+        }));
+        let combine = |left: Rc<CssSelector>, combinator_type, right: Rc<CssSelector>| CssSelector::Combinator(CssCombinatorSelector {
+            location: document.location.clone(),
+            left,
+            right,
+            combinator_type,
+        });
+        let group = Rc::new(CssElement::new(CssTypeName::new(SPARK, "Group")));
+        let image = Rc::new(CssElement::new(CssTypeName::new(SPARK, "Image")));
+        let label = Rc::new(CssElement::new(CssTypeName::new(SPARK, "Label")));
+        // <s:Group><s:Label/><s:Image/><s:Button/></s:Group>
+        let button = CssElement {
+            parent: Some(group.clone()),
+            previous_siblings: vec![image.clone(), label.clone()],
+            ..CssElement::new(CssTypeName::new(SPARK, "Button"))
+        };
+        assert!(matcher.matches(&combine(base("Group"), CssCombinatorType::Child, base("Button")), &button));
+        assert!(matcher.matches(&combine(base("Image"), CssCombinatorType::Preceded, base("Button")), &button));
+        assert!(!matcher.matches(&combine(base("Label"), CssCombinatorType::Preceded, base("Button")), &button));
+        assert!(matcher.matches(&combine(base("Label"), CssCombinatorType::Sibling, base("Button")), &button));
+        // Group > Label ~ Button
+        let selector = combine(Rc::new(combine(base("Group"), CssCombinatorType::Child, base("Label"))), CssCombinatorType::Sibling, base("Button"));
+        assert!(matcher.matches(&selector, &button));
+        // Label > Image + Button
+        let selector = combine(Rc::new(combine(base("Label"), CssCombinatorType::Child, base("Image"))), CssCombinatorType::Preceded, base("Button"));
+        assert!(!matcher.matches(&selector, &button));
+    }
+
+    #[test]
+    fn test_effective_style() {
+        let document = parse(r#"
+            @namespace s "library://ns.adobe.com/flex/spark";
+            .primary { color: #0000ff; font-weight: bold }
+            s|Button { color: #000000; font-size: 12 }
+            s|Button.primary { font-size: 14 }
+            global { font-family: Arial }
+            s|Label { color: #00ff00 }
+        "#);
+        let matcher = CssSelectorMatcher::new(&document);
+        let button = CssElement {
+            style_names: vec!["primary".into()],
+            ..CssElement::new(CssTypeName::new(SPARK, "Button"))
+        };
+        let style: Vec<(String, String)> = matcher.effective_style(&button).into_iter().map(|(name, property)| (name, property.value.location().text())).collect();
+        assert_eq!(style, [
+            ("color".to_owned(), "#0000ff".to_owned()),
+            ("fontFamily".to_owned(), "Arial".to_owned()),
+            ("fontSize".to_owned(), "14".to_owned()),
+            ("fontWeight".to_owned(), "bold".to_owned()),
+        ]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use crate::ns::*;
+
+/// A Flex component manifest, mapping the component names of
+/// a namespace to fully qualified class names.
+///
+/// A manifest is an XML document such as:
+///
+/// ```xml
+/// <componentPackage>
+///     <component id="Button" class="spark.components.Button"/>
+/// </componentPackage>
+/// ```
+#[derive(Clone, Default)]
+pub struct MxmlManifest {
+    components: BTreeMap<String, String>,
+}
+
+impl MxmlManifest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a manifest, reporting syntax errors to its compilation unit.
+    /// A `component` element lacking an `id` attribute is named after
+    /// the last name of its class.
+    pub fn parse(compilation_unit: &Rc<CompilationUnit>) -> Self {
+        let document = ParserFacade(compilation_unit, default()).parse_mxml();
+        let mut manifest = Self::new();
+        let Some(root) = document.root() else {
+            return manifest;
+        };
+        for component in root.child_elements() {
+            if component.name.name != "component" {
+                continue;
+            }
+            let Some(class) = component.attribute("class").map(|class| class.value.0.trim().to_owned()) else {
+                continue;
+            };
+            let name = component.attribute("id").map_or_else(|| class.rsplit('.').next().unwrap().to_owned(), |id| id.value.0.trim().to_owned());
+            manifest.insert(&name, &class);
+        }
+        manifest
+    }
+
+    pub fn insert(&mut self, name: &str, class: &str) {
+        self.components.insert(name.to_owned(), class.to_owned());
+    }
+
+    /// The fully qualified class name of a component.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.components.get(name).cloned()
+    }
+
+    /// The components of the manifest, as pairs of names and
+    /// fully qualified class names, ordered by name.
+    pub fn components(&self) -> Vec<(String, String)> {
+        self.components.iter().map(|(name, class)| (name.clone(), class.clone())).collect()
+    }
+}
+
+/// The meaning of an MXML element, as resolved by `MxmlComponentResolver`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MxmlElementKind {
+    /// A component, with the fully qualified name of its class.
+    Component(String),
+    /// A language element that does not stand for a component, such as `fx:Script`.
+    Language,
+    /// An element named after a property of its parent component, such as `<s:layout>`.
+    Property,
+    /// An element that resolves to no component.
+    Unresolved,
+}
+
+/// The language elements of MXML that do not stand for a component.
+const MXML_LANGUAGE_ELEMENTS: [&str; 13] = [
+    "Binding", "Component", "Declarations", "Definition", "DesignLayer", "Library",
+    "Metadata", "Model", "Private", "Reparent", "Repeater", "Script", "Style",
+];
+
+/// The language elements of MXML whose content consists of components.
+const MXML_CONTAINER_ELEMENTS: [&str; 5] = ["Component", "Declarations", "Definition", "DesignLayer", "Library"];
+
+/// The top-level classes that the MXML language namespace provides as elements, such as `fx:String`.
+const MXML_LANGUAGE_CLASSES: [&str; 14] = [
+    "Array", "Boolean", "Class", "Date", "Function", "int", "Number",
+    "Object", "RegExp", "String", "uint", "Vector", "XML", "XMLList",
+];
+
+/// Resolves MXML elements to the classes of the components they stand for.
+///
+/// The namespace of an element determines how its local name is resolved:
+///
+/// - A package-style namespace, such as `views.*`, refers to the classes
+///   of a package; the `*` namespace refers to the top-level package.
+/// - The MXML language namespaces provide top-level classes, such as `fx:String`,
+///   besides language elements, such as `fx:Script`.
+/// - Other namespaces, such as `library://ns.adobe.com/flex/spark`,
+///   are resolved through the manifest added for them.
+///
+/// A child element in the namespace of its parent component whose name starts
+/// with a lowercase letter, such as `<s:layout>`, stands for a property.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let manifest = CompilationUnit::new(None, r#"<componentPackage><component id="Button" class="spark.components.Button"/></componentPackage>"#.into());
+/// let mut resolver = MxmlComponentResolver::new();
+/// resolver.add_manifest("library://ns.adobe.com/flex/spark", MxmlManifest::parse(&manifest));
+///
+/// let cu = CompilationUnit::new(None, r#"<s:Button xmlns:s="library://ns.adobe.com/flex/spark"/>"#.into());
+/// let mxml = ParserFacade(&cu, default()).parse_mxml();
+/// let resolutions = resolver.resolve_document(&mxml);
+/// assert_eq!(resolutions.get(&mxml.root().unwrap()), Some(MxmlElementKind::Component("spark.components.Button".into())));
+/// ```
+#[derive(Clone, Default)]
+pub struct MxmlComponentResolver {
+    manifests: HashMap<String, MxmlManifest>,
+}
+
+impl MxmlComponentResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the manifest of a namespace, merging it with any manifest
+    /// previously added for the same namespace.
+    pub fn add_manifest(&mut self, uri: &str, manifest: MxmlManifest) {
+        self.manifests.entry(uri.to_owned()).or_default().components.extend(manifest.components);
+    }
+
+    /// Loads and adds the manifest file of a namespace, returning its compilation
+    /// unit, to which syntax errors in the manifest are reported.
+    pub fn load_manifest(&mut self, uri: &str, file_path: &str) -> std::io::Result<Rc<CompilationUnit>> {
+        let text = std::fs::read_to_string(file_path)?;
+        let compilation_unit = CompilationUnit::new(Some(file_path.to_owned()), text);
+        self.add_manifest(uri, MxmlManifest::parse(&compilation_unit));
+        Ok(compilation_unit)
+    }
+
+    pub fn manifest(&self, uri: &str) -> Option<&MxmlManifest> {
+        self.manifests.get(uri)
+    }
+
+    /// Resolves a local name within a namespace to the fully qualified name of
+    /// a component class. Language elements, such as `fx:Script`, resolve to nothing.
+    pub fn resolve_name(&self, uri: &str, name: &str) -> Option<String> {
+        if uri == "*" {
+            return Some(name.to_owned());
+        }
+        if let Some(package) = uri.strip_suffix(".*") {
+            return Some(format!("{package}.{name}"));
+        }
+        if Self::is_language_namespace(uri) {
+            if MXML_LANGUAGE_CLASSES.contains(&name) {
+                return Some(name.to_owned());
+            }
+            if MXML_LANGUAGE_ELEMENTS.contains(&name) {
+                return None;
+            }
+        }
+        self.manifests.get(uri).and_then(|manifest| manifest.get(name))
+    }
+
+    /// Resolves every element of a document, reporting the elements that
+    /// resolve to no component as verify errors. The content of language
+    /// elements other than those containing components, such as `fx:Script`,
+    /// and of `fx:XML`, `fx:XMLList` and `fx:Model` elements is not resolved.
+    pub fn resolve_document(&self, mxml: &Rc<Mxml>) -> TreeSemantics<MxmlElementKind> {
+        let resolutions = TreeSemantics::new();
+        if let Some(root) = mxml.root() {
+            self.resolve_element(&root, None, &resolutions);
+        }
+        resolutions
+    }
+
+    fn resolve_element(&self, element: &Rc<MxmlElement>, parent_uri: Option<&str>, resolutions: &TreeSemantics<MxmlElementKind>) {
+        // Undefined prefixes are reported by the parser.
+        let Ok(uri) = element.name.resolve_prefix(&element.namespace) else {
+            resolutions.set(element, Some(MxmlElementKind::Unresolved));
+            return;
+        };
+        let name = element.name.name.as_str();
+        let kind = if Self::is_language_namespace(&uri) && MXML_LANGUAGE_ELEMENTS.contains(&name) {
+            MxmlElementKind::Language
+        } else if parent_uri == Some(uri.as_str()) && name.starts_with(|ch: char| ch.is_lowercase()) {
+            MxmlElementKind::Property
+        } else if let Some(class) = self.resolve_name(&uri, name) {
+            MxmlElementKind::Component(class)
+        } else {
+            let location = &element.name.location;
+            location.compilation_unit().add_diagnostic(Diagnostic::new_verify_error(location, DiagnosticKind::UnresolvedMxmlComponent, diagarg![location.text()]));
+            MxmlElementKind::Unresolved
+        };
+        resolutions.set(element, Some(kind.clone()));
+
+        let child_parent_uri = match &kind {
+            MxmlElementKind::Language if MXML_CONTAINER_ELEMENTS.contains(&name) => None,
+            MxmlElementKind::Language => return,
+            MxmlElementKind::Component(class) if class == "XML" || class == "XMLList" => return,
+            MxmlElementKind::Component(_) | MxmlElementKind::Unresolved => Some(uri.as_str()),
+            MxmlElementKind::Property => None,
+        };
+        for child in element.child_elements() {
+            self.resolve_element(&child, child_parent_uri, resolutions);
+        }
+    }
+
+    fn is_language_namespace(uri: &str) -> bool {
+        uri == MxmlNamespace::MXML_2009 || uri == MxmlNamespace::MXML_2006
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn spark_resolver() -> MxmlComponentResolver {
+        let manifest = CompilationUnit::new(None, r#"<?xml version="1.0"?>
+<componentPackage>
+    <component id="Application" class="spark.components.Application"/>
+    <component id="Button" class="spark.components.Button"/>
+    <component class="spark.layouts.VerticalLayout"/>
+</componentPackage>"#.into());
+        let mut resolver = MxmlComponentResolver::new();
+        resolver.add_manifest("library://ns.adobe.com/flex/spark", MxmlManifest::parse(&manifest));
+        assert!(!manifest.invalidated());
+        resolver
+    }
+
+    #[test]
+    fn test_manifest() {
+        let resolver = spark_resolver();
+        let manifest = resolver.manifest("library://ns.adobe.com/flex/spark").unwrap();
+        assert_eq!(manifest.get("VerticalLayout").as_deref(), Some("spark.layouts.VerticalLayout"));
+        assert_eq!(manifest.components().len(), 3);
+        assert_eq!(resolver.resolve_name("views.*", "Main").as_deref(), Some("views.Main"));
+        assert_eq!(resolver.resolve_name("*", "Main").as_deref(), Some("Main"));
+        assert_eq!(resolver.resolve_name(MxmlNamespace::MXML_2009, "String").as_deref(), Some("String"));
+        assert_eq!(resolver.resolve_name(MxmlNamespace::MXML_2009, "Script"), None);
+    }
+
+    #[test]
+    fn test_resolve_document() {
+        let cu = CompilationUnit::new(None, r#"<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.adobe.com/flex/spark" xmlns:v="views.*">
+    <fx:Declarations><fx:XML><root><child/></root></fx:XML></fx:Declarations>
+    <fx:Script><![CDATA[ var x; ]]></fx:Script>
+    <s:layout><s:VerticalLayout/></s:layout>
+    <s:Button/>
+    <v:Sidebar/>
+    <s:Unknown/>
+</s:Application>"#.into());
+        let mxml = ParserFacade(&cu, default()).parse_mxml();
+        assert!(!cu.invalidated());
+        let resolutions = spark_resolver().resolve_document(&mxml);
+
+        let root = mxml.root().unwrap();
+        assert_eq!(resolutions.get(&root), Some(MxmlElementKind::Component("spark.components.Application".into())));
+        let children = root.child_elements();
+        assert_eq!(resolutions.get(&children[0]), Some(MxmlElementKind::Language));
+        let xml = &children[0].child_elements()[0];
+        assert_eq!(resolutions.get(xml), Some(MxmlElementKind::Component("XML".into())));
+        assert_eq!(resolutions.get(&xml.child_elements()[0]), None);
+        assert_eq!(resolutions.get(&children[1]), Some(MxmlElementKind::Language));
+        assert_eq!(resolutions.get(&children[2]), Some(MxmlElementKind::Property));
+        assert_eq!(resolutions.get(&children[2].child_elements()[0]), Some(MxmlElementKind::Component("spark.layouts.VerticalLayout".into())));
+        assert_eq!(resolutions.get(&children[3]), Some(MxmlElementKind::Component("spark.components.Button".into())));
+        assert_eq!(resolutions.get(&children[4]), Some(MxmlElementKind::Component("views.Sidebar".into())));
+        assert_eq!(resolutions.get(&children[5]), Some(MxmlElementKind::Unresolved));
+
+        let diagnostics: Vec<String> = cu.diagnostics().iter().map(|diagnostic| diagnostic.format_english()).collect();
+        assert_eq!(diagnostics, ["7:6: Verify error #1112: Could not resolve <s:Unknown> to a component implementation."]);
+    }
+}
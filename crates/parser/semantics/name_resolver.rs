@@ -0,0 +1,1065 @@
+use crate::ns::*;
+use by_address::ByAddress;
+
+/// Names of the top-level built-in definitions of ActionScript.
+const TOP_LEVEL_BUILTINS: [&str; 46] = [
+    "ArgumentError", "Array", "Boolean", "Class", "Date", "DefinitionError", "Error", "EvalError",
+    "Function", "int", "JSON", "Math", "Namespace", "Number", "Object", "QName", "RangeError",
+    "ReferenceError", "RegExp", "SecurityError", "String", "SyntaxError", "TypeError", "uint",
+    "URIError", "Vector", "VerifyError", "XML", "XMLList", "decodeURI", "decodeURIComponent",
+    "encodeURI", "encodeURIComponent", "escape", "isFinite", "isNaN", "isXMLName", "parseFloat",
+    "parseInt", "trace", "unescape", "Infinity", "NaN", "undefined", "AS3", "UninitializedError",
+];
+
+/// Resolves the names of several programs to the definitions they refer to.
+///
+/// The resolver builds the scopes of every program through `ScopeBuilder`,
+/// indexes the definitions of each package by package name and then resolves,
+/// across all programs:
+///
+/// - Import directives, including wildcard (`p.*`) and recursive (`p.**`)
+///   imports and aliases (`import x = p.C`).
+/// - Package concatenation directives (`public += p.*`), whose definitions
+///   become visible through the package containing the directive.
+/// - Use namespace directives.
+/// - Identifiers within expressions and type annotations, including
+///   fully package-qualified names such as `p.q.C`.
+///
+/// Each resolved identifier expression, import, package concatenation and
+/// use namespace directive and shorthand object field is mapped to the
+/// `Symbol` of its definition in `resolutions()`. Names that cannot be resolved
+/// are reported as verify errors to the compilation unit in which they appear.
+///
+/// Built-in definitions such as `Object` and `trace` are resolved only if
+/// one of the programs defines them in the top-level package, which is
+/// open to every program; otherwise, references to the top-level built-in
+/// definitions of ActionScript are not reported. Packages are assumed to be
+/// defined by external libraries, which are not read, if none of the programs
+/// defines a package with the same first name, such as `flash` for
+/// `flash.display`; imports of such packages and the names they may provide
+/// are not reported either. Names within `with` statements and XML filter
+/// expressions are not reported, since they may refer to dynamic properties;
+/// neither are names qualified by a configuration namespace, such as `CONFIG::debug`,
+/// including the namespaces of the constants defined by the compiler options
+/// of the compilation units.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu1 = CompilationUnit::new(None, "package p { public class C {} }".into());
+/// let cu2 = CompilationUnit::new(None, "import p.*; var x: C; y".into());
+/// let program1 = ParserFacade(&cu1, default()).parse_program();
+/// let program2 = ParserFacade(&cu2, default()).parse_program();
+///
+/// let mut resolver = NameResolver::new();
+/// resolver.resolve_programs(&[program1.clone(), program2.clone()]);
+///
+/// let Directive::VariableDefinition(defn) = program2.directives[1].as_ref() else { unreachable!() };
+/// let type_annotation = defn.bindings[0].destructuring.type_annotation.as_ref().unwrap();
+/// let symbol = resolver.resolutions().get(type_annotation).unwrap();
+/// assert_eq!(symbol.kind, SymbolKind::Class);
+/// assert!(Rc::ptr_eq(&symbol.location.compilation_unit(), &cu1));
+///
+/// assert_eq!(cu2.diagnostics()[0].format_english(), "1:23: Verify error #1091: Access of undefined property 'y'.");
+/// ```
+pub struct NameResolver {
+    scopes: TreeSemantics<Rc<Scope>>,
+    resolutions: TreeSemantics<Rc<Symbol>>,
+    /// Package-level definitions by package name.
+    packages: RefCell<HashMap<String, Vec<Rc<Symbol>>>>,
+    package_scopes: RefCell<HashMap<ByAddress<Rc<Scope>>, String>>,
+    imports: RefCell<HashMap<ByAddress<Rc<Scope>>, Vec<ImportedName>>>,
+    /// Package concatenation directives by the name of their package.
+    concatenations: RefCell<HashMap<String, Vec<ImportedName>>>,
+    /// Extends clauses by class scope.
+    base_classes: RefCell<HashMap<ByAddress<Rc<Scope>>, Rc<Expression>>>,
+    configuration_namespaces: RefCell<HashSet<String>>,
+    /// Programs by package-level definition.
+    owners: RefCell<HashMap<ByAddress<Rc<Symbol>>, Rc<Program>>>,
+    dependencies: TreeSemantics<Vec<Rc<Symbol>>>,
+}
+
+impl Default for NameResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NameResolver {
+    pub fn new() -> Self {
+        Self {
+            scopes: TreeSemantics::new(),
+            resolutions: TreeSemantics::new(),
+            packages: RefCell::new(HashMap::new()),
+            package_scopes: RefCell::new(HashMap::new()),
+            imports: RefCell::new(HashMap::new()),
+            concatenations: RefCell::new(HashMap::new()),
+            base_classes: RefCell::new(HashMap::new()),
+            configuration_namespaces: RefCell::new(HashSet::from(["CONFIG".to_owned()])),
+            owners: RefCell::new(HashMap::new()),
+            dependencies: TreeSemantics::new(),
+        }
+    }
+
+    /// Resolves the names of the given programs, which may refer to
+    /// each other's package-level definitions.
+    pub fn resolve_programs(&mut self, programs: &[Rc<Program>]) {
+        for program in programs {
+            ScopeBuilder::new(&self.scopes).build_program(program);
+            if let Some(options) = program.location.compilation_unit().compiler_options() {
+                self.configuration_namespaces.borrow_mut().extend(options.configuration_namespaces());
+            }
+        }
+        for program in programs {
+            let _ = walk_program(&mut NameResolverWalker::new(self, program, true), program);
+        }
+        for program in programs {
+            let _ = walk_program(&mut NameResolverWalker::new(self, program, false), program);
+        }
+    }
+
+    /// The scopes of the resolved programs, as built by `ScopeBuilder`.
+    pub fn scopes(&self) -> &TreeSemantics<Rc<Scope>> {
+        &self.scopes
+    }
+
+    /// The definitions of resolved expressions, import directives,
+    /// package concatenation directives, use namespace directives
+    /// and shorthand object fields.
+    pub fn resolutions(&self) -> &TreeSemantics<Rc<Symbol>> {
+        &self.resolutions
+    }
+
+    /// The definitions of a package, in declaration order.
+    pub fn package_definitions(&self, package: &str) -> Vec<Rc<Symbol>> {
+        self.packages.borrow().get(package).cloned().unwrap_or_default()
+    }
+
+    /// The package-level definitions of other programs that a program
+    /// refers to, in order of first reference.
+    pub fn dependencies(&self, program: &Rc<Program>) -> Vec<Rc<Symbol>> {
+        self.dependencies.get(program).unwrap_or_default()
+    }
+
+    fn is_public(symbol: &Symbol) -> bool {
+        Attribute::find_public(symbol.attributes()).is_some()
+    }
+
+    fn is_package(&self, name: &str) -> bool {
+        self.packages.borrow().contains_key(name)
+    }
+
+    /// Indicates whether `name` is a package or the beginning of a package name.
+    fn is_package_prefix(&self, name: &str) -> bool {
+        self.packages.borrow().keys().any(|package| Self::is_within_package(package, name))
+    }
+
+    fn is_within_package(package: &str, name: &str) -> bool {
+        package == name || (package.starts_with(name) && package[name.len()..].starts_with('.'))
+    }
+
+    /// Indicates whether a package is assumed to be defined by an external
+    /// library, as none of the programs defines a package with the same first name.
+    fn is_external_package(&self, package: &str) -> bool {
+        !self.is_package_prefix(package.split('.').next().unwrap_or(package))
+    }
+
+    /// The packages whose name is `name` or begins with `name` followed by a dot.
+    fn packages_within(&self, name: &str) -> Vec<String> {
+        let mut packages: Vec<String> = self.packages.borrow().keys().filter(|package| Self::is_within_package(package, name)).cloned().collect();
+        packages.sort();
+        packages
+    }
+
+    /// The name of the innermost package enclosing a scope.
+    fn enclosing_package(&self, scope: &Rc<Scope>) -> Option<String> {
+        let mut scope = scope.clone();
+        loop {
+            if let Some(package) = self.package_scopes.borrow().get(&ByAddress(scope.clone())) {
+                return Some(package.clone());
+            }
+            scope = scope.parent()?;
+        }
+    }
+
+    /// Returns the definition named `name` of a package, including the
+    /// definitions concatenated to the package. Internal definitions are
+    /// accessible only from the package itself.
+    fn package_definition(&self, package: &str, name: &str, from_package: Option<&str>, visited: &mut Vec<String>) -> Option<Rc<Symbol>> {
+        if visited.iter().any(|visited| visited == package) {
+            return None;
+        }
+        visited.push(package.to_owned());
+        let internal = from_package == Some(package);
+        let symbol = self.packages.borrow().get(package).and_then(|symbols| {
+            symbols.iter().find(|symbol| symbol.name == name && (internal || Self::is_public(symbol))).cloned()
+        });
+        if symbol.is_some() {
+            return symbol;
+        }
+        let concatenations = self.concatenations.borrow().get(package).cloned().unwrap_or_default();
+        concatenations.iter().find_map(|imported| self.imported_definition(imported, name, from_package, visited))
+    }
+
+    /// Returns the definition that an imported name provides as `name`.
+    fn imported_definition(&self, imported: &ImportedName, name: &str, from_package: Option<&str>, visited: &mut Vec<String>) -> Option<Rc<Symbol>> {
+        match &imported.specifier {
+            ImportedSpecifier::Definition(definition_name) => {
+                if imported.alias.as_deref().unwrap_or(definition_name) != name {
+                    return None;
+                }
+                self.package_definition(&imported.package, definition_name, from_package, visited)
+            },
+            ImportedSpecifier::Wildcard => {
+                self.package_definition(&imported.package, name, from_package, visited)
+            },
+            ImportedSpecifier::Recursive => {
+                self.packages_within(&imported.package).iter().find_map(|package| self.package_definition(package, name, from_package, visited))
+            },
+        }
+    }
+
+    /// Looks up `name` from a scope.
+    fn lookup(&self, scope: &Rc<Scope>, name: &str) -> Lookup {
+        let from_package = self.enclosing_package(scope);
+        let mut dynamic = false;
+        let mut external = TOP_LEVEL_BUILTINS.contains(&name);
+        let mut next = Some(scope.clone());
+        while let Some(scope) = next {
+            // Constructors are not referred to by name; their class is.
+            if let Some(symbol) = scope.lookup_local(name).filter(|symbol| symbol.kind != SymbolKind::Constructor) {
+                return Lookup::Symbol(symbol);
+            }
+            match scope.kind() {
+                ScopeKind::Class => {
+                    if let Some(symbol) = self.inherited_member(&scope, name) {
+                        return Lookup::Symbol(symbol);
+                    }
+                },
+                ScopeKind::Package => {
+                    let package = self.package_scopes.borrow().get(&ByAddress(scope.clone())).cloned();
+                    if let Some(symbol) = package.and_then(|package| self.package_definition(&package, name, Some(&package), &mut vec![])) {
+                        return Lookup::Symbol(symbol);
+                    }
+                },
+                ScopeKind::With => {
+                    dynamic = true;
+                },
+                _ => {},
+            }
+            if let Some(lookup) = self.lookup_imported(&scope, name, from_package.as_deref()) {
+                return lookup;
+            }
+            external = external || self.imports_external(&scope, name);
+            next = scope.parent();
+        }
+
+        // The top-level package is open to every program.
+        if let Some(symbol) = self.package_definition("", name, from_package.as_deref(), &mut vec![]) {
+            return Lookup::Symbol(symbol);
+        }
+        if dynamic {
+            Lookup::Dynamic
+        } else if external {
+            Lookup::External
+        } else {
+            Lookup::Undefined
+        }
+    }
+
+    /// Indicates whether a scope imports an external package that may
+    /// provide `name`, either as a definition or as its first name.
+    fn imports_external(&self, scope: &Rc<Scope>, name: &str) -> bool {
+        let imports = self.imports.borrow();
+        let Some(imports) = imports.get(&ByAddress(scope.clone())) else {
+            return false;
+        };
+        imports.iter().any(|imported| {
+            if !self.is_external_package(&imported.package) {
+                return false;
+            }
+            Self::is_within_package(&imported.package, name) || match &imported.specifier {
+                ImportedSpecifier::Definition(definition_name) => imported.alias.as_deref().unwrap_or(definition_name) == name,
+                ImportedSpecifier::Wildcard | ImportedSpecifier::Recursive => imported.alias.is_none(),
+            }
+        })
+    }
+
+    /// Looks up `name` among the names imported within a scope. Single
+    /// definition imports take precedence over wildcard and recursive imports.
+    fn lookup_imported(&self, scope: &Rc<Scope>, name: &str, from_package: Option<&str>) -> Option<Lookup> {
+        let imports = self.imports.borrow().get(&ByAddress(scope.clone())).cloned()?;
+        for imported in &imports {
+            if let ImportedSpecifier::Definition(_) = imported.specifier {
+                if let Some(symbol) = self.imported_definition(imported, name, from_package, &mut vec![]) {
+                    return Some(Lookup::Symbol(symbol));
+                }
+            } else if imported.alias.as_deref() == Some(name) {
+                return Some(Lookup::Package(imported.package.clone()));
+            }
+        }
+        let mut candidates: Vec<Rc<Symbol>> = vec![];
+        for imported in imports.iter().filter(|imported| imported.alias.is_none()) {
+            if let ImportedSpecifier::Definition(_) = imported.specifier {
+                continue;
+            }
+            if let Some(symbol) = self.imported_definition(imported, name, from_package, &mut vec![]) {
+                if !candidates.iter().any(|candidate| Rc::ptr_eq(candidate, &symbol)) {
+                    candidates.push(symbol);
+                }
+            }
+        }
+        match candidates.len() {
+            0 => None,
+            1 => Some(Lookup::Symbol(candidates.remove(0))),
+            _ => Some(Lookup::Ambiguous),
+        }
+    }
+
+    /// Returns the non-private member named `name` that a class
+    /// inherits from its base classes.
+    fn inherited_member(&self, class_scope: &Rc<Scope>, name: &str) -> Option<Rc<Symbol>> {
+        let mut visited = vec![class_scope.clone()];
+        let mut scope = class_scope.clone();
+        loop {
+            let base = self.base_classes.borrow().get(&ByAddress(scope.clone())).cloned()?;
+            let base = self.resolve_silently(&base, &scope.parent()?)?;
+            if base.kind != SymbolKind::Class {
+                return None;
+            }
+            scope = self.scopes.get(base.definition.as_ref()?)?;
+            if visited.iter().any(|visited| Rc::ptr_eq(visited, &scope)) {
+                return None;
+            }
+            let member = scope.symbols().into_iter().find(|symbol| {
+                symbol.name == name
+                    && !matches!(symbol.kind, SymbolKind::TypeParameter | SymbolKind::Constructor)
+                    && Attribute::find_private(symbol.attributes()).is_none()
+            });
+            if member.is_some() {
+                return member;
+            }
+            visited.push(scope.clone());
+        }
+    }
+
+    /// Resolves a type expression without reporting errors.
+    fn resolve_silently(&self, exp: &Rc<Expression>, scope: &Rc<Scope>) -> Option<Rc<Symbol>> {
+        match exp.as_ref() {
+            Expression::WithTypeArguments(exp) => self.resolve_silently(&exp.base, scope),
+            Expression::QualifiedIdentifier(id) => match self.lookup(scope, &id.to_identifier_name()?.0) {
+                Lookup::Symbol(symbol) => Some(symbol),
+                _ => None,
+            },
+            Expression::Member(_) => {
+                let chain = member_chain(exp)?;
+                match self.resolve_package_chain(scope, &chain)? {
+                    ChainResolution::Definition(index, symbol) if index + 1 == chain.len() => Some(symbol),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolves a chain of identifiers that begins with a package name
+    /// or the alias of a package, such as `p.q.C`. Returns `None` if the
+    /// first identifier is neither.
+    fn resolve_package_chain(&self, scope: &Rc<Scope>, chain: &[(String, Rc<Expression>)]) -> Option<ChainResolution> {
+        let from_package = self.enclosing_package(scope);
+        let (mut package, start) = match self.lookup(scope, &chain[0].0) {
+            Lookup::Package(package) => (package, 1),
+            Lookup::Dynamic | Lookup::External | Lookup::Undefined if self.is_package_prefix(&chain[0].0) => (String::new(), 0),
+            _ => return None,
+        };
+        for (index, (name, _)) in chain.iter().enumerate().skip(start) {
+            if index != 0 {
+                if let Some(symbol) = self.package_definition(&package, name, from_package.as_deref(), &mut vec![]) {
+                    return Some(ChainResolution::Definition(index, symbol));
+                }
+            }
+            let subpackage = if index == 0 { name.clone() } else { format!("{package}.{name}") };
+            if !self.is_package_prefix(&subpackage) {
+                return Some(ChainResolution::Undefined(index, subpackage));
+            }
+            package = subpackage;
+        }
+        Some(ChainResolution::Package)
+    }
+
+    fn add_dependency(&self, program: &Rc<Program>, symbol: &Rc<Symbol>) {
+        let Some(owner) = self.owners.borrow().get(&ByAddress(symbol.clone())).cloned() else {
+            return;
+        };
+        if Rc::ptr_eq(&owner, program) {
+            return;
+        }
+        let mut dependencies = self.dependencies.get(program).unwrap_or_default();
+        if !dependencies.iter().any(|dependency| Rc::ptr_eq(dependency, symbol)) {
+            dependencies.push(symbol.clone());
+            self.dependencies.set(program, Some(dependencies));
+        }
+    }
+}
+
+/// A name imported by an import or package concatenation directive.
+#[derive(Clone)]
+struct ImportedName {
+    alias: Option<String>,
+    package: String,
+    specifier: ImportedSpecifier,
+}
+
+#[derive(Clone)]
+enum ImportedSpecifier {
+    Definition(String),
+    Wildcard,
+    Recursive,
+}
+
+impl ImportedName {
+    fn new(alias: &Option<(String, Location)>, package_name: &[(String, Location)], specifier: &ImportSpecifier) -> Self {
+        Self {
+            alias: alias.as_ref().map(|(alias, _)| alias.clone()),
+            package: package_name_string(package_name),
+            specifier: match specifier {
+                ImportSpecifier::Identifier((name, _)) => ImportedSpecifier::Definition(name.clone()),
+                ImportSpecifier::Wildcard(_) => ImportedSpecifier::Wildcard,
+                ImportSpecifier::Recursive(_) => ImportedSpecifier::Recursive,
+            },
+        }
+    }
+}
+
+enum Lookup {
+    Symbol(Rc<Symbol>),
+    /// The alias of a package imported through a wildcard or recursive import.
+    Package(String),
+    Ambiguous,
+    /// The name is undefined, but may be a dynamic property
+    /// of the object of a `with` statement.
+    Dynamic,
+    /// The name is undefined, but may be a top-level built-in definition
+    /// or a definition of an external package.
+    External,
+    Undefined,
+}
+
+enum ChainResolution {
+    /// The identifier at the given index names a definition.
+    Definition(usize, Rc<Symbol>),
+    /// The identifier at the given index names neither a definition nor
+    /// a package; the string is the qualified name up to the identifier.
+    Undefined(usize, String),
+    /// The entire chain names a package.
+    Package,
+}
+
+/// Position of an expression that affects how its names are resolved.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ExpressionPosition {
+    Type,
+    /// The names of a destructuring pattern are declared rather than resolved.
+    Pattern,
+    /// The names of an XML filter expression may refer to XML properties.
+    Dynamic,
+}
+
+fn package_name_string(package_name: &[(String, Location)]) -> String {
+    package_name.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>().join(".")
+}
+
+/// Flattens a member expression such as `p.q.C` into its identifiers,
+/// each paired with the expression that it ends.
+fn member_chain(exp: &Rc<Expression>) -> Option<Vec<(String, Rc<Expression>)>> {
+    match exp.as_ref() {
+        Expression::QualifiedIdentifier(id) => Some(vec![(id.to_identifier_name()?.0, exp.clone())]),
+        Expression::Member(member) => {
+            let mut chain = member_chain(&member.base)?;
+            chain.push((member.identifier.to_identifier_name()?.0, exp.clone()));
+            Some(chain)
+        },
+        _ => None,
+    }
+}
+
+/// Traverses a program while tracking the innermost scope, either to collect
+/// the package definitions, imports and base classes of the program, or to
+/// resolve its names.
+struct NameResolverWalker<'a> {
+    resolver: &'a NameResolver,
+    program: Rc<Program>,
+    collecting: bool,
+    scopes: Vec<Rc<Scope>>,
+    positions: HashMap<ByAddress<Rc<Expression>>, ExpressionPosition>,
+    position_stack: Vec<ExpressionPosition>,
+}
+
+impl<'a> NameResolverWalker<'a> {
+    fn new(resolver: &'a NameResolver, program: &Rc<Program>, collecting: bool) -> Self {
+        Self {
+            resolver,
+            program: program.clone(),
+            collecting,
+            scopes: vec![],
+            positions: HashMap::new(),
+            position_stack: vec![],
+        }
+    }
+
+    fn current_scope(&self) -> Rc<Scope> {
+        self.scopes.last().unwrap().clone()
+    }
+
+    fn enter_scope(&mut self, scope: Option<Rc<Scope>>) {
+        if let Some(scope) = scope {
+            self.scopes.push(scope);
+        }
+    }
+
+    fn exit_scope(&mut self, scope: Option<Rc<Scope>>) {
+        if scope.is_some() {
+            self.scopes.pop();
+        }
+    }
+
+    /// The scope of a directive, except for `with` statements, whose
+    /// scope is entered after their object is resolved.
+    fn directive_scope(&self, directive: &Rc<Directive>) -> Option<Rc<Scope>> {
+        if let Directive::WithStatement(_) = directive.as_ref() {
+            None
+        } else {
+            self.resolver.scopes.get(directive)
+        }
+    }
+
+    fn position(&self) -> Option<ExpressionPosition> {
+        self.position_stack.last().copied()
+    }
+
+    fn set_position(&mut self, exp: &Rc<Expression>, position: ExpressionPosition) {
+        self.positions.insert(ByAddress(exp.clone()), position);
+    }
+
+    fn report(&self, location: &Location, kind: DiagnosticKind, name: String) {
+        location.compilation_unit().add_diagnostic(Diagnostic::new_verify_error(location, kind, diagarg![name]));
+    }
+
+    fn collect_package_definition(&mut self, package: &Rc<PackageDefinition>, scope: &Rc<Scope>) {
+        let name = package_name_string(&package.name);
+        let symbols = scope.symbols();
+        let mut owners = self.resolver.owners.borrow_mut();
+        for symbol in &symbols {
+            owners.insert(ByAddress(symbol.clone()), self.program.clone());
+        }
+        self.resolver.packages.borrow_mut().entry(name.clone()).or_default().extend(symbols);
+        self.resolver.package_scopes.borrow_mut().insert(ByAddress(scope.clone()), name);
+    }
+
+    fn collect_directive(&mut self, directive: &Rc<Directive>) {
+        match directive.as_ref() {
+            Directive::ImportDirective(drtv) => {
+                let imported = ImportedName::new(&drtv.alias, &drtv.package_name, &drtv.import_specifier);
+                self.resolver.imports.borrow_mut().entry(ByAddress(self.current_scope())).or_default().push(imported);
+            },
+            Directive::PackageConcatDirective(drtv) => {
+                if let Some(package) = self.resolver.enclosing_package(&self.current_scope()) {
+                    let imported = ImportedName::new(&None, &drtv.package_name, &drtv.import_specifier);
+                    self.resolver.concatenations.borrow_mut().entry(package).or_default().push(imported);
+                }
+            },
+            Directive::ClassDefinition(defn) => {
+                if let (Some(scope), Some(base)) = (self.resolver.scopes.get(directive), &defn.extends_clause) {
+                    self.resolver.base_classes.borrow_mut().insert(ByAddress(scope), base.clone());
+                }
+            },
+            Directive::NormalConfigurationDirective(drtv) => {
+                self.resolver.configuration_namespaces.borrow_mut().insert(drtv.namespace.0.clone());
+            },
+            _ => {},
+        }
+    }
+
+    fn resolve_directive(&mut self, directive: &Rc<Directive>) {
+        match directive.as_ref() {
+            Directive::ImportDirective(drtv) => {
+                self.resolve_imported_name(directive, &drtv.package_name, &drtv.import_specifier);
+            },
+            Directive::PackageConcatDirective(drtv) => {
+                self.resolve_imported_name(directive, &drtv.package_name, &drtv.import_specifier);
+            },
+            Directive::ClassDefinition(defn) => {
+                for exp in defn.extends_clause.iter().chain(defn.implements_clause.iter().flatten()) {
+                    self.set_position(exp, ExpressionPosition::Type);
+                }
+            },
+            Directive::InterfaceDefinition(defn) => {
+                for exp in defn.extends_clause.iter().flatten() {
+                    self.set_position(exp, ExpressionPosition::Type);
+                }
+            },
+            Directive::EnumDefinition(defn) => {
+                if let Some(exp) = &defn.as_clause {
+                    self.set_position(exp, ExpressionPosition::Type);
+                }
+            },
+            Directive::TypeDefinition(defn) => {
+                self.set_position(&defn.right, ExpressionPosition::Type);
+            },
+            _ => {},
+        }
+    }
+
+    fn resolve_imported_name(&mut self, directive: &Rc<Directive>, package_name: &[(String, Location)], specifier: &ImportSpecifier) {
+        let Some(((_, first_location), (_, last_location))) = package_name.first().zip(package_name.last()) else {
+            return;
+        };
+        let package = package_name_string(package_name);
+        if self.resolver.is_external_package(&package) {
+            return;
+        }
+        match specifier {
+            ImportSpecifier::Identifier((name, location)) => {
+                let from_package = self.resolver.enclosing_package(&self.current_scope());
+                if let Some(symbol) = self.resolver.package_definition(&package, name, from_package.as_deref(), &mut vec![]) {
+                    self.resolver.resolutions.set(directive, Some(symbol.clone()));
+                    self.resolver.add_dependency(&self.program, &symbol);
+                } else {
+                    self.report(&first_location.combine_with(location.clone()), DiagnosticKind::DefinitionNotFound, format!("{package}.{name}"));
+                }
+            },
+            ImportSpecifier::Wildcard(_) => {
+                if !self.resolver.is_package(&package) {
+                    self.report(&first_location.combine_with(last_location.clone()), DiagnosticKind::PackageNotFound, package);
+                }
+            },
+            ImportSpecifier::Recursive(_) => {
+                if !self.resolver.is_package_prefix(&package) {
+                    self.report(&first_location.combine_with(last_location.clone()), DiagnosticKind::PackageNotFound, package);
+                }
+            },
+        }
+    }
+
+    fn resolve_qualified_identifier(&mut self, exp: &Rc<Expression>, id: &QualifiedIdentifier) -> VisitorControl {
+        if id.attribute {
+            return VisitorControl::Continue;
+        }
+        if let Some(Expression::QualifiedIdentifier(qualifier)) = id.qualifier.as_deref() {
+            if qualifier.to_identifier_name().is_some_and(|(name, _)| self.resolver.configuration_namespaces.borrow().contains(&name)) {
+                return VisitorControl::SkipChildren;
+            }
+        }
+        let QualifiedIdentifierIdentifier::Id((name, location)) = &id.id else {
+            return VisitorControl::Continue;
+        };
+        if name == "*" || self.position() == Some(ExpressionPosition::Pattern) {
+            return VisitorControl::Continue;
+        }
+        if let Some(symbol) = self.resolve_name(name, location) {
+            self.record(exp, symbol);
+        }
+        VisitorControl::Continue
+    }
+
+    /// Resolves an identifier from the current scope, reporting
+    /// it if it is undefined or ambiguous.
+    fn resolve_name(&self, name: &str, location: &Location) -> Option<Rc<Symbol>> {
+        let scope = self.current_scope();
+        match self.resolver.lookup(&scope, name) {
+            Lookup::Symbol(symbol) => {
+                return Some(symbol);
+            },
+            Lookup::Package(_) | Lookup::Dynamic | Lookup::External => {},
+            Lookup::Ambiguous => {
+                self.report(location, DiagnosticKind::AmbiguousReference, name.to_owned());
+            },
+            Lookup::Undefined => {
+                // The `arguments` object is implicitly defined within functions.
+                let arguments = name == "arguments" && self.scopes.iter().any(|scope| scope.kind() == ScopeKind::Function);
+                if arguments || self.position() == Some(ExpressionPosition::Dynamic) {
+                    return None;
+                }
+                let kind = if self.position() == Some(ExpressionPosition::Type) { DiagnosticKind::TypeNotFound } else { DiagnosticKind::UndefinedProperty };
+                self.report(location, kind, name.to_owned());
+            },
+        }
+        None
+    }
+
+    fn resolve_member_chain(&mut self, exp: &Rc<Expression>) -> VisitorControl {
+        if self.position() == Some(ExpressionPosition::Pattern) {
+            return VisitorControl::Continue;
+        }
+        let Some(chain) = member_chain(exp) else {
+            return VisitorControl::Continue;
+        };
+        match self.resolver.resolve_package_chain(&self.current_scope(), &chain) {
+            Some(ChainResolution::Definition(index, symbol)) => {
+                self.record(&chain[index].1, symbol);
+            },
+            Some(ChainResolution::Undefined(index, name)) => {
+                self.report(&chain[index].1.location(), DiagnosticKind::DefinitionNotFound, name);
+            },
+            Some(ChainResolution::Package) => {},
+            None => {
+                return VisitorControl::Continue;
+            },
+        }
+        VisitorControl::SkipChildren
+    }
+
+    fn record(&self, exp: &Rc<Expression>, symbol: Rc<Symbol>) {
+        self.resolver.add_dependency(&self.program, &symbol);
+        self.resolver.resolutions.set(exp, Some(symbol));
+    }
+}
+
+impl Visitor for NameResolverWalker<'_> {
+    fn visit_program(&mut self, node: &Rc<Program>) -> VisitorControl {
+        self.enter_scope(self.resolver.scopes.get(node));
+        VisitorControl::Continue
+    }
+
+    fn leave_program(&mut self, node: &Rc<Program>) {
+        self.exit_scope(self.resolver.scopes.get(node));
+    }
+
+    fn visit_package_definition(&mut self, node: &Rc<PackageDefinition>) -> VisitorControl {
+        let scope = self.resolver.scopes.get(node);
+        if let (true, Some(scope)) = (self.collecting, &scope) {
+            self.collect_package_definition(node, scope);
+        }
+        self.enter_scope(scope);
+        VisitorControl::Continue
+    }
+
+    fn leave_package_definition(&mut self, node: &Rc<PackageDefinition>) {
+        self.exit_scope(self.resolver.scopes.get(node));
+    }
+
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        self.enter_scope(self.directive_scope(node));
+        if self.collecting {
+            self.collect_directive(node);
+        } else {
+            self.resolve_directive(node);
+        }
+        match node.as_ref() {
+            // The object of a `with` statement is resolved outside of its scope.
+            Directive::WithStatement(stmt) => {
+                let _ = walk_expression(self, &stmt.object);
+                let scope = self.resolver.scopes.get(node);
+                self.enter_scope(scope.clone());
+                let _ = walk_directive(self, &stmt.body);
+                self.exit_scope(scope);
+                VisitorControl::SkipChildren
+            },
+            // The block of a `try` statement is only reachable as a `Block`.
+            Directive::TryStatement(stmt) => {
+                let scope = self.resolver.scopes.get(&stmt.block);
+                self.enter_scope(scope.clone());
+                let _ = walk_block(self, &stmt.block);
+                self.exit_scope(scope);
+                for catch_clause in &stmt.catch_clauses {
+                    let _ = walk_catch_clause(self, catch_clause);
+                }
+                if let Some(finally_clause) = &stmt.finally_clause {
+                    let _ = walk_finally_clause(self, finally_clause);
+                }
+                VisitorControl::SkipChildren
+            },
+            _ => VisitorControl::Continue,
+        }
+    }
+
+    fn leave_directive(&mut self, node: &Rc<Directive>) {
+        if let (false, Directive::UseNamespaceDirective(drtv)) = (self.collecting, node.as_ref()) {
+            if let Some(symbol) = self.resolver.resolutions.get(&drtv.expression) {
+                self.resolver.resolutions.set(node, Some(symbol));
+            }
+        }
+        self.exit_scope(self.directive_scope(node));
+    }
+
+    fn visit_function_common(&mut self, node: &Rc<FunctionCommon>) -> VisitorControl {
+        self.enter_scope(self.resolver.scopes.get(node));
+        if let (false, Some(result_type)) = (self.collecting, &node.signature.result_type) {
+            self.set_position(result_type, ExpressionPosition::Type);
+        }
+        VisitorControl::Continue
+    }
+
+    fn leave_function_common(&mut self, node: &Rc<FunctionCommon>) {
+        self.exit_scope(self.resolver.scopes.get(node));
+    }
+
+    fn visit_catch_clause(&mut self, node: &CatchClause) -> VisitorControl {
+        self.enter_scope(self.resolver.scopes.get(&node.block));
+        VisitorControl::Continue
+    }
+
+    fn leave_catch_clause(&mut self, node: &CatchClause) {
+        self.exit_scope(self.resolver.scopes.get(&node.block));
+    }
+
+    fn visit_finally_clause(&mut self, node: &FinallyClause) -> VisitorControl {
+        self.enter_scope(self.resolver.scopes.get(&node.block));
+        VisitorControl::Continue
+    }
+
+    fn leave_finally_clause(&mut self, node: &FinallyClause) {
+        self.exit_scope(self.resolver.scopes.get(&node.block));
+    }
+
+    fn visit_type_case(&mut self, node: &TypeCase) -> VisitorControl {
+        self.enter_scope(self.resolver.scopes.get(&node.block));
+        VisitorControl::Continue
+    }
+
+    fn leave_type_case(&mut self, node: &TypeCase) {
+        self.exit_scope(self.resolver.scopes.get(&node.block));
+    }
+
+    fn visit_typed_destructuring(&mut self, node: &TypedDestructuring) -> VisitorControl {
+        if !self.collecting {
+            self.set_position(&node.destructuring, ExpressionPosition::Pattern);
+            if let Some(type_annotation) = &node.type_annotation {
+                self.set_position(type_annotation, ExpressionPosition::Type);
+            }
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_initializer_field(&mut self, node: &Rc<InitializerField>) -> VisitorControl {
+        if self.collecting || self.position() == Some(ExpressionPosition::Pattern) {
+            return VisitorControl::Continue;
+        }
+        // A field with a value, such as `{ a: 1 }`, names a property rather than a variable.
+        if let InitializerField::Field { value: Some(_), .. } = node.as_ref() {
+            return VisitorControl::Continue;
+        }
+        if let Some((name, location)) = node.shorthand().and_then(|id| id.to_identifier_name()) {
+            if let Some(symbol) = self.resolve_name(&name, &location) {
+                self.resolver.add_dependency(&self.program, &symbol);
+                self.resolver.resolutions.set(node, Some(symbol));
+            }
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        if self.collecting {
+            return VisitorControl::Continue;
+        }
+        if let Some(position) = self.positions.get(&ByAddress(node.clone())) {
+            self.position_stack.push(*position);
+        }
+        match node.as_ref() {
+            Expression::QualifiedIdentifier(id) => {
+                return self.resolve_qualified_identifier(node, id);
+            },
+            Expression::Member(_) => {
+                return self.resolve_member_chain(node);
+            },
+            Expression::VectorLiteral(exp) => {
+                self.set_position(&exp.element_type, ExpressionPosition::Type);
+            },
+            Expression::WithTypeArguments(exp) => {
+                for argument in &exp.arguments {
+                    self.set_position(argument, ExpressionPosition::Type);
+                }
+            },
+            Expression::Filter(exp) => {
+                self.set_position(&exp.test, ExpressionPosition::Dynamic);
+            },
+            _ => {},
+        }
+        VisitorControl::Continue
+    }
+
+    fn leave_expression(&mut self, node: &Rc<Expression>) {
+        if !self.collecting && self.positions.remove(&ByAddress(node.clone())).is_some() {
+            self.position_stack.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn parse(text: &str) -> (Rc<CompilationUnit>, Rc<Program>) {
+        let cu = CompilationUnit::new(None, text.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(cu.nested_diagnostics().is_empty(), "{text}");
+        (cu, program)
+    }
+
+    fn diagnostics(cu: &Rc<CompilationUnit>) -> Vec<String> {
+        cu.diagnostics().iter().map(|diagnostic| {
+            assert!(diagnostic.is_verify_error());
+            format!("{} {}", diagnostic.location().text(), diagnostic.format_english())
+        }).collect()
+    }
+
+    /// Returns the definition of the first resolved expression whose text is `text`.
+    fn resolution_of(resolver: &NameResolver, program: &Rc<Program>, text: &str) -> Option<Rc<Symbol>> {
+        struct Finder<'a>(&'a NameResolver, &'a str, Option<Rc<Symbol>>);
+        impl Visitor for Finder<'_> {
+            fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+                if node.location().text() == self.1 && self.0.resolutions().get(node).is_some() {
+                    self.2 = self.0.resolutions().get(node);
+                    return VisitorControl::Stop;
+                }
+                VisitorControl::Continue
+            }
+        }
+        let mut finder = Finder(resolver, text, None);
+        let _ = walk_program(&mut finder, program);
+        finder.2
+    }
+
+    #[test]
+    fn test_resolve_programs() {
+        let (builtins_cu, builtins) = parse(r#"
+            package {
+                public class Object {}
+                public function trace(...rest): void {}
+            }
+        "#);
+        let (_, program_p) = parse(r#"
+            package p {
+                public class A { public var x; private var secret; }
+                public class B extends A {
+                    function f() { x; secret }
+                }
+                class Internal {}
+                public namespace ns = "ns";
+            }
+        "#);
+        let (_, program_q) = parse(r#"
+            package p.q {
+                public var Q;
+            }
+            package r {
+                public += p.*;
+            }
+        "#);
+        let (cu, program) = parse(r#"
+            package s {
+                import p.B;
+                import Alias = p.A;
+                import pq = p.q.*;
+                import r.*;
+                import p.**;
+                import p.Missing;
+                import p.missing.*;
+                use namespace ns;
+                public class C extends B {
+                    function g(a: Alias, b: Object, [c, d]: Array = null) {
+                        x; a; b; c; pq.Q; p.q.Q; p.Nope; Internal;
+                        trace(arguments, { a, undefined1 });
+                        with (a) { dynamic1 }
+                        CONFIG::debug;
+                    }
+                }
+            }
+        "#);
+        let mut resolver = NameResolver::new();
+        resolver.resolve_programs(&[builtins.clone(), program_p.clone(), program_q.clone(), program.clone()]);
+
+        assert!(builtins_cu.diagnostics().is_empty());
+        assert_eq!(diagnostics(&cu), [
+            "p.Missing 8:24: Verify error #1093: Definition was not found: 'p.Missing'.",
+            "p.missing 9:24: Verify error #1094: Package was not found: 'p.missing'.",
+            "p.Nope 13:50: Verify error #1093: Definition was not found: 'p.Nope'.",
+            "Internal 13:58: Verify error #1091: Access of undefined property 'Internal'.",
+            "undefined1 14:47: Verify error #1091: Access of undefined property 'undefined1'.",
+        ]);
+        // Private members are not inherited.
+        let program_p_cu = program_p.location.compilation_unit();
+        assert_eq!(diagnostics(&program_p_cu), ["secret 5:39: Verify error #1091: Access of undefined property 'secret'."]);
+
+        let class_b = resolution_of(&resolver, &program, "B").unwrap();
+        assert_eq!((class_b.name.as_str(), class_b.kind), ("B", SymbolKind::Class));
+        assert_eq!(resolution_of(&resolver, &program, "Alias").unwrap().name, "A");
+        assert_eq!(resolution_of(&resolver, &program, "Object").unwrap().name, "Object");
+        assert_eq!(resolution_of(&resolver, &program, "x").unwrap().kind, SymbolKind::Variable);
+        assert_eq!(resolution_of(&resolver, &program, "ns").unwrap().kind, SymbolKind::Namespace);
+        assert_eq!(resolution_of(&resolver, &program, "c").unwrap().kind, SymbolKind::Parameter);
+        assert_eq!(resolution_of(&resolver, &program, "pq.Q").unwrap().name, "Q");
+        assert_eq!(resolution_of(&resolver, &program, "p.q.Q").unwrap().name, "Q");
+
+        // Import and use namespace directives.
+        let Directive::ImportDirective(_) = program.packages[0].block.directives[0].as_ref() else { panic!() };
+        let imported = resolver.resolutions().get(&program.packages[0].block.directives[0]).unwrap();
+        assert!(Rc::ptr_eq(&imported, &class_b));
+        let use_namespace = &program.packages[0].block.directives[7];
+        assert_eq!(resolver.resolutions().get(use_namespace).unwrap().name, "ns");
+
+        let dependencies: Vec<String> = resolver.dependencies(&program).iter().map(|symbol| symbol.name.clone()).collect();
+        assert_eq!(dependencies, ["B", "A", "ns", "Object", "Q", "trace"]);
+        assert_eq!(resolver.package_definitions("p").len(), 4);
+    }
+
+    #[test]
+    fn test_external_names() {
+        let (cu, program) = parse(r#"
+            import flash.display.Sprite;
+            import flash.events.*;
+            var s: Sprite = new Sprite();
+            var e: Event;
+            flash.utils.getTimer();
+            trace(String(Math.PI), int.MAX_VALUE, NaN, undefined);
+            var v: Vector.<XML>;
+        "#);
+        // Imports of external packages apply only to the programs containing them.
+        let (cu2, program2) = parse("Sprite; trace(Math)");
+        NameResolver::new().resolve_programs(&[program, program2]);
+        assert!(cu.diagnostics().is_empty());
+        assert_eq!(diagnostics(&cu2), ["Sprite 1:1: Verify error #1091: Access of undefined property 'Sprite'."]);
+    }
+
+    #[test]
+    fn test_ambiguous_reference() {
+        let (_, program1) = parse("package p1 { public class C {} } package p2 { public class C {} }");
+        let (cu, program2) = parse("import p1.*; import p2.*; C; import p1.C; function f() { C }");
+        NameResolver::new().resolve_programs(&[program1, program2]);
+        // The single definition import applies to the entire program scope.
+        assert!(cu.diagnostics().is_empty());
+
+        let (_, program1) = parse("package p1 { public class C {} } package p2 { public class C {} }");
+        let (cu, program2) = parse("import p1.*; import p2.*; C");
+        NameResolver::new().resolve_programs(&[program1, program2]);
+        assert_eq!(diagnostics(&cu), ["C 1:27: Verify error #1095: Ambiguous reference to 'C'."]);
+    }
+
+    #[test]
+    fn test_object_initializer_fields() {
+        let (cu, program) = parse("var a = 0; var o1 = { a: 1, b: 2 }; var o2 = { a, c }");
+        let mut resolver = NameResolver::new();
+        resolver.resolve_programs(&[program]);
+        assert_eq!(diagnostics(&cu), ["c 1:51: Verify error #1091: Access of undefined property 'c'."]);
+    }
+
+    #[test]
+    fn test_enum_members() {
+        let (cu, program) = parse("enum E { A, B = 2, C } enum F { const D = 1 }");
+        let mut resolver = NameResolver::new();
+        resolver.resolve_programs(&[program]);
+        assert!(cu.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_configuration_namespaces() {
+        let (cu, program) = parse("var x = COMPILE::debug; y");
+        let mut options = CompilerOptions::default();
+        options.defines.insert("COMPILE::debug".into(), "true".into());
+        cu.set_compiler_options(Some(Rc::new(options)));
+        NameResolver::new().resolve_programs(&[program]);
+        assert_eq!(diagnostics(&cu), ["y 1:25: Verify error #1091: Access of undefined property 'y'."]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::ns::*;
+
+/// A lexical scope and the symbols declared within it.
+///
+/// A scope holds its child scopes, whereas it refers to its parent
+/// weakly; the outermost scope of a program therefore owns the entire
+/// scope tree.
+pub struct Scope {
+    kind: ScopeKind,
+    location: Location,
+    parent: Option<Weak<Scope>>,
+    children: RefCell<Vec<Rc<Scope>>>,
+    symbols: RefCell<Vec<Rc<Symbol>>>,
+}
+
+impl Scope {
+    /// Constructs a scope, adding it to the children of `parent`.
+    pub fn new(kind: ScopeKind, location: &Location, parent: Option<&Rc<Scope>>) -> Rc<Self> {
+        let scope = Rc::new(Self {
+            kind,
+            location: location.clone(),
+            parent: parent.map(Rc::downgrade),
+            children: RefCell::new(vec![]),
+            symbols: RefCell::new(vec![]),
+        });
+        if let Some(parent) = parent {
+            parent.children.borrow_mut().push(scope.clone());
+        }
+        scope
+    }
+
+    pub fn kind(&self) -> ScopeKind {
+        self.kind
+    }
+
+    /// Location of the node that introduces the scope.
+    pub fn location(&self) -> Location {
+        self.location.clone()
+    }
+
+    pub fn parent(&self) -> Option<Rc<Scope>> {
+        self.parent.as_ref().and_then(|parent| parent.upgrade())
+    }
+
+    pub fn children(&self) -> Vec<Rc<Scope>> {
+        self.children.borrow().clone()
+    }
+
+    /// The symbols declared within the scope, in declaration order.
+    pub fn symbols(&self) -> Vec<Rc<Symbol>> {
+        self.symbols.borrow().clone()
+    }
+
+    pub fn declare(&self, symbol: Rc<Symbol>) {
+        self.symbols.borrow_mut().push(symbol);
+    }
+
+    /// Returns the first symbol named `name` declared within the scope.
+    pub fn lookup_local(&self, name: &str) -> Option<Rc<Symbol>> {
+        self.symbols.borrow().iter().find(|symbol| symbol.name == name).cloned()
+    }
+
+    /// Returns the first symbol named `name` declared within the scope
+    /// or, failing that, within its enclosing scopes.
+    pub fn lookup(&self, name: &str) -> Option<Rc<Symbol>> {
+        self.lookup_local(name).or_else(|| self.parent().and_then(|parent| parent.lookup(name)))
+    }
+
+    /// Returns the innermost scope, from this scope, whose location contains `offset`.
+    pub fn scope_at(self: &Rc<Self>, offset: usize) -> Rc<Scope> {
+        let mut scope = self.clone();
+        while let Some(child) = scope.children().into_iter().find(|child| {
+            child.location.first_offset() <= offset && offset < child.location.last_offset()
+        }) {
+            scope = child;
+        }
+        scope
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScopeKind {
+    Program,
+    Package,
+    Class,
+    Interface,
+    Enum,
+    Function,
+    Block,
+    Catch,
+    With,
+}
+
+impl ScopeKind {
+    /// Indicates whether variable and function definitions appearing within
+    /// nested blocks are declared in a scope of this kind, as definitions are
+    /// hoisted out of blocks in ActionScript 3.
+    pub fn is_definition_scope(&self) -> bool {
+        !matches!(self, Self::Block | Self::Catch | Self::With)
+    }
+}
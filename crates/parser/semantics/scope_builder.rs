@@ -0,0 +1,655 @@
+use crate::ns::*;
+
+/// Builds the lexical scopes of a program and records the declarations
+/// within them.
+///
+/// Each node that introduces a scope is mapped to its scope in a
+/// `TreeSemantics`:
+///
+/// | Node | Scope |
+/// |------|-------|
+/// | `Program` | `ScopeKind::Program` |
+/// | `PackageDefinition` and its block | `ScopeKind::Package` |
+/// | Class, interface and enum definitions and their blocks | `ScopeKind::Class`, `ScopeKind::Interface` and `ScopeKind::Enum` |
+/// | `FunctionCommon` and its block body | `ScopeKind::Function` |
+/// | Block statements, the blocks of `try` statements and `switch type` case blocks | `ScopeKind::Block` |
+/// | Catch clause blocks | `ScopeKind::Catch` |
+/// | `with` statements | `ScopeKind::With` |
+///
+/// As in ActionScript 3, variable, function and other definitions within
+/// blocks are declared in the enclosing package, class, function or program
+/// scope. Parameters and the name of a function expression are declared in the
+/// function scope, type parameters in the class or interface scope, and the
+/// parameters of catch clauses and `switch type` cases in their own scopes.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, "function f(a) { if (a) { var b; } }".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let semantics = TreeSemantics::new();
+/// let scope = ScopeBuilder::new(&semantics).build_program(&program);
+/// assert_eq!(scope.lookup_local("f").unwrap().kind, SymbolKind::Function);
+///
+/// let Directive::FunctionDefinition(f) = program.directives[0].as_ref() else { unreachable!() };
+/// let function_scope = semantics.get(&f.common).unwrap();
+/// let names: Vec<String> = function_scope.symbols().iter().map(|symbol| symbol.name.clone()).collect();
+/// assert_eq!(names, ["a", "b"]);
+/// ```
+pub struct ScopeBuilder<'a> {
+    semantics: &'a TreeSemantics<Rc<Scope>>,
+    scope: Option<Rc<Scope>>,
+}
+
+impl<'a> ScopeBuilder<'a> {
+    pub fn new(semantics: &'a TreeSemantics<Rc<Scope>>) -> Self {
+        Self {
+            semantics,
+            scope: None,
+        }
+    }
+
+    /// Builds the scopes of a program, returning its outermost scope.
+    pub fn build_program(&mut self, program: &Rc<Program>) -> Rc<Scope> {
+        let scope = self.enter_scope(ScopeKind::Program, &program.location);
+        self.semantics.set(program, Some(scope.clone()));
+        for package in &program.packages {
+            self.build_package_definition(package);
+        }
+        self.build_directives(&program.directives);
+        self.exit_scope();
+        scope
+    }
+
+    fn build_package_definition(&mut self, package: &Rc<PackageDefinition>) {
+        let scope = self.enter_scope(ScopeKind::Package, &package.location);
+        self.semantics.set(package, Some(scope.clone()));
+        self.semantics.set(&package.block, Some(scope));
+        self.build_directives(&package.block.directives);
+        self.exit_scope();
+    }
+
+    fn build_directives(&mut self, directives: &[Rc<Directive>]) {
+        for directive in directives {
+            self.build_directive(directive);
+        }
+    }
+
+    fn build_directive(&mut self, directive: &Rc<Directive>) {
+        match directive.as_ref() {
+            Directive::EmptyStatement(_) |
+            Directive::BreakStatement(_) |
+            Directive::ContinueStatement(_) |
+            Directive::ImportDirective(_) |
+            Directive::PackageConcatDirective(_) |
+            Directive::Invalidated(_) => {},
+            Directive::ExpressionStatement(stmt) => {
+                self.build_expression(&stmt.expression);
+            },
+            Directive::SuperStatement(stmt) => {
+                self.build_expressions(&stmt.arguments);
+            },
+            Directive::Block(block) => {
+                let scope = self.enter_scope(ScopeKind::Block, &block.location);
+                self.semantics.set(directive, Some(scope));
+                self.build_directives(&block.directives);
+                self.exit_scope();
+            },
+            Directive::LabeledStatement(stmt) => {
+                self.build_directive(&stmt.substatement);
+            },
+            Directive::IfStatement(stmt) => {
+                self.build_expression(&stmt.test);
+                self.build_directive(&stmt.consequent);
+                if let Some(alternative) = &stmt.alternative {
+                    self.build_directive(alternative);
+                }
+            },
+            Directive::SwitchStatement(stmt) => {
+                self.build_expression(&stmt.discriminant);
+                for case in &stmt.cases {
+                    for label in &case.labels {
+                        if let CaseLabel::Case((exp, _)) = label {
+                            self.build_expression(exp);
+                        }
+                    }
+                    self.build_directives(&case.directives);
+                }
+            },
+            Directive::SwitchTypeStatement(stmt) => {
+                self.build_expression(&stmt.discriminant);
+                for case in &stmt.cases {
+                    let scope = self.enter_scope(ScopeKind::Block, &case.location);
+                    self.semantics.set(&case.block, Some(scope));
+                    if let Some(parameter) = &case.parameter {
+                        self.declare_typed_destructuring(parameter, SymbolKind::Variable);
+                    }
+                    self.build_directives(&case.block.directives);
+                    self.exit_scope();
+                }
+            },
+            Directive::DoStatement(stmt) => {
+                self.build_directive(&stmt.body);
+                self.build_expression(&stmt.test);
+            },
+            Directive::WhileStatement(stmt) => {
+                self.build_expression(&stmt.test);
+                self.build_directive(&stmt.body);
+            },
+            Directive::ForStatement(stmt) => {
+                match &stmt.init {
+                    Some(ForInitializer::Expression(exp)) => {
+                        self.build_expression(exp);
+                    },
+                    Some(ForInitializer::VariableDefinition(defn)) => {
+                        self.build_simple_variable_definition(defn);
+                    },
+                    None => {},
+                }
+                if let Some(test) = &stmt.test {
+                    self.build_expression(test);
+                }
+                if let Some(update) = &stmt.update {
+                    self.build_expression(update);
+                }
+                self.build_directive(&stmt.body);
+            },
+            Directive::ForInStatement(stmt) => {
+                match &stmt.left {
+                    ForInBinding::Expression(exp) => {
+                        self.build_expression(exp);
+                    },
+                    ForInBinding::VariableDefinition(defn) => {
+                        self.build_simple_variable_definition(defn);
+                    },
+                }
+                self.build_expression(&stmt.right);
+                self.build_directive(&stmt.body);
+            },
+            Directive::WithStatement(stmt) => {
+                self.build_expression(&stmt.object);
+                let scope = self.enter_scope(ScopeKind::With, &stmt.location);
+                self.semantics.set(directive, Some(scope));
+                self.build_directive(&stmt.body);
+                self.exit_scope();
+            },
+            Directive::ReturnStatement(stmt) => {
+                if let Some(exp) = &stmt.expression {
+                    self.build_expression(exp);
+                }
+            },
+            Directive::ThrowStatement(stmt) => {
+                self.build_expression(&stmt.expression);
+            },
+            Directive::DefaultXmlNamespaceStatement(stmt) => {
+                self.build_expression(&stmt.right);
+            },
+            Directive::TryStatement(stmt) => {
+                self.build_block(&stmt.block, ScopeKind::Block);
+                for catch_clause in &stmt.catch_clauses {
+                    let scope = self.enter_scope(ScopeKind::Catch, &catch_clause.location);
+                    self.semantics.set(&catch_clause.block, Some(scope));
+                    self.declare_typed_destructuring(&catch_clause.parameter, SymbolKind::Variable);
+                    self.build_directives(&catch_clause.block.directives);
+                    self.exit_scope();
+                }
+                if let Some(finally_clause) = &stmt.finally_clause {
+                    self.build_block(&finally_clause.block, ScopeKind::Block);
+                }
+            },
+            Directive::ConfigurationDirective(drtv) => {
+                self.build_directive(&drtv.directive);
+            },
+            Directive::UseNamespaceDirective(drtv) => {
+                self.build_expression(&drtv.expression);
+            },
+            Directive::IncludeDirective(drtv) => {
+                for package in &drtv.nested_packages {
+                    self.build_package_definition(package);
+                }
+                self.build_directives(&drtv.nested_directives);
+            },
+            Directive::NormalConfigurationDirective(drtv) => {
+                self.build_directive(&drtv.directive);
+            },
+            Directive::DirectiveInjection(drtv) => {
+                let directives = drtv.directives.borrow().clone();
+                self.build_directives(&directives);
+            },
+            Directive::VariableDefinition(defn) => {
+                let kind = Self::variable_symbol_kind(defn.kind.0);
+                for binding in &defn.bindings {
+                    if let Some(init) = &binding.initializer {
+                        self.build_expression(init);
+                    }
+                    self.declare_destructuring(&binding.destructuring, kind, Some(directive));
+                }
+            },
+            Directive::FunctionDefinition(defn) => {
+                let (name, location) = defn.name_identifier();
+                let kind = match defn.name {
+                    FunctionName::Identifier(_) => SymbolKind::Function,
+                    FunctionName::Getter(_) => SymbolKind::Getter,
+                    FunctionName::Setter(_) => SymbolKind::Setter,
+                    FunctionName::Constructor(_) => SymbolKind::Constructor,
+                };
+                self.declare(name, kind, location, None, Some(directive));
+                self.build_function_common(&defn.common);
+            },
+            Directive::ClassDefinition(defn) => {
+                self.declare(defn.name.0.clone(), SymbolKind::Class, defn.name.1.clone(), None, Some(directive));
+                let scope = self.enter_scope(ScopeKind::Class, &defn.location);
+                self.semantics.set(directive, Some(scope.clone()));
+                self.semantics.set(&defn.block, Some(scope));
+                self.declare_type_parameters(&defn.type_parameters);
+                if let Some(exp) = &defn.extends_clause {
+                    self.build_expression(exp);
+                }
+                if let Some(list) = &defn.implements_clause {
+                    self.build_expressions(list);
+                }
+                self.build_directives(&defn.block.directives);
+                self.exit_scope();
+            },
+            Directive::EnumDefinition(defn) => {
+                self.declare(defn.name.0.clone(), SymbolKind::Enum, defn.name.1.clone(), None, Some(directive));
+                let scope = self.enter_scope(ScopeKind::Enum, &defn.location);
+                self.semantics.set(directive, Some(scope.clone()));
+                self.semantics.set(&defn.block, Some(scope));
+                if let Some(exp) = &defn.as_clause {
+                    self.build_expression(exp);
+                }
+                // Members without `const`, such as `A` and `B = 2` in `enum E { A, B = 2 }`,
+                // are expression statements.
+                for member in &defn.block.directives {
+                    if let Directive::ExpressionStatement(statement) = member.as_ref() {
+                        let mut names = vec![];
+                        Self::collect_enum_member_names(&statement.expression, &mut names);
+                        for (name, location) in names {
+                            self.declare(name, SymbolKind::Constant, location, None, Some(member));
+                        }
+                    }
+                }
+                self.build_directives(&defn.block.directives);
+                self.exit_scope();
+            },
+            Directive::InterfaceDefinition(defn) => {
+                self.declare(defn.name.0.clone(), SymbolKind::Interface, defn.name.1.clone(), None, Some(directive));
+                let scope = self.enter_scope(ScopeKind::Interface, &defn.location);
+                self.semantics.set(directive, Some(scope.clone()));
+                self.semantics.set(&defn.block, Some(scope));
+                self.declare_type_parameters(&defn.type_parameters);
+                if let Some(list) = &defn.extends_clause {
+                    self.build_expressions(list);
+                }
+                self.build_directives(&defn.block.directives);
+                self.exit_scope();
+            },
+            Directive::TypeDefinition(defn) => {
+                self.declare(defn.left.0.clone(), SymbolKind::Type, defn.left.1.clone(), None, Some(directive));
+                self.build_expression(&defn.right);
+            },
+            Directive::NamespaceDefinition(defn) => {
+                self.declare(defn.left.0.clone(), SymbolKind::Namespace, defn.left.1.clone(), None, Some(directive));
+                if let Some(exp) = &defn.right {
+                    self.build_expression(exp);
+                }
+            },
+        }
+    }
+
+    fn build_block(&mut self, block: &Rc<Block>, kind: ScopeKind) {
+        let scope = self.enter_scope(kind, &block.location);
+        self.semantics.set(block, Some(scope));
+        self.build_directives(&block.directives);
+        self.exit_scope();
+    }
+
+    fn build_simple_variable_definition(&mut self, defn: &Rc<SimpleVariableDefinition>) {
+        let kind = Self::variable_symbol_kind(defn.kind.0);
+        for binding in &defn.bindings {
+            if let Some(init) = &binding.initializer {
+                self.build_expression(init);
+            }
+            self.declare_destructuring(&binding.destructuring, kind, None);
+        }
+    }
+
+    fn build_function_common(&mut self, common: &Rc<FunctionCommon>) -> Rc<Scope> {
+        let scope = self.enter_scope(ScopeKind::Function, &common.location);
+        self.semantics.set(common, Some(scope.clone()));
+        for parameter in &common.signature.parameters {
+            if let Some(default_value) = &parameter.default_value {
+                self.build_expression(default_value);
+            }
+            self.declare_typed_destructuring(&parameter.destructuring, SymbolKind::Parameter);
+        }
+        if let Some(result_type) = &common.signature.result_type {
+            self.build_expression(result_type);
+        }
+        match &common.body {
+            Some(FunctionBody::Block(block)) => {
+                self.semantics.set(block, Some(scope.clone()));
+                self.build_directives(&block.directives);
+            },
+            Some(FunctionBody::Expression(exp)) => {
+                self.build_expression(exp);
+            },
+            None => {},
+        }
+        self.exit_scope();
+        scope
+    }
+
+    fn build_expressions(&mut self, list: &[Rc<Expression>]) {
+        for exp in list {
+            self.build_expression(exp);
+        }
+    }
+
+    /// Builds the scopes of the function expressions within an expression.
+    fn build_expression(&mut self, exp: &Rc<Expression>) {
+        let _ = walk_expression(self, exp);
+    }
+
+    fn declare_type_parameters(&mut self, list: &Option<Vec<Rc<TypeParameter>>>) {
+        for type_parameter in list.iter().flatten() {
+            self.declare(type_parameter.name.0.clone(), SymbolKind::TypeParameter, type_parameter.name.1.clone(), None, None);
+        }
+    }
+
+    /// Declares the names of a parameter-like destructuring in the current scope.
+    fn declare_typed_destructuring(&mut self, destructuring: &TypedDestructuring, kind: SymbolKind) {
+        self.build_pattern_expressions(&destructuring.destructuring);
+        let mut names = vec![];
+        Self::collect_pattern_names(&destructuring.destructuring, &mut names);
+        let type_annotation = Self::identifier_type_annotation(destructuring);
+        for (name, location) in names {
+            self.declare(name, kind, location, type_annotation.clone(), None);
+        }
+    }
+
+    /// Declares the names of a variable binding in the enclosing definition scope.
+    fn declare_destructuring(&mut self, destructuring: &TypedDestructuring, kind: SymbolKind, definition: Option<&Rc<Directive>>) {
+        self.build_pattern_expressions(&destructuring.destructuring);
+        let mut names = vec![];
+        Self::collect_pattern_names(&destructuring.destructuring, &mut names);
+        let type_annotation = Self::identifier_type_annotation(destructuring);
+        let scope = self.definition_scope();
+        for (name, location) in names {
+            scope.declare(Rc::new(Symbol {
+                name,
+                kind,
+                location,
+                type_annotation: type_annotation.clone(),
+                definition: definition.cloned(),
+            }));
+        }
+    }
+
+    /// The type annotation applies to a single name only if the
+    /// destructuring is an identifier.
+    fn identifier_type_annotation(destructuring: &TypedDestructuring) -> Option<Rc<Expression>> {
+        let mut pattern = &destructuring.destructuring;
+        while let Expression::Unary(UnaryExpression { operator: Operator::NonNull, expression, .. }) = pattern.as_ref() {
+            pattern = expression;
+        }
+        if matches!(pattern.as_ref(), Expression::QualifiedIdentifier(_)) {
+            destructuring.type_annotation.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Builds the scopes of function expressions within the
+    /// computed field names of a destructuring pattern.
+    fn build_pattern_expressions(&mut self, pattern: &Rc<Expression>) {
+        match pattern.as_ref() {
+            Expression::ArrayLiteral(literal) => {
+                for element in &literal.elements {
+                    if let Element::Expression(exp) | Element::Rest((exp, _)) = element {
+                        self.build_pattern_expressions(exp);
+                    }
+                }
+            },
+            Expression::ObjectInitializer(init) => {
+                for field in &init.fields {
+                    match field.as_ref() {
+                        InitializerField::Field { name, value, .. } => {
+                            if let FieldName::Brackets(exp) = &name.0 {
+                                self.build_expression(exp);
+                            }
+                            if let Some(value) = value {
+                                self.build_pattern_expressions(value);
+                            }
+                        },
+                        InitializerField::Rest((exp, _)) => {
+                            self.build_pattern_expressions(exp);
+                        },
+                    }
+                }
+            },
+            Expression::Unary(UnaryExpression { expression, .. }) => {
+                self.build_pattern_expressions(expression);
+            },
+            _ => {},
+        }
+    }
+
+    /// Collects the names of the enum members of an expression statement.
+    fn collect_enum_member_names(exp: &Rc<Expression>, names: &mut Vec<(String, Location)>) {
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(id) => {
+                names.extend(id.to_identifier_name());
+            },
+            Expression::Assignment(AssignmentExpression { compound: None, left, .. }) => {
+                Self::collect_enum_member_names(left, names);
+            },
+            Expression::Sequence(SequenceExpression { left, right, .. }) => {
+                Self::collect_enum_member_names(left, names);
+                Self::collect_enum_member_names(right, names);
+            },
+            _ => {},
+        }
+    }
+
+    /// Collects the names bound by a destructuring pattern.
+    fn collect_pattern_names(pattern: &Rc<Expression>, names: &mut Vec<(String, Location)>) {
+        match pattern.as_ref() {
+            Expression::QualifiedIdentifier(id) => {
+                if let Some(name) = id.to_identifier_name() {
+                    names.push(name);
+                }
+            },
+            Expression::ArrayLiteral(literal) => {
+                for element in &literal.elements {
+                    if let Element::Expression(exp) | Element::Rest((exp, _)) = element {
+                        Self::collect_pattern_names(exp, names);
+                    }
+                }
+            },
+            Expression::ObjectInitializer(init) => {
+                for field in &init.fields {
+                    match field.as_ref() {
+                        InitializerField::Field { value: Some(value), .. } => {
+                            Self::collect_pattern_names(value, names);
+                        },
+                        InitializerField::Field { value: None, .. } => {
+                            if let Some(name) = field.shorthand().and_then(|id| id.to_identifier_name()) {
+                                names.push(name);
+                            }
+                        },
+                        InitializerField::Rest((exp, _)) => {
+                            Self::collect_pattern_names(exp, names);
+                        },
+                    }
+                }
+            },
+            Expression::Unary(UnaryExpression { operator: Operator::NonNull, expression, .. }) => {
+                Self::collect_pattern_names(expression, names);
+            },
+            _ => {},
+        }
+    }
+
+    fn variable_symbol_kind(kind: VariableDefinitionKind) -> SymbolKind {
+        match kind {
+            VariableDefinitionKind::Var => SymbolKind::Variable,
+            VariableDefinitionKind::Const => SymbolKind::Constant,
+        }
+    }
+
+    fn declare(&mut self, name: String, kind: SymbolKind, location: Location, type_annotation: Option<Rc<Expression>>, definition: Option<&Rc<Directive>>) {
+        let scope = if definition.is_some() { self.definition_scope() } else { self.current_scope() };
+        scope.declare(Rc::new(Symbol {
+            name,
+            kind,
+            location,
+            type_annotation,
+            definition: definition.cloned(),
+        }));
+    }
+
+    fn current_scope(&self) -> Rc<Scope> {
+        self.scope.clone().unwrap()
+    }
+
+    /// The innermost scope in which definitions are declared.
+    fn definition_scope(&self) -> Rc<Scope> {
+        let mut scope = self.current_scope();
+        while !scope.kind().is_definition_scope() {
+            let Some(parent) = scope.parent() else {
+                break;
+            };
+            scope = parent;
+        }
+        scope
+    }
+
+    fn enter_scope(&mut self, kind: ScopeKind, location: &Location) -> Rc<Scope> {
+        let scope = Scope::new(kind, location, self.scope.as_ref());
+        self.scope = Some(scope.clone());
+        scope
+    }
+
+    fn exit_scope(&mut self) {
+        self.scope = self.scope.as_ref().and_then(|scope| scope.parent());
+    }
+}
+
+impl Visitor for ScopeBuilder<'_> {
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        let Expression::Function(exp) = node.as_ref() else {
+            return VisitorControl::Continue;
+        };
+        let scope = self.build_function_common(&exp.common);
+        if let Some((name, location)) = &exp.name {
+            scope.declare(Rc::new(Symbol {
+                name: name.clone(),
+                kind: SymbolKind::Function,
+                location: location.clone(),
+                type_annotation: None,
+                definition: None,
+            }));
+        }
+        VisitorControl::SkipChildren
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn symbol_names(scope: &Rc<Scope>) -> Vec<(String, SymbolKind)> {
+        scope.symbols().iter().map(|symbol| (symbol.name.clone(), symbol.kind)).collect()
+    }
+
+    #[test]
+    fn test_scopes() {
+        let source = r#"
+            package p {
+                public namespace ns = "ns";
+                public class C.<T> {
+                    public var x: Number, [y, ...z] = [];
+                    public function C(a: T, {b, c: d}) {
+                        if (a) {
+                            const e = function g(h) {};
+                        }
+                        for each (var v in b) {}
+                        try {} catch (error: Error) {}
+                        with (a) { f(); }
+                    }
+                    public function get w(): Number { return 0 }
+                }
+            }
+            type N = Number;
+            enum E { const A, B }
+            enum F { C, D = 2 }
+        "#;
+        let cu = CompilationUnit::new(None, source.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(cu.nested_diagnostics().is_empty());
+        let semantics = TreeSemantics::new();
+        let program_scope = ScopeBuilder::new(&semantics).build_program(&program);
+        assert_eq!(program_scope.kind(), ScopeKind::Program);
+        assert_eq!(symbol_names(&program_scope), [("N".into(), SymbolKind::Type), ("E".into(), SymbolKind::Enum), ("F".into(), SymbolKind::Enum)]);
+
+        let package = &program.packages[0];
+        let package_scope = semantics.get(package).unwrap();
+        assert!(Rc::ptr_eq(&package_scope.parent().unwrap(), &program_scope));
+        assert_eq!(symbol_names(&package_scope), [("ns".into(), SymbolKind::Namespace), ("C".into(), SymbolKind::Class)]);
+        assert!(package_scope.lookup_local("C").unwrap().definition.is_some());
+
+        let class_directive = &package.block.directives[1];
+        let class_scope = semantics.get(class_directive).unwrap();
+        assert_eq!(class_scope.kind(), ScopeKind::Class);
+        assert_eq!(symbol_names(&class_scope), [
+            ("T".into(), SymbolKind::TypeParameter),
+            ("x".into(), SymbolKind::Variable),
+            ("y".into(), SymbolKind::Variable),
+            ("z".into(), SymbolKind::Variable),
+            ("C".into(), SymbolKind::Constructor),
+            ("w".into(), SymbolKind::Getter),
+        ]);
+        assert_eq!(class_scope.lookup_local("x").unwrap().type_annotation.as_ref().unwrap().location().text(), "Number");
+        assert!(class_scope.lookup_local("y").unwrap().type_annotation.is_none());
+        assert!(class_scope.lookup("ns").is_some());
+
+        let Directive::ClassDefinition(class) = class_directive.as_ref() else { panic!() };
+        let Directive::FunctionDefinition(constructor) = class.block.directives[1].as_ref() else { panic!() };
+        let function_scope = semantics.get(&constructor.common).unwrap();
+        assert_eq!(function_scope.kind(), ScopeKind::Function);
+        // Variable definitions in blocks are declared in the function scope.
+        assert_eq!(symbol_names(&function_scope), [
+            ("a".into(), SymbolKind::Parameter),
+            ("b".into(), SymbolKind::Parameter),
+            ("d".into(), SymbolKind::Parameter),
+            ("e".into(), SymbolKind::Constant),
+            ("v".into(), SymbolKind::Variable),
+        ]);
+        let kinds: Vec<ScopeKind> = function_scope.children().iter().map(|scope| scope.kind()).collect();
+        assert_eq!(kinds, [ScopeKind::Block, ScopeKind::Block, ScopeKind::Block, ScopeKind::Catch, ScopeKind::With]);
+
+        // The function expression is nested within the block of the if statement.
+        let if_scope = &function_scope.children()[0];
+        let expression_scope = &if_scope.children()[0];
+        assert_eq!(symbol_names(expression_scope), [("h".into(), SymbolKind::Parameter), ("g".into(), SymbolKind::Function)]);
+        assert_eq!(expression_scope.lookup("T").unwrap().kind, SymbolKind::TypeParameter);
+        assert!(expression_scope.lookup("v").is_some());
+
+        let catch_scope = &function_scope.children()[3];
+        assert_eq!(symbol_names(catch_scope), [("error".into(), SymbolKind::Variable)]);
+        let offset = source.find("f();").unwrap();
+        assert_eq!(program_scope.scope_at(offset).kind(), ScopeKind::Block);
+        assert_eq!(program_scope.scope_at(offset).parent().unwrap().kind(), ScopeKind::With);
+
+        let Directive::EnumDefinition(enum_definition) = program.directives[1].as_ref() else { panic!() };
+        let enum_scope = semantics.get(&enum_definition.block).unwrap();
+        assert_eq!(symbol_names(&enum_scope), [("A".into(), SymbolKind::Constant), ("B".into(), SymbolKind::Constant)]);
+        let Directive::EnumDefinition(enum_definition) = program.directives[2].as_ref() else { panic!() };
+        let enum_scope = semantics.get(&enum_definition.block).unwrap();
+        assert_eq!(symbol_names(&enum_scope), [("C".into(), SymbolKind::Constant), ("D".into(), SymbolKind::Constant)]);
+    }
+}
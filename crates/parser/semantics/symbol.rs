@@ -0,0 +1,65 @@
+use crate::ns::*;
+
+/// A name declared within a lexical scope.
+#[derive(Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Location of the declared name.
+    pub location: Location,
+    /// Type annotation of a variable, constant or parameter declared
+    /// through an identifier binding.
+    pub type_annotation: Option<Rc<Expression>>,
+    /// The definition that declares the symbol, if it is a directive.
+    /// This is `None` for parameters, type parameters and variables
+    /// declared by `for` and `for..in` statements.
+    pub definition: Option<Rc<Directive>>,
+}
+
+impl Symbol {
+    /// The attributes of the definition that declares the symbol.
+    pub fn attributes(&self) -> &[Attribute] {
+        match self.definition.as_ref().map(|definition| definition.as_ref()) {
+            Some(Directive::VariableDefinition(defn)) => &defn.attributes,
+            Some(Directive::FunctionDefinition(defn)) => &defn.attributes,
+            Some(Directive::ClassDefinition(defn)) => &defn.attributes,
+            Some(Directive::EnumDefinition(defn)) => &defn.attributes,
+            Some(Directive::InterfaceDefinition(defn)) => &defn.attributes,
+            Some(Directive::TypeDefinition(defn)) => &defn.attributes,
+            Some(Directive::NamespaceDefinition(defn)) => &defn.attributes,
+            _ => &[],
+        }
+    }
+
+    /// The ASDoc comment of the definition that declares the symbol.
+    pub fn asdoc(&self) -> Option<Rc<AsDoc>> {
+        match self.definition.as_ref().map(|definition| definition.as_ref()) {
+            Some(Directive::VariableDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::FunctionDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::ClassDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::EnumDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::InterfaceDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::TypeDefinition(defn)) => defn.asdoc.clone(),
+            Some(Directive::NamespaceDefinition(defn)) => defn.asdoc.clone(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SymbolKind {
+    Class,
+    Interface,
+    Enum,
+    Function,
+    Getter,
+    Setter,
+    Constructor,
+    Variable,
+    Constant,
+    Parameter,
+    TypeParameter,
+    Namespace,
+    /// A type alias declared by a `type` definition.
+    Type,
+}
@@ -0,0 +1,134 @@
+use crate::ns::*;
+use std::fmt;
+
+/// A static type, either evaluated from a type expression or
+/// inferred for an expression by the `Verifier`.
+#[derive(Clone)]
+pub enum Type {
+    /// The any type (`*`), which is also the type of any
+    /// expression whose type is unknown.
+    Any,
+    Void,
+    /// The type of the `null` literal.
+    Null,
+    /// An instance of a class, interface or enum, along with
+    /// its type arguments.
+    Instance(Rc<Symbol>, Vec<Type>),
+    /// A class, interface or enum itself, as referred to by its name,
+    /// along with its type arguments.
+    Definition(Rc<Symbol>, Vec<Type>),
+    /// The `?T` type.
+    Nullable(Box<Type>),
+    /// The `T!` type.
+    NonNullable(Box<Type>),
+    /// The `[T]` type.
+    Array(Box<Type>),
+    /// The `[T1, T2]` type.
+    Tuple(Vec<Type>),
+    Function(FunctionType),
+}
+
+impl Type {
+    pub fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+
+    /// The type without its nullable or non-nullable wrappers.
+    pub fn non_null_base(&self) -> &Type {
+        match self {
+            Self::Nullable(base) | Self::NonNullable(base) => base.non_null_base(),
+            _ => self,
+        }
+    }
+
+    /// The class, interface or enum of an instance type.
+    pub fn instance_symbol(&self) -> Option<Rc<Symbol>> {
+        match self.non_null_base() {
+            Self::Instance(symbol, _) => Some(symbol.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Any, Self::Any) |
+            (Self::Void, Self::Void) |
+            (Self::Null, Self::Null) => true,
+            (Self::Instance(a, a_arguments), Self::Instance(b, b_arguments)) |
+            (Self::Definition(a, a_arguments), Self::Definition(b, b_arguments)) => {
+                Rc::ptr_eq(a, b) && a_arguments == b_arguments
+            },
+            (Self::Nullable(a), Self::Nullable(b)) |
+            (Self::NonNullable(a), Self::NonNullable(b)) |
+            (Self::Array(a), Self::Array(b)) => a == b,
+            (Self::Tuple(a), Self::Tuple(b)) => a == b,
+            (Self::Function(a), Self::Function(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "*"),
+            Self::Void => write!(f, "void"),
+            Self::Null => write!(f, "null"),
+            Self::Instance(symbol, arguments) => {
+                write!(f, "{}", symbol.name)?;
+                if !arguments.is_empty() {
+                    write!(f, ".<{}>", join_types(arguments))?;
+                }
+                Ok(())
+            },
+            Self::Definition(..) => write!(f, "Class"),
+            Self::Nullable(base) => write!(f, "?{base}"),
+            Self::NonNullable(base) => write!(f, "{base}!"),
+            Self::Array(element) => write!(f, "[{element}]"),
+            Self::Tuple(elements) => write!(f, "[{}]", join_types(elements)),
+            Self::Function(function) => write!(f, "{function}"),
+        }
+    }
+}
+
+/// The type of a function, as evaluated from its signature
+/// or from a function type expression.
+#[derive(Clone, PartialEq)]
+pub struct FunctionType {
+    pub parameters: Vec<(ParameterKind, Type)>,
+    pub result_type: Box<Type>,
+}
+
+impl FunctionType {
+    /// The number of required parameters.
+    pub fn min_arguments(&self) -> usize {
+        self.parameters.iter().filter(|(kind, _)| *kind == ParameterKind::Required).count()
+    }
+
+    /// The number of parameters, or `None` if the function
+    /// has a rest parameter.
+    pub fn max_arguments(&self) -> Option<usize> {
+        if self.parameters.iter().any(|(kind, _)| *kind == ParameterKind::Rest) {
+            None
+        } else {
+            Some(self.parameters.len())
+        }
+    }
+}
+
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<String> = self.parameters.iter().map(|(kind, parameter_type)| match kind {
+            ParameterKind::Required => parameter_type.to_string(),
+            ParameterKind::Optional => format!("{parameter_type}="),
+            ParameterKind::Rest => "...".into(),
+        }).collect();
+        write!(f, "function({}): {}", parameters.join(", "), self.result_type)
+    }
+}
+
+fn join_types(list: &[Type]) -> String {
+    list.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+}
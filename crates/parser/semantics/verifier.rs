@@ -0,0 +1,958 @@
+use crate::ns::*;
+
+/// Verifies the types of several programs after resolving their names.
+///
+/// The verifier resolves the names of the programs through a `NameResolver`,
+/// evaluates their type expressions and infers the static type of each
+/// expression, storing it in `expression_types()`. It then reports, as verify
+/// errors, incompatibilities in:
+///
+/// - Variable initializers, parameter default values and assignments.
+/// - The arguments of calls and `new` expressions.
+/// - Return values, including return values of `void` functions.
+/// - Property accesses on instances of non-dynamic classes and interfaces,
+///   and static property accesses on classes.
+/// - The right-hand side of the `as`, `is` and `is not` operators.
+/// - Type expressions that refer to non-type definitions or
+///   that supply an incorrect number of type arguments.
+///
+/// Built-in types such as `Number`, `String` and `Array` are used for the
+/// types of literals and are related to the `*` type and each other only if
+/// one of the programs defines them in the top-level package; otherwise,
+/// literals are of the `*` type. The `Object` and `Boolean` types accept
+/// values of any type, and the `Number`, `int` and `uint` types are mutually
+/// compatible. Type parameters are evaluated as the `*` type. Nothing is
+/// reported unless one of the programs defines the top-level `Object` class,
+/// since the types of most expressions are unknown without the built-in
+/// definitions.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, r#"
+///     package { public class Object {} public class Number {} public class String {} }
+///     var x: Number = "text";
+/// "#.into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let mut verifier = Verifier::new(default());
+/// verifier.verify_programs(&[program]);
+/// assert_eq!(cu.diagnostics()[0].format_message_english(), "Implicit coercion of a value of type 'String' to an unrelated type 'Number'.");
+/// ```
+pub struct Verifier {
+    resolver: NameResolver,
+    options: VerifierOptions,
+    expression_types: TreeSemantics<Type>,
+    /// Type aliases whose right-hand side is being evaluated.
+    evaluating_aliases: RefCell<Vec<Rc<Symbol>>>,
+}
+
+pub struct VerifierOptions {
+    /// Indicates whether type incompatibilities are reported, as in the
+    /// strict mode of the ActionScript compiler. When false, only invalid
+    /// type expressions are reported. Default: true.
+    ///
+    /// `CompilerOptions::strict`, when set for a compilation unit,
+    /// takes precedence for the programs of the compilation unit.
+    pub strict: bool,
+}
+
+impl Default for VerifierOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+        }
+    }
+}
+
+impl Verifier {
+    pub fn new(options: VerifierOptions) -> Self {
+        Self {
+            resolver: NameResolver::new(),
+            options,
+            expression_types: TreeSemantics::new(),
+            evaluating_aliases: RefCell::new(vec![]),
+        }
+    }
+
+    /// Resolves the names of the given programs and verifies them.
+    pub fn verify_programs(&mut self, programs: &[Rc<Program>]) {
+        self.resolver.resolve_programs(programs);
+        for program in programs {
+            let _ = walk_program(&mut VerifierWalker::new(self), program);
+        }
+    }
+
+    /// The name resolver of the verified programs.
+    pub fn resolver(&self) -> &NameResolver {
+        &self.resolver
+    }
+
+    /// The static types of the verified expressions, except type expressions.
+    pub fn expression_types(&self) -> &TreeSemantics<Type> {
+        &self.expression_types
+    }
+
+    /// Evaluates a type expression.
+    pub fn evaluate_type(&self, exp: &Rc<Expression>) -> Type {
+        self.evaluate_type_expression(exp, false)
+    }
+
+    /// The static type of a reference to a symbol.
+    pub fn symbol_type(&self, symbol: &Rc<Symbol>) -> Type {
+        match (symbol.kind, symbol.definition.as_ref().map(|definition| definition.as_ref())) {
+            (SymbolKind::Variable | SymbolKind::Constant | SymbolKind::Parameter, _) => {
+                symbol.type_annotation.as_ref().map_or(Type::Any, |exp| self.evaluate_type(exp))
+            },
+            (SymbolKind::Function | SymbolKind::Constructor, Some(Directive::FunctionDefinition(defn))) => {
+                Type::Function(self.function_type(&defn.common.signature))
+            },
+            (SymbolKind::Getter, Some(Directive::FunctionDefinition(defn))) => {
+                defn.common.signature.result_type.as_ref().map_or(Type::Any, |exp| self.evaluate_type(exp))
+            },
+            (SymbolKind::Setter, Some(Directive::FunctionDefinition(defn))) => {
+                defn.common.signature.parameters.first()
+                    .and_then(|parameter| parameter.destructuring.type_annotation.as_ref())
+                    .map_or(Type::Any, |exp| self.evaluate_type(exp))
+            },
+            (SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum, _) => {
+                Type::Definition(symbol.clone(), vec![])
+            },
+            (SymbolKind::Namespace, _) => self.builtin_instance("Namespace"),
+            _ => Type::Any,
+        }
+    }
+
+    /// The type of a function with the given signature.
+    pub fn function_type(&self, signature: &FunctionSignature) -> FunctionType {
+        FunctionType {
+            parameters: signature.parameters.iter().map(|parameter| {
+                let parameter_type = parameter.destructuring.type_annotation.as_ref().map_or(Type::Any, |exp| self.evaluate_type(exp));
+                (parameter.kind, parameter_type)
+            }).collect(),
+            result_type: Box::new(signature.result_type.as_ref().map_or(Type::Any, |exp| self.evaluate_type(exp))),
+        }
+    }
+
+    /// Indicates whether a value of type `from` may be implicitly
+    /// coerced to the type `to`. Types whose relationship cannot be
+    /// determined, such as classes extending unresolved classes,
+    /// are considered compatible.
+    pub fn is_compatible(&self, from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Null, Type::NonNullable(_)) => false,
+            (Type::Null, _) => true,
+            (_, Type::Nullable(base) | Type::NonNullable(base)) => self.is_compatible(from, base),
+            (Type::Nullable(base) | Type::NonNullable(base), _) => self.is_compatible(base, to),
+            (_, Type::Instance(symbol, _)) if self.is_builtin(symbol, "Object") || self.is_builtin(symbol, "Boolean") => true,
+            (Type::Void, to) => matches!(to, Type::Void),
+            (_, Type::Void) => false,
+            (Type::Array(from_element), Type::Array(to_element)) => self.is_compatible(from_element, to_element),
+            (Type::Tuple(from_elements), Type::Tuple(to_elements)) => {
+                from_elements.len() == to_elements.len()
+                    && from_elements.iter().zip(to_elements).all(|(from, to)| self.is_compatible(from, to))
+            },
+            (Type::Tuple(_), Type::Array(_)) => true,
+            (Type::Instance(symbol, _), Type::Array(_) | Type::Tuple(_)) |
+            (Type::Array(_) | Type::Tuple(_), Type::Instance(symbol, _)) => self.is_builtin(symbol, "Array"),
+            (Type::Function(_), Type::Function(_)) => true,
+            (Type::Instance(symbol, _), Type::Function(_)) |
+            (Type::Function(_), Type::Instance(symbol, _)) => self.is_builtin(symbol, "Function"),
+            (Type::Definition(..), Type::Instance(symbol, _)) => self.is_builtin(symbol, "Class"),
+            (Type::Instance(from_symbol, from_arguments), Type::Instance(to_symbol, to_arguments)) => {
+                if Rc::ptr_eq(from_symbol, to_symbol) {
+                    return from_arguments.is_empty() || to_arguments.is_empty() || from_arguments == to_arguments;
+                }
+                (self.is_numeric(from_symbol) && self.is_numeric(to_symbol)) || self.is_subtype(from_symbol, to_symbol)
+            },
+            (_, Type::Array(_) | Type::Tuple(_) | Type::Function(_)) => false,
+            _ => true,
+        }
+    }
+
+    fn report(&self, location: &Location, kind: DiagnosticKind, arguments: Vec<Rc<dyn DiagnosticArgument>>) {
+        if self.builtin("Object").is_none() {
+            return;
+        }
+        let type_expression_error = matches!(kind, DiagnosticKind::NotAType | DiagnosticKind::IncorrectTypeArgumentCount);
+        let strict = location.compilation_unit().compiler_options().and_then(|options| options.strict).unwrap_or(self.options.strict);
+        if strict || type_expression_error {
+            location.compilation_unit().add_diagnostic(Diagnostic::new_verify_error(location, kind, arguments));
+        }
+    }
+
+    fn evaluate_type_expression(&self, exp: &Rc<Expression>, report: bool) -> Type {
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) | Expression::Member(_) => {
+                let Some(symbol) = self.resolver.resolutions().get(exp) else {
+                    return Type::Any;
+                };
+                match symbol.kind {
+                    SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum => Type::Instance(symbol, vec![]),
+                    SymbolKind::Type => self.evaluate_type_alias(&symbol),
+                    SymbolKind::TypeParameter => Type::Any,
+                    _ => {
+                        if report {
+                            self.report(&exp.location(), DiagnosticKind::NotAType, diagarg![exp.location().text()]);
+                        }
+                        Type::Any
+                    },
+                }
+            },
+            Expression::Paren(exp) => self.evaluate_type_expression(&exp.expression, report),
+            Expression::WithTypeArguments(type_exp) => {
+                let arguments: Vec<Type> = type_exp.arguments.iter().map(|argument| self.evaluate_type_expression(argument, report)).collect();
+                match self.evaluate_type_expression(&type_exp.base, report) {
+                    Type::Instance(symbol, _) => {
+                        if report {
+                            self.check_type_argument_count(&symbol, arguments.len(), &exp.location());
+                        }
+                        Type::Instance(symbol, arguments)
+                    },
+                    base => base,
+                }
+            },
+            Expression::NullableType(exp) => Type::Nullable(Box::new(self.evaluate_type_expression(&exp.base, report))),
+            Expression::NonNullableType(exp) => Type::NonNullable(Box::new(self.evaluate_type_expression(&exp.base, report))),
+            Expression::AnyType(_) => Type::Any,
+            Expression::VoidType(_) => Type::Void,
+            Expression::ArrayType(exp) => Type::Array(Box::new(self.evaluate_type_expression(&exp.expression, report))),
+            Expression::TupleType(exp) => {
+                Type::Tuple(exp.expressions.iter().map(|element| self.evaluate_type_expression(element, report)).collect())
+            },
+            Expression::FunctionType(exp) => Type::Function(FunctionType {
+                parameters: exp.parameters.iter().map(|parameter| {
+                    let parameter_type = parameter.type_expression.as_ref().map_or(Type::Any, |exp| self.evaluate_type_expression(exp, report));
+                    (parameter.kind, parameter_type)
+                }).collect(),
+                result_type: Box::new(exp.result_type.as_ref().map_or(Type::Any, |exp| self.evaluate_type_expression(exp, report))),
+            }),
+            Expression::Invalidated(_) => Type::Any,
+            _ => {
+                if report {
+                    self.report(&exp.location(), DiagnosticKind::NotAType, diagarg![exp.location().text()]);
+                }
+                Type::Any
+            },
+        }
+    }
+
+    fn evaluate_type_alias(&self, symbol: &Rc<Symbol>) -> Type {
+        let Some(Directive::TypeDefinition(defn)) = symbol.definition.as_ref().map(|definition| definition.as_ref()) else {
+            return Type::Any;
+        };
+        if self.evaluating_aliases.borrow().iter().any(|alias| Rc::ptr_eq(alias, symbol)) {
+            return Type::Any;
+        }
+        self.evaluating_aliases.borrow_mut().push(symbol.clone());
+        let result = self.evaluate_type(&defn.right);
+        self.evaluating_aliases.borrow_mut().pop();
+        result
+    }
+
+    fn check_type_argument_count(&self, symbol: &Rc<Symbol>, count: usize, location: &Location) {
+        let expected = self.type_parameter_count(symbol);
+        if count != expected {
+            self.report(location, DiagnosticKind::IncorrectTypeArgumentCount, diagarg![expected.to_string()]);
+        }
+    }
+
+    /// The number of type parameters of a class or interface. The
+    /// top-level `Vector` class takes a single type argument.
+    fn type_parameter_count(&self, symbol: &Rc<Symbol>) -> usize {
+        if self.is_builtin(symbol, "Vector") {
+            return 1;
+        }
+        match symbol.definition.as_ref().map(|definition| definition.as_ref()) {
+            Some(Directive::ClassDefinition(defn)) => defn.type_parameters.as_ref().map_or(0, |list| list.len()),
+            Some(Directive::InterfaceDefinition(defn)) => defn.type_parameters.as_ref().map_or(0, |list| list.len()),
+            _ => 0,
+        }
+    }
+
+    /// Returns a class, interface or enum defined in the top-level package.
+    fn builtin(&self, name: &str) -> Option<Rc<Symbol>> {
+        self.resolver.package_definitions("").into_iter().find(|symbol| {
+            symbol.name == name && matches!(symbol.kind, SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum)
+        })
+    }
+
+    fn builtin_instance(&self, name: &str) -> Type {
+        self.builtin(name).map_or(Type::Any, |symbol| Type::Instance(symbol, vec![]))
+    }
+
+    fn is_builtin(&self, symbol: &Rc<Symbol>, name: &str) -> bool {
+        symbol.name == name && self.builtin(name).is_some_and(|builtin| Rc::ptr_eq(&builtin, symbol))
+    }
+
+    fn is_numeric(&self, symbol: &Rc<Symbol>) -> bool {
+        ["Number", "int", "uint"].iter().any(|name| self.is_builtin(symbol, name))
+    }
+
+    /// Returns the class, interface or enum to which a type expression refers.
+    fn type_expression_symbol(&self, exp: &Rc<Expression>) -> Option<Rc<Symbol>> {
+        match exp.as_ref() {
+            Expression::WithTypeArguments(exp) => self.type_expression_symbol(&exp.base),
+            _ => self.resolver.resolutions().get(exp),
+        }
+    }
+
+    /// The direct base classes and interfaces of a class, interface or enum,
+    /// or `None` if any of them is unresolved. Classes and enums without an
+    /// extends clause extend the top-level `Object` class.
+    fn supertypes(&self, symbol: &Rc<Symbol>) -> Option<Vec<Rc<Symbol>>> {
+        let mut supertypes = vec![];
+        let (extends_clause, implements_clause) = match symbol.definition.as_ref().map(|definition| definition.as_ref()) {
+            Some(Directive::ClassDefinition(defn)) => (defn.extends_clause.clone(), defn.implements_clause.clone().unwrap_or_default()),
+            Some(Directive::InterfaceDefinition(defn)) => (None, defn.extends_clause.clone().unwrap_or_default()),
+            Some(Directive::EnumDefinition(_)) => (None, vec![]),
+            _ => return Some(vec![]),
+        };
+        if let Some(exp) = extends_clause {
+            supertypes.push(self.type_expression_symbol(&exp)?);
+        } else if symbol.kind != SymbolKind::Interface {
+            supertypes.extend(self.builtin("Object").filter(|object| !Rc::ptr_eq(object, symbol)));
+        }
+        for exp in &implements_clause {
+            supertypes.push(self.type_expression_symbol(exp)?);
+        }
+        Some(supertypes)
+    }
+
+    /// Indicates whether a class, interface or enum is `sup` or inherits
+    /// from `sup`. If the hierarchy cannot be determined, returns true.
+    fn is_subtype(&self, sub: &Rc<Symbol>, sup: &Rc<Symbol>) -> bool {
+        let mut visited = vec![];
+        let mut pending = vec![sub.clone()];
+        while let Some(symbol) = pending.pop() {
+            if Rc::ptr_eq(&symbol, sup) {
+                return true;
+            }
+            if visited.iter().any(|visited| Rc::ptr_eq(visited, &symbol)) {
+                continue;
+            }
+            let Some(supertypes) = self.supertypes(&symbol) else {
+                return true;
+            };
+            visited.push(symbol);
+            pending.extend(supertypes);
+        }
+        false
+    }
+
+    /// The members declared by a class, interface or enum.
+    fn members(&self, symbol: &Rc<Symbol>) -> Vec<Rc<Symbol>> {
+        let Some(scope) = symbol.definition.as_ref().and_then(|definition| self.resolver.scopes().get(definition)) else {
+            return vec![];
+        };
+        scope.symbols().into_iter().filter(|member| member.kind != SymbolKind::TypeParameter).collect()
+    }
+
+    fn find_member(&self, base: &Type, name: &str) -> MemberLookup {
+        match base.non_null_base() {
+            Type::Instance(symbol, _) => self.find_instance_member(symbol, name),
+            Type::Definition(symbol, _) => {
+                let is_static = |member: &Rc<Symbol>| Attribute::find_static(member.attributes()).is_some();
+                if let Some(member) = self.members(symbol).into_iter().find(|member| member.name == name && is_static(member)) {
+                    return MemberLookup::Found(member);
+                }
+                self.builtin("Class").map_or(MemberLookup::Unknown, |class| self.find_instance_member(&class, name))
+            },
+            Type::Array(_) | Type::Tuple(_) => {
+                self.builtin("Array").map_or(MemberLookup::Unknown, |array| self.find_instance_member(&array, name))
+            },
+            Type::Function(_) => {
+                self.builtin("Function").map_or(MemberLookup::Unknown, |function| self.find_instance_member(&function, name))
+            },
+            _ => MemberLookup::Unknown,
+        }
+    }
+
+    /// Looks up an instance member of a class or interface, including
+    /// the non-private members that it inherits.
+    fn find_instance_member(&self, symbol: &Rc<Symbol>, name: &str) -> MemberLookup {
+        if symbol.kind == SymbolKind::Enum {
+            return self.members(symbol).into_iter().find(|member| member.name == name).map_or(MemberLookup::Unknown, MemberLookup::Found);
+        }
+        let mut incomplete = Attribute::find_dynamic(symbol.attributes()).is_some();
+        let mut visited: Vec<Rc<Symbol>> = vec![];
+        let mut pending = vec![symbol.clone()];
+        while let Some(current) = pending.pop() {
+            if visited.iter().any(|visited| Rc::ptr_eq(visited, &current)) {
+                continue;
+            }
+            let inherited = !Rc::ptr_eq(&current, symbol);
+            let member = self.members(&current).into_iter().find(|member| {
+                member.name == name
+                    && Attribute::find_static(member.attributes()).is_none()
+                    && !(inherited && Attribute::find_private(member.attributes()).is_some())
+            });
+            if let Some(member) = member {
+                return MemberLookup::Found(member);
+            }
+            match self.supertypes(&current) {
+                Some(supertypes) => pending.extend(supertypes.into_iter().rev()),
+                None => incomplete = true,
+            }
+            visited.push(current);
+        }
+        if incomplete { MemberLookup::Unknown } else { MemberLookup::NotFound }
+    }
+
+    /// Returns the symbol of a class, interface or enum definition.
+    fn definition_symbol(&self, directive: &Rc<Directive>) -> Option<Rc<Symbol>> {
+        let mut scope = self.resolver.scopes().get(directive)?.parent()?;
+        while !scope.kind().is_definition_scope() {
+            scope = scope.parent()?;
+        }
+        scope.symbols().into_iter().find(|symbol| {
+            symbol.definition.as_ref().is_some_and(|definition| Rc::ptr_eq(definition, directive))
+        })
+    }
+}
+
+enum MemberLookup {
+    Found(Rc<Symbol>),
+    NotFound,
+    /// The member may exist, such as in a dynamic class or
+    /// in a class whose base class is unresolved.
+    Unknown,
+}
+
+enum Frame {
+    Class(Option<Rc<Symbol>>),
+    Function {
+        result_type: Option<Type>,
+        this_type: Type,
+    },
+}
+
+/// Traverses a program, inferring the types of its expressions
+/// and reporting incompatibilities.
+struct VerifierWalker<'a> {
+    verifier: &'a Verifier,
+    frames: Vec<Frame>,
+    /// Type expressions that are yet to be visited.
+    type_expressions: HashSet<NodeAsKey<Rc<Expression>>>,
+}
+
+impl<'a> VerifierWalker<'a> {
+    fn new(verifier: &'a Verifier) -> Self {
+        Self {
+            verifier,
+            frames: vec![],
+            type_expressions: HashSet::new(),
+        }
+    }
+
+    fn mark_type_expression(&mut self, exp: &Rc<Expression>) {
+        self.type_expressions.insert(NodeAsKey(exp.clone()));
+    }
+
+    fn type_of(&self, exp: &Rc<Expression>) -> Type {
+        self.verifier.expression_types.get(exp).unwrap_or(Type::Any)
+    }
+
+    fn function_frame(&self) -> Option<(&Option<Type>, &Type)> {
+        self.frames.iter().rev().find_map(|frame| match frame {
+            Frame::Function { result_type, this_type } => Some((result_type, this_type)),
+            Frame::Class(_) => None,
+        })
+    }
+
+    /// Indicates whether an expression denotes a type rather than a value.
+    fn is_type_expression(&self, exp: &Rc<Expression>) -> bool {
+        match exp.as_ref() {
+            Expression::NullableType(_) |
+            Expression::NonNullableType(_) |
+            Expression::AnyType(_) |
+            Expression::VoidType(_) |
+            Expression::ArrayType(_) |
+            Expression::TupleType(_) |
+            Expression::FunctionType(_) |
+            Expression::WithTypeArguments(_) => true,
+            Expression::QualifiedIdentifier(_) | Expression::Member(_) => {
+                self.verifier.resolver.resolutions().get(exp).is_some_and(|symbol| {
+                    matches!(symbol.kind, SymbolKind::Class | SymbolKind::Interface | SymbolKind::Enum | SymbolKind::Type | SymbolKind::TypeParameter)
+                })
+            },
+            _ => false,
+        }
+    }
+
+    fn check_coercion(&self, exp: &Rc<Expression>, to: &Type) {
+        let from = self.type_of(exp);
+        if !self.verifier.is_compatible(&from, to) {
+            self.verifier.report(&exp.location(), DiagnosticKind::IncompatibleTypes, diagarg![from.to_string(), to.to_string()]);
+        }
+    }
+
+    fn check_arguments(&self, function: &FunctionType, arguments: &[Rc<Expression>], location: &Location) {
+        let min = function.min_arguments();
+        if arguments.len() < min {
+            self.verifier.report(location, DiagnosticKind::IncorrectArgumentCount, diagarg![min.to_string()]);
+        } else if let Some(max) = function.max_arguments().filter(|max| arguments.len() > *max) {
+            self.verifier.report(location, DiagnosticKind::TooManyArguments, diagarg![max.to_string()]);
+        }
+        for (argument, (kind, parameter_type)) in arguments.iter().zip(&function.parameters) {
+            if *kind != ParameterKind::Rest {
+                self.check_coercion(argument, parameter_type);
+            }
+        }
+    }
+
+    fn check_variable_bindings(&self, bindings: &[Rc<VariableBinding>]) {
+        for binding in bindings {
+            if let (Some(type_annotation), Some(init)) = (Self::identifier_type_annotation(&binding.destructuring), &binding.initializer) {
+                self.check_coercion(init, &self.verifier.evaluate_type(&type_annotation));
+            }
+        }
+    }
+
+    /// The type annotation of a destructuring that is an identifier.
+    fn identifier_type_annotation(destructuring: &TypedDestructuring) -> Option<Rc<Expression>> {
+        if matches!(destructuring.destructuring.as_ref(), Expression::QualifiedIdentifier(_)) {
+            destructuring.type_annotation.clone()
+        } else {
+            None
+        }
+    }
+
+    fn check_return(&self, stmt: &ReturnStatement) {
+        let (Some((Some(result_type), _)), Some(exp)) = (self.function_frame(), &stmt.expression) else {
+            return;
+        };
+        if matches!(result_type, Type::Void) {
+            self.verifier.report(&exp.location(), DiagnosticKind::ReturnValueMustBeUndefined, vec![]);
+        } else {
+            self.check_coercion(exp, result_type);
+        }
+    }
+
+    fn infer(&self, exp: &Rc<Expression>) -> Type {
+        let verifier = self.verifier;
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) => {
+                verifier.resolver.resolutions().get(exp).map_or(Type::Any, |symbol| verifier.symbol_type(&symbol))
+            },
+            Expression::Paren(exp) => self.type_of(&exp.expression),
+            Expression::NullLiteral(_) => Type::Null,
+            Expression::BooleanLiteral(_) => verifier.builtin_instance("Boolean"),
+            Expression::NumericLiteral(_) => verifier.builtin_instance("Number"),
+            Expression::StringLiteral(_) => verifier.builtin_instance("String"),
+            Expression::RegExpLiteral(_) => verifier.builtin_instance("RegExp"),
+            Expression::Xml(_) | Expression::XmlMarkup(_) => verifier.builtin_instance("XML"),
+            Expression::XmlList(_) => verifier.builtin_instance("XMLList"),
+            Expression::ArrayLiteral(_) => verifier.builtin_instance("Array"),
+            Expression::ObjectInitializer(_) => verifier.builtin_instance("Object"),
+            Expression::ThisLiteral(_) => self.function_frame().map_or(Type::Any, |(_, this_type)| this_type.clone()),
+            Expression::VectorLiteral(exp) => {
+                let Some(vector) = verifier.builtin("Vector") else {
+                    return Type::Any;
+                };
+                Type::Instance(vector, vec![verifier.evaluate_type(&exp.element_type)])
+            },
+            Expression::Function(exp) => Type::Function(verifier.function_type(&exp.common.signature)),
+            Expression::New(new_exp) => {
+                let Type::Definition(symbol, type_arguments) = self.type_of(&new_exp.base) else {
+                    return Type::Any;
+                };
+                let constructor = verifier.members(&symbol).into_iter().find(|member| member.kind == SymbolKind::Constructor);
+                if let Some(Type::Function(constructor)) = constructor.map(|constructor| verifier.symbol_type(&constructor)) {
+                    self.check_arguments(&constructor, new_exp.arguments.as_deref().unwrap_or_default(), &exp.location());
+                }
+                Type::Instance(symbol, type_arguments)
+            },
+            Expression::Member(member) => {
+                if let Some(symbol) = verifier.resolver.resolutions().get(exp) {
+                    return verifier.symbol_type(&symbol);
+                }
+                let Some((name, location)) = member.identifier.to_identifier_name() else {
+                    return Type::Any;
+                };
+                let base_type = self.type_of(&member.base);
+                match verifier.find_member(&base_type, &name) {
+                    MemberLookup::Found(symbol) => verifier.symbol_type(&symbol),
+                    MemberLookup::NotFound => {
+                        verifier.report(&location, DiagnosticKind::UndefinedPropertyThroughReference, diagarg![name, base_type.to_string()]);
+                        Type::Any
+                    },
+                    MemberLookup::Unknown => Type::Any,
+                }
+            },
+            Expression::ComputedMember(member) => match self.type_of(&member.base).non_null_base() {
+                Type::Array(element) => element.as_ref().clone(),
+                Type::Tuple(elements) => {
+                    let Expression::NumericLiteral(key) = member.key.as_ref() else {
+                        return Type::Any;
+                    };
+                    key.parse_uint().ok()
+                        .and_then(|index| elements.get(index as usize))
+                        .cloned().unwrap_or(Type::Any)
+                },
+                Type::Instance(symbol, arguments) if verifier.is_builtin(symbol, "Vector") && arguments.len() == 1 => arguments[0].clone(),
+                _ => Type::Any,
+            },
+            Expression::Call(call) => match self.type_of(&call.base).non_null_base() {
+                Type::Function(function) => {
+                    self.check_arguments(function, &call.arguments, &exp.location());
+                    function.result_type.as_ref().clone()
+                },
+                // Calling a class converts its argument to the class.
+                Type::Definition(symbol, type_arguments) => {
+                    if call.arguments.len() != 1 {
+                        verifier.report(&exp.location(), DiagnosticKind::IncorrectArgumentCount, diagarg!["1".to_owned()]);
+                    }
+                    Type::Instance(symbol.clone(), type_arguments.clone())
+                },
+                _ => Type::Any,
+            },
+            Expression::WithTypeArguments(type_exp) => {
+                let Type::Definition(symbol, _) = self.type_of(&type_exp.base) else {
+                    return Type::Any;
+                };
+                verifier.check_type_argument_count(&symbol, type_exp.arguments.len(), &exp.location());
+                Type::Definition(symbol, type_exp.arguments.iter().map(|argument| verifier.evaluate_type(argument)).collect())
+            },
+            Expression::Unary(exp) => match exp.operator {
+                Operator::LogicalNot | Operator::Delete => verifier.builtin_instance("Boolean"),
+                Operator::Typeof => verifier.builtin_instance("String"),
+                Operator::BitwiseNot => verifier.builtin_instance("int"),
+                Operator::Positive | Operator::Negative |
+                Operator::PreIncrement | Operator::PreDecrement |
+                Operator::PostIncrement | Operator::PostDecrement => verifier.builtin_instance("Number"),
+                Operator::NonNull => match self.type_of(&exp.expression) {
+                    Type::Nullable(base) => *base,
+                    operand => operand,
+                },
+                _ => Type::Any,
+            },
+            Expression::Binary(exp) => self.infer_binary(exp),
+            Expression::Conditional(exp) => {
+                let consequent = self.type_of(&exp.consequent);
+                if consequent == self.type_of(&exp.alternative) { consequent } else { Type::Any }
+            },
+            Expression::Assignment(exp) => {
+                if exp.compound.is_none() && !Self::is_destructuring_pattern(&exp.left) {
+                    self.check_coercion(&exp.right, &self.type_of(&exp.left));
+                }
+                self.type_of(&exp.right)
+            },
+            Expression::Sequence(exp) => self.type_of(&exp.right),
+            _ => Type::Any,
+        }
+    }
+
+    fn infer_binary(&self, exp: &BinaryExpression) -> Type {
+        let verifier = self.verifier;
+        let left = self.type_of(&exp.left);
+        let right = self.type_of(&exp.right);
+        match exp.operator {
+            Operator::Add => {
+                let is = |t: &Type, name: &str| t.instance_symbol().is_some_and(|symbol| verifier.is_builtin(&symbol, name));
+                let is_numeric = |t: &Type| t.instance_symbol().is_some_and(|symbol| verifier.is_numeric(&symbol));
+                if is(&left, "String") || is(&right, "String") {
+                    verifier.builtin_instance("String")
+                } else if is_numeric(&left) && is_numeric(&right) {
+                    verifier.builtin_instance("Number")
+                } else {
+                    Type::Any
+                }
+            },
+            Operator::Subtract | Operator::Multiply | Operator::Divide |
+            Operator::Remainder | Operator::Power => verifier.builtin_instance("Number"),
+            Operator::ShiftLeft | Operator::ShiftRight | Operator::BitwiseAnd |
+            Operator::BitwiseXor | Operator::BitwiseOr => verifier.builtin_instance("int"),
+            Operator::ShiftRightUnsigned => verifier.builtin_instance("uint"),
+            Operator::Lt | Operator::Gt | Operator::Le | Operator::Ge |
+            Operator::Equals | Operator::NotEquals | Operator::StrictEquals | Operator::StrictNotEquals |
+            Operator::Instanceof | Operator::In | Operator::NotIn => verifier.builtin_instance("Boolean"),
+            Operator::Is | Operator::IsNot | Operator::As => {
+                let result_type = if self.is_type_expression(&exp.right) {
+                    verifier.evaluate_type(&exp.right)
+                } else {
+                    let is_class = right.instance_symbol().is_some_and(|symbol| verifier.is_builtin(&symbol, "Class") || verifier.is_builtin(&symbol, "Object"));
+                    if !(right.is_any() || is_class || matches!(right, Type::Definition(..))) {
+                        verifier.report(&exp.right.location(), DiagnosticKind::NotAType, diagarg![exp.right.location().text()]);
+                    }
+                    Type::Any
+                };
+                if exp.operator == Operator::As { result_type } else { verifier.builtin_instance("Boolean") }
+            },
+            _ => if left == right { left } else { Type::Any },
+        }
+    }
+
+    fn is_destructuring_pattern(exp: &Rc<Expression>) -> bool {
+        match exp.as_ref() {
+            Expression::ArrayLiteral(_) | Expression::ObjectInitializer(_) => true,
+            Expression::Unary(UnaryExpression { operator: Operator::NonNull, expression, .. }) => Self::is_destructuring_pattern(expression),
+            _ => false,
+        }
+    }
+}
+
+impl Visitor for VerifierWalker<'_> {
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl {
+        match node.as_ref() {
+            Directive::ClassDefinition(defn) => {
+                self.frames.push(Frame::Class(self.verifier.definition_symbol(node)));
+                for exp in defn.extends_clause.iter().chain(defn.implements_clause.iter().flatten()) {
+                    self.mark_type_expression(exp);
+                }
+            },
+            Directive::InterfaceDefinition(defn) => {
+                self.frames.push(Frame::Class(self.verifier.definition_symbol(node)));
+                for exp in defn.extends_clause.iter().flatten() {
+                    self.mark_type_expression(exp);
+                }
+            },
+            Directive::EnumDefinition(defn) => {
+                self.frames.push(Frame::Class(self.verifier.definition_symbol(node)));
+                if let Some(exp) = &defn.as_clause {
+                    self.mark_type_expression(exp);
+                }
+            },
+            Directive::TypeDefinition(defn) => {
+                self.mark_type_expression(&defn.right);
+            },
+            _ => {},
+        }
+        VisitorControl::Continue
+    }
+
+    fn leave_directive(&mut self, node: &Rc<Directive>) {
+        match node.as_ref() {
+            Directive::ClassDefinition(_) | Directive::InterfaceDefinition(_) | Directive::EnumDefinition(_) => {
+                self.frames.pop();
+            },
+            Directive::VariableDefinition(defn) => {
+                self.check_variable_bindings(&defn.bindings);
+            },
+            Directive::ReturnStatement(stmt) => {
+                self.check_return(stmt);
+            },
+            _ => {},
+        }
+    }
+
+    fn leave_simple_variable_definition(&mut self, node: &Rc<SimpleVariableDefinition>) {
+        self.check_variable_bindings(&node.bindings);
+    }
+
+    fn visit_function_common(&mut self, node: &Rc<FunctionCommon>) -> VisitorControl {
+        let this_type = match self.frames.last() {
+            Some(Frame::Class(Some(symbol))) => Type::Instance(symbol.clone(), vec![]),
+            _ => Type::Any,
+        };
+        let result_type = node.signature.result_type.as_ref().map(|exp| self.verifier.evaluate_type(exp));
+        if let Some(exp) = &node.signature.result_type {
+            self.mark_type_expression(exp);
+        }
+        self.frames.push(Frame::Function { result_type, this_type });
+        VisitorControl::Continue
+    }
+
+    fn leave_function_common(&mut self, node: &Rc<FunctionCommon>) {
+        if let (Some(FunctionBody::Expression(exp)), Some((Some(result_type), _))) = (&node.body, self.function_frame()) {
+            if !matches!(result_type, Type::Void) {
+                self.check_coercion(exp, result_type);
+            }
+        }
+        self.frames.pop();
+    }
+
+    fn visit_typed_destructuring(&mut self, node: &TypedDestructuring) -> VisitorControl {
+        if let Some(type_annotation) = &node.type_annotation {
+            self.mark_type_expression(type_annotation);
+        }
+        VisitorControl::Continue
+    }
+
+    fn leave_parameter(&mut self, node: &Rc<Parameter>) {
+        if let (Some(type_annotation), Some(default_value)) = (Self::identifier_type_annotation(&node.destructuring), &node.default_value) {
+            self.check_coercion(default_value, &self.verifier.evaluate_type(&type_annotation));
+        }
+    }
+
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+        if self.type_expressions.contains(&NodeAsKey(node.clone())) {
+            self.verifier.evaluate_type_expression(node, true);
+            return VisitorControl::SkipChildren;
+        }
+        match node.as_ref() {
+            Expression::Binary(exp) if matches!(exp.operator, Operator::Is | Operator::IsNot | Operator::As) => {
+                if self.is_type_expression(&exp.right) {
+                    self.mark_type_expression(&exp.right);
+                }
+            },
+            Expression::VectorLiteral(exp) => {
+                self.mark_type_expression(&exp.element_type);
+            },
+            Expression::WithTypeArguments(exp) => {
+                for argument in &exp.arguments {
+                    self.mark_type_expression(argument);
+                }
+            },
+            _ => {},
+        }
+        VisitorControl::Continue
+    }
+
+    fn leave_expression(&mut self, node: &Rc<Expression>) {
+        if self.type_expressions.remove(&NodeAsKey(node.clone())) {
+            return;
+        }
+        let node_type = self.infer(node);
+        self.verifier.expression_types.set(node, Some(node_type));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    const BUILTINS: &str = r#"
+        package {
+            public dynamic class Object { public function toString(): String { return "" } }
+            public final class Number {}
+            public final class int {}
+            public final class uint {}
+            public final class Boolean {}
+            public final class String { public function get length(): int { return 0 } }
+            public dynamic class Array { public function push(...items): uint { return 0 } }
+            public final class Class {}
+            public final class Function {}
+            public final class Vector {}
+        }
+    "#;
+
+    fn verify(text: &str, options: VerifierOptions) -> Vec<String> {
+        verify_with_compiler_options(text, options, None)
+    }
+
+    fn verify_with_compiler_options(text: &str, options: VerifierOptions, compiler_options: Option<Rc<CompilerOptions>>) -> Vec<String> {
+        let builtins_cu = CompilationUnit::new(None, BUILTINS.into());
+        let builtins = ParserFacade(&builtins_cu, default()).parse_program();
+        let cu = CompilationUnit::new(None, text.into());
+        cu.set_compiler_options(compiler_options);
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(cu.nested_diagnostics().is_empty(), "{text}");
+        let mut verifier = Verifier::new(options);
+        verifier.verify_programs(&[builtins, program]);
+        assert!(builtins_cu.diagnostics().is_empty());
+        cu.diagnostics().iter().map(|diagnostic| {
+            format!("{} {}", diagnostic.location().text(), diagnostic.format_message_english())
+        }).collect()
+    }
+
+    #[test]
+    fn test_verify() {
+        let source = r#"
+            package p {
+                public interface I { function m(a: Number, b: String = ""): void; }
+                public class A implements I {
+                    public static const K: int = 0;
+                    public var x: Number;
+                    public function A(x: Number) { this.x = x }
+                    public function m(a: Number, b: String = ""): void { return a }
+                }
+                public class B extends A {
+                    public function B() { super(0) }
+                    public function f(...rest): [String] { return ["s"] }
+                }
+                public class G.<T> {}
+            }
+            import p.*;
+            var a: A = new A(1);
+            var b: B = a;
+            var i: I = new B();
+            var n: int = "text";
+            var s: String = a.x + "s";
+            var u: uint = i.m(1);
+            a.m(1, "s", 3);
+            i.m();
+            a.y;
+            A.K; A.x; B.K;
+            a.toString().length;
+            new A();
+            var t: [Number, String] = [1, "s"];
+            var f: function(Number): void = a.m;
+            var g: G.<Number, String>;
+            var v: Vector.<int> = new Vector.<int>();
+            var v2: Vector.<String> = v;
+            var q: a;
+            var w: ?Number = null, z: Number! = null;
+            n = v[0];
+            s = v[0];
+            b = a as B;
+            a is 3;
+            String(n);
+        "#;
+        assert_eq!(verify(source, default()), [
+            "a Return value must be undefined.",
+            "a Implicit coercion of a value of type 'A' to an unrelated type 'B'.",
+            "\"text\" Implicit coercion of a value of type 'String' to an unrelated type 'int'.",
+            "i.m(1) Implicit coercion of a value of type 'void' to an unrelated type 'uint'.",
+            "a.m(1, \"s\", 3) Incorrect number of arguments. Expected no more than 2.",
+            "i.m() Incorrect number of arguments. Expected 1.",
+            "y Access of possibly undefined property 'y' through a reference with static type 'A'.",
+            "x Access of possibly undefined property 'x' through a reference with static type 'Class'.",
+            "K Access of possibly undefined property 'K' through a reference with static type 'Class'.",
+            "new A() Incorrect number of arguments. Expected 1.",
+            "G.<Number, String> Incorrect number of type arguments. Expected 1.",
+            "v Implicit coercion of a value of type 'Vector.<int>' to an unrelated type 'Vector.<String>'.",
+            "a 'a' is not a type.",
+            "null Implicit coercion of a value of type 'null' to an unrelated type 'Number!'.",
+            "v[0] Implicit coercion of a value of type 'int' to an unrelated type 'String'.",
+            "3 '3' is not a type.",
+        ]);
+    }
+
+    #[test]
+    fn test_non_strict() {
+        let source = r#"
+            var n: Number = "text";
+            var m: n;
+            function f(): void { return 0 }
+        "#;
+        assert_eq!(verify(source, VerifierOptions { strict: false }), ["n 'n' is not a type."]);
+
+        // Compiler options take precedence over the verifier options.
+        let compiler_options = Rc::new(CompilerOptions { strict: Some(false), ..default() });
+        assert_eq!(verify_with_compiler_options(source, default(), Some(compiler_options)), ["n 'n' is not a type."]);
+    }
+
+    #[test]
+    fn test_without_builtins() {
+        let cu = CompilationUnit::new(None, r#"
+            var s: String = "text";
+            var n: int = s.length;
+            trace(s, n, s.charAt(n));
+            function f(): void { return 0 }
+        "#.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        Verifier::new(default()).verify_programs(&[program]);
+        assert!(cu.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_expression_types() {
+        let builtins_cu = CompilationUnit::new(None, BUILTINS.into());
+        let builtins = ParserFacade(&builtins_cu, default()).parse_program();
+        let cu = CompilationUnit::new(None, "var f: function(Number, ...): [?String] = null; f(1)[0]".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let mut verifier = Verifier::new(default());
+        verifier.verify_programs(&[builtins, program.clone()]);
+        assert!(cu.diagnostics().is_empty());
+
+        let Directive::VariableDefinition(defn) = program.directives[0].as_ref() else { panic!() };
+        let type_annotation = defn.bindings[0].destructuring.type_annotation.as_ref().unwrap();
+        assert_eq!(verifier.evaluate_type(type_annotation).to_string(), "function(Number, ...): [?String]");
+
+        let Directive::ExpressionStatement(stmt) = program.directives[1].as_ref() else { panic!() };
+        assert_eq!(verifier.expression_types().get(&stmt.expression).unwrap().to_string(), "?String");
+    }
+}
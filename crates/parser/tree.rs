@@ -162,4 +162,12 @@ mod css;
 pub use css::*;
 
 mod tree_semantics;
-pub use tree_semantics::*;
\ No newline at end of file
+pub use tree_semantics::*;
+
+// Traversal
+mod visitor;
+pub use visitor::*;
+mod visitor_mut;
+pub use visitor_mut::*;
+mod location_rebinder;
+pub use location_rebinder::*;
\ No newline at end of file
@@ -5,7 +5,7 @@ use serde::{Serialize, Deserialize};
 pub struct IncludeDirective {
     pub location: Location,
     pub source: String,
-    #[serde(skip)]
+    #[serde(default, skip_serializing_if = "serializes_locations_as_text", with = "compilation_unit_reference")]
     pub nested_compilation_unit: Rc<CompilationUnit>,
     pub nested_packages: Vec<Rc<PackageDefinition>>,
     pub nested_directives: Vec<Rc<Directive>>,
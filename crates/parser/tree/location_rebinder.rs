@@ -0,0 +1,437 @@
+use crate::ns::*;
+
+/// Rebinds the locations of a syntactic tree from a compilation unit
+/// to another compilation unit, shifting their offsets by a fixed amount.
+///
+/// Only locations belonging to the source compilation unit are rebound,
+/// so rebinding a node twice has no further effect and the locations of
+/// included compilation units are preserved. Shared nodes are copied
+/// on write, leaving the original tree unchanged.
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// let cu = CompilationUnit::new(None, "x = 10".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+///
+/// let new_cu = CompilationUnit::new(None, "  x = 10".into());
+/// let mut directive = program.directives[0].clone();
+/// LocationRebinder::new(&cu, &new_cu, 2).rebind_directive(&mut directive);
+/// assert_eq!(directive.location().text(), "x = 10");
+/// ```
+pub struct LocationRebinder {
+    from: Rc<CompilationUnit>,
+    to: Rc<CompilationUnit>,
+    shift: isize,
+}
+
+impl LocationRebinder {
+    pub fn new(from: &Rc<CompilationUnit>, to: &Rc<CompilationUnit>, shift: isize) -> Self {
+        Self {
+            from: from.clone(),
+            to: to.clone(),
+            shift,
+        }
+    }
+
+    /// Returns the location rebound to the target compilation unit,
+    /// or the location itself if it belongs to another compilation unit.
+    pub fn rebind(&self, location: &Location) -> Location {
+        if !Rc::ptr_eq(&location.compilation_unit, &self.from) {
+            return location.clone();
+        }
+        Location::with_offsets(&self.to, location.first_offset.saturating_add_signed(self.shift), location.last_offset.saturating_add_signed(self.shift))
+    }
+
+    fn rebind_mut(&self, location: &mut Location) {
+        *location = self.rebind(location);
+    }
+
+    fn rebind_pair(&self, pair: &mut (String, Location)) {
+        self.rebind_mut(&mut pair.1);
+    }
+
+    pub fn rebind_package_definition(&mut self, node: &mut Rc<PackageDefinition>) {
+        let _ = walk_package_definition_mut(self, node);
+    }
+
+    pub fn rebind_directive(&mut self, node: &mut Rc<Directive>) {
+        let _ = walk_directive_mut(self, node);
+    }
+
+    pub fn rebind_expression(&mut self, node: &mut Rc<Expression>) {
+        let _ = walk_expression_mut(self, node);
+    }
+
+    /// Returns a copy of a comment rebound to the target compilation unit.
+    pub fn rebind_comment(&self, comment: &Comment) -> Rc<Comment> {
+        Rc::new(Comment::new(comment.multiline, comment.content.borrow().clone(), self.rebind(&comment.location.borrow())))
+    }
+
+    /// Returns a copy of a diagnostic rebound to the target compilation unit.
+    pub fn rebind_diagnostic(&self, diagnostic: &Diagnostic) -> Diagnostic {
+        Diagnostic {
+            location: self.rebind(&diagnostic.location),
+            kind: diagnostic.kind,
+            is_warning: diagnostic.is_warning,
+            is_verify_error: diagnostic.is_verify_error,
+            arguments: diagnostic.arguments.clone(),
+            custom_kind: RefCell::new(diagnostic.custom_kind.borrow().clone()),
+        }
+    }
+
+    fn rebind_import_specifier(&self, specifier: &mut ImportSpecifier) {
+        match specifier {
+            ImportSpecifier::Wildcard(location) |
+            ImportSpecifier::Recursive(location) => self.rebind_mut(location),
+            ImportSpecifier::Identifier(name) => self.rebind_pair(name),
+        }
+    }
+
+    fn rebind_elements(&self, elements: &mut [Element]) {
+        for element in elements {
+            if let Element::Rest((_, location)) = element {
+                self.rebind_mut(location);
+            }
+        }
+    }
+
+    fn rebind_xml_tag_name(&self, name: &mut XmlTagName) {
+        if let XmlTagName::Name(name) = name {
+            self.rebind_pair(name);
+        }
+    }
+}
+
+impl VisitorMut for LocationRebinder {
+    fn visit_package_definition(&mut self, node: &mut Rc<PackageDefinition>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        for name in &mut node.name {
+            self.rebind_pair(name);
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_directive(&mut self, node: &mut Rc<Directive>) -> VisitorControl {
+        match Rc::make_mut(node) {
+            Directive::EmptyStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ExpressionStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::SuperStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::Block(_) => {},
+            Directive::LabeledStatement(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                self.rebind_pair(&mut drtv.label);
+            },
+            Directive::IfStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::SwitchStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::SwitchTypeStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::DoStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::WhileStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ForStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ForInStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::BreakStatement(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                if let Some(label) = &mut drtv.label {
+                    self.rebind_pair(label);
+                }
+            },
+            Directive::ContinueStatement(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                if let Some(label) = &mut drtv.label {
+                    self.rebind_pair(label);
+                }
+            },
+            Directive::WithStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ReturnStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ThrowStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::DefaultXmlNamespaceStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::TryStatement(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::Invalidated(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ConfigurationDirective(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::ImportDirective(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                if let Some(alias) = &mut drtv.alias {
+                    self.rebind_pair(alias);
+                }
+                for name in &mut drtv.package_name {
+                    self.rebind_pair(name);
+                }
+                self.rebind_import_specifier(&mut drtv.import_specifier);
+            },
+            Directive::UseNamespaceDirective(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::IncludeDirective(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::NormalConfigurationDirective(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                self.rebind_pair(&mut drtv.namespace);
+                self.rebind_pair(&mut drtv.constant_name);
+            },
+            Directive::PackageConcatDirective(drtv) => {
+                self.rebind_mut(&mut drtv.location);
+                for name in &mut drtv.package_name {
+                    self.rebind_pair(name);
+                }
+                self.rebind_import_specifier(&mut drtv.import_specifier);
+            },
+            Directive::DirectiveInjection(drtv) => self.rebind_mut(&mut drtv.location),
+            Directive::VariableDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_mut(&mut defn.kind.1);
+            },
+            Directive::FunctionDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                match &mut defn.name {
+                    FunctionName::Identifier(name) |
+                    FunctionName::Getter(name) |
+                    FunctionName::Setter(name) |
+                    FunctionName::Constructor(name) => self.rebind_pair(name),
+                }
+            },
+            Directive::ClassDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_pair(&mut defn.name);
+            },
+            Directive::EnumDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_pair(&mut defn.name);
+            },
+            Directive::InterfaceDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_pair(&mut defn.name);
+            },
+            Directive::TypeDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_pair(&mut defn.left);
+            },
+            Directive::NamespaceDefinition(defn) => {
+                self.rebind_mut(&mut defn.location);
+                self.rebind_pair(&mut defn.left);
+            },
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_expression(&mut self, node: &mut Rc<Expression>) -> VisitorControl {
+        match Rc::make_mut(node) {
+            // Visited as a qualified identifier.
+            Expression::QualifiedIdentifier(_) => {},
+            Expression::Paren(exp) => self.rebind_mut(&mut exp.location),
+            Expression::NullLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::BooleanLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::NumericLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::StringLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::ThisLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::RegExpLiteral(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Xml(exp) => self.rebind_mut(&mut exp.location),
+            Expression::XmlMarkup(exp) => self.rebind_mut(&mut exp.location),
+            Expression::XmlList(exp) => self.rebind_mut(&mut exp.location),
+            Expression::ArrayLiteral(exp) => {
+                self.rebind_mut(&mut exp.location);
+                self.rebind_elements(&mut exp.elements);
+            },
+            Expression::VectorLiteral(exp) => {
+                self.rebind_mut(&mut exp.location);
+                self.rebind_elements(&mut exp.elements);
+            },
+            Expression::ObjectInitializer(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Function(exp) => {
+                self.rebind_mut(&mut exp.location);
+                if let Some(name) = &mut exp.name {
+                    self.rebind_pair(name);
+                }
+            },
+            Expression::ImportMeta(exp) => self.rebind_mut(&mut exp.location),
+            Expression::New(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Member(exp) => self.rebind_mut(&mut exp.location),
+            Expression::ComputedMember(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Descendants(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Filter(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Super(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Call(exp) => self.rebind_mut(&mut exp.location),
+            Expression::WithTypeArguments(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Unary(exp) => self.rebind_mut(&mut exp.location),
+            Expression::OptionalChaining(exp) => self.rebind_mut(&mut exp.location),
+            Expression::OptionalChainingPlaceholder(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Binary(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Conditional(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Assignment(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Sequence(exp) => self.rebind_mut(&mut exp.location),
+            Expression::NullableType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::NonNullableType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::AnyType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::VoidType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::ArrayType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::TupleType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::FunctionType(exp) => self.rebind_mut(&mut exp.location),
+            Expression::Invalidated(exp) => self.rebind_mut(&mut exp.location),
+            Expression::ReservedNamespace(exp) => match exp {
+                ReservedNamespaceExpression::Public(location) |
+                ReservedNamespaceExpression::Private(location) |
+                ReservedNamespaceExpression::Protected(location) |
+                ReservedNamespaceExpression::Internal(location) => self.rebind_mut(location),
+            },
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_block(&mut self, node: &mut Block) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_qualified_identifier(&mut self, node: &mut QualifiedIdentifier) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        if let QualifiedIdentifierIdentifier::Id(id) = &mut node.id {
+            self.rebind_pair(id);
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_attribute(&mut self, node: &mut Attribute) -> VisitorControl {
+        match node {
+            Attribute::Metadata(_) |
+            Attribute::Expression(_) => {},
+            Attribute::Public(location) |
+            Attribute::Private(location) |
+            Attribute::Protected(location) |
+            Attribute::Internal(location) |
+            Attribute::Final(location) |
+            Attribute::Native(location) |
+            Attribute::Static(location) |
+            Attribute::Abstract(location) |
+            Attribute::Override(location) |
+            Attribute::Dynamic(location) => self.rebind_mut(location),
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_metadata(&mut self, node: &mut Rc<Metadata>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_pair(&mut node.name);
+        for entry in node.entries.iter_mut().flatten() {
+            let entry = Rc::make_mut(entry);
+            self.rebind_mut(&mut entry.location);
+            if let Some(key) = &mut entry.key {
+                self.rebind_pair(key);
+            }
+            match Rc::make_mut(&mut entry.value) {
+                MetadataValue::IdentifierString(value) |
+                MetadataValue::String(value) => self.rebind_pair(value),
+            }
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_asdoc(&mut self, node: &mut Rc<AsDoc>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        if let Some(main_body) = &mut node.main_body {
+            self.rebind_pair(main_body);
+        }
+        for (_, location) in &mut node.tags {
+            self.rebind_mut(location);
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_type_parameter(&mut self, node: &mut Rc<TypeParameter>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_pair(&mut node.name);
+        VisitorControl::Continue
+    }
+
+    fn visit_function_common(&mut self, node: &mut Rc<FunctionCommon>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_mut(&mut node.signature.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_parameter(&mut self, node: &mut Rc<Parameter>) -> VisitorControl {
+        self.rebind_mut(&mut Rc::make_mut(node).location);
+        VisitorControl::Continue
+    }
+
+    fn visit_function_type_parameter(&mut self, node: &mut Rc<FunctionTypeParameter>) -> VisitorControl {
+        self.rebind_mut(&mut Rc::make_mut(node).location);
+        VisitorControl::Continue
+    }
+
+    fn visit_typed_destructuring(&mut self, node: &mut TypedDestructuring) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_simple_variable_definition(&mut self, node: &mut Rc<SimpleVariableDefinition>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_mut(&mut node.kind.1);
+        VisitorControl::Continue
+    }
+
+    fn visit_initializer_field(&mut self, node: &mut Rc<InitializerField>) -> VisitorControl {
+        match Rc::make_mut(node) {
+            InitializerField::Field { name, .. } => self.rebind_mut(&mut name.1),
+            InitializerField::Rest((_, location)) => self.rebind_mut(location),
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_case(&mut self, node: &mut Case) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        for label in &mut node.labels {
+            match label {
+                CaseLabel::Case((_, location)) |
+                CaseLabel::Default(location) => self.rebind_mut(location),
+            }
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_type_case(&mut self, node: &mut TypeCase) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_catch_clause(&mut self, node: &mut CatchClause) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_finally_clause(&mut self, node: &mut FinallyClause) -> VisitorControl {
+        self.rebind_mut(&mut node.location);
+        VisitorControl::Continue
+    }
+
+    fn visit_xml_element(&mut self, node: &mut Rc<XmlElement>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_xml_tag_name(&mut node.name);
+        if let Some(name) = &mut node.closing_name {
+            self.rebind_xml_tag_name(name);
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_xml_attribute(&mut self, node: &mut Rc<XmlAttribute>) -> VisitorControl {
+        let node = Rc::make_mut(node);
+        self.rebind_mut(&mut node.location);
+        self.rebind_pair(&mut node.name);
+        if let XmlAttributeValue::Value(value) = &mut node.value {
+            self.rebind_pair(value);
+        }
+        VisitorControl::Continue
+    }
+
+    fn visit_xml_content(&mut self, node: &mut Rc<XmlContent>) -> VisitorControl {
+        match Rc::make_mut(node) {
+            XmlContent::Characters(content) |
+            XmlContent::Markup(content) => self.rebind_pair(content),
+            XmlContent::Element(_) |
+            XmlContent::Expression(_) => {},
+        }
+        VisitorControl::Continue
+    }
+}
@@ -4,6 +4,7 @@ use crate::ns::*;
 use serde::{Serialize, Deserialize};
 
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "SerializedMxml")]
 pub struct Mxml {
     pub location: Location,
     pub version: XmlVersion,
@@ -11,6 +12,108 @@ pub struct Mxml {
     pub content: Vec<Rc<MxmlContent>>,
 }
 
+/// `Mxml` as deserialized, before the namespace mappings of its
+/// elements, which are not serialized, are rebuilt from their
+/// `xmlns` attributes.
+#[derive(Deserialize)]
+struct SerializedMxml {
+    location: Location,
+    version: XmlVersion,
+    encoding: String,
+    content: Vec<Rc<MxmlContent>>,
+}
+
+impl From<SerializedMxml> for Mxml {
+    fn from(document: SerializedMxml) -> Self {
+        let mut content = document.content;
+        rebuild_namespaces(&mut content, &Rc::new(MxmlNamespace::new(None)));
+        Self {
+            location: document.location,
+            version: document.version,
+            encoding: document.encoding,
+            content,
+        }
+    }
+}
+
+fn rebuild_namespaces(content: &mut [Rc<MxmlContent>], parent: &Rc<MxmlNamespace>) {
+    for node in content {
+        let MxmlContent::Element(element) = Rc::make_mut(node) else {
+            continue;
+        };
+        let element = Rc::make_mut(element);
+        let namespace = Rc::new(MxmlNamespace::new(Some(parent)));
+        for attribute in element.attributes.iter().filter(|attribute| attribute.xmlns) {
+            if attribute.name.prefix.is_none() {
+                namespace.set(MxmlNamespace::DEFAULT_NAMESPACE, &attribute.value.0);
+            } else {
+                namespace.set(&attribute.name.name, &attribute.value.0);
+            }
+        }
+        if let Some(content) = element.content.as_mut() {
+            rebuild_namespaces(content, &namespace);
+        }
+        element.namespace = namespace;
+    }
+}
+
+impl Mxml {
+    /// The style sheets of the `fx:Style` elements of the document, in document order.
+    pub fn css_documents(&self) -> Vec<Rc<CssDocument>> {
+        let mut documents = vec![];
+        for content in &self.content {
+            if let MxmlContent::Element(element) = content.as_ref() {
+                element.collect_css_documents(&mut documents);
+            }
+        }
+        documents
+    }
+
+    /// The view states declared by the `states` property element
+    /// of the root element, in document order.
+    pub fn states(&self) -> Vec<MxmlState> {
+        let mut states = vec![];
+        let Some(root) = self.root() else {
+            return states;
+        };
+        for states_element in root.child_elements() {
+            if states_element.name.name != "states" || states_element.name.state.is_some() {
+                continue;
+            }
+            for state in states_element.child_elements() {
+                if state.name.name != "State" {
+                    continue;
+                }
+                let Some(name) = state.attribute("name") else {
+                    continue;
+                };
+                states.push(MxmlState {
+                    name: (name.value.0.trim().to_owned(), name.value.1.clone()),
+                    state_groups: state.attribute("stateGroups").map_or(vec![], |groups| groups.list_items()),
+                });
+            }
+        }
+        states
+    }
+
+    /// The root element of the document.
+    pub fn root(&self) -> Option<Rc<MxmlElement>> {
+        self.content.iter().find_map(|content| match content.as_ref() {
+            MxmlContent::Element(element) => Some(element.clone()),
+            _ => None,
+        })
+    }
+}
+
+/// A view state declared by an `s:State` element.
+#[derive(Clone)]
+pub struct MxmlState {
+    /// The value of the `name` attribute. The location data includes the quotes.
+    pub name: (String, Location),
+    /// The items of the `stateGroups` attribute.
+    pub state_groups: Vec<(String, Location)>,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum XmlVersion {
     /// XML version 1.0.
@@ -28,9 +131,51 @@ pub struct MxmlElement {
     pub namespace: Rc<MxmlNamespace>,
     pub content: Option<Vec<Rc<MxmlContent>>>,
     pub closing_name: Option<MxmlName>,
+    /// The ActionScript code of an `fx:Script` or `mx:Script` element.
+    pub script: Option<Rc<MxmlScript>>,
+    /// The style sheets of an `fx:Style` or `mx:Style` element.
+    pub style: Option<Rc<MxmlStyle>>,
+    /// The literal text and binding expressions of the content of an element
+    /// whose content consists of text only, excluding script and style elements.
+    pub text_value: Option<Vec<MxmlValueSegment>>,
 }
 
 impl MxmlElement {
+    /// Indicates whether the element is the MXML language element
+    /// of the given local name, such as `fx:Script` for `"Script"`,
+    /// in either the MXML 2009 or the MXML 2006 namespace.
+    pub fn is_language_element(&self, local_name: &str) -> bool {
+        self.name.name == local_name && self.name.resolve_prefix(&self.namespace).is_ok_and(|uri| {
+            uri == MxmlNamespace::MXML_2009 || uri == MxmlNamespace::MXML_2006
+        })
+    }
+
+    /// Finds an unprefixed attribute that is not state-specific.
+    pub fn attribute(&self, name: &str) -> Option<&Rc<MxmlAttribute>> {
+        self.attributes.iter().find(|attribute| {
+            !attribute.xmlns && attribute.name.prefix.is_none() && attribute.name.state.is_none() && attribute.name.name == name
+        })
+    }
+
+    /// The child elements, in document order.
+    pub fn child_elements(&self) -> Vec<Rc<MxmlElement>> {
+        self.content.iter().flatten().filter_map(|content| match content.as_ref() {
+            MxmlContent::Element(element) => Some(element.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    fn collect_css_documents(&self, documents: &mut Vec<Rc<CssDocument>>) {
+        if let Some(style) = &self.style {
+            documents.extend(style.documents.iter().cloned());
+        }
+        for content in self.content.iter().flatten() {
+            if let MxmlContent::Element(element) = content.as_ref() {
+                element.collect_css_documents(documents);
+            }
+        }
+    }
+
     pub fn inner_text(&self) -> String {
         let mut j = String::new();
         if let Some(c) = self.content.as_ref() {
@@ -42,6 +187,35 @@ impl MxmlElement {
     }
 }
 
+/// The ActionScript code of an `fx:Script` element, parsed as
+/// directives of a class block.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MxmlScript {
+    /// The value of the `source` attribute, referring to an external file.
+    pub source: Option<String>,
+    /// The compilation unit of the file referred to by the `source` attribute.
+    #[serde(default, skip_serializing_if = "serializes_locations_as_text", with = "optional_compilation_unit_reference")]
+    pub nested_compilation_unit: Option<Rc<CompilationUnit>>,
+    /// The directives of the external file, if any, followed by the directives
+    /// of the character and CDATA content of the element, whose locations
+    /// point into the MXML file.
+    pub directives: Vec<Rc<Directive>>,
+}
+
+/// The style sheets of an `fx:Style` element.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MxmlStyle {
+    /// The value of the `source` attribute, referring to an external CSS file.
+    pub source: Option<String>,
+    /// The compilation unit of the file referred to by the `source` attribute.
+    #[serde(default, skip_serializing_if = "serializes_locations_as_text", with = "optional_compilation_unit_reference")]
+    pub nested_compilation_unit: Option<Rc<CompilationUnit>>,
+    /// The style sheet of the external file, if any, followed by a style sheet
+    /// for each character or CDATA chunk of the element content, whose locations
+    /// point into the MXML file.
+    pub documents: Vec<Rc<CssDocument>>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MxmlAttribute {
     pub location: Location,
@@ -50,6 +224,62 @@ pub struct MxmlAttribute {
     pub name: MxmlName,
     /// Attribute value. The location data includes the quotes.
     pub value: (String, Location),
+    /// The attribute value as literal text and binding expressions,
+    /// or as the code of an event handler.
+    pub typed_value: MxmlAttributeValue,
+}
+
+impl MxmlAttribute {
+    /// The items of a comma-separated attribute value, such as the state names
+    /// of an `includeIn` attribute, with surrounding whitespace removed.
+    /// Empty items are excluded.
+    pub fn list_items(&self) -> Vec<(String, Location)> {
+        let location = &self.value.1;
+        let source = location.text();
+        // Exclude the quotes
+        let first_offset = location.first_offset() + if source.is_empty() { 0 } else { 1 };
+        let raw = source.get(1..source.len().saturating_sub(1)).unwrap_or_default();
+        let mut items = vec![];
+        let mut item_offset = 0;
+        for item in raw.split(',') {
+            let leading = item.len() - item.trim_start().len();
+            let trimmed = item.trim();
+            if !trimmed.is_empty() {
+                let item_first = first_offset + item_offset + leading;
+                items.push((unescape_xml(trimmed), Location::with_offsets(&location.compilation_unit(), item_first, item_first + trimmed.len())));
+            }
+            item_offset += item.len() + 1;
+        }
+        items
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MxmlAttributeValue {
+    /// Literal text and binding expressions. The segments of a `xmlns`
+    /// or `xmlns:` attribute consist of its literal value only.
+    Text(Vec<MxmlValueSegment>),
+    /// The directives of an event handler attribute, such as `click="onClick(event)"`.
+    EventHandler(Vec<Rc<Directive>>),
+}
+
+/// A segment of MXML text that may contain binding expressions.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MxmlValueSegment {
+    /// Literal text, with character references and
+    /// escaped braces (`\{` and `\}`) unescaped.
+    Literal((String, Location)),
+    Binding(Rc<MxmlBinding>),
+}
+
+/// A binding expression, as in `{model.name}` or `@{model.name}`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MxmlBinding {
+    /// Location of the binding, including the braces and the `@` character.
+    pub location: Location,
+    /// Indicates whether the binding is a two-way binding, as in `@{model.name}`.
+    pub two_way: bool,
+    pub expression: Rc<Expression>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -57,7 +287,11 @@ pub struct MxmlName {
     pub location: Location,
     /// The unresolved prefix of the name.
     pub prefix: Option<String>,
+    /// The local name, excluding the state of a state-specific name.
     pub name: String,
+    /// The state or state group of a state-specific name,
+    /// such as `over` in `label.over`.
+    pub state: Option<(String, Location)>,
 }
 
 impl MxmlName {
@@ -82,7 +316,7 @@ impl MxmlName {
     }
 
     pub fn equals_name(&self, other: &Self, namespace: &Rc<MxmlNamespace>) -> Result<bool, MxmlNameError> {
-        if self.name != other.name {
+        if self.name != other.name || self.state.as_ref().map(|(state, _)| state) != other.state.as_ref().map(|(state, _)| state) {
             return Ok(false);
         }
         let p1 = self.resolve_prefix(namespace)?;
@@ -92,6 +326,10 @@ impl MxmlName {
 
     pub fn to_string(&self, namespace: &Rc<MxmlNamespace>) -> String {
         self.resolve_name(namespace).map(|(uri, localname)| {
+            let localname = match &self.state {
+                Some((state, _)) => format!("{localname}.{state}"),
+                None => localname,
+            };
             if uri.is_empty() {
                 return localname;
             }
@@ -161,6 +399,12 @@ impl MxmlNamespace {
     /// Returns the prefix used for the default XML namespace.
     pub const DEFAULT_NAMESPACE: &'static str = "";
 
+    /// The MXML 2009 language namespace, usually bound to the `fx` prefix.
+    pub const MXML_2009: &'static str = "http://ns.adobe.com/mxml/2009";
+
+    /// The MXML 2006 language namespace of Flex 3, usually bound to the `mx` prefix.
+    pub const MXML_2006: &'static str = "http://www.adobe.com/2006/mxml";
+
     /// Constructs an empty set of namespace mappings.
     pub fn new(parent: Option<&Rc<MxmlNamespace>>) -> Self {
         let mut ns = Self {
@@ -133,8 +133,6 @@ impl NumericLiteral {
 mod tests {
     #[allow(unused)]
     use crate::ns::*;
-    #[allow(unused)]
-    use std::rc::Rc;
 
     #[test]
     fn test_minimum_maximum() {
@@ -0,0 +1,1036 @@
+use crate::ns::*;
+use std::ops::ControlFlow;
+
+/// Result of entering a node through a `Visitor` or `VisitorMut`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VisitorControl {
+    /// Visits the children of the node.
+    Continue,
+    /// Does not visit the children of the node, but continues
+    /// the traversal with its siblings.
+    SkipChildren,
+    /// Stops the entire traversal.
+    Stop,
+}
+
+/// Read-only traversal over the syntactic tree.
+///
+/// Every node type has a `visit_*` method, invoked before its children
+/// are walked, and a `leave_*` method, invoked after its children are walked.
+/// All methods have default implementations, so an implementor only overrides
+/// the node types it is interested in.
+///
+/// A traversal is started through one of the `walk_*` functions, such as
+/// `walk_program()`, which return `ControlFlow::Break` if the traversal
+/// was stopped through `VisitorControl::Stop`.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// #[derive(Default)]
+/// struct CallCounter(usize);
+///
+/// impl Visitor for CallCounter {
+///     fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+///         if matches!(node.as_ref(), Expression::Call(_)) {
+///             self.0 += 1;
+///         }
+///         VisitorControl::Continue
+///     }
+/// }
+///
+/// let cu = CompilationUnit::new(None, "f(g(), h())".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let mut counter = CallCounter::default();
+/// let _ = walk_program(&mut counter, &program);
+/// assert_eq!(counter.0, 3);
+/// ```
+#[allow(unused_variables)]
+pub trait Visitor {
+    fn visit_program(&mut self, node: &Rc<Program>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_program(&mut self, node: &Rc<Program>) {}
+    fn visit_package_definition(&mut self, node: &Rc<PackageDefinition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_package_definition(&mut self, node: &Rc<PackageDefinition>) {}
+    fn visit_directive(&mut self, node: &Rc<Directive>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_directive(&mut self, node: &Rc<Directive>) {}
+    fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_expression(&mut self, node: &Rc<Expression>) {}
+    fn visit_block(&mut self, node: &Block) -> VisitorControl { VisitorControl::Continue }
+    fn leave_block(&mut self, node: &Block) {}
+    fn visit_qualified_identifier(&mut self, node: &QualifiedIdentifier) -> VisitorControl { VisitorControl::Continue }
+    fn leave_qualified_identifier(&mut self, node: &QualifiedIdentifier) {}
+    fn visit_attribute(&mut self, node: &Attribute) -> VisitorControl { VisitorControl::Continue }
+    fn leave_attribute(&mut self, node: &Attribute) {}
+    fn visit_metadata(&mut self, node: &Rc<Metadata>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_metadata(&mut self, node: &Rc<Metadata>) {}
+    fn visit_asdoc(&mut self, node: &Rc<AsDoc>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_asdoc(&mut self, node: &Rc<AsDoc>) {}
+    fn visit_type_parameter(&mut self, node: &Rc<TypeParameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_type_parameter(&mut self, node: &Rc<TypeParameter>) {}
+    fn visit_function_common(&mut self, node: &Rc<FunctionCommon>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_function_common(&mut self, node: &Rc<FunctionCommon>) {}
+    fn visit_parameter(&mut self, node: &Rc<Parameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_parameter(&mut self, node: &Rc<Parameter>) {}
+    fn visit_function_type_parameter(&mut self, node: &Rc<FunctionTypeParameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_function_type_parameter(&mut self, node: &Rc<FunctionTypeParameter>) {}
+    fn visit_typed_destructuring(&mut self, node: &TypedDestructuring) -> VisitorControl { VisitorControl::Continue }
+    fn leave_typed_destructuring(&mut self, node: &TypedDestructuring) {}
+    fn visit_variable_binding(&mut self, node: &Rc<VariableBinding>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_variable_binding(&mut self, node: &Rc<VariableBinding>) {}
+    fn visit_simple_variable_definition(&mut self, node: &Rc<SimpleVariableDefinition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_simple_variable_definition(&mut self, node: &Rc<SimpleVariableDefinition>) {}
+    fn visit_initializer_field(&mut self, node: &Rc<InitializerField>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_initializer_field(&mut self, node: &Rc<InitializerField>) {}
+    fn visit_case(&mut self, node: &Case) -> VisitorControl { VisitorControl::Continue }
+    fn leave_case(&mut self, node: &Case) {}
+    fn visit_type_case(&mut self, node: &TypeCase) -> VisitorControl { VisitorControl::Continue }
+    fn leave_type_case(&mut self, node: &TypeCase) {}
+    fn visit_catch_clause(&mut self, node: &CatchClause) -> VisitorControl { VisitorControl::Continue }
+    fn leave_catch_clause(&mut self, node: &CatchClause) {}
+    fn visit_finally_clause(&mut self, node: &FinallyClause) -> VisitorControl { VisitorControl::Continue }
+    fn leave_finally_clause(&mut self, node: &FinallyClause) {}
+    fn visit_xml_element(&mut self, node: &Rc<XmlElement>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_element(&mut self, node: &Rc<XmlElement>) {}
+    fn visit_xml_attribute(&mut self, node: &Rc<XmlAttribute>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_attribute(&mut self, node: &Rc<XmlAttribute>) {}
+    fn visit_xml_content(&mut self, node: &Rc<XmlContent>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_content(&mut self, node: &Rc<XmlContent>) {}
+    fn visit_mxml(&mut self, node: &Rc<Mxml>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml(&mut self, node: &Rc<Mxml>) {}
+    fn visit_mxml_element(&mut self, node: &Rc<MxmlElement>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_element(&mut self, node: &Rc<MxmlElement>) {}
+    fn visit_mxml_attribute(&mut self, node: &Rc<MxmlAttribute>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_attribute(&mut self, node: &Rc<MxmlAttribute>) {}
+    fn visit_mxml_content(&mut self, node: &Rc<MxmlContent>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_content(&mut self, node: &Rc<MxmlContent>) {}
+    fn visit_css_document(&mut self, node: &Rc<CssDocument>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_document(&mut self, node: &Rc<CssDocument>) {}
+    fn visit_css_directive(&mut self, node: &Rc<CssDirective>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_directive(&mut self, node: &Rc<CssDirective>) {}
+    fn visit_css_rule(&mut self, node: &CssRule) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_rule(&mut self, node: &CssRule) {}
+    fn visit_css_selector(&mut self, node: &Rc<CssSelector>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_selector(&mut self, node: &Rc<CssSelector>) {}
+    fn visit_css_selector_condition(&mut self, node: &Rc<CssSelectorCondition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_selector_condition(&mut self, node: &Rc<CssSelectorCondition>) {}
+    fn visit_css_property(&mut self, node: &Rc<CssProperty>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_property(&mut self, node: &Rc<CssProperty>) {}
+    fn visit_css_property_value(&mut self, node: &Rc<CssPropertyValue>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_property_value(&mut self, node: &Rc<CssPropertyValue>) {}
+    fn visit_css_media_query_condition(&mut self, node: &Rc<CssMediaQueryCondition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_media_query_condition(&mut self, node: &Rc<CssMediaQueryCondition>) {}
+}
+
+/// Invokes a `visit_*` method and returns early from the enclosing
+/// walk function unless the children are to be visited.
+pub(crate) macro enter_node($visitor:expr, $node:expr, $visit:ident, $leave:ident) {
+    match $visitor.$visit($node) {
+        VisitorControl::Continue => {},
+        VisitorControl::SkipChildren => {
+            $visitor.$leave($node);
+            return ControlFlow::Continue(());
+        },
+        VisitorControl::Stop => {
+            return ControlFlow::Break(());
+        },
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Program>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_program, leave_program);
+    for pckg in &node.packages {
+        walk_package_definition(visitor, pckg)?;
+    }
+    walk_directives(visitor, &node.directives)?;
+    visitor.leave_program(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_package_definition<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<PackageDefinition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_package_definition, leave_package_definition);
+    if let Some(asdoc) = &node.asdoc {
+        walk_asdoc(visitor, asdoc)?;
+    }
+    walk_block(visitor, &node.block)?;
+    visitor.leave_package_definition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_directives<V: Visitor + ?Sized>(visitor: &mut V, list: &[Rc<Directive>]) -> ControlFlow<()> {
+    for directive in list {
+        walk_directive(visitor, directive)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_expressions<V: Visitor + ?Sized>(visitor: &mut V, list: &[Rc<Expression>]) -> ControlFlow<()> {
+    for exp in list {
+        walk_expression(visitor, exp)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, node: &Block) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_block, leave_block);
+    walk_directives(visitor, &node.directives)?;
+    visitor.leave_block(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_annotations<V: Visitor + ?Sized>(visitor: &mut V, asdoc: &Option<Rc<AsDoc>>, attributes: &[Attribute]) -> ControlFlow<()> {
+    if let Some(asdoc) = asdoc {
+        walk_asdoc(visitor, asdoc)?;
+    }
+    for attribute in attributes {
+        walk_attribute(visitor, attribute)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_type_parameters<V: Visitor + ?Sized>(visitor: &mut V, list: &Option<Vec<Rc<TypeParameter>>>) -> ControlFlow<()> {
+    for type_parameter in list.iter().flatten() {
+        walk_type_parameter(visitor, type_parameter)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_directive<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Directive>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_directive, leave_directive);
+    match node.as_ref() {
+        Directive::EmptyStatement(_) |
+        Directive::BreakStatement(_) |
+        Directive::ContinueStatement(_) |
+        Directive::ImportDirective(_) |
+        Directive::PackageConcatDirective(_) |
+        Directive::Invalidated(_) => {},
+        Directive::ExpressionStatement(stmt) => {
+            walk_expression(visitor, &stmt.expression)?;
+        },
+        Directive::SuperStatement(stmt) => {
+            walk_expressions(visitor, &stmt.arguments)?;
+        },
+        Directive::Block(block) => {
+            walk_block(visitor, block)?;
+        },
+        Directive::LabeledStatement(stmt) => {
+            walk_directive(visitor, &stmt.substatement)?;
+        },
+        Directive::IfStatement(stmt) => {
+            walk_expression(visitor, &stmt.test)?;
+            walk_directive(visitor, &stmt.consequent)?;
+            if let Some(alternative) = &stmt.alternative {
+                walk_directive(visitor, alternative)?;
+            }
+        },
+        Directive::SwitchStatement(stmt) => {
+            walk_expression(visitor, &stmt.discriminant)?;
+            for case in &stmt.cases {
+                walk_case(visitor, case)?;
+            }
+        },
+        Directive::SwitchTypeStatement(stmt) => {
+            walk_expression(visitor, &stmt.discriminant)?;
+            for case in &stmt.cases {
+                walk_type_case(visitor, case)?;
+            }
+        },
+        Directive::DoStatement(stmt) => {
+            walk_directive(visitor, &stmt.body)?;
+            walk_expression(visitor, &stmt.test)?;
+        },
+        Directive::WhileStatement(stmt) => {
+            walk_expression(visitor, &stmt.test)?;
+            walk_directive(visitor, &stmt.body)?;
+        },
+        Directive::ForStatement(stmt) => {
+            match &stmt.init {
+                Some(ForInitializer::Expression(exp)) => {
+                    walk_expression(visitor, exp)?;
+                },
+                Some(ForInitializer::VariableDefinition(defn)) => {
+                    walk_simple_variable_definition(visitor, defn)?;
+                },
+                None => {},
+            }
+            if let Some(test) = &stmt.test {
+                walk_expression(visitor, test)?;
+            }
+            if let Some(update) = &stmt.update {
+                walk_expression(visitor, update)?;
+            }
+            walk_directive(visitor, &stmt.body)?;
+        },
+        Directive::ForInStatement(stmt) => {
+            match &stmt.left {
+                ForInBinding::Expression(exp) => {
+                    walk_expression(visitor, exp)?;
+                },
+                ForInBinding::VariableDefinition(defn) => {
+                    walk_simple_variable_definition(visitor, defn)?;
+                },
+            }
+            walk_expression(visitor, &stmt.right)?;
+            walk_directive(visitor, &stmt.body)?;
+        },
+        Directive::WithStatement(stmt) => {
+            walk_expression(visitor, &stmt.object)?;
+            walk_directive(visitor, &stmt.body)?;
+        },
+        Directive::ReturnStatement(stmt) => {
+            if let Some(exp) = &stmt.expression {
+                walk_expression(visitor, exp)?;
+            }
+        },
+        Directive::ThrowStatement(stmt) => {
+            walk_expression(visitor, &stmt.expression)?;
+        },
+        Directive::DefaultXmlNamespaceStatement(stmt) => {
+            walk_expression(visitor, &stmt.right)?;
+        },
+        Directive::TryStatement(stmt) => {
+            walk_block(visitor, &stmt.block)?;
+            for catch_clause in &stmt.catch_clauses {
+                walk_catch_clause(visitor, catch_clause)?;
+            }
+            if let Some(finally_clause) = &stmt.finally_clause {
+                walk_finally_clause(visitor, finally_clause)?;
+            }
+        },
+        Directive::ConfigurationDirective(drtv) => {
+            walk_directive(visitor, &drtv.directive)?;
+        },
+        Directive::UseNamespaceDirective(drtv) => {
+            walk_expression(visitor, &drtv.expression)?;
+        },
+        Directive::IncludeDirective(drtv) => {
+            for pckg in &drtv.nested_packages {
+                walk_package_definition(visitor, pckg)?;
+            }
+            walk_directives(visitor, &drtv.nested_directives)?;
+        },
+        Directive::NormalConfigurationDirective(drtv) => {
+            walk_directive(visitor, &drtv.directive)?;
+        },
+        Directive::DirectiveInjection(drtv) => {
+            let directives = drtv.directives.borrow().clone();
+            walk_directives(visitor, &directives)?;
+        },
+        Directive::VariableDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            for binding in &defn.bindings {
+                walk_variable_binding(visitor, binding)?;
+            }
+        },
+        Directive::FunctionDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            walk_function_common(visitor, &defn.common)?;
+        },
+        Directive::ClassDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            walk_type_parameters(visitor, &defn.type_parameters)?;
+            if let Some(exp) = &defn.extends_clause {
+                walk_expression(visitor, exp)?;
+            }
+            if let Some(list) = &defn.implements_clause {
+                walk_expressions(visitor, list)?;
+            }
+            walk_block(visitor, &defn.block)?;
+        },
+        Directive::EnumDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            if let Some(exp) = &defn.as_clause {
+                walk_expression(visitor, exp)?;
+            }
+            walk_block(visitor, &defn.block)?;
+        },
+        Directive::InterfaceDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            walk_type_parameters(visitor, &defn.type_parameters)?;
+            if let Some(list) = &defn.extends_clause {
+                walk_expressions(visitor, list)?;
+            }
+            walk_block(visitor, &defn.block)?;
+        },
+        Directive::TypeDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            walk_expression(visitor, &defn.right)?;
+        },
+        Directive::NamespaceDefinition(defn) => {
+            walk_annotations(visitor, &defn.asdoc, &defn.attributes)?;
+            if let Some(exp) = &defn.right {
+                walk_expression(visitor, exp)?;
+            }
+        },
+    }
+    visitor.leave_directive(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_elements<V: Visitor + ?Sized>(visitor: &mut V, list: &[Element]) -> ControlFlow<()> {
+    for element in list {
+        match element {
+            Element::Elision => {},
+            Element::Expression(exp) |
+            Element::Rest((exp, _)) => {
+                walk_expression(visitor, exp)?;
+            },
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Expression>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_expression, leave_expression);
+    match node.as_ref() {
+        Expression::NullLiteral(_) |
+        Expression::BooleanLiteral(_) |
+        Expression::NumericLiteral(_) |
+        Expression::StringLiteral(_) |
+        Expression::ThisLiteral(_) |
+        Expression::RegExpLiteral(_) |
+        Expression::XmlMarkup(_) |
+        Expression::ImportMeta(_) |
+        Expression::OptionalChainingPlaceholder(_) |
+        Expression::AnyType(_) |
+        Expression::VoidType(_) |
+        Expression::Invalidated(_) |
+        Expression::ReservedNamespace(_) => {},
+        Expression::QualifiedIdentifier(id) => {
+            walk_qualified_identifier(visitor, id)?;
+        },
+        Expression::Paren(exp) => {
+            walk_expression(visitor, &exp.expression)?;
+        },
+        Expression::Xml(exp) => {
+            walk_xml_element(visitor, &exp.element)?;
+        },
+        Expression::XmlList(exp) => {
+            for content in &exp.content {
+                walk_xml_content(visitor, content)?;
+            }
+        },
+        Expression::ArrayLiteral(exp) => {
+            if let Some(asdoc) = &exp.asdoc {
+                walk_asdoc(visitor, asdoc)?;
+            }
+            walk_elements(visitor, &exp.elements)?;
+        },
+        Expression::VectorLiteral(exp) => {
+            walk_expression(visitor, &exp.element_type)?;
+            walk_elements(visitor, &exp.elements)?;
+        },
+        Expression::ObjectInitializer(exp) => {
+            for field in &exp.fields {
+                walk_initializer_field(visitor, field)?;
+            }
+        },
+        Expression::Function(exp) => {
+            walk_function_common(visitor, &exp.common)?;
+        },
+        Expression::New(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            if let Some(arguments) = &exp.arguments {
+                walk_expressions(visitor, arguments)?;
+            }
+        },
+        Expression::Member(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_qualified_identifier(visitor, &exp.identifier)?;
+        },
+        Expression::ComputedMember(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            if let Some(asdoc) = &exp.asdoc {
+                walk_asdoc(visitor, asdoc)?;
+            }
+            walk_expression(visitor, &exp.key)?;
+        },
+        Expression::Descendants(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_qualified_identifier(visitor, &exp.identifier)?;
+        },
+        Expression::Filter(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_expression(visitor, &exp.test)?;
+        },
+        Expression::Super(exp) => {
+            if let Some(object) = &exp.object {
+                walk_expressions(visitor, object)?;
+            }
+        },
+        Expression::Call(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_expressions(visitor, &exp.arguments)?;
+        },
+        Expression::WithTypeArguments(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_expressions(visitor, &exp.arguments)?;
+        },
+        Expression::Unary(exp) => {
+            walk_expression(visitor, &exp.expression)?;
+        },
+        Expression::OptionalChaining(exp) => {
+            walk_expression(visitor, &exp.base)?;
+            walk_expression(visitor, &exp.expression)?;
+        },
+        Expression::Binary(exp) => {
+            walk_expression(visitor, &exp.left)?;
+            walk_expression(visitor, &exp.right)?;
+        },
+        Expression::Conditional(exp) => {
+            walk_expression(visitor, &exp.test)?;
+            walk_expression(visitor, &exp.consequent)?;
+            walk_expression(visitor, &exp.alternative)?;
+        },
+        Expression::Assignment(exp) => {
+            walk_expression(visitor, &exp.left)?;
+            walk_expression(visitor, &exp.right)?;
+        },
+        Expression::Sequence(exp) => {
+            walk_expression(visitor, &exp.left)?;
+            walk_expression(visitor, &exp.right)?;
+        },
+        Expression::NullableType(exp) => {
+            walk_expression(visitor, &exp.base)?;
+        },
+        Expression::NonNullableType(exp) => {
+            walk_expression(visitor, &exp.base)?;
+        },
+        Expression::ArrayType(exp) => {
+            walk_expression(visitor, &exp.expression)?;
+        },
+        Expression::TupleType(exp) => {
+            walk_expressions(visitor, &exp.expressions)?;
+        },
+        Expression::FunctionType(exp) => {
+            for parameter in &exp.parameters {
+                walk_function_type_parameter(visitor, parameter)?;
+            }
+            if let Some(result_type) = &exp.result_type {
+                walk_expression(visitor, result_type)?;
+            }
+        },
+    }
+    visitor.leave_expression(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_qualified_identifier<V: Visitor + ?Sized>(visitor: &mut V, node: &QualifiedIdentifier) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_qualified_identifier, leave_qualified_identifier);
+    if let Some(qualifier) = &node.qualifier {
+        walk_expression(visitor, qualifier)?;
+    }
+    if let QualifiedIdentifierIdentifier::Brackets(exp) = &node.id {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_qualified_identifier(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_attribute<V: Visitor + ?Sized>(visitor: &mut V, node: &Attribute) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_attribute, leave_attribute);
+    match node {
+        Attribute::Metadata(metadata) => {
+            walk_metadata(visitor, metadata)?;
+        },
+        Attribute::Expression(exp) => {
+            walk_expression(visitor, exp)?;
+        },
+        _ => {},
+    }
+    visitor.leave_attribute(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_metadata<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Metadata>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_metadata, leave_metadata);
+    if let Some(asdoc) = &node.asdoc {
+        walk_asdoc(visitor, asdoc)?;
+    }
+    visitor.leave_metadata(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_asdoc<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<AsDoc>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_asdoc, leave_asdoc);
+    for (tag, _) in &node.tags {
+        match tag {
+            AsDocTag::Copy(reference) |
+            AsDocTag::See { reference, .. } => {
+                if let Some(base) = &reference.base {
+                    walk_expression(visitor, base)?;
+                }
+                if let Some(id) = &reference.instance_property {
+                    walk_qualified_identifier(visitor, id)?;
+                }
+            },
+            AsDocTag::EventType(exp) => {
+                walk_expression(visitor, exp)?;
+            },
+            AsDocTag::Throws { class_reference, .. } => {
+                walk_expression(visitor, class_reference)?;
+            },
+            _ => {},
+        }
+    }
+    visitor.leave_asdoc(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_type_parameter<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<TypeParameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_type_parameter, leave_type_parameter);
+    visitor.leave_type_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_function_common<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<FunctionCommon>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_function_common, leave_function_common);
+    for parameter in &node.signature.parameters {
+        walk_parameter(visitor, parameter)?;
+    }
+    if let Some(result_type) = &node.signature.result_type {
+        walk_expression(visitor, result_type)?;
+    }
+    match &node.body {
+        Some(FunctionBody::Expression(exp)) => {
+            walk_expression(visitor, exp)?;
+        },
+        Some(FunctionBody::Block(block)) => {
+            walk_block(visitor, block)?;
+        },
+        None => {},
+    }
+    visitor.leave_function_common(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_parameter<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Parameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_parameter, leave_parameter);
+    walk_typed_destructuring(visitor, &node.destructuring)?;
+    if let Some(exp) = &node.default_value {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_function_type_parameter<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<FunctionTypeParameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_function_type_parameter, leave_function_type_parameter);
+    if let Some(exp) = &node.type_expression {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_function_type_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_typed_destructuring<V: Visitor + ?Sized>(visitor: &mut V, node: &TypedDestructuring) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_typed_destructuring, leave_typed_destructuring);
+    walk_expression(visitor, &node.destructuring)?;
+    if let Some(exp) = &node.type_annotation {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_typed_destructuring(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_variable_binding<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<VariableBinding>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_variable_binding, leave_variable_binding);
+    walk_typed_destructuring(visitor, &node.destructuring)?;
+    if let Some(exp) = &node.initializer {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_variable_binding(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_simple_variable_definition<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<SimpleVariableDefinition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_simple_variable_definition, leave_simple_variable_definition);
+    for binding in &node.bindings {
+        walk_variable_binding(visitor, binding)?;
+    }
+    visitor.leave_simple_variable_definition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_initializer_field<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<InitializerField>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_initializer_field, leave_initializer_field);
+    match node.as_ref() {
+        InitializerField::Field { name, value, .. } => {
+            match &name.0 {
+                FieldName::Identifier(id) => {
+                    walk_qualified_identifier(visitor, id)?;
+                },
+                FieldName::Brackets(exp) |
+                FieldName::StringLiteral(exp) |
+                FieldName::NumericLiteral(exp) => {
+                    walk_expression(visitor, exp)?;
+                },
+            }
+            if let Some(value) = value {
+                walk_expression(visitor, value)?;
+            }
+        },
+        InitializerField::Rest((exp, _)) => {
+            walk_expression(visitor, exp)?;
+        },
+    }
+    visitor.leave_initializer_field(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_case<V: Visitor + ?Sized>(visitor: &mut V, node: &Case) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_case, leave_case);
+    for label in &node.labels {
+        if let CaseLabel::Case((exp, _)) = label {
+            walk_expression(visitor, exp)?;
+        }
+    }
+    walk_directives(visitor, &node.directives)?;
+    visitor.leave_case(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_type_case<V: Visitor + ?Sized>(visitor: &mut V, node: &TypeCase) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_type_case, leave_type_case);
+    if let Some(parameter) = &node.parameter {
+        walk_typed_destructuring(visitor, parameter)?;
+    }
+    walk_block(visitor, &node.block)?;
+    visitor.leave_type_case(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_catch_clause<V: Visitor + ?Sized>(visitor: &mut V, node: &CatchClause) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_catch_clause, leave_catch_clause);
+    walk_typed_destructuring(visitor, &node.parameter)?;
+    walk_block(visitor, &node.block)?;
+    visitor.leave_catch_clause(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_finally_clause<V: Visitor + ?Sized>(visitor: &mut V, node: &FinallyClause) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_finally_clause, leave_finally_clause);
+    walk_block(visitor, &node.block)?;
+    visitor.leave_finally_clause(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_element<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<XmlElement>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_element, leave_xml_element);
+    if let XmlTagName::Expression(exp) = &node.name {
+        walk_expression(visitor, exp)?;
+    }
+    for attribute in &node.attributes {
+        walk_xml_attribute(visitor, attribute)?;
+    }
+    if let Some(exp) = &node.attribute_expression {
+        walk_expression(visitor, exp)?;
+    }
+    for content in node.content.iter().flatten() {
+        walk_xml_content(visitor, content)?;
+    }
+    if let Some(XmlTagName::Expression(exp)) = &node.closing_name {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_xml_element(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_attribute<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<XmlAttribute>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_attribute, leave_xml_attribute);
+    if let XmlAttributeValue::Expression(exp) = &node.value {
+        walk_expression(visitor, exp)?;
+    }
+    visitor.leave_xml_attribute(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_content<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<XmlContent>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_content, leave_xml_content);
+    match node.as_ref() {
+        XmlContent::Element(element) => {
+            walk_xml_element(visitor, element)?;
+        },
+        XmlContent::Expression(exp) => {
+            walk_expression(visitor, exp)?;
+        },
+        XmlContent::Characters(_) |
+        XmlContent::Markup(_) => {},
+    }
+    visitor.leave_xml_content(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<Mxml>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml, leave_mxml);
+    for content in &node.content {
+        walk_mxml_content(visitor, content)?;
+    }
+    visitor.leave_mxml(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_element<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<MxmlElement>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_element, leave_mxml_element);
+    for attribute in &node.attributes {
+        walk_mxml_attribute(visitor, attribute)?;
+    }
+    for content in node.content.iter().flatten() {
+        walk_mxml_content(visitor, content)?;
+    }
+    if let Some(script) = &node.script {
+        walk_directives(visitor, &script.directives)?;
+    }
+    for document in node.style.iter().flat_map(|style| &style.documents) {
+        walk_css_document(visitor, document)?;
+    }
+    if let Some(segments) = &node.text_value {
+        walk_mxml_value_segments(visitor, segments)?;
+    }
+    visitor.leave_mxml_element(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_attribute<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<MxmlAttribute>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_attribute, leave_mxml_attribute);
+    match &node.typed_value {
+        MxmlAttributeValue::Text(segments) => walk_mxml_value_segments(visitor, segments)?,
+        MxmlAttributeValue::EventHandler(directives) => walk_directives(visitor, directives)?,
+    }
+    visitor.leave_mxml_attribute(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_mxml_value_segments<V: Visitor + ?Sized>(visitor: &mut V, list: &[MxmlValueSegment]) -> ControlFlow<()> {
+    for segment in list {
+        if let MxmlValueSegment::Binding(binding) = segment {
+            walk_expression(visitor, &binding.expression)?;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_content<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<MxmlContent>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_content, leave_mxml_content);
+    if let MxmlContent::Element(element) = node.as_ref() {
+        walk_mxml_element(visitor, element)?;
+    }
+    visitor.leave_mxml_content(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_document<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssDocument>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_document, leave_css_document);
+    for directive in &node.directives {
+        walk_css_directive(visitor, directive)?;
+    }
+    visitor.leave_css_document(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_directive<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssDirective>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_directive, leave_css_directive);
+    match node.as_ref() {
+        CssDirective::FontFace(font_face) => {
+            for property in &font_face.properties {
+                walk_css_property(visitor, property)?;
+            }
+        },
+        CssDirective::MediaQuery(media_query) => {
+            for condition in &media_query.conditions {
+                walk_css_media_query_condition(visitor, condition)?;
+            }
+            for rule in &media_query.rules {
+                walk_css_rule(visitor, rule)?;
+            }
+        },
+        CssDirective::Rule(rule) => {
+            walk_css_rule(visitor, rule)?;
+        },
+        CssDirective::Invalidated(_) |
+        CssDirective::NamespaceDefinition(_) => {},
+    }
+    visitor.leave_css_directive(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_rule<V: Visitor + ?Sized>(visitor: &mut V, node: &CssRule) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_rule, leave_css_rule);
+    for selector in &node.selectors {
+        walk_css_selector(visitor, selector)?;
+    }
+    for property in &node.properties {
+        walk_css_property(visitor, property)?;
+    }
+    visitor.leave_css_rule(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_selector<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssSelector>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_selector, leave_css_selector);
+    match node.as_ref() {
+        CssSelector::Base(base) => {
+            for condition in &base.conditions {
+                walk_css_selector_condition(visitor, condition)?;
+            }
+        },
+        CssSelector::Combinator(combinator) => {
+            walk_css_selector(visitor, &combinator.left)?;
+            walk_css_selector(visitor, &combinator.right)?;
+        },
+        CssSelector::Invalidated(_) => {},
+    }
+    visitor.leave_css_selector(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_selector_condition<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssSelectorCondition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_selector_condition, leave_css_selector_condition);
+    if let CssSelectorCondition::Not { condition, .. } = node.as_ref() {
+        walk_css_selector_condition(visitor, condition)?;
+    }
+    visitor.leave_css_selector_condition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_property<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssProperty>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_property, leave_css_property);
+    walk_css_property_value(visitor, &node.value)?;
+    visitor.leave_css_property(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_property_value<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssPropertyValue>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_property_value, leave_css_property_value);
+    match node.as_ref() {
+        CssPropertyValue::Array(array) => {
+            for element in &array.elements {
+                walk_css_property_value(visitor, element)?;
+            }
+        },
+        CssPropertyValue::MultiValue(multi_value) => {
+            for value in &multi_value.values {
+                walk_css_property_value(visitor, value)?;
+            }
+        },
+        _ => {},
+    }
+    visitor.leave_css_property_value(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_media_query_condition<V: Visitor + ?Sized>(visitor: &mut V, node: &Rc<CssMediaQueryCondition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_media_query_condition, leave_css_media_query_condition);
+    match node.as_ref() {
+        CssMediaQueryCondition::ParenProperty((property, _)) => {
+            walk_css_property(visitor, property)?;
+        },
+        CssMediaQueryCondition::And { left, right, .. } => {
+            walk_css_media_query_condition(visitor, left)?;
+            walk_css_media_query_condition(visitor, right)?;
+        },
+        _ => {},
+    }
+    visitor.leave_css_media_query_condition(node);
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[derive(Default)]
+    struct IdentifierCollector {
+        names: Vec<String>,
+        skip_functions: bool,
+        stop_at: Option<String>,
+    }
+
+    impl Visitor for IdentifierCollector {
+        fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+            if let Some((name, _)) = node.to_identifier_name() {
+                if self.stop_at.as_ref() == Some(&name) {
+                    return VisitorControl::Stop;
+                }
+                self.names.push(name);
+            }
+            VisitorControl::Continue
+        }
+
+        fn visit_function_common(&mut self, _node: &Rc<FunctionCommon>) -> VisitorControl {
+            if self.skip_functions { VisitorControl::SkipChildren } else { VisitorControl::Continue }
+        }
+    }
+
+    fn parse(text: &str) -> Rc<Program> {
+        let cu = CompilationUnit::new(None, text.into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        assert!(!cu.invalidated());
+        program
+    }
+
+    #[test]
+    fn test_visit_order_and_control() {
+        let program = parse("package p { public function f(a) { b } } switch (c) { case d: e } try {} catch (g) { h }");
+
+        let mut collector = IdentifierCollector::default();
+        assert!(walk_program(&mut collector, &program).is_continue());
+        assert_eq!(collector.names, ["a", "b", "c", "d", "e", "g", "h"]);
+
+        let mut collector = IdentifierCollector { skip_functions: true, ..default() };
+        assert!(walk_program(&mut collector, &program).is_continue());
+        assert_eq!(collector.names, ["c", "d", "e", "g", "h"]);
+
+        let mut collector = IdentifierCollector { stop_at: Some("e".into()), ..default() };
+        assert!(walk_program(&mut collector, &program).is_break());
+        assert_eq!(collector.names, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_mxml_script() {
+        let cu = CompilationUnit::new(None, "<fx:Object xmlns:fx=\"http://ns.adobe.com/mxml/2009\"><fx:Script><![CDATA[ a; ]]> b; </fx:Script></fx:Object>".into());
+        let mxml = ParserFacade(&cu, default()).parse_mxml();
+        assert!(!cu.invalidated());
+
+        let mut collector = IdentifierCollector::default();
+        assert!(walk_mxml(&mut collector, &mxml).is_continue());
+        assert_eq!(collector.names, ["a", "b"]);
+    }
+
+    #[derive(Default)]
+    struct CssPropertyCollector {
+        names: Vec<(String, Location)>,
+    }
+
+    impl Visitor for CssPropertyCollector {
+        fn visit_css_property(&mut self, node: &Rc<CssProperty>) -> VisitorControl {
+            self.names.push(node.name.clone());
+            VisitorControl::Continue
+        }
+    }
+
+    #[test]
+    fn test_mxml_style() {
+        let cu = CompilationUnit::new(None, "<fx:Object xmlns:fx=\"http://ns.adobe.com/mxml/2009\"><fx:Style>a { b: c; }</fx:Style><fx:Style><![CDATA[ d e { f: g; } ]]></fx:Style></fx:Object>".into());
+        let mxml = ParserFacade(&cu, default()).parse_mxml();
+        assert!(!cu.invalidated());
+        assert_eq!(mxml.css_documents().len(), 2);
+
+        let mut collector = CssPropertyCollector::default();
+        assert!(walk_mxml(&mut collector, &mxml).is_continue());
+        let names: Vec<(String, usize)> = collector.names.iter().map(|(name, location)| (name.clone(), location.first_offset())).collect();
+        assert_eq!(names, [("b".into(), 66), ("f".into(), 110)]);
+    }
+
+    #[test]
+    fn test_mxml_bindings() {
+        let cu = CompilationUnit::new(None, "<s:Label xmlns:s=\"library://ns.adobe.com/flex/spark\" text=\"{a} and @{b}\" click=\"c();\">{d &lt; e}</s:Label>".into());
+        let mxml = ParserFacade(&cu, default()).parse_mxml();
+        assert!(!cu.invalidated());
+
+        let mut collector = IdentifierCollector::default();
+        assert!(walk_mxml(&mut collector, &mxml).is_continue());
+        assert_eq!(collector.names, ["a", "b", "c", "d", "e"]);
+    }
+}
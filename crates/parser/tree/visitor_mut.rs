@@ -0,0 +1,978 @@
+use crate::ns::*;
+use std::ops::ControlFlow;
+
+/// Mutable traversal over the syntactic tree.
+///
+/// `VisitorMut` mirrors `Visitor`, except that nodes are received by mutable
+/// reference, allowing a `visit_*` method to rewrite a node or replace it
+/// entirely before its children are walked.
+///
+/// Walking a node makes it unique through `Rc::make_mut()` before walking its
+/// children, whether or not the visitor changes anything: every walked node that is
+/// shared with other owners is copied, so that any other reference to the original
+/// tree continues to observe the original nodes. Walking a tree that shares its
+/// nodes therefore copies all of the walked nodes; to mutate in place, walk a tree
+/// that has no other owners.
+///
+/// # Example
+///
+/// ```
+/// use as3_parser::ns::*;
+///
+/// struct Renamer;
+///
+/// impl VisitorMut for Renamer {
+///     fn visit_expression(&mut self, node: &mut Rc<Expression>) -> VisitorControl {
+///         if let Some((name, location)) = node.to_identifier_name() {
+///             if name == "x" {
+///                 *node = Rc::new(Expression::QualifiedIdentifier(QualifiedIdentifier {
+///                     location: location.clone(),
+///                     attribute: false,
+///                     qualifier: None,
+///                     id: QualifiedIdentifierIdentifier::Id(("y".into(), location)),
+///                 }));
+///             }
+///         }
+///         VisitorControl::Continue
+///     }
+/// }
+///
+/// let cu = CompilationUnit::new(None, "x + 1".into());
+/// let program = ParserFacade(&cu, default()).parse_program();
+/// let mut new_program = program.clone();
+/// let _ = walk_program_mut(&mut Renamer, &mut new_program);
+/// ```
+#[allow(unused_variables)]
+pub trait VisitorMut {
+    fn visit_program(&mut self, node: &mut Rc<Program>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_program(&mut self, node: &mut Rc<Program>) {}
+    fn visit_package_definition(&mut self, node: &mut Rc<PackageDefinition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_package_definition(&mut self, node: &mut Rc<PackageDefinition>) {}
+    fn visit_directive(&mut self, node: &mut Rc<Directive>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_directive(&mut self, node: &mut Rc<Directive>) {}
+    fn visit_expression(&mut self, node: &mut Rc<Expression>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_expression(&mut self, node: &mut Rc<Expression>) {}
+    fn visit_block(&mut self, node: &mut Block) -> VisitorControl { VisitorControl::Continue }
+    fn leave_block(&mut self, node: &mut Block) {}
+    fn visit_qualified_identifier(&mut self, node: &mut QualifiedIdentifier) -> VisitorControl { VisitorControl::Continue }
+    fn leave_qualified_identifier(&mut self, node: &mut QualifiedIdentifier) {}
+    fn visit_attribute(&mut self, node: &mut Attribute) -> VisitorControl { VisitorControl::Continue }
+    fn leave_attribute(&mut self, node: &mut Attribute) {}
+    fn visit_metadata(&mut self, node: &mut Rc<Metadata>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_metadata(&mut self, node: &mut Rc<Metadata>) {}
+    fn visit_asdoc(&mut self, node: &mut Rc<AsDoc>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_asdoc(&mut self, node: &mut Rc<AsDoc>) {}
+    fn visit_type_parameter(&mut self, node: &mut Rc<TypeParameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_type_parameter(&mut self, node: &mut Rc<TypeParameter>) {}
+    fn visit_function_common(&mut self, node: &mut Rc<FunctionCommon>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_function_common(&mut self, node: &mut Rc<FunctionCommon>) {}
+    fn visit_parameter(&mut self, node: &mut Rc<Parameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_parameter(&mut self, node: &mut Rc<Parameter>) {}
+    fn visit_function_type_parameter(&mut self, node: &mut Rc<FunctionTypeParameter>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_function_type_parameter(&mut self, node: &mut Rc<FunctionTypeParameter>) {}
+    fn visit_typed_destructuring(&mut self, node: &mut TypedDestructuring) -> VisitorControl { VisitorControl::Continue }
+    fn leave_typed_destructuring(&mut self, node: &mut TypedDestructuring) {}
+    fn visit_variable_binding(&mut self, node: &mut Rc<VariableBinding>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_variable_binding(&mut self, node: &mut Rc<VariableBinding>) {}
+    fn visit_simple_variable_definition(&mut self, node: &mut Rc<SimpleVariableDefinition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_simple_variable_definition(&mut self, node: &mut Rc<SimpleVariableDefinition>) {}
+    fn visit_initializer_field(&mut self, node: &mut Rc<InitializerField>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_initializer_field(&mut self, node: &mut Rc<InitializerField>) {}
+    fn visit_case(&mut self, node: &mut Case) -> VisitorControl { VisitorControl::Continue }
+    fn leave_case(&mut self, node: &mut Case) {}
+    fn visit_type_case(&mut self, node: &mut TypeCase) -> VisitorControl { VisitorControl::Continue }
+    fn leave_type_case(&mut self, node: &mut TypeCase) {}
+    fn visit_catch_clause(&mut self, node: &mut CatchClause) -> VisitorControl { VisitorControl::Continue }
+    fn leave_catch_clause(&mut self, node: &mut CatchClause) {}
+    fn visit_finally_clause(&mut self, node: &mut FinallyClause) -> VisitorControl { VisitorControl::Continue }
+    fn leave_finally_clause(&mut self, node: &mut FinallyClause) {}
+    fn visit_xml_element(&mut self, node: &mut Rc<XmlElement>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_element(&mut self, node: &mut Rc<XmlElement>) {}
+    fn visit_xml_attribute(&mut self, node: &mut Rc<XmlAttribute>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_attribute(&mut self, node: &mut Rc<XmlAttribute>) {}
+    fn visit_xml_content(&mut self, node: &mut Rc<XmlContent>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_xml_content(&mut self, node: &mut Rc<XmlContent>) {}
+    fn visit_mxml(&mut self, node: &mut Rc<Mxml>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml(&mut self, node: &mut Rc<Mxml>) {}
+    fn visit_mxml_element(&mut self, node: &mut Rc<MxmlElement>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_element(&mut self, node: &mut Rc<MxmlElement>) {}
+    fn visit_mxml_attribute(&mut self, node: &mut Rc<MxmlAttribute>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_attribute(&mut self, node: &mut Rc<MxmlAttribute>) {}
+    fn visit_mxml_content(&mut self, node: &mut Rc<MxmlContent>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_mxml_content(&mut self, node: &mut Rc<MxmlContent>) {}
+    fn visit_css_document(&mut self, node: &mut Rc<CssDocument>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_document(&mut self, node: &mut Rc<CssDocument>) {}
+    fn visit_css_directive(&mut self, node: &mut Rc<CssDirective>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_directive(&mut self, node: &mut Rc<CssDirective>) {}
+    fn visit_css_rule(&mut self, node: &mut CssRule) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_rule(&mut self, node: &mut CssRule) {}
+    fn visit_css_selector(&mut self, node: &mut Rc<CssSelector>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_selector(&mut self, node: &mut Rc<CssSelector>) {}
+    fn visit_css_selector_condition(&mut self, node: &mut Rc<CssSelectorCondition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_selector_condition(&mut self, node: &mut Rc<CssSelectorCondition>) {}
+    fn visit_css_property(&mut self, node: &mut Rc<CssProperty>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_property(&mut self, node: &mut Rc<CssProperty>) {}
+    fn visit_css_property_value(&mut self, node: &mut Rc<CssPropertyValue>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_property_value(&mut self, node: &mut Rc<CssPropertyValue>) {}
+    fn visit_css_media_query_condition(&mut self, node: &mut Rc<CssMediaQueryCondition>) -> VisitorControl { VisitorControl::Continue }
+    fn leave_css_media_query_condition(&mut self, node: &mut Rc<CssMediaQueryCondition>) {}
+}
+
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Program>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_program, leave_program);
+    let inner = Rc::make_mut(node);
+    for pckg in &mut inner.packages {
+        walk_package_definition_mut(visitor, pckg)?;
+    }
+    walk_directives_mut(visitor, &mut inner.directives)?;
+    visitor.leave_program(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_package_definition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<PackageDefinition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_package_definition, leave_package_definition);
+    let inner = Rc::make_mut(node);
+    if let Some(asdoc) = &mut inner.asdoc {
+        walk_asdoc_mut(visitor, asdoc)?;
+    }
+    walk_block_mut(visitor, Rc::make_mut(&mut inner.block))?;
+    visitor.leave_package_definition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_directives_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut [Rc<Directive>]) -> ControlFlow<()> {
+    for directive in list {
+        walk_directive_mut(visitor, directive)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_expressions_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut [Rc<Expression>]) -> ControlFlow<()> {
+    for exp in list {
+        walk_expression_mut(visitor, exp)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Block) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_block, leave_block);
+    walk_directives_mut(visitor, &mut node.directives)?;
+    visitor.leave_block(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_annotations_mut<V: VisitorMut + ?Sized>(visitor: &mut V, asdoc: &mut Option<Rc<AsDoc>>, attributes: &mut [Attribute]) -> ControlFlow<()> {
+    if let Some(asdoc) = asdoc {
+        walk_asdoc_mut(visitor, asdoc)?;
+    }
+    for attribute in attributes {
+        walk_attribute_mut(visitor, attribute)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_type_parameters_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut Option<Vec<Rc<TypeParameter>>>) -> ControlFlow<()> {
+    for type_parameter in list.iter_mut().flatten() {
+        walk_type_parameter_mut(visitor, type_parameter)?;
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_directive_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Directive>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_directive, leave_directive);
+    match Rc::make_mut(node) {
+        Directive::EmptyStatement(_) |
+        Directive::BreakStatement(_) |
+        Directive::ContinueStatement(_) |
+        Directive::ImportDirective(_) |
+        Directive::PackageConcatDirective(_) |
+        Directive::Invalidated(_) => {},
+        Directive::ExpressionStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.expression)?;
+        },
+        Directive::SuperStatement(stmt) => {
+            walk_expressions_mut(visitor, &mut stmt.arguments)?;
+        },
+        Directive::Block(block) => {
+            walk_block_mut(visitor, block)?;
+        },
+        Directive::LabeledStatement(stmt) => {
+            walk_directive_mut(visitor, &mut stmt.substatement)?;
+        },
+        Directive::IfStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.test)?;
+            walk_directive_mut(visitor, &mut stmt.consequent)?;
+            if let Some(alternative) = &mut stmt.alternative {
+                walk_directive_mut(visitor, alternative)?;
+            }
+        },
+        Directive::SwitchStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.discriminant)?;
+            for case in &mut stmt.cases {
+                walk_case_mut(visitor, case)?;
+            }
+        },
+        Directive::SwitchTypeStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.discriminant)?;
+            for case in &mut stmt.cases {
+                walk_type_case_mut(visitor, case)?;
+            }
+        },
+        Directive::DoStatement(stmt) => {
+            walk_directive_mut(visitor, &mut stmt.body)?;
+            walk_expression_mut(visitor, &mut stmt.test)?;
+        },
+        Directive::WhileStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.test)?;
+            walk_directive_mut(visitor, &mut stmt.body)?;
+        },
+        Directive::ForStatement(stmt) => {
+            match &mut stmt.init {
+                Some(ForInitializer::Expression(exp)) => {
+                    walk_expression_mut(visitor, exp)?;
+                },
+                Some(ForInitializer::VariableDefinition(defn)) => {
+                    walk_simple_variable_definition_mut(visitor, defn)?;
+                },
+                None => {},
+            }
+            if let Some(test) = &mut stmt.test {
+                walk_expression_mut(visitor, test)?;
+            }
+            if let Some(update) = &mut stmt.update {
+                walk_expression_mut(visitor, update)?;
+            }
+            walk_directive_mut(visitor, &mut stmt.body)?;
+        },
+        Directive::ForInStatement(stmt) => {
+            match &mut stmt.left {
+                ForInBinding::Expression(exp) => {
+                    walk_expression_mut(visitor, exp)?;
+                },
+                ForInBinding::VariableDefinition(defn) => {
+                    walk_simple_variable_definition_mut(visitor, defn)?;
+                },
+            }
+            walk_expression_mut(visitor, &mut stmt.right)?;
+            walk_directive_mut(visitor, &mut stmt.body)?;
+        },
+        Directive::WithStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.object)?;
+            walk_directive_mut(visitor, &mut stmt.body)?;
+        },
+        Directive::ReturnStatement(stmt) => {
+            if let Some(exp) = &mut stmt.expression {
+                walk_expression_mut(visitor, exp)?;
+            }
+        },
+        Directive::ThrowStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.expression)?;
+        },
+        Directive::DefaultXmlNamespaceStatement(stmt) => {
+            walk_expression_mut(visitor, &mut stmt.right)?;
+        },
+        Directive::TryStatement(stmt) => {
+            walk_block_mut(visitor, Rc::make_mut(&mut stmt.block))?;
+            for catch_clause in &mut stmt.catch_clauses {
+                walk_catch_clause_mut(visitor, catch_clause)?;
+            }
+            if let Some(finally_clause) = &mut stmt.finally_clause {
+                walk_finally_clause_mut(visitor, finally_clause)?;
+            }
+        },
+        Directive::ConfigurationDirective(drtv) => {
+            walk_directive_mut(visitor, &mut drtv.directive)?;
+        },
+        Directive::UseNamespaceDirective(drtv) => {
+            walk_expression_mut(visitor, &mut drtv.expression)?;
+        },
+        Directive::IncludeDirective(drtv) => {
+            for pckg in &mut drtv.nested_packages {
+                walk_package_definition_mut(visitor, pckg)?;
+            }
+            walk_directives_mut(visitor, &mut drtv.nested_directives)?;
+        },
+        Directive::NormalConfigurationDirective(drtv) => {
+            walk_directive_mut(visitor, &mut drtv.directive)?;
+        },
+        Directive::DirectiveInjection(drtv) => {
+            let directives = drtv.directives.get_mut();
+            walk_directives_mut(visitor, directives)?;
+        },
+        Directive::VariableDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            for binding in &mut defn.bindings {
+                walk_variable_binding_mut(visitor, binding)?;
+            }
+        },
+        Directive::FunctionDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            walk_function_common_mut(visitor, &mut defn.common)?;
+        },
+        Directive::ClassDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            walk_type_parameters_mut(visitor, &mut defn.type_parameters)?;
+            if let Some(exp) = &mut defn.extends_clause {
+                walk_expression_mut(visitor, exp)?;
+            }
+            if let Some(list) = &mut defn.implements_clause {
+                walk_expressions_mut(visitor, list)?;
+            }
+            walk_block_mut(visitor, Rc::make_mut(&mut defn.block))?;
+        },
+        Directive::EnumDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            if let Some(exp) = &mut defn.as_clause {
+                walk_expression_mut(visitor, exp)?;
+            }
+            walk_block_mut(visitor, Rc::make_mut(&mut defn.block))?;
+        },
+        Directive::InterfaceDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            walk_type_parameters_mut(visitor, &mut defn.type_parameters)?;
+            if let Some(list) = &mut defn.extends_clause {
+                walk_expressions_mut(visitor, list)?;
+            }
+            walk_block_mut(visitor, Rc::make_mut(&mut defn.block))?;
+        },
+        Directive::TypeDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            walk_expression_mut(visitor, &mut defn.right)?;
+        },
+        Directive::NamespaceDefinition(defn) => {
+            walk_annotations_mut(visitor, &mut defn.asdoc, &mut defn.attributes)?;
+            if let Some(exp) = &mut defn.right {
+                walk_expression_mut(visitor, exp)?;
+            }
+        },
+    }
+    visitor.leave_directive(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_elements_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut [Element]) -> ControlFlow<()> {
+    for element in list {
+        match element {
+            Element::Elision => {},
+            Element::Expression(exp) |
+            Element::Rest((exp, _)) => {
+                walk_expression_mut(visitor, exp)?;
+            },
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Expression>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_expression, leave_expression);
+    match Rc::make_mut(node) {
+        Expression::NullLiteral(_) |
+        Expression::BooleanLiteral(_) |
+        Expression::NumericLiteral(_) |
+        Expression::StringLiteral(_) |
+        Expression::ThisLiteral(_) |
+        Expression::RegExpLiteral(_) |
+        Expression::XmlMarkup(_) |
+        Expression::ImportMeta(_) |
+        Expression::OptionalChainingPlaceholder(_) |
+        Expression::AnyType(_) |
+        Expression::VoidType(_) |
+        Expression::Invalidated(_) |
+        Expression::ReservedNamespace(_) => {},
+        Expression::QualifiedIdentifier(id) => {
+            walk_qualified_identifier_mut(visitor, id)?;
+        },
+        Expression::Paren(exp) => {
+            walk_expression_mut(visitor, &mut exp.expression)?;
+        },
+        Expression::Xml(exp) => {
+            walk_xml_element_mut(visitor, &mut exp.element)?;
+        },
+        Expression::XmlList(exp) => {
+            for content in &mut exp.content {
+                walk_xml_content_mut(visitor, content)?;
+            }
+        },
+        Expression::ArrayLiteral(exp) => {
+            if let Some(asdoc) = &mut exp.asdoc {
+                walk_asdoc_mut(visitor, asdoc)?;
+            }
+            walk_elements_mut(visitor, &mut exp.elements)?;
+        },
+        Expression::VectorLiteral(exp) => {
+            walk_expression_mut(visitor, &mut exp.element_type)?;
+            walk_elements_mut(visitor, &mut exp.elements)?;
+        },
+        Expression::ObjectInitializer(exp) => {
+            for field in &mut exp.fields {
+                walk_initializer_field_mut(visitor, field)?;
+            }
+        },
+        Expression::Function(exp) => {
+            walk_function_common_mut(visitor, &mut exp.common)?;
+        },
+        Expression::New(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            if let Some(arguments) = &mut exp.arguments {
+                walk_expressions_mut(visitor, arguments)?;
+            }
+        },
+        Expression::Member(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_qualified_identifier_mut(visitor, &mut exp.identifier)?;
+        },
+        Expression::ComputedMember(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            if let Some(asdoc) = &mut exp.asdoc {
+                walk_asdoc_mut(visitor, asdoc)?;
+            }
+            walk_expression_mut(visitor, &mut exp.key)?;
+        },
+        Expression::Descendants(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_qualified_identifier_mut(visitor, &mut exp.identifier)?;
+        },
+        Expression::Filter(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_expression_mut(visitor, &mut exp.test)?;
+        },
+        Expression::Super(exp) => {
+            if let Some(object) = &mut exp.object {
+                walk_expressions_mut(visitor, object)?;
+            }
+        },
+        Expression::Call(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_expressions_mut(visitor, &mut exp.arguments)?;
+        },
+        Expression::WithTypeArguments(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_expressions_mut(visitor, &mut exp.arguments)?;
+        },
+        Expression::Unary(exp) => {
+            walk_expression_mut(visitor, &mut exp.expression)?;
+        },
+        Expression::OptionalChaining(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+            walk_expression_mut(visitor, &mut exp.expression)?;
+        },
+        Expression::Binary(exp) => {
+            walk_expression_mut(visitor, &mut exp.left)?;
+            walk_expression_mut(visitor, &mut exp.right)?;
+        },
+        Expression::Conditional(exp) => {
+            walk_expression_mut(visitor, &mut exp.test)?;
+            walk_expression_mut(visitor, &mut exp.consequent)?;
+            walk_expression_mut(visitor, &mut exp.alternative)?;
+        },
+        Expression::Assignment(exp) => {
+            walk_expression_mut(visitor, &mut exp.left)?;
+            walk_expression_mut(visitor, &mut exp.right)?;
+        },
+        Expression::Sequence(exp) => {
+            walk_expression_mut(visitor, &mut exp.left)?;
+            walk_expression_mut(visitor, &mut exp.right)?;
+        },
+        Expression::NullableType(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+        },
+        Expression::NonNullableType(exp) => {
+            walk_expression_mut(visitor, &mut exp.base)?;
+        },
+        Expression::ArrayType(exp) => {
+            walk_expression_mut(visitor, &mut exp.expression)?;
+        },
+        Expression::TupleType(exp) => {
+            walk_expressions_mut(visitor, &mut exp.expressions)?;
+        },
+        Expression::FunctionType(exp) => {
+            for parameter in &mut exp.parameters {
+                walk_function_type_parameter_mut(visitor, parameter)?;
+            }
+            if let Some(result_type) = &mut exp.result_type {
+                walk_expression_mut(visitor, result_type)?;
+            }
+        },
+    }
+    visitor.leave_expression(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_qualified_identifier_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut QualifiedIdentifier) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_qualified_identifier, leave_qualified_identifier);
+    if let Some(qualifier) = &mut node.qualifier {
+        walk_expression_mut(visitor, qualifier)?;
+    }
+    if let QualifiedIdentifierIdentifier::Brackets(exp) = &mut node.id {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_qualified_identifier(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_attribute_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Attribute) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_attribute, leave_attribute);
+    match node {
+        Attribute::Metadata(metadata) => {
+            walk_metadata_mut(visitor, metadata)?;
+        },
+        Attribute::Expression(exp) => {
+            walk_expression_mut(visitor, exp)?;
+        },
+        _ => {},
+    }
+    visitor.leave_attribute(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_metadata_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Metadata>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_metadata, leave_metadata);
+    let inner = Rc::make_mut(node);
+    if let Some(asdoc) = &mut inner.asdoc {
+        walk_asdoc_mut(visitor, asdoc)?;
+    }
+    visitor.leave_metadata(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_asdoc_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<AsDoc>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_asdoc, leave_asdoc);
+    let inner = Rc::make_mut(node);
+    for (tag, _) in &mut inner.tags {
+        match tag {
+            AsDocTag::Copy(reference) |
+            AsDocTag::See { reference, .. } => {
+                let reference = Rc::make_mut(reference);
+                if let Some(base) = &mut reference.base {
+                    walk_expression_mut(visitor, base)?;
+                }
+                if let Some(id) = &mut reference.instance_property {
+                    walk_qualified_identifier_mut(visitor, Rc::make_mut(id))?;
+                }
+            },
+            AsDocTag::EventType(exp) => {
+                walk_expression_mut(visitor, exp)?;
+            },
+            AsDocTag::Throws { class_reference, .. } => {
+                walk_expression_mut(visitor, class_reference)?;
+            },
+            _ => {},
+        }
+    }
+    visitor.leave_asdoc(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_type_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<TypeParameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_type_parameter, leave_type_parameter);
+    visitor.leave_type_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_function_common_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<FunctionCommon>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_function_common, leave_function_common);
+    let inner = Rc::make_mut(node);
+    for parameter in &mut inner.signature.parameters {
+        walk_parameter_mut(visitor, parameter)?;
+    }
+    if let Some(result_type) = &mut inner.signature.result_type {
+        walk_expression_mut(visitor, result_type)?;
+    }
+    match &mut inner.body {
+        Some(FunctionBody::Expression(exp)) => {
+            walk_expression_mut(visitor, exp)?;
+        },
+        Some(FunctionBody::Block(block)) => {
+            walk_block_mut(visitor, Rc::make_mut(block))?;
+        },
+        None => {},
+    }
+    visitor.leave_function_common(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Parameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_parameter, leave_parameter);
+    let inner = Rc::make_mut(node);
+    walk_typed_destructuring_mut(visitor, &mut inner.destructuring)?;
+    if let Some(exp) = &mut inner.default_value {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_function_type_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<FunctionTypeParameter>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_function_type_parameter, leave_function_type_parameter);
+    let inner = Rc::make_mut(node);
+    if let Some(exp) = &mut inner.type_expression {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_function_type_parameter(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_typed_destructuring_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut TypedDestructuring) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_typed_destructuring, leave_typed_destructuring);
+    walk_expression_mut(visitor, &mut node.destructuring)?;
+    if let Some(exp) = &mut node.type_annotation {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_typed_destructuring(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_variable_binding_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<VariableBinding>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_variable_binding, leave_variable_binding);
+    let inner = Rc::make_mut(node);
+    walk_typed_destructuring_mut(visitor, &mut inner.destructuring)?;
+    if let Some(exp) = &mut inner.initializer {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_variable_binding(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_simple_variable_definition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<SimpleVariableDefinition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_simple_variable_definition, leave_simple_variable_definition);
+    let inner = Rc::make_mut(node);
+    for binding in &mut inner.bindings {
+        walk_variable_binding_mut(visitor, binding)?;
+    }
+    visitor.leave_simple_variable_definition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_initializer_field_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<InitializerField>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_initializer_field, leave_initializer_field);
+    match Rc::make_mut(node) {
+        InitializerField::Field { name, value, .. } => {
+            match &mut name.0 {
+                FieldName::Identifier(id) => {
+                    walk_qualified_identifier_mut(visitor, id)?;
+                },
+                FieldName::Brackets(exp) |
+                FieldName::StringLiteral(exp) |
+                FieldName::NumericLiteral(exp) => {
+                    walk_expression_mut(visitor, exp)?;
+                },
+            }
+            if let Some(value) = value {
+                walk_expression_mut(visitor, value)?;
+            }
+        },
+        InitializerField::Rest((exp, _)) => {
+            walk_expression_mut(visitor, exp)?;
+        },
+    }
+    visitor.leave_initializer_field(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_case_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Case) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_case, leave_case);
+    for label in &mut node.labels {
+        if let CaseLabel::Case((exp, _)) = label {
+            walk_expression_mut(visitor, exp)?;
+        }
+    }
+    walk_directives_mut(visitor, &mut node.directives)?;
+    visitor.leave_case(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_type_case_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut TypeCase) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_type_case, leave_type_case);
+    if let Some(parameter) = &mut node.parameter {
+        walk_typed_destructuring_mut(visitor, parameter)?;
+    }
+    walk_block_mut(visitor, Rc::make_mut(&mut node.block))?;
+    visitor.leave_type_case(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_catch_clause_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CatchClause) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_catch_clause, leave_catch_clause);
+    walk_typed_destructuring_mut(visitor, &mut node.parameter)?;
+    walk_block_mut(visitor, Rc::make_mut(&mut node.block))?;
+    visitor.leave_catch_clause(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_finally_clause_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut FinallyClause) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_finally_clause, leave_finally_clause);
+    walk_block_mut(visitor, Rc::make_mut(&mut node.block))?;
+    visitor.leave_finally_clause(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_element_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<XmlElement>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_element, leave_xml_element);
+    let inner = Rc::make_mut(node);
+    if let XmlTagName::Expression(exp) = &mut inner.name {
+        walk_expression_mut(visitor, exp)?;
+    }
+    for attribute in &mut inner.attributes {
+        walk_xml_attribute_mut(visitor, attribute)?;
+    }
+    if let Some(exp) = &mut inner.attribute_expression {
+        walk_expression_mut(visitor, exp)?;
+    }
+    for content in inner.content.iter_mut().flatten() {
+        walk_xml_content_mut(visitor, content)?;
+    }
+    if let Some(XmlTagName::Expression(exp)) = &mut inner.closing_name {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_xml_element(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_attribute_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<XmlAttribute>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_attribute, leave_xml_attribute);
+    let inner = Rc::make_mut(node);
+    if let XmlAttributeValue::Expression(exp) = &mut inner.value {
+        walk_expression_mut(visitor, exp)?;
+    }
+    visitor.leave_xml_attribute(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_xml_content_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<XmlContent>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_xml_content, leave_xml_content);
+    match Rc::make_mut(node) {
+        XmlContent::Element(element) => {
+            walk_xml_element_mut(visitor, element)?;
+        },
+        XmlContent::Expression(exp) => {
+            walk_expression_mut(visitor, exp)?;
+        },
+        XmlContent::Characters(_) |
+        XmlContent::Markup(_) => {},
+    }
+    visitor.leave_xml_content(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<Mxml>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml, leave_mxml);
+    let inner = Rc::make_mut(node);
+    for content in &mut inner.content {
+        walk_mxml_content_mut(visitor, content)?;
+    }
+    visitor.leave_mxml(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_element_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<MxmlElement>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_element, leave_mxml_element);
+    let inner = Rc::make_mut(node);
+    for attribute in &mut inner.attributes {
+        walk_mxml_attribute_mut(visitor, attribute)?;
+    }
+    for content in inner.content.iter_mut().flatten() {
+        walk_mxml_content_mut(visitor, content)?;
+    }
+    if let Some(script) = &mut inner.script {
+        walk_directives_mut(visitor, &mut Rc::make_mut(script).directives)?;
+    }
+    if let Some(style) = &mut inner.style {
+        for document in &mut Rc::make_mut(style).documents {
+            walk_css_document_mut(visitor, document)?;
+        }
+    }
+    if let Some(segments) = &mut inner.text_value {
+        walk_mxml_value_segments_mut(visitor, segments)?;
+    }
+    visitor.leave_mxml_element(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_attribute_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<MxmlAttribute>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_attribute, leave_mxml_attribute);
+    match &mut Rc::make_mut(node).typed_value {
+        MxmlAttributeValue::Text(segments) => walk_mxml_value_segments_mut(visitor, segments)?,
+        MxmlAttributeValue::EventHandler(directives) => walk_directives_mut(visitor, directives)?,
+    }
+    visitor.leave_mxml_attribute(node);
+    ControlFlow::Continue(())
+}
+
+fn walk_mxml_value_segments_mut<V: VisitorMut + ?Sized>(visitor: &mut V, list: &mut [MxmlValueSegment]) -> ControlFlow<()> {
+    for segment in list {
+        if let MxmlValueSegment::Binding(binding) = segment {
+            walk_expression_mut(visitor, &mut Rc::make_mut(binding).expression)?;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+pub fn walk_mxml_content_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<MxmlContent>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_mxml_content, leave_mxml_content);
+    if let MxmlContent::Element(element) = Rc::make_mut(node) {
+        walk_mxml_element_mut(visitor, element)?;
+    }
+    visitor.leave_mxml_content(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_document_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssDocument>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_document, leave_css_document);
+    let inner = Rc::make_mut(node);
+    for directive in &mut inner.directives {
+        walk_css_directive_mut(visitor, directive)?;
+    }
+    visitor.leave_css_document(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_directive_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssDirective>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_directive, leave_css_directive);
+    match Rc::make_mut(node) {
+        CssDirective::FontFace(font_face) => {
+            for property in &mut font_face.properties {
+                walk_css_property_mut(visitor, property)?;
+            }
+        },
+        CssDirective::MediaQuery(media_query) => {
+            for condition in &mut media_query.conditions {
+                walk_css_media_query_condition_mut(visitor, condition)?;
+            }
+            for rule in &mut media_query.rules {
+                walk_css_rule_mut(visitor, Rc::make_mut(rule))?;
+            }
+        },
+        CssDirective::Rule(rule) => {
+            walk_css_rule_mut(visitor, rule)?;
+        },
+        CssDirective::Invalidated(_) |
+        CssDirective::NamespaceDefinition(_) => {},
+    }
+    visitor.leave_css_directive(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_rule_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CssRule) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_rule, leave_css_rule);
+    for selector in &mut node.selectors {
+        walk_css_selector_mut(visitor, selector)?;
+    }
+    for property in &mut node.properties {
+        walk_css_property_mut(visitor, property)?;
+    }
+    visitor.leave_css_rule(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_selector_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssSelector>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_selector, leave_css_selector);
+    match Rc::make_mut(node) {
+        CssSelector::Base(base) => {
+            for condition in &mut base.conditions {
+                walk_css_selector_condition_mut(visitor, condition)?;
+            }
+        },
+        CssSelector::Combinator(combinator) => {
+            walk_css_selector_mut(visitor, &mut combinator.left)?;
+            walk_css_selector_mut(visitor, &mut combinator.right)?;
+        },
+        CssSelector::Invalidated(_) => {},
+    }
+    visitor.leave_css_selector(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_selector_condition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssSelectorCondition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_selector_condition, leave_css_selector_condition);
+    if let CssSelectorCondition::Not { condition, .. } = Rc::make_mut(node) {
+        walk_css_selector_condition_mut(visitor, condition)?;
+    }
+    visitor.leave_css_selector_condition(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_property_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssProperty>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_property, leave_css_property);
+    let inner = Rc::make_mut(node);
+    walk_css_property_value_mut(visitor, &mut inner.value)?;
+    visitor.leave_css_property(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_property_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssPropertyValue>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_property_value, leave_css_property_value);
+    match Rc::make_mut(node) {
+        CssPropertyValue::Array(array) => {
+            for element in &mut array.elements {
+                walk_css_property_value_mut(visitor, element)?;
+            }
+        },
+        CssPropertyValue::MultiValue(multi_value) => {
+            for value in &mut multi_value.values {
+                walk_css_property_value_mut(visitor, value)?;
+            }
+        },
+        _ => {},
+    }
+    visitor.leave_css_property_value(node);
+    ControlFlow::Continue(())
+}
+
+pub fn walk_css_media_query_condition_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Rc<CssMediaQueryCondition>) -> ControlFlow<()> {
+    enter_node!(visitor, node, visit_css_media_query_condition, leave_css_media_query_condition);
+    match Rc::make_mut(node) {
+        CssMediaQueryCondition::ParenProperty((property, _)) => {
+            walk_css_property_mut(visitor, property)?;
+        },
+        CssMediaQueryCondition::And { left, right, .. } => {
+            walk_css_media_query_condition_mut(visitor, left)?;
+            walk_css_media_query_condition_mut(visitor, right)?;
+        },
+        _ => {},
+    }
+    visitor.leave_css_media_query_condition(node);
+    ControlFlow::Continue(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    struct NumberDoubler;
+
+    impl VisitorMut for NumberDoubler {
+        fn visit_expression(&mut self, node: &mut Rc<Expression>) -> VisitorControl {
+            if let Expression::NumericLiteral(literal) = Rc::make_mut(node) {
+                literal.value = format!("{}", literal.parse_double(false).unwrap() * 2.0);
+            }
+            VisitorControl::Continue
+        }
+    }
+
+    #[test]
+    fn test_clone_on_write() {
+        let cu = CompilationUnit::new(None, "function f(a = 1) { return [2, 3] }".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let mut new_program = program.clone();
+        assert!(walk_program_mut(&mut NumberDoubler, &mut new_program).is_continue());
+
+        let collect = |program: &Rc<Program>| {
+            struct Collector(Vec<String>);
+            impl Visitor for Collector {
+                fn visit_expression(&mut self, node: &Rc<Expression>) -> VisitorControl {
+                    if let Expression::NumericLiteral(literal) = node.as_ref() {
+                        self.0.push(literal.value.clone());
+                    }
+                    VisitorControl::Continue
+                }
+            }
+            let mut collector = Collector(vec![]);
+            let _ = walk_program(&mut collector, program);
+            collector.0
+        };
+        assert_eq!(collect(&program), ["1", "2", "3"]);
+        assert_eq!(collect(&new_program), ["2", "4", "6"]);
+    }
+}
@@ -14,9 +14,25 @@ pub use escaping::*;
 mod css;
 pub use css::*;
 
-pub use std::cell::{Cell, RefCell};
 pub use std::collections::{HashMap, HashSet};
+
+// Shared ownership and interior mutability types, which are
+// thread-safe with the `sync` feature.
+#[cfg(not(feature = "sync"))]
+pub use std::cell::{Cell, RefCell, Ref, RefMut};
+#[cfg(not(feature = "sync"))]
 pub use std::rc::{Rc, Weak};
+#[cfg(feature = "sync")]
+mod sync;
+#[cfg(feature = "sync")]
+pub use sync::*;
+
+/// A dynamically typed value, such as compiler options,
+/// which is `Send` and `Sync` with the `sync` feature.
+#[cfg(not(feature = "sync"))]
+pub type DynAny = dyn std::any::Any;
+#[cfg(feature = "sync")]
+pub type DynAny = dyn std::any::Any + Send + Sync;
 
 pub fn default<T: Default>() -> T {
     T::default()
@@ -27,6 +43,20 @@ pub fn count_first_whitespace_characters(input: &str) -> usize {
     input.chars().count() - input.trim_start().chars().count()
 }
 
+/// The 64-bit FNV-1a hash of byte strings, each preceded by its length.
+/// Unlike the hashers of the standard library, the result is stable
+/// across builds.
+pub(crate) fn fnv1a_hash(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in (part.len() as u64).to_le_bytes().iter().chain(part.iter()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
 /// Decreases the last offset of a range without ever going behind the first offset.
 pub fn decrease_last_offset(first_offset: usize, mut last_offset: usize, count: usize) -> usize {
     for _ in 0..count {
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::{Rc, Weak}};
+use crate::util::{RefCell, Rc, Weak};
 
 pub struct Arena<T> {
     data: RefCell<Vec<Rc<T>>>,
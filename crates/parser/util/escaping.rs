@@ -8,4 +8,32 @@ pub fn escape_xml(characters: &str) -> String {
 pub fn unescape_xml(input: &str) -> String {
     let unescaped = htmlentity::entity::decode(input.as_ref());
     String::from_utf8_lossy(&unescaped.bytes().into_owned()).into_owned()
-}
\ No newline at end of file
+}
+
+/// Unescapes XML entities like `unescape_xml()`, along with ascending pairs of
+/// byte offsets into the unescaped text and into the input, starting at zero and
+/// following each character reference.
+pub fn unescape_xml_with_offset_table(input: &str) -> (String, Vec<(usize, usize)>) {
+    let mut output = String::new();
+    let mut offset_table = vec![(0, 0)];
+    let mut copied = 0;
+    for (offset, _) in input.match_indices('&') {
+        let Some(length) = input[offset + 1..].find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '#')) else {
+            break;
+        };
+        let reference = &input[offset..offset + length + 2];
+        if !reference.ends_with(';') {
+            continue;
+        }
+        let unescaped = unescape_xml(reference);
+        if unescaped == reference {
+            continue;
+        }
+        output.push_str(&input[copied..offset]);
+        output.push_str(&unescaped);
+        copied = offset + reference.len();
+        offset_table.push((output.len(), copied));
+    }
+    output.push_str(&input[copied..]);
+    (output, offset_table)
+}
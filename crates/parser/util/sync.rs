@@ -0,0 +1,158 @@
+//! Thread-safe counterparts of the shared ownership and interior mutability
+//! types, used in place of `Rc`, `RefCell` and `Cell` with the `sync` feature.
+//!
+//! `RefCell` and `Cell` keep the interface of their standard counterparts, so that
+//! the rest of the crate is written once; borrowing a `RefCell` locks it.
+
+use std::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+pub use std::sync::{Arc as Rc, Weak};
+
+pub type Ref<'a, T> = RwLockReadGuard<'a, T>;
+pub type RefMut<'a, T> = RwLockWriteGuard<'a, T>;
+
+/// A mutable memory location guarded by a read-write lock.
+#[derive(Default)]
+pub struct RefCell<T>(RwLock<T>);
+
+impl<T> RefCell<T> {
+    pub const fn new(value: T) -> Self {
+        Self(RwLock::new(value))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.0.read().unwrap_or_else(|error| error.into_inner())
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.0.write().unwrap_or_else(|error| error.into_inner())
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.borrow_mut(), value)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut().unwrap_or_else(|error| error.into_inner())
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0.into_inner().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl<T: Default> RefCell<T> {
+    pub fn take(&self) -> T {
+        self.replace(T::default())
+    }
+}
+
+impl<T: Clone> Clone for RefCell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.borrow().clone())
+    }
+}
+
+impl<T: PartialEq> PartialEq for RefCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.borrow() == *other.borrow()
+    }
+}
+
+impl<T: Eq> Eq for RefCell<T> {}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for RefCell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RefCell").field(&*self.borrow()).finish()
+    }
+}
+
+impl<T: Serialize> Serialize for RefCell<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.borrow().serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RefCell<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// A mutable memory location for `Copy` values guarded by a mutex.
+#[derive(Default)]
+pub struct Cell<T>(Mutex<T>);
+
+impl<T: Copy> Cell<T> {
+    pub const fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+
+    pub fn get(&self) -> T {
+        *self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.0.lock().unwrap_or_else(|error| error.into_inner()) = value;
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.0.lock().unwrap_or_else(|error| error.into_inner()), value)
+    }
+}
+
+impl<T: Copy> Clone for Cell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<T: Copy + PartialEq> PartialEq for Cell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Copy + std::fmt::Debug> std::fmt::Debug for Cell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cell").field(&self.get()).finish()
+    }
+}
+
+impl<T: Copy + Serialize> Serialize for Cell<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<'de, T: Copy + Deserialize<'de>> Deserialize<'de> for Cell<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<CompilationUnit>();
+        assert_send_sync::<Program>();
+        assert_send_sync::<Mxml>();
+        assert_send_sync::<CssDocument>();
+        assert_send_sync::<Diagnostic>();
+
+        let cu = CompilationUnit::new(None, "x = 10; y =".into());
+        let program = ParserFacade(&cu, default()).parse_program();
+        let (cu, program) = std::thread::spawn(move || {
+            assert_eq!(program.directives[0].location().text(), "x = 10;");
+            (cu, program)
+        }).join().unwrap();
+        assert_eq!(program.directives.len(), 2);
+        assert_eq!(cu.diagnostics().len(), 1);
+    }
+}
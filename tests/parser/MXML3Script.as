@@ -0,0 +1,3 @@
+public function MXML3() {
+    super();
+}